@@ -0,0 +1,140 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Offline inspection of the availability store, e.g. for debugging availability issues.
+//!
+//! The parachains database is opened read-only, so this may be run alongside the node.
+
+use crate::{
+	cli::{AvStoreCmd, AvStoreExportCmd},
+	error::Error,
+};
+use parity_scale_codec::Encode;
+use polkadot_node_core_av_store::{
+	inspect::{self, CandidateInfo, CandidateState},
+	Config,
+};
+use polkadot_primitives::v2::{CandidateHash, ValidatorIndex};
+use service::{Database, DatabaseSource};
+use std::{fs, path::Path, sync::Arc};
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Runs an `av-store` sub-command against the given database.
+pub fn run(cmd: &AvStoreCmd, database: &DatabaseSource) -> Result<()> {
+	let db = service::open_database_read_only(database)?;
+	let config = service::availability_config(Default::default());
+
+	match cmd {
+		AvStoreCmd::List(_) => list(&db, &config),
+		AvStoreCmd::Export(cmd) => export(cmd, &db, &config),
+	}
+}
+
+fn list(db: &Arc<dyn Database>, config: &Config) -> Result<()> {
+	let candidates = inspect::candidates(db, config).map_err(av_store_error)?;
+	for info in &candidates {
+		print_candidate(info);
+	}
+	println!("{} candidates", candidates.len());
+	Ok(())
+}
+
+fn export(cmd: &AvStoreExportCmd, db: &Arc<dyn Database>, config: &Config) -> Result<()> {
+	let candidate_hash = CandidateHash(cmd.candidate_hash);
+	let info = inspect::candidate(db, config, candidate_hash)
+		.map_err(av_store_error)?
+		.ok_or_else(|| Error::Other(format!("unknown candidate {:?}", candidate_hash)))?;
+
+	fs::create_dir_all(&cmd.out)
+		.map_err(|err| Error::Other(format!("cannot create {}: {}", cmd.out.display(), err)))?;
+
+	if !cmd.all_chunks && cmd.chunks.is_empty() {
+		let available_data = inspect::available_data(db, config, candidate_hash)
+			.map_err(av_store_error)?
+			.ok_or_else(|| {
+				Error::Other(format!("the available data of {:?} is not stored", candidate_hash))
+			})?;
+
+		write_file(&cmd.out.join("available_data.scale"), &available_data)?;
+		write_file(&cmd.out.join("pov.scale"), &*available_data.pov)?;
+		write_file(&cmd.out.join("pvd.scale"), &available_data.validation_data)?;
+		return Ok(())
+	}
+
+	let indices: Vec<ValidatorIndex> = if cmd.all_chunks {
+		info.chunks_stored.iter_ones().map(|i| ValidatorIndex(i as _)).collect()
+	} else {
+		cmd.chunks.iter().copied().map(ValidatorIndex).collect()
+	};
+
+	for index in indices {
+		let chunk = inspect::chunk(db, config, candidate_hash, index)
+			.map_err(av_store_error)?
+			.ok_or_else(|| {
+				Error::Other(format!("chunk {} of {:?} is not stored", index.0, candidate_hash))
+			})?;
+
+		write_file(&cmd.out.join(format!("chunk_{}.scale", index.0)), &chunk)?;
+	}
+
+	Ok(())
+}
+
+fn print_candidate(info: &CandidateInfo) {
+	let state = match info.state {
+		CandidateState::Unavailable { observed_at } =>
+			format!("unavailable, observed at {}", observed_at.as_secs()),
+		CandidateState::Unfinalized { observed_at, ref blocks } => format!(
+			"included in {} unfinalized block(s) {:?}, observed at {}",
+			blocks.len(),
+			blocks,
+			observed_at.as_secs(),
+		),
+		CandidateState::Finalized { finalized_at } =>
+			format!("finalized at {}", finalized_at.as_secs()),
+	};
+	let prune_at = match info.prune_at {
+		Some(prune_at) => prune_at.as_secs().to_string(),
+		None if info.kept => "never".into(),
+		None => "-".into(),
+	};
+
+	println!("{:?}", info.candidate_hash);
+	println!("  state: {}", state);
+	println!("  data available: {}", info.data_available);
+	println!(
+		"  chunks stored: {}/{} [{}]",
+		info.chunks_stored.count_ones(),
+		info.chunks_stored.len(),
+		info.chunks_stored
+			.iter()
+			.map(|b| if *b { '1' } else { '0' })
+			.collect::<String>(),
+	);
+	println!("  prune at: {}", prune_at);
+}
+
+fn write_file(path: &Path, value: &impl Encode) -> Result<()> {
+	fs::write(path, value.encode())
+		.map_err(|err| Error::Other(format!("cannot write {}: {}", path.display(), err)))?;
+	println!("Wrote {}", path.display());
+	Ok(())
+}
+
+fn av_store_error(err: polkadot_node_core_av_store::Error) -> Error {
+	Error::Other(format!("cannot read the availability store: {}", err))
+}
@@ -17,6 +17,7 @@
 //! Polkadot CLI library.
 
 use clap::Parser;
+use std::{num::NonZeroU64, path::PathBuf};
 
 #[allow(missing_docs)]
 #[derive(Debug, Parser)]
@@ -59,6 +60,14 @@ pub enum Subcommand {
 	/// capabilities of running a validator.
 	HostPerfCheck,
 
+	/// Validates a candidate offline by running the PVF against the given PoV and persisted
+	/// validation data, e.g. in order to reproduce a dispute.
+	ValidateCandidate(ValidateCandidateCmd),
+
+	/// Inspect the availability store of the node, or export data out of it.
+	#[clap(subcommand)]
+	AvStore(AvStoreCmd),
+
 	/// Try some command against runtime state.
 	#[cfg(feature = "try-runtime")]
 	TryRuntime(try_runtime_cli::TryRuntimeCmd),
@@ -82,6 +91,123 @@ pub struct ValidationWorkerCommand {
 	pub socket_path: String,
 }
 
+/// The encoding of the input files of the `validate-candidate` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ArgEnum)]
+pub enum InputFormat {
+	/// SCALE encoded files. The validation code is the plain or compressed Wasm blob.
+	Scale,
+	/// JSON files. The validation code is a hex string.
+	Json,
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct ValidateCandidateCmd {
+	/// The path to the validation code of the para.
+	#[clap(long)]
+	pub code: PathBuf,
+
+	/// The path to the proof of validity of the candidate.
+	#[clap(long)]
+	pub pov: PathBuf,
+
+	/// The path to the persisted validation data of the candidate.
+	#[clap(long)]
+	pub pvd: PathBuf,
+
+	/// The path to the executor parameters of the session the candidate was validated in.
+	///
+	/// The default parameters are used if omitted.
+	#[clap(long)]
+	pub executor_params: Option<PathBuf>,
+
+	/// The encoding of the input files.
+	#[clap(long, arg_enum, default_value = "scale")]
+	pub format: InputFormat,
+
+	/// Use the backing execution timeout instead of the approval one.
+	#[clap(long)]
+	pub backing: bool,
+
+	/// The directory to store the prepared artifact in.
+	///
+	/// A temporary directory, removed afterwards, is used if omitted.
+	#[clap(long)]
+	pub cache_path: Option<PathBuf>,
+
+	/// Prepare and execute the PVF within this process instead of the worker processes.
+	///
+	/// Useful for debugging the executor itself. No timeouts are enforced in this mode.
+	#[clap(long)]
+	pub in_process: bool,
+}
+
+/// The `av-store` sub-commands.
+#[derive(Debug, clap::Subcommand)]
+pub enum AvStoreCmd {
+	/// List the candidates in the availability store, along with their state, the chunks stored
+	/// and when they are due to be pruned.
+	List(AvStoreListCmd),
+
+	/// Export the available data or the erasure chunks of a candidate to SCALE encoded files.
+	///
+	/// The available data is also exported as the PoV and persisted validation data files taken by
+	/// the `validate-candidate` command.
+	Export(AvStoreExportCmd),
+}
+
+/// The parameters shared by the `av-store` sub-commands.
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct AvStoreParams {
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+impl sc_cli::CliConfiguration for AvStoreParams {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct AvStoreListCmd {
+	#[clap(flatten)]
+	pub params: AvStoreParams,
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct AvStoreExportCmd {
+	/// The hash of the candidate.
+	pub candidate_hash: polkadot_primitives::v2::Hash,
+
+	/// The directory to write the files to. It is created if it doesn't exist.
+	#[clap(long)]
+	pub out: PathBuf,
+
+	/// Export the chunk of the given validator index, along with its Merkle proof, instead of the
+	/// available data. May be given multiple times.
+	#[clap(long = "chunk", conflicts_with = "all-chunks")]
+	pub chunks: Vec<u32>,
+
+	/// Export all the stored chunks, along with their Merkle proofs, instead of the available
+	/// data.
+	#[clap(long)]
+	pub all_chunks: bool,
+
+	#[clap(flatten)]
+	pub params: AvStoreParams,
+}
+
 #[allow(missing_docs)]
 #[derive(Debug, Parser)]
 pub struct RunCmd {
@@ -143,6 +269,52 @@ pub struct RunCmd {
 	/// **Dangerous!** Do not touch unless explicitly adviced to.
 	#[clap(long)]
 	pub overseer_channel_capacity_override: Option<usize>,
+
+	/// Sandbox the PVF prepare and execute workers.
+	///
+	/// A sandboxed worker can only access the PVF artifact cache directory and cannot use the
+	/// network. Only supported on Linux. If the running system doesn't support sandboxing or a
+	/// sandboxed worker fails the self-test on startup, the workers run unsandboxed and a warning is
+	/// printed out in the logs.
+	#[clap(long)]
+	pub pvf_sandbox: bool,
+
+	/// The maximum total size of the compiled PVF artifacts kept on disk, in MiB.
+	///
+	/// When the cache grows beyond it, the least recently used artifacts are removed. Artifacts
+	/// that are being executed are never removed. Unlimited by default.
+	#[clap(long)]
+	pub pvf_artifacts_cache_max_size: Option<u64>,
+
+	/// How long to keep the availability data of candidates which are not included in a finalized
+	/// block, in seconds. One hour by default.
+	#[clap(long)]
+	pub av_store_keep_unavailable_for: Option<u64>,
+
+	/// How long to keep the availability data of finalized candidates, in seconds. 25 hours by
+	/// default.
+	///
+	/// Must cover the dispute window of `dispute_period` sessions, which is checked on startup.
+	#[clap(long)]
+	pub av_store_keep_finalized_for: Option<u64>,
+
+	/// How often to prune the availability store, in seconds. 5 minutes by default.
+	#[clap(long)]
+	pub av_store_pruning_interval: Option<NonZeroU64>,
+
+	/// Keep the availability data of the finalized candidates of the given para indefinitely.
+	///
+	/// Can be passed multiple times.
+	#[clap(long = "av-store-keep-para")]
+	pub av_store_keep_paras: Vec<u32>,
+
+	/// Store our own chunk of the candidates recovered by this validator, if it is missing, and
+	/// serve it to the other validators.
+	///
+	/// Helps the availability of candidates whose backers went offline, at the cost of re-encoding
+	/// the recovered data.
+	#[clap(long)]
+	pub serve_recovered_chunks: bool,
 }
 
 #[allow(missing_docs)]
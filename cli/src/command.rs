@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::cli::{Cli, Subcommand};
+use crate::cli::{AvStoreCmd, Cli, Subcommand};
 use frame_benchmarking_cli::{BenchmarkCmd, ExtrinsicFactory, SUBSTRATE_REFERENCE_HARDWARE};
 use futures::future::TryFutureExt;
 use log::info;
@@ -25,7 +25,7 @@ use sc_cli::{RuntimeVersion, SubstrateCli};
 use service::{self, HeaderBackend, IdentifyVariant};
 use sp_core::crypto::Ss58AddressFormatRegistry;
 use sp_keyring::Sr25519Keyring;
-use std::net::ToSocketAddrs;
+use std::{net::ToSocketAddrs, time::Duration};
 
 pub use crate::{error::Error, service::BlockId};
 pub use polkadot_performance_test::PerfCheckError;
@@ -327,6 +327,19 @@ where
 		None
 	};
 
+	let mut availability_pruning_config = service::AvailabilityPruningConfig::default();
+	if let Some(secs) = cli.run.av_store_keep_unavailable_for {
+		availability_pruning_config.keep_unavailable_for = Duration::from_secs(secs);
+	}
+	if let Some(secs) = cli.run.av_store_keep_finalized_for {
+		availability_pruning_config.keep_finalized_for = Duration::from_secs(secs);
+	}
+	if let Some(secs) = cli.run.av_store_pruning_interval {
+		availability_pruning_config.pruning_interval = Duration::from_secs(secs.get());
+	}
+	availability_pruning_config.keep_finalized_paras =
+		cli.run.av_store_keep_paras.iter().copied().map(service::ParaId::from).collect();
+
 	runner.run_node_until_exit(move |config| async move {
 		let hwbench = if !cli.run.no_hardware_benchmarks {
 			config.database.path().map(|database_path| {
@@ -349,6 +362,10 @@ where
 			cli.run.overseer_channel_capacity_override,
 			maybe_malus_finality_delay,
 			hwbench,
+			cli.run.pvf_sandbox,
+			cli.run.pvf_artifacts_cache_max_size.map(|mib| mib.saturating_mul(1024 * 1024)),
+			availability_pruning_config,
+			cli.run.serve_recovered_chunks,
 		)
 		.map(|full| full.task_manager)
 		.map_err(Into::into)
@@ -635,6 +652,32 @@ pub fn run() -> Result<()> {
 
 			host_perf_check()
 		},
+		Some(Subcommand::ValidateCandidate(cmd)) => {
+			let mut builder = sc_cli::LoggerBuilder::new("");
+			builder.with_colors(true);
+			builder.init()?;
+
+			#[cfg(target_os = "android")]
+			{
+				return Err(sc_cli::Error::Input(
+					"PVF validation is not supported under this platform".into(),
+				)
+				.into())
+			}
+
+			#[cfg(not(target_os = "android"))]
+			{
+				crate::validate_candidate::validate_candidate(cmd)
+			}
+		},
+		Some(Subcommand::AvStore(cmd)) => {
+			let params = match cmd {
+				AvStoreCmd::List(cmd) => &cmd.params,
+				AvStoreCmd::Export(cmd) => &cmd.params,
+			};
+			let runner = cli.create_runner(params)?;
+			runner.sync_run(|config| crate::av_store::run(cmd, &config.database))
+		},
 		Some(Subcommand::Key(cmd)) => Ok(cmd.run(&cli)?),
 		#[cfg(feature = "try-runtime")]
 		Some(Subcommand::TryRuntime(cmd)) => {
@@ -18,6 +18,8 @@
 
 #![warn(missing_docs)]
 
+#[cfg(feature = "cli")]
+mod av_store;
 #[cfg(feature = "cli")]
 mod cli;
 #[cfg(feature = "cli")]
@@ -26,6 +28,8 @@ mod command;
 mod error;
 #[cfg(all(feature = "hostperfcheck", build_type = "release"))]
 mod host_perf_check;
+#[cfg(all(feature = "cli", not(target_os = "android")))]
+mod validate_candidate;
 
 #[cfg(feature = "full-node")]
 pub use service::RuntimeApiCollection;
@@ -0,0 +1,244 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Offline validation of a candidate, meant for reproducing disputes.
+//!
+//! The PVF is prepared and executed the same way a validator does it, by the validation host and
+//! its worker processes, with the production timeouts.
+
+use crate::{
+	cli::{InputFormat, ValidateCandidateCmd},
+	error::Error,
+};
+use futures::{channel::oneshot, FutureExt as _};
+use log::info;
+use parity_scale_codec::{Decode, Encode};
+use polkadot_node_core_pvf::{
+	sp_maybe_compressed_blob, ExecutionPhases, Priority, Pvf, ValidationError, ValidationHost,
+};
+use polkadot_node_primitives::{
+	InvalidCandidate, PoV, APPROVAL_EXECUTION_TIMEOUT, BACKING_EXECUTION_TIMEOUT, POV_BOMB_LIMIT,
+	VALIDATION_CODE_BOMB_LIMIT,
+};
+use polkadot_parachain::primitives::{
+	BlockData, ValidationCode, ValidationParams, ValidationResult as WasmValidationResult,
+};
+use polkadot_primitives::{v2::PersistedValidationData, vstaging::ExecutorParams};
+use std::{
+	path::{Path, PathBuf},
+	time::{Duration, Instant},
+};
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// The outcome of a single execution of the PVF.
+type Outcome = std::result::Result<WasmValidationResult, String>;
+
+/// A single execution of the PVF by the validation host.
+struct Execution {
+	outcome: Outcome,
+	/// The wall-clock time from submitting the request until receiving the outcome.
+	duration: Duration,
+	/// The breakdown of the execution into phases, only known if the candidate is valid.
+	phases: Option<ExecutionPhases>,
+}
+
+/// Runs the `validate-candidate` command.
+pub fn validate_candidate(cmd: &ValidateCandidateCmd) -> Result<()> {
+	let code = match cmd.format {
+		InputFormat::Scale => ValidationCode(read_file(&cmd.code)?),
+		InputFormat::Json => read_input(&cmd.code, cmd.format)?,
+	};
+	let pov: PoV = read_input(&cmd.pov, cmd.format)?;
+	let pvd: PersistedValidationData = read_input(&cmd.pvd, cmd.format)?;
+	let executor_params = match cmd.executor_params {
+		Some(ref path) => read_input(path, cmd.format)?,
+		None => ExecutorParams::default(),
+	};
+
+	info!(
+		"Validating the candidate with PoV {:?} against the validation code {:?}",
+		pov.hash(),
+		code.hash(),
+	);
+
+	// The same checks the candidate validation subsystem performs before the execution.
+	let raw_code = match sp_maybe_compressed_blob::decompress(&code.0, VALIDATION_CODE_BOMB_LIMIT) {
+		Ok(raw_code) => raw_code.into_owned(),
+		Err(_) => return report_invalid(InvalidCandidate::CodeDecompressionFailure),
+	};
+	let encoded_pov_size = pov.encoded_size();
+	if encoded_pov_size > pvd.max_pov_size as usize {
+		return report_invalid(InvalidCandidate::ParamsTooLarge(encoded_pov_size as u64))
+	}
+	let block_data = match sp_maybe_compressed_blob::decompress(&pov.block_data.0, POV_BOMB_LIMIT) {
+		Ok(block_data) => BlockData(block_data.into_owned()),
+		Err(_) => return report_invalid(InvalidCandidate::PoVDecompressionFailure),
+	};
+	let params = ValidationParams {
+		parent_head: pvd.parent_head.clone(),
+		block_data,
+		relay_parent_number: pvd.relay_parent_number,
+		relay_parent_storage_root: pvd.relay_parent_storage_root,
+	};
+
+	if cmd.in_process {
+		let start = Instant::now();
+		let outcome = polkadot_node_core_pvf::testing::validate_candidate_with_executor_params(
+			&raw_code,
+			&params.encode(),
+			&executor_params,
+		)
+		.map_err(|err| err.to_string())
+		.and_then(|result| {
+			WasmValidationResult::decode(&mut &result[..])
+				.map_err(|err| format!("cannot decode the validation result: {}", err))
+		});
+		println!("Preparation and execution took {:?}", start.elapsed());
+		return report(outcome)
+	}
+
+	let timeout = if cmd.backing { BACKING_EXECUTION_TIMEOUT } else { APPROVAL_EXECUTION_TIMEOUT };
+	let (cold, warm) = run_with_validation_host(
+		cmd.cache_path.clone(),
+		Pvf::from_code(raw_code, executor_params),
+		timeout,
+		params.encode(),
+	)?;
+
+	// The first execution includes the preparation of the artifact, the second one reuses it.
+	println!("Preparation and execution took {:?}", cold.duration);
+	println!("Execution took {:?}", warm.duration);
+	if let Some(phases) = warm.phases {
+		for (phase, duration) in phases.iter() {
+			println!("  {}: {:?}", phase, duration);
+		}
+	}
+	println!("Preparation took about {:?}", cold.duration.saturating_sub(warm.duration));
+
+	if cold.outcome != warm.outcome {
+		println!("The outcome is not deterministic, the second execution resulted in:");
+		print_outcome(&warm.outcome);
+		println!("The first one in:");
+	}
+	report(cold.outcome)
+}
+
+/// Executes the PVF twice using a validation host spawning the workers from the current
+/// executable. Returns both executions.
+fn run_with_validation_host(
+	cache_path: Option<PathBuf>,
+	pvf: Pvf,
+	timeout: Duration,
+	params: Vec<u8>,
+) -> Result<(Execution, Execution)> {
+	let (cache_path, is_temporary) = match cache_path {
+		Some(cache_path) => (cache_path, false),
+		None => (
+			std::env::temp_dir()
+				.join(format!("polkadot-validate-candidate-{}", std::process::id())),
+			true,
+		),
+	};
+	let program_path = std::env::current_exe()
+		.map_err(|err| Error::Other(format!("cannot locate the worker executable: {}", err)))?;
+
+	let (mut validation_host, task) = polkadot_node_core_pvf::start(
+		polkadot_node_core_pvf::Config::new(cache_path.clone(), program_path),
+		Default::default(),
+	);
+
+	let executions = async move {
+		let cold = execute(&mut validation_host, pvf.clone(), timeout, params.clone()).await?;
+		let warm = execute(&mut validation_host, pvf, timeout, params).await?;
+		Ok((cold, warm))
+	}
+	.fuse();
+	let task = task.fuse();
+	futures::pin_mut!(executions, task);
+
+	let result = futures::executor::block_on(async move {
+		futures::select! {
+			result = executions => result,
+			() = task => Err(Error::Other("the validation host stopped".into())),
+		}
+	});
+
+	if is_temporary {
+		let _ = std::fs::remove_dir_all(&cache_path);
+	}
+
+	result
+}
+
+/// Executes the PVF once and returns the outcome along with the time it took.
+async fn execute(
+	validation_host: &mut ValidationHost,
+	pvf: Pvf,
+	timeout: Duration,
+	params: Vec<u8>,
+) -> Result<Execution> {
+	let start = Instant::now();
+	let (result_tx, result_rx) = oneshot::channel();
+	validation_host
+		.execute_pvf(pvf, timeout, params, Priority::Critical, result_tx)
+		.await?;
+
+	let (outcome, phases) = match result_rx.await {
+		Ok(Ok((result, phases))) => (Ok(result), Some(phases)),
+		Ok(Err(ValidationError::InvalidCandidate(reason))) => (Err(format!("{:?}", reason)), None),
+		Ok(Err(ValidationError::InternalError(err))) =>
+			return Err(Error::Other(format!("internal error: {}", err))),
+		Ok(Err(ValidationError::WallClockTimeout)) =>
+			return Err(Error::Other("the execution exceeded the wall-clock timeout".into())),
+		Err(_) => return Err(Error::Other("the validation host hung up".into())),
+	};
+
+	Ok(Execution { outcome, duration: start.elapsed(), phases })
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>> {
+	std::fs::read(path)
+		.map_err(|err| Error::Other(format!("cannot read {}: {}", path.display(), err)))
+}
+
+fn read_input<T: Decode + serde::de::DeserializeOwned>(
+	path: &Path,
+	format: InputFormat,
+) -> Result<T> {
+	let bytes = read_file(path)?;
+	match format {
+		InputFormat::Scale => T::decode(&mut &bytes[..]).map_err(|err| err.to_string()),
+		InputFormat::Json => serde_json::from_slice(&bytes).map_err(|err| err.to_string()),
+	}
+	.map_err(|err| Error::Other(format!("cannot decode {}: {}", path.display(), err)))
+}
+
+fn report_invalid(reason: InvalidCandidate) -> Result<()> {
+	report(Err(format!("{:?}", reason)))
+}
+
+fn report(outcome: Outcome) -> Result<()> {
+	print_outcome(&outcome);
+	Ok(())
+}
+
+fn print_outcome(outcome: &Outcome) {
+	match outcome {
+		Ok(result) => println!("The candidate is valid: {:#?}", result),
+		Err(reason) => println!("The candidate is invalid: {}", reason),
+	}
+}
@@ -96,6 +96,24 @@ pub const fn recovery_threshold(n_validators: usize) -> Result<usize, Error> {
 	Ok(needed + 1)
 }
 
+/// Obtain the number of systematic chunks, i.e. the chunks carrying the original data verbatim.
+///
+/// The chunks with indices `0..systematic_recovery_threshold(n_validators)` are enough to recover
+/// the data by just putting them back together, without any Reed-Solomon decoding. The threshold
+/// is the data shard count of the code, which is the largest power of two not exceeding
+/// [`recovery_threshold`].
+pub fn systematic_recovery_threshold(n_validators: usize) -> Result<usize, Error> {
+	// `CodeParams::derive_parameters` rounds the wanted data shard count down to a power of two.
+	let threshold = recovery_threshold(n_validators)?;
+	Ok(1 << (usize::BITS - 1 - threshold.leading_zeros()))
+}
+
+/// Whether the chunk with the given index is a systematic chunk, see
+/// [`systematic_recovery_threshold`].
+pub fn is_systematic_chunk(n_validators: usize, chunk_index: usize) -> Result<bool, Error> {
+	Ok(chunk_index < systematic_recovery_threshold(n_validators)?)
+}
+
 fn code_params(n_validators: usize) -> Result<CodeParams, Error> {
 	// we need to be able to reconstruct from 1/3 - eps
 
@@ -139,6 +157,73 @@ pub fn obtain_chunks<T: Encode>(n_validators: usize, data: &T) -> Result<Vec<Vec
 	Ok(shards.into_iter().map(|w: WrappedShard| w.into_inner()).collect())
 }
 
+/// Obtain erasure-coded chunks for v1 `AvailableData`, one for each validator, encoding parts of
+/// the data on multiple threads.
+///
+/// Works only up to 65536 validators, and `n_validators` must be non-zero.
+pub fn obtain_chunks_v1_parallel(
+	n_validators: usize,
+	data: &AvailableData,
+) -> Result<Vec<Vec<u8>>, Error> {
+	obtain_chunks_parallel(n_validators, data)
+}
+
+/// Obtain erasure-coded chunks, one for each validator, encoding parts of the data on multiple
+/// threads.
+///
+/// The chunks are the same as the ones of [`obtain_chunks`]. They are all allocated up front, as
+/// every part of the data contributes to each of them.
+///
+/// Works only up to 65536 validators, and `n_validators` must be non-zero.
+pub fn obtain_chunks_parallel<T: Encode>(
+	n_validators: usize,
+	data: &T,
+) -> Result<Vec<Vec<u8>>, Error> {
+	use rayon::prelude::*;
+
+	let params = code_params(n_validators)?;
+	let encoded = data.encode();
+
+	if encoded.is_empty() {
+		return Err(Error::BadPayload)
+	}
+
+	// Each encoding run turns `2 * k` bytes of the payload into one `GF(2^16)` symbol of every
+	// chunk, independently of the other runs. So the payload is split into pieces of whole runs,
+	// which are encoded separately, each into its own range of the chunks. Using a few pieces per
+	// thread bounds the memory used for the intermediate shards of the pieces being encoded.
+	let run_len = systematic_recovery_threshold(n_validators)? * 2;
+	let n_runs = (encoded.len() + run_len - 1) / run_len;
+	let runs_per_piece = {
+		let max_pieces = std::cmp::min(n_runs, rayon::current_num_threads() * 4);
+		(n_runs + max_pieces - 1) / max_pieces
+	};
+	let n_pieces = (n_runs + runs_per_piece - 1) / runs_per_piece;
+
+	let mut chunks = vec![vec![0u8; n_runs * 2]; n_validators];
+	let mut piece_ranges: Vec<Vec<&mut [u8]>> =
+		(0..n_pieces).map(|_| Vec::with_capacity(n_validators)).collect();
+	for chunk in chunks.iter_mut() {
+		for (range, ranges) in chunk.chunks_mut(runs_per_piece * 2).zip(piece_ranges.iter_mut()) {
+			ranges.push(range);
+		}
+	}
+
+	encoded
+		.par_chunks(runs_per_piece * run_len)
+		.zip(piece_ranges.into_par_iter())
+		.for_each(|(piece, ranges)| {
+			let shards = params.make_encoder().encode::<WrappedShard>(piece).expect(
+				"Payload non-empty, shard sizes are uniform, and validator numbers checked; qed",
+			);
+			for (range, shard) in ranges.into_iter().zip(shards) {
+				range.copy_from_slice(shard.as_ref());
+			}
+		});
+
+	Ok(chunks)
+}
+
 /// Reconstruct the v1 available data from a set of chunks.
 ///
 /// Provide an iterator containing chunk data and the corresponding index.
@@ -153,12 +238,62 @@ where
 	reconstruct(n_validators, chunks)
 }
 
+/// Reconstruct the v1 available data from the systematic chunks, see
+/// [`systematic_recovery_threshold`].
+///
+/// `chunks` must start with the systematic chunks in the order of their indices. Any chunks past
+/// the systematic ones are ignored.
+///
+/// Works only up to 65536 validators, and `n_validators` must be non-zero.
+pub fn reconstruct_from_systematic_v1<I: AsRef<[u8]>>(
+	n_validators: usize,
+	chunks: &[I],
+) -> Result<AvailableData, Error> {
+	reconstruct_from_systematic(n_validators, chunks)
+}
+
+/// Reconstruct decodable data from the systematic chunks, see [`systematic_recovery_threshold`].
+///
+/// `chunks` must start with the systematic chunks in the order of their indices. Any chunks past
+/// the systematic ones are ignored.
+///
+/// Works only up to 65536 validators, and `n_validators` must be non-zero.
+pub fn reconstruct_from_systematic<I: AsRef<[u8]>, T: Decode>(
+	n_validators: usize,
+	chunks: &[I],
+) -> Result<T, Error> {
+	let systematic_threshold = systematic_recovery_threshold(n_validators)?;
+	let chunks = chunks.get(..systematic_threshold).ok_or(Error::NotEnoughChunks)?;
+
+	let shard_len = chunks[0].as_ref().len();
+	if shard_len % 2 != 0 {
+		return Err(Error::UnevenLength)
+	}
+	if shard_len == 0 || chunks.iter().any(|chunk| chunk.as_ref().len() != shard_len) {
+		return Err(Error::NonUniformChunks)
+	}
+
+	// Each encoding run spreads its data over the systematic chunks, one `GF(2^16)` symbol per
+	// chunk, so the data is recovered by interleaving the symbols of the chunks.
+	let mut payload_bytes = Vec::with_capacity(shard_len * systematic_threshold);
+	for symbol in (0..shard_len).step_by(2) {
+		for chunk in chunks {
+			payload_bytes.extend_from_slice(&chunk.as_ref()[symbol..symbol + 2]);
+		}
+	}
+
+	Decode::decode(&mut &payload_bytes[..]).or_else(|_e| Err(Error::BadPayload))
+}
+
 /// Reconstruct decodable data from a set of chunks.
 ///
 /// Provide an iterator containing chunk data and the corresponding index.
 /// The indices of the present chunks must be indicated. If too few chunks
 /// are provided, recovery is not possible.
 ///
+/// If all the systematic chunks are present, the data is recovered from them directly, see
+/// [`reconstruct_from_systematic`].
+///
 /// Works only up to 65536 validators, and `n_validators` must be non-zero.
 pub fn reconstruct<'a, I: 'a, T: Decode>(n_validators: usize, chunks: I) -> Result<T, Error>
 where
@@ -185,6 +320,15 @@ where
 		received_shards[chunk_idx] = Some(WrappedShard::new(chunk_data.to_vec()));
 	}
 
+	let systematic_threshold = systematic_recovery_threshold(n_validators)?;
+	let systematic_chunks = received_shards[..systematic_threshold]
+		.iter()
+		.map(|shard| shard.as_ref().map(AsRef::<[u8]>::as_ref))
+		.collect::<Option<Vec<&[u8]>>>();
+	if let Some(systematic_chunks) = systematic_chunks {
+		return reconstruct_from_systematic(n_validators, &systematic_chunks)
+	}
+
 	let res = params.make_encoder().reconstruct(received_shards);
 
 	let payload_bytes = match res {
@@ -204,26 +348,73 @@ where
 	Decode::decode(&mut &payload_bytes[..]).or_else(|_e| Err(Error::BadPayload))
 }
 
-/// An iterator that yields merkle branches and chunk data for all chunks to
-/// be sent to other validators.
-pub struct Branches<'a, I> {
+/// Incrementally builds the trie of the chunks of an erasure-coded value.
+///
+/// Only the hashes of the chunks are kept, so the chunks can be dropped once they are pushed.
+/// Note that this does not lower the peak memory of encoding: all the chunks are in memory as
+/// soon as they are obtained, as every part of the data contributes to each of them.
+#[derive(Debug, Default, Clone)]
+pub struct BranchesBuilder {
+	chunk_hashes: Vec<H256>,
+}
+
+impl BranchesBuilder {
+	/// Create a builder expecting the given number of chunks.
+	pub fn with_capacity(n_chunks: usize) -> Self {
+		BranchesBuilder { chunk_hashes: Vec::with_capacity(n_chunks) }
+	}
+
+	/// Add the next chunk. Chunks must be pushed in the order of their indices.
+	pub fn push(&mut self, chunk: &[u8]) {
+		self.chunk_hashes.push(BlakeTwo256::hash(chunk));
+	}
+
+	/// The number of chunks pushed so far.
+	pub fn len(&self) -> usize {
+		self.chunk_hashes.len()
+	}
+
+	/// Whether no chunks were pushed so far.
+	pub fn is_empty(&self) -> bool {
+		self.chunk_hashes.is_empty()
+	}
+
+	/// Construct the trie of the pushed chunks.
+	pub fn build(self) -> ChunkProofs {
+		let mut trie_storage: MemoryDB<Blake2Hasher> = MemoryDB::default();
+		let mut root = H256::default();
+
+		// construct trie mapping each chunk's index to its hash.
+		{
+			let mut trie = TrieDBMutBuilder::new(&mut trie_storage, &mut root).build();
+			for (i, chunk_hash) in self.chunk_hashes.iter().enumerate() {
+				(i as u32).using_encoded(|encoded_index| {
+					trie.insert(encoded_index, chunk_hash.as_ref()).expect(
+						"a fresh trie stored in memory cannot have errors loading nodes; qed",
+					);
+				})
+			}
+		}
+
+		ChunkProofs { trie_storage, root }
+	}
+}
+
+/// The trie of the chunks of an erasure-coded value, from which merkle proofs of the chunks
+/// are generated on demand.
+pub struct ChunkProofs {
 	trie_storage: MemoryDB<Blake2Hasher>,
 	root: H256,
-	chunks: &'a [I],
-	current_pos: usize,
 }
 
-impl<'a, I: AsRef<[u8]>> Branches<'a, I> {
+impl ChunkProofs {
 	/// Get the trie root.
 	pub fn root(&self) -> H256 {
 		self.root.clone()
 	}
-}
 
-impl<'a, I: AsRef<[u8]>> Iterator for Branches<'a, I> {
-	type Item = (Proof, &'a [u8]);
-
-	fn next(&mut self) -> Option<Self::Item> {
+	/// Generate the merkle proof of the chunk with the given index, if there is such a chunk.
+	pub fn proof(&self, index: usize) -> Option<Proof> {
 		use sp_trie::Recorder;
 
 		let mut recorder = Recorder::<LayoutV0<Blake2Hasher>>::new();
@@ -232,45 +423,60 @@ impl<'a, I: AsRef<[u8]>> Iterator for Branches<'a, I> {
 				.with_recorder(&mut recorder)
 				.build();
 
-			(self.current_pos as u32).using_encoded(|s| trie.get(s))
+			(index as u32).using_encoded(|s| trie.get(s))
 		};
 
 		match res.expect("all nodes in trie present; qed") {
 			Some(_) => {
 				let nodes: Vec<Vec<u8>> = recorder.drain().into_iter().map(|r| r.data).collect();
-				let chunk = self.chunks.get(self.current_pos).expect(
-					"there is a one-to-one mapping of chunks to valid merkle branches; qed",
-				);
-				self.current_pos += 1;
-				Proof::try_from(nodes).ok().map(|proof| (proof, chunk.as_ref()))
+				Proof::try_from(nodes).ok()
 			},
 			None => None,
 		}
 	}
 }
 
+/// An iterator that yields merkle branches and chunk data for all chunks to
+/// be sent to other validators.
+pub struct Branches<'a, I> {
+	proofs: ChunkProofs,
+	chunks: &'a [I],
+	current_pos: usize,
+}
+
+impl<'a, I: AsRef<[u8]>> Branches<'a, I> {
+	/// Get the trie root.
+	pub fn root(&self) -> H256 {
+		self.proofs.root()
+	}
+}
+
+impl<'a, I: AsRef<[u8]>> Iterator for Branches<'a, I> {
+	type Item = (Proof, &'a [u8]);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let proof = self.proofs.proof(self.current_pos)?;
+		let chunk = self
+			.chunks
+			.get(self.current_pos)
+			.expect("there is a one-to-one mapping of chunks to valid merkle branches; qed");
+		self.current_pos += 1;
+		Some((proof, chunk.as_ref()))
+	}
+}
+
 /// Construct a trie from chunks of an erasure-coded value. This returns the root hash and an
 /// iterator of merkle proofs, one for each validator.
 pub fn branches<'a, I: 'a>(chunks: &'a [I]) -> Branches<'a, I>
 where
 	I: AsRef<[u8]>,
 {
-	let mut trie_storage: MemoryDB<Blake2Hasher> = MemoryDB::default();
-	let mut root = H256::default();
-
-	// construct trie mapping each chunk's index to its hash.
-	{
-		let mut trie = TrieDBMutBuilder::new(&mut trie_storage, &mut root).build();
-		for (i, chunk) in chunks.as_ref().iter().enumerate() {
-			(i as u32).using_encoded(|encoded_index| {
-				let chunk_hash = BlakeTwo256::hash(chunk.as_ref());
-				trie.insert(encoded_index, chunk_hash.as_ref())
-					.expect("a fresh trie stored in memory cannot have errors loading nodes; qed");
-			})
-		}
+	let mut builder = BranchesBuilder::with_capacity(chunks.len());
+	for chunk in chunks {
+		builder.push(chunk.as_ref());
 	}
 
-	Branches { trie_storage, root, chunks, current_pos: 0 }
+	Branches { proofs: builder.build(), chunks, current_pos: 0 }
 }
 
 /// Verify a merkle branch, yielding the chunk hash meant to be present at that
@@ -379,6 +585,90 @@ mod tests {
 		assert_eq!(reconstructed, available_data);
 	}
 
+	#[test]
+	fn systematic_recovery_threshold_is_a_power_of_two() {
+		assert_eq!(systematic_recovery_threshold(2), Ok(1));
+		assert_eq!(systematic_recovery_threshold(7), Ok(2));
+		assert_eq!(systematic_recovery_threshold(10), Ok(4));
+		assert_eq!(systematic_recovery_threshold(100), Ok(32));
+		assert_eq!(systematic_recovery_threshold(1000), Ok(256));
+		assert_eq!(systematic_recovery_threshold(1), Err(Error::NotEnoughValidators));
+	}
+
+	#[test]
+	fn round_trip_systematic_works() {
+		let pov = PoV { block_data: BlockData((0..255).collect()) };
+		let available_data = AvailableData { pov: pov.into(), validation_data: Default::default() };
+
+		for n_validators in [4, 7, 10, 100, 301] {
+			let chunks = obtain_chunks(n_validators, &available_data).unwrap();
+			let threshold = systematic_recovery_threshold(n_validators).unwrap();
+
+			let reconstructed: AvailableData =
+				reconstruct_from_systematic(n_validators, &chunks[..threshold]).unwrap();
+			assert_eq!(reconstructed, available_data);
+
+			// The regular reconstruction takes the same path given the systematic chunks.
+			let reconstructed = reconstruct_v1(
+				n_validators,
+				chunks.iter().take(threshold).enumerate().map(|(i, chunk)| (&chunk[..], i)),
+			)
+			.unwrap();
+			assert_eq!(reconstructed, available_data);
+		}
+	}
+
+	#[test]
+	fn reconstruct_from_systematic_needs_all_systematic_chunks() {
+		let pov = PoV { block_data: BlockData((0..255).collect()) };
+		let available_data = AvailableData { pov: pov.into(), validation_data: Default::default() };
+		let chunks = obtain_chunks(10, &available_data).unwrap();
+
+		assert_eq!(reconstruct_from_systematic_v1(10, &chunks[..3]), Err(Error::NotEnoughChunks));
+
+		let mut chunks = chunks;
+		chunks[1].pop();
+		assert_eq!(reconstruct_from_systematic_v1(10, &chunks), Err(Error::NonUniformChunks));
+	}
+
+	#[test]
+	fn parallel_encoding_matches_sequential_encoding() {
+		for len in [1, 255, 4096, 100_000] {
+			let pov = PoV { block_data: BlockData((0..len).map(|i| i as u8).collect()) };
+			let available_data =
+				AvailableData { pov: pov.into(), validation_data: Default::default() };
+
+			for n_validators in [2, 4, 7, 10, 100, 301, 1024] {
+				assert_eq!(
+					obtain_chunks_v1_parallel(n_validators, &available_data).unwrap(),
+					obtain_chunks_v1(n_validators, &available_data).unwrap(),
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn branches_builder_matches_branches() {
+		let pov = PoV { block_data: BlockData((0..255).collect()) };
+		let available_data = AvailableData { pov: pov.into(), validation_data: Default::default() };
+		let chunks = obtain_chunks(100, &available_data).unwrap();
+
+		let mut builder = BranchesBuilder::with_capacity(chunks.len());
+		for chunk in &chunks {
+			builder.push(chunk);
+		}
+		assert_eq!(builder.len(), chunks.len());
+		let proofs = builder.build();
+
+		let branches = branches(chunks.as_ref());
+		assert_eq!(proofs.root(), branches.root());
+		for (i, (proof, chunk)) in branches.enumerate() {
+			assert_eq!(proofs.proof(i), Some(proof));
+			assert_eq!(chunk, &chunks[i][..]);
+		}
+		assert_eq!(proofs.proof(chunks.len()), None);
+	}
+
 	#[test]
 	fn reconstruct_does_not_panic_on_low_validator_count() {
 		let reconstructed = reconstruct_v1(1, [].iter().cloned());
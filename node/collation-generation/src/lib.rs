@@ -442,6 +442,11 @@ fn erasure_root(
 	let available_data =
 		AvailableData { validation_data: persisted_validation, pov: Arc::new(pov) };
 
-	let chunks = polkadot_erasure_coding::obtain_chunks_v1(n_validators, &available_data)?;
-	Ok(polkadot_erasure_coding::branches(&chunks).root())
+	let chunks = polkadot_erasure_coding::obtain_chunks_v1_parallel(n_validators, &available_data)?;
+
+	let mut branches = polkadot_erasure_coding::BranchesBuilder::with_capacity(chunks.len());
+	for chunk in chunks {
+		branches.push(&chunk);
+	}
+	Ok(branches.build().root())
 }
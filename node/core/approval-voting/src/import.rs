@@ -46,7 +46,7 @@ use polkadot_node_subsystem_util::{
 };
 use polkadot_primitives::v2::{
 	BlockNumber, CandidateEvent, CandidateHash, CandidateReceipt, ConsensusLog, CoreIndex,
-	GroupIndex, Hash, Header, SessionIndex,
+	GroupIndex, Hash, Header, SessionIndex, ValidatorIndex,
 };
 use sc_keystore::LocalKeystore;
 use sp_consensus_slots::Slot;
@@ -75,6 +75,7 @@ struct ImportedBlockInfo {
 	relay_vrf_story: RelayVRFStory,
 	slot: Slot,
 	force_approve: Option<BlockNumber>,
+	disabled_validators: Vec<ValidatorIndex>,
 }
 
 struct ImportedBlockInfoEnv<'a> {
@@ -291,6 +292,36 @@ async fn imported_block_info<Context>(
 			},
 		});
 
+	// The disabled validators only affect how approval-distribution routes their messages, so
+	// the import doesn't fail for lack of them.
+	let disabled_validators = {
+		let (s_tx, s_rx) = oneshot::channel();
+
+		ctx.send_message(RuntimeApiMessage::Request(
+			block_header.parent_hash,
+			RuntimeApiRequest::DisabledValidators(s_tx),
+		))
+		.await;
+
+		match s_rx.await {
+			Ok(Ok(disabled_validators)) => disabled_validators,
+			// Nobody is disabled on runtimes which don't support disabling yet.
+			Ok(Err(RuntimeApiError::NotSupported { .. })) => Vec::new(),
+			Ok(Err(error)) => {
+				gum::debug!(
+					target: LOG_TARGET,
+					?error,
+					?block_hash,
+					"Failed to fetch disabled validators",
+				);
+
+				Vec::new()
+			},
+			Err(error) =>
+				return Err(ImportedBlockInfoError::FutureCancelled("DisabledValidators", error)),
+		}
+	};
+
 	Ok(ImportedBlockInfo {
 		included_candidates,
 		session_index,
@@ -299,6 +330,7 @@ async fn imported_block_info<Context>(
 		relay_vrf_story,
 		slot,
 		force_approve,
+		disabled_validators,
 	})
 }
 
@@ -460,6 +492,7 @@ pub(crate) async fn handle_new_head<Context, B: Backend>(
 			relay_vrf_story,
 			slot,
 			force_approve,
+			disabled_validators,
 		} = imported_block_info;
 
 		let session_info = state
@@ -583,6 +616,7 @@ pub(crate) async fn handle_new_head<Context, B: Backend>(
 			candidates: included_candidates.iter().map(|(hash, _, _, _)| *hash).collect(),
 			slot,
 			session: session_index,
+			disabled_validators,
 		});
 
 		imported_candidates.push(BlockImportedCandidates {
@@ -848,6 +882,17 @@ pub(crate) mod tests {
 					}));
 				}
 			);
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					h,
+					RuntimeApiRequest::DisabledValidators(c_tx),
+				)) => {
+					assert_eq!(h, header.parent_hash);
+					let _ = c_tx.send(Ok(Vec::new()));
+				}
+			);
 		});
 
 		futures::executor::block_on(futures::future::join(test_fut, aux_fut));
@@ -1155,6 +1200,17 @@ pub(crate) mod tests {
 					}));
 				}
 			);
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					h,
+					RuntimeApiRequest::DisabledValidators(c_tx),
+				)) => {
+					assert_eq!(h, header.parent_hash);
+					let _ = c_tx.send(Ok(Vec::new()));
+				}
+			);
 		});
 
 		futures::executor::block_on(futures::future::join(test_fut, aux_fut));
@@ -1373,6 +1429,17 @@ pub(crate) mod tests {
 				}
 			);
 
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					h,
+					RuntimeApiRequest::DisabledValidators(c_tx),
+				)) => {
+					assert_eq!(h, parent_hash);
+					let _ = c_tx.send(Ok(Vec::new()));
+				}
+			);
+
 			assert_matches!(
 				handle.recv().await,
 				AllMessages::ApprovalDistribution(ApprovalDistributionMessage::NewBlocks(
@@ -35,7 +35,7 @@ use polkadot_node_subsystem::{
 		ApprovalVotingMessage, AssignmentCheckError, AssignmentCheckResult,
 		AvailabilityRecoveryMessage, BlockDescription, CandidateValidationMessage, ChainApiMessage,
 		ChainSelectionMessage, DisputeCoordinatorMessage, HighestApprovedAncestorBlock,
-		RuntimeApiMessage, RuntimeApiRequest,
+		RecoveryPolicy, RuntimeApiMessage, RuntimeApiRequest,
 	},
 	overseer, FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError, SubsystemResult,
 	SubsystemSender,
@@ -1019,6 +1019,8 @@ fn distribution_messages_for_activation(
 			candidates: block_entry.candidates().iter().map(|(_, c_hash)| *c_hash).collect(),
 			slot: block_entry.slot(),
 			session: block_entry.session(),
+			// Not persisted, so their messages get routed like anybody else's after a restart.
+			disabled_validators: Vec::new(),
 		});
 
 		for (i, (_, candidate_hash)) in block_entry.candidates().iter().enumerate() {
@@ -2270,6 +2272,7 @@ async fn launch_approval<Context>(
 		candidate.clone(),
 		session_index,
 		Some(backing_group),
+		RecoveryPolicy::Default,
 		a_tx,
 	))
 	.await;
@@ -2286,7 +2289,7 @@ async fn launch_approval<Context>(
 	let background = async move {
 		// Force the move of the timer into the background task.
 		let _timer = timer;
-		let _span = jaeger::Span::from_encodable((block_hash, candidate_hash), "launch-approval")
+		let span = jaeger::Span::from_encodable((block_hash, candidate_hash), "launch-approval")
 			.with_relay_parent(block_hash)
 			.with_candidate(candidate_hash)
 			.with_stage(jaeger::Stage::ApprovalChecking);
@@ -2355,7 +2358,9 @@ async fn launch_approval<Context>(
 				validation_code,
 				candidate.clone(),
 				available_data.pov,
+				session_index,
 				APPROVAL_EXECUTION_TIMEOUT,
+				span.child("request-validation"),
 				val_tx,
 			))
 			.await;
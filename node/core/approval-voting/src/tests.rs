@@ -943,6 +943,20 @@ async fn import_block(
 				}));
 			}
 		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(
+					req_block_hash,
+					RuntimeApiRequest::DisabledValidators(d_tx),
+				)
+			) => {
+				let hash = &hashes[(number-1) as usize];
+				assert_eq!(req_block_hash, hash.0.clone());
+				d_tx.send(Ok(Vec::new())).unwrap();
+			}
+		);
 	}
 
 	if number == 0 {
@@ -2420,7 +2434,7 @@ pub async fn handle_double_assignment_import(
 				assert_eq!(candidate_index, c_index);
 			},
 			AllMessages::CandidateValidation(
-				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, tx),
+				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, _, timeout, _, tx),
 			) if timeout == APPROVAL_EXECUTION_TIMEOUT => {
 				tx.send(Ok(ValidationResult::Valid(Default::default(), Default::default())))
 					.unwrap();
@@ -2462,7 +2476,7 @@ async fn recover_available_data(virtual_overseer: &mut VirtualOverseer) {
 	assert_matches!(
 		virtual_overseer.recv().await,
 		AllMessages::AvailabilityRecovery(
-			AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, tx)
+			AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, _, tx)
 		) => {
 			tx.send(Ok(available_data)).unwrap();
 		},
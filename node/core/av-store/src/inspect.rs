@@ -0,0 +1,154 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Read-only access to the contents of the availability store, for offline tooling.
+
+use super::*;
+
+/// The state of a candidate in the availability store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CandidateState {
+	/// Not included in any block, first observed at the given time.
+	Unavailable {
+		/// The time since the Unix epoch.
+		observed_at: Duration,
+	},
+	/// Included in the given unfinalized blocks, first observed at the given time.
+	Unfinalized {
+		/// The time since the Unix epoch.
+		observed_at: Duration,
+		/// The blocks including the candidate, sorted ascending by number and hash.
+		blocks: Vec<(BlockNumber, Hash)>,
+	},
+	/// Included in a finalized block, finalized at the given time.
+	Finalized {
+		/// The time since the Unix epoch.
+		finalized_at: Duration,
+	},
+}
+
+impl From<State> for CandidateState {
+	fn from(state: State) -> Self {
+		match state {
+			State::Unavailable(at) => CandidateState::Unavailable { observed_at: at.into() },
+			State::Unfinalized(at, blocks) => CandidateState::Unfinalized {
+				observed_at: at.into(),
+				blocks: blocks.into_iter().map(|(number, hash)| (number.0, hash)).collect(),
+			},
+			State::Finalized(at) => CandidateState::Finalized { finalized_at: at.into() },
+		}
+	}
+}
+
+/// Everything the availability store knows about a candidate.
+#[derive(Debug, Clone)]
+pub struct CandidateInfo {
+	/// The hash of the candidate.
+	pub candidate_hash: CandidateHash,
+	/// The state of the candidate.
+	pub state: CandidateState,
+	/// Whether the full available data is stored.
+	pub data_available: bool,
+	/// Which chunks are stored, by validator index.
+	pub chunks_stored: BitVec<u8, BitOrderLsb0>,
+	/// When the candidate is due to be pruned, as time since the Unix epoch. `None` while it is
+	/// unfinalized, or if it is kept indefinitely.
+	pub prune_at: Option<Duration>,
+	/// Whether the candidate is of a para kept indefinitely once finalized.
+	pub kept: bool,
+}
+
+/// Load all the candidates in the store, ordered by candidate hash.
+pub fn candidates(db: &Arc<dyn Database>, config: &Config) -> Result<Vec<CandidateInfo>, Error> {
+	let prune_times = prune_times(db, config)?;
+
+	let mut candidates = Vec::new();
+	for r in db.iter_with_prefix(config.col_meta, META_PREFIX) {
+		let (k, v) = r?;
+		let candidate_hash = CandidateHash::decode(&mut &k[META_PREFIX.len()..])?;
+		let meta = CandidateMeta::decode(&mut &v[..])?;
+
+		candidates.push(candidate_info(db, config, &prune_times, candidate_hash, meta)?);
+	}
+
+	Ok(candidates)
+}
+
+/// Load a single candidate, if it is in the store.
+pub fn candidate(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	candidate_hash: CandidateHash,
+) -> Result<Option<CandidateInfo>, Error> {
+	match load_meta(db, config, &candidate_hash)? {
+		Some(meta) => {
+			let prune_times = prune_times(db, config)?;
+			candidate_info(db, config, &prune_times, candidate_hash, meta).map(Some)
+		},
+		None => Ok(None),
+	}
+}
+
+/// Load the full available data of a candidate, if it is stored.
+pub fn available_data(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	candidate_hash: CandidateHash,
+) -> Result<Option<AvailableData>, Error> {
+	load_available_data(db, config, &candidate_hash)
+}
+
+/// Load a chunk of a candidate along with its Merkle proof, if it is stored.
+pub fn chunk(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	candidate_hash: CandidateHash,
+	index: ValidatorIndex,
+) -> Result<Option<ErasureChunk>, Error> {
+	load_chunk(db, config, &candidate_hash, index)
+}
+
+fn candidate_info(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	prune_times: &HashMap<CandidateHash, Duration>,
+	candidate_hash: CandidateHash,
+	meta: CandidateMeta,
+) -> Result<CandidateInfo, Error> {
+	Ok(CandidateInfo {
+		candidate_hash,
+		state: meta.state.into(),
+		data_available: meta.data_available,
+		chunks_stored: meta.chunks_stored,
+		prune_at: prune_times.get(&candidate_hash).copied(),
+		kept: is_kept(db, config, &candidate_hash)?,
+	})
+}
+
+// There is no index of the pruning keys by candidate, so all of them are loaded.
+fn prune_times(
+	db: &Arc<dyn Database>,
+	config: &Config,
+) -> Result<HashMap<CandidateHash, Duration>, Error> {
+	let mut prune_times = HashMap::new();
+	for r in db.iter_with_prefix(config.col_meta, PRUNE_BY_TIME_PREFIX) {
+		let (k, _v) = r?;
+		let (prune_at, candidate_hash) = decode_pruning_key(&k[..])?;
+		prune_times.insert(candidate_hash, prune_at);
+	}
+
+	Ok(prune_times)
+}
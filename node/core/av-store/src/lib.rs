@@ -40,11 +40,14 @@ use polkadot_node_subsystem::{
 };
 use polkadot_node_subsystem_util as util;
 use polkadot_primitives::v2::{
-	BlockNumber, CandidateEvent, CandidateHash, CandidateReceipt, Hash, Header, ValidatorIndex,
+	BlockNumber, CandidateEvent, CandidateHash, CandidateReceipt, GroupIndex, Hash, Header,
+	Id as ParaId, SessionIndex, ValidatorIndex,
 };
 
+pub mod inspect;
 mod metrics;
 pub use self::metrics::*;
+mod scrub;
 
 #[cfg(test)]
 mod tests;
@@ -58,6 +61,8 @@ const CHUNK_PREFIX: &[u8; 5] = b"chunk";
 const META_PREFIX: &[u8; 4] = b"meta";
 const UNFINALIZED_PREFIX: &[u8; 11] = b"unfinalized";
 const PRUNE_BY_TIME_PREFIX: &[u8; 13] = b"prune_by_time";
+const KEEP_PREFIX: &[u8; 4] = b"keep";
+const SCRUB_PREFIX: &[u8; 5] = b"scrub";
 
 // We have some keys we want to map to empty values because existence of the key is enough. We use this because
 // rocksdb doesn't support empty values.
@@ -72,6 +77,12 @@ const KEEP_FINALIZED_FOR: Duration = Duration::from_secs(25 * 60 * 60);
 /// The pruning interval.
 const PRUNING_INTERVAL: Duration = Duration::from_secs(60 * 5);
 
+/// The scrubbing interval.
+const SCRUBBING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The number of candidates scrubbed at once.
+const SCRUBBING_BATCH_SIZE: usize = 128;
+
 /// Unix time wrapper with big-endian encoding.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
 struct BETimestamp(u64);
@@ -150,6 +161,17 @@ struct CandidateMeta {
 	chunks_stored: BitVec<u8, BitOrderLsb0>,
 }
 
+/// What is needed to check the stored data of a backed candidate and to fetch its chunks again.
+#[derive(Debug, Clone, Encode, Decode, PartialEq)]
+struct ScrubInfo {
+	erasure_root: Hash,
+	pov_hash: Hash,
+	persisted_validation_data_hash: Hash,
+	relay_parent: Hash,
+	session_index: SessionIndex,
+	group_index: GroupIndex,
+}
+
 fn query_inner<D: Decode>(
 	db: &Arc<dyn Database>,
 	column: u32,
@@ -250,6 +272,39 @@ fn delete_meta(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
 	tx.delete(config.col_meta, &key[..])
 }
 
+// Candidates of the paras in `PruningConfig::keep_finalized_paras` are marked for being kept once
+// finalized, as the para id is not part of the meta.
+fn is_kept(db: &Arc<dyn Database>, config: &Config, hash: &CandidateHash) -> Result<bool, Error> {
+	let key = (KEEP_PREFIX, hash).encode();
+	Ok(db.get(config.col_meta, &key)?.is_some())
+}
+
+fn write_keep_marker(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
+	let key = (KEEP_PREFIX, hash).encode();
+	tx.put(config.col_meta, &key, TOMBSTONE_VALUE);
+}
+
+fn delete_keep_marker(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
+	let key = (KEEP_PREFIX, hash).encode();
+	tx.delete(config.col_meta, &key[..])
+}
+
+fn write_scrub_info(
+	tx: &mut DBTransaction,
+	config: &Config,
+	hash: &CandidateHash,
+	info: &ScrubInfo,
+) {
+	let key = (SCRUB_PREFIX, hash).encode();
+
+	tx.put_vec(config.col_meta, &key, info.encode());
+}
+
+fn delete_scrub_info(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
+	let key = (SCRUB_PREFIX, hash).encode();
+	tx.delete(config.col_meta, &key[..])
+}
+
 fn delete_unfinalized_height(tx: &mut DBTransaction, config: &Config, block_number: BlockNumber) {
 	let prefix = (UNFINALIZED_PREFIX, BEBlockNumber(block_number)).encode();
 	tx.delete_prefix(config.col_meta, &prefix);
@@ -365,6 +420,11 @@ pub enum Error {
 
 	#[error("Custom databases are not supported")]
 	CustomDatabase,
+
+	#[error(
+		"Finalized data is kept for {keep_finalized_for:?}, less than the dispute window of {dispute_window:?}"
+	)]
+	RetentionShorterThanDisputeWindow { keep_finalized_for: Duration, dispute_window: Duration },
 }
 
 impl Error {
@@ -396,18 +456,22 @@ impl Error {
 }
 
 /// Struct holding pruning timing configuration.
-/// The only purpose of this structure is to use different timing
-/// configurations in production and in testing.
-#[derive(Clone)]
-struct PruningConfig {
+#[derive(Debug, Clone)]
+pub struct PruningConfig {
 	/// How long unavailable data should be kept.
-	keep_unavailable_for: Duration,
+	pub keep_unavailable_for: Duration,
 
 	/// How long finalized data should be kept.
-	keep_finalized_for: Duration,
+	///
+	/// Candidates can be disputed for `dispute_period` sessions, so this must not be any shorter.
+	pub keep_finalized_for: Duration,
 
 	/// How often to perform data pruning.
-	pruning_interval: Duration,
+	pub pruning_interval: Duration,
+
+	/// Paras whose finalized candidates are never pruned, along with their available data and
+	/// chunks as far as they are stored.
+	pub keep_finalized_paras: Vec<ParaId>,
 }
 
 impl Default for PruningConfig {
@@ -416,17 +480,53 @@ impl Default for PruningConfig {
 			keep_unavailable_for: KEEP_UNAVAILABLE_FOR,
 			keep_finalized_for: KEEP_FINALIZED_FOR,
 			pruning_interval: PRUNING_INTERVAL,
+			keep_finalized_paras: Vec::new(),
 		}
 	}
 }
 
+impl PruningConfig {
+	/// Check that finalized data is kept for at least the given dispute window, the time in which
+	/// finalized candidates can still be disputed.
+	pub fn check_dispute_window(&self, dispute_window: Duration) -> Result<(), Error> {
+		if self.keep_finalized_for < dispute_window {
+			return Err(Error::RetentionShorterThanDisputeWindow {
+				keep_finalized_for: self.keep_finalized_for,
+				dispute_window,
+			})
+		}
+
+		Ok(())
+	}
+}
+
+/// Struct holding the configuration of the scrubbing, the periodic check of the stored data.
+#[derive(Debug, Clone)]
+pub struct ScrubbingConfig {
+	/// How often to check a batch of candidates.
+	pub interval: Duration,
+
+	/// How many candidates to check at once.
+	pub batch_size: usize,
+}
+
+impl Default for ScrubbingConfig {
+	fn default() -> Self {
+		Self { interval: SCRUBBING_INTERVAL, batch_size: SCRUBBING_BATCH_SIZE }
+	}
+}
+
 /// Configuration for the availability store.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Config {
 	/// The column family for availability data and chunks.
 	pub col_data: u32,
 	/// The column family for availability store meta information.
 	pub col_meta: u32,
+	/// When to prune the stored data.
+	pub pruning: PruningConfig,
+	/// How to scrub the stored data.
+	pub scrubbing: ScrubbingConfig,
 }
 
 trait Clock: Send + Sync {
@@ -444,11 +544,12 @@ impl Clock for SystemClock {
 
 /// An implementation of the Availability Store subsystem.
 pub struct AvailabilityStoreSubsystem {
-	pruning_config: PruningConfig,
 	config: Config,
 	db: Arc<dyn Database>,
 	known_blocks: KnownUnfinalizedBlocks,
 	finalized_number: Option<BlockNumber>,
+	/// The last candidate scrubbed, the next batch starts after it.
+	scrub_cursor: Option<CandidateHash>,
 	metrics: Metrics,
 	clock: Box<dyn Clock>,
 }
@@ -456,31 +557,24 @@ pub struct AvailabilityStoreSubsystem {
 impl AvailabilityStoreSubsystem {
 	/// Create a new `AvailabilityStoreSubsystem` with a given config on disk.
 	pub fn new(db: Arc<dyn Database>, config: Config, metrics: Metrics) -> Self {
-		Self::with_pruning_config_and_clock(
-			db,
-			config,
-			PruningConfig::default(),
-			Box::new(SystemClock),
-			metrics,
-		)
+		Self::with_clock(db, config, Box::new(SystemClock), metrics)
 	}
 
 	/// Create a new `AvailabilityStoreSubsystem` with a given config on disk.
-	fn with_pruning_config_and_clock(
+	fn with_clock(
 		db: Arc<dyn Database>,
 		config: Config,
-		pruning_config: PruningConfig,
 		clock: Box<dyn Clock>,
 		metrics: Metrics,
 	) -> Self {
 		Self {
-			pruning_config,
 			config,
 			db,
 			metrics,
 			clock,
 			known_blocks: KnownUnfinalizedBlocks::default(),
 			finalized_number: None,
+			scrub_cursor: None,
 		}
 	}
 }
@@ -529,10 +623,19 @@ impl<Context> AvailabilityStoreSubsystem {
 
 #[overseer::contextbounds(AvailabilityStore, prefix = self::overseer)]
 async fn run<Context>(mut subsystem: AvailabilityStoreSubsystem, mut ctx: Context) {
-	let mut next_pruning = Delay::new(subsystem.pruning_config.pruning_interval).fuse();
+	let mut next_pruning = Delay::new(subsystem.config.pruning.pruning_interval).fuse();
+	let mut next_scrubbing = Delay::new(subsystem.config.scrubbing.interval).fuse();
+	let mut scrubbing = future::Fuse::terminated();
 
 	loop {
-		let res = run_iteration(&mut ctx, &mut subsystem, &mut next_pruning).await;
+		let res = run_iteration(
+			&mut ctx,
+			&mut subsystem,
+			&mut next_pruning,
+			&mut next_scrubbing,
+			&mut scrubbing,
+		)
+		.await;
 		match res {
 			Err(e) => {
 				e.trace();
@@ -554,6 +657,8 @@ async fn run_iteration<Context>(
 	ctx: &mut Context,
 	subsystem: &mut AvailabilityStoreSubsystem,
 	mut next_pruning: &mut future::Fuse<Delay>,
+	mut next_scrubbing: &mut future::Fuse<Delay>,
+	mut scrubbing: &mut future::Fuse<oneshot::Receiver<Result<scrub::ScrubbedBatch, Error>>>,
 ) -> Result<bool, Error> {
 	select! {
 		incoming = ctx.recv().fuse() => {
@@ -588,11 +693,20 @@ async fn run_iteration<Context>(
 		_ = next_pruning => {
 			// It's important to set the delay before calling `prune_all` because an error in `prune_all`
 			// could lead to the delay not being set again. Then we would never prune anything anymore.
-			*next_pruning = Delay::new(subsystem.pruning_config.pruning_interval).fuse();
+			*next_pruning = Delay::new(subsystem.config.pruning.pruning_interval).fuse();
 
 			let _timer = subsystem.metrics.time_pruning();
 			prune_all(&subsystem.db, &subsystem.config, &*subsystem.clock)?;
 		}
+		_ = next_scrubbing => {
+			// Same as for pruning, the delay must be set before scrubbing.
+			*next_scrubbing = Delay::new(subsystem.config.scrubbing.interval).fuse();
+
+			scrub::start_next_batch(ctx, subsystem, scrubbing)?;
+		}
+		batch = scrubbing => {
+			scrub::finish_batch(ctx, subsystem, batch??).await?;
+		}
 	}
 
 	Ok(false)
@@ -632,17 +746,7 @@ async fn process_block_activated<Context>(
 		// it's important to commit the db transactions for a head before the next one is processed
 		// alternatively, we could utilize the OverlayBackend from approval-voting
 		let mut tx = DBTransaction::new();
-		process_new_head(
-			ctx,
-			&subsystem.db,
-			&mut tx,
-			&subsystem.config,
-			&subsystem.pruning_config,
-			now,
-			hash,
-			header,
-		)
-		.await?;
+		process_new_head(ctx, &subsystem.db, &mut tx, &subsystem.config, now, hash, header).await?;
 		subsystem.known_blocks.insert(hash, block_number);
 		subsystem.db.write(tx)?;
 	}
@@ -656,7 +760,6 @@ async fn process_new_head<Context>(
 	db: &Arc<dyn Database>,
 	db_transaction: &mut DBTransaction,
 	config: &Config,
-	pruning_config: &PruningConfig,
 	now: Duration,
 	hash: Hash,
 	header: Header,
@@ -668,28 +771,27 @@ async fn process_new_head<Context>(
 	let n_validators =
 		util::request_validators(header.parent_hash, ctx.sender()).await.await??.len();
 
+	// Candidates backed or included in this block were backed in the session of its parent state,
+	// as cores are cleared at session boundaries.
+	let backed_or_included = candidate_events.iter().any(|event| {
+		matches!(event, CandidateEvent::CandidateBacked(..) | CandidateEvent::CandidateIncluded(..))
+	});
+	if !backed_or_included {
+		return Ok(())
+	}
+	let session_index = util::request_session_index_for_child(header.parent_hash, ctx.sender())
+		.await
+		.await??;
+
 	for event in candidate_events {
 		match event {
-			CandidateEvent::CandidateBacked(receipt, _head, _core_index, _group_index) => {
-				note_block_backed(
-					db,
-					db_transaction,
-					config,
-					pruning_config,
-					now,
-					n_validators,
-					receipt,
-				)?;
+			CandidateEvent::CandidateBacked(receipt, _head, _core_index, group_index) => {
+				note_scrub_info(db_transaction, config, session_index, group_index, &receipt);
+				note_block_backed(db, db_transaction, config, now, n_validators, receipt)?;
 			},
-			CandidateEvent::CandidateIncluded(receipt, _head, _core_index, _group_index) => {
-				note_block_included(
-					db,
-					db_transaction,
-					config,
-					pruning_config,
-					(header.number, hash),
-					receipt,
-				)?;
+			CandidateEvent::CandidateIncluded(receipt, _head, _core_index, group_index) => {
+				note_scrub_info(db_transaction, config, session_index, group_index, &receipt);
+				note_block_included(db, db_transaction, config, (header.number, hash), receipt)?;
 			},
 			_ => {},
 		}
@@ -698,11 +800,29 @@ async fn process_new_head<Context>(
 	Ok(())
 }
 
+fn note_scrub_info(
+	db_transaction: &mut DBTransaction,
+	config: &Config,
+	session_index: SessionIndex,
+	group_index: GroupIndex,
+	candidate: &CandidateReceipt,
+) {
+	let info = ScrubInfo {
+		erasure_root: candidate.descriptor.erasure_root,
+		pov_hash: candidate.descriptor.pov_hash,
+		persisted_validation_data_hash: candidate.descriptor.persisted_validation_data_hash,
+		relay_parent: candidate.descriptor.relay_parent,
+		session_index,
+		group_index,
+	};
+
+	write_scrub_info(db_transaction, config, &candidate.hash(), &info);
+}
+
 fn note_block_backed(
 	db: &Arc<dyn Database>,
 	db_transaction: &mut DBTransaction,
 	config: &Config,
-	pruning_config: &PruningConfig,
 	now: Duration,
 	n_validators: usize,
 	candidate: CandidateReceipt,
@@ -718,7 +838,7 @@ fn note_block_backed(
 			chunks_stored: bitvec::bitvec![u8, BitOrderLsb0; 0; n_validators],
 		};
 
-		let prune_at = now + pruning_config.keep_unavailable_for;
+		let prune_at = now + config.pruning.keep_unavailable_for;
 
 		write_pruning_key(db_transaction, config, prune_at, &candidate_hash);
 		write_meta(db_transaction, config, &candidate_hash, &meta);
@@ -731,7 +851,6 @@ fn note_block_included(
 	db: &Arc<dyn Database>,
 	db_transaction: &mut DBTransaction,
 	config: &Config,
-	pruning_config: &PruningConfig,
 	block: (BlockNumber, Hash),
 	candidate: CandidateReceipt,
 ) -> Result<(), Error> {
@@ -755,7 +874,7 @@ fn note_block_included(
 			meta.state = match meta.state {
 				State::Unavailable(at) => {
 					let at_d: Duration = at.into();
-					let prune_at = at_d + pruning_config.keep_unavailable_for;
+					let prune_at = at_d + config.pruning.keep_unavailable_for;
 					delete_pruning_key(db_transaction, config, prune_at, &candidate_hash);
 
 					State::Unfinalized(at, vec![be_block])
@@ -782,6 +901,9 @@ fn note_block_included(
 				&block.1,
 				&candidate_hash,
 			);
+			if config.pruning.keep_finalized_paras.contains(&candidate.descriptor.para_id) {
+				write_keep_marker(db_transaction, config, &candidate_hash);
+			}
 			write_meta(db_transaction, config, &candidate_hash, &meta);
 		},
 	}
@@ -969,14 +1091,16 @@ fn update_blocks_at_finalized_height(
 
 			meta.state = State::Finalized(now.into());
 
-			// Write the meta and a pruning record.
+			// Write the meta and a pruning record, unless the candidate is to be kept.
 			write_meta(db_transaction, &subsystem.config, &candidate_hash, &meta);
-			write_pruning_key(
-				db_transaction,
-				&subsystem.config,
-				now + subsystem.pruning_config.keep_finalized_for,
-				&candidate_hash,
-			);
+			if !is_kept(&subsystem.db, &subsystem.config, &candidate_hash)? {
+				write_pruning_key(
+					db_transaction,
+					&subsystem.config,
+					now + subsystem.config.pruning.keep_finalized_for,
+					&candidate_hash,
+				);
+			}
 		} else {
 			meta.state = match meta.state {
 				State::Finalized(_) => continue,   // sanity.
@@ -989,7 +1113,7 @@ fn update_blocks_at_finalized_height(
 					// aware of any blocks this is included in.
 					if blocks.is_empty() {
 						let at_d: Duration = at.into();
-						let prune_at = at_d + subsystem.pruning_config.keep_unavailable_for;
+						let prune_at = at_d + subsystem.config.pruning.keep_unavailable_for;
 						write_pruning_key(
 							db_transaction,
 							&subsystem.config,
@@ -1169,7 +1293,7 @@ fn store_available_data(
 			let now = subsystem.clock.now()?;
 
 			// Write a pruning record.
-			let prune_at = now + subsystem.pruning_config.keep_unavailable_for;
+			let prune_at = now + subsystem.config.pruning.keep_unavailable_for;
 			write_pruning_key(&mut tx, &subsystem.config, prune_at, &candidate_hash);
 
 			CandidateMeta {
@@ -1180,7 +1304,7 @@ fn store_available_data(
 		},
 	};
 
-	let chunks = erasure::obtain_chunks_v1(n_validators, &available_data)?;
+	let chunks = erasure::obtain_chunks_v1_parallel(n_validators, &available_data)?;
 	let branches = erasure::branches(chunks.as_ref());
 
 	let erasure_chunks = chunks.iter().zip(branches.map(|(proof, _)| proof)).enumerate().map(
@@ -1227,6 +1351,8 @@ fn prune_all(db: &Arc<dyn Database>, config: &Config, clock: &dyn Clock) -> Resu
 		};
 
 		delete_meta(&mut tx, config, &candidate_hash);
+		delete_keep_marker(&mut tx, config, &candidate_hash);
+		delete_scrub_info(&mut tx, config, &candidate_hash);
 
 		// Clean up all attached data of the candidate.
 		if let Some(meta) = load_meta(db, config, &candidate_hash)? {
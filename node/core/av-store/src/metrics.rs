@@ -26,6 +26,10 @@ pub(crate) struct MetricsInner {
 	store_available_data: prometheus::Histogram,
 	store_chunk: prometheus::Histogram,
 	get_chunk: prometheus::Histogram,
+	scrubbing: prometheus::Histogram,
+	scrubbed_chunks_total: prometheus::Counter<prometheus::U64>,
+	corrupt_entries_total: prometheus::CounterVec<prometheus::U64>,
+	refetched_chunks_total: prometheus::Counter<prometheus::U64>,
 }
 
 /// Availability metrics.
@@ -85,6 +89,35 @@ impl Metrics {
 	pub(crate) fn time_get_chunk(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.get_chunk.start_timer())
 	}
+
+	/// Provide a timer for `scrub_batch` which observes on drop.
+	pub(crate) fn time_scrubbing(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
+		self.0.as_ref().map(|metrics| metrics.scrubbing.start_timer())
+	}
+
+	pub(crate) fn on_chunks_scrubbed(&self, count: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.scrubbed_chunks_total.inc_by(count as u64);
+		}
+	}
+
+	pub(crate) fn on_corrupt_chunks(&self, count: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.corrupt_entries_total.with_label_values(&["chunk"]).inc_by(count as u64);
+		}
+	}
+
+	pub(crate) fn on_corrupt_available_data(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.corrupt_entries_total.with_label_values(&["available_data"]).inc();
+		}
+	}
+
+	pub(crate) fn on_chunks_refetched(&self, count: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.refetched_chunks_total.inc_by(count as u64);
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -146,6 +179,37 @@ impl metrics::Metrics for Metrics {
 				))?,
 				registry,
 			)?,
+			scrubbing: prometheus::register(
+				prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
+					"polkadot_parachain_av_store_scrubbing",
+					"Time spent within `av_store::scrub_batch`",
+				))?,
+				registry,
+			)?,
+			scrubbed_chunks_total: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_av_store_scrubbed_chunks_total",
+					"Number of stored chunks checked against the erasure root.",
+				)?,
+				registry,
+			)?,
+			corrupt_entries_total: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_av_store_corrupt_entries_total",
+						"Number of corrupt chunks and available data found by scrubbing.",
+					),
+					&["kind"],
+				)?,
+				registry,
+			)?,
+			refetched_chunks_total: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_av_store_refetched_chunks_total",
+					"Number of corrupt chunks requested from the backing group once more.",
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
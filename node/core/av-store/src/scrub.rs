@@ -0,0 +1,420 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Scrubbing: the periodic check of the stored chunks and available data against the erasure
+//! root of their candidate, so corruption on disk is found before peers reject what we serve.
+//!
+//! The chunks are checked against their proofs and the available data against the hashes in the
+//! candidate descriptor. Corrupt chunks are restored from the available data if it is intact,
+//! otherwise they are deleted and fetched from the backing group once more.
+//!
+//! The checks run on a blocking task, the repairs they call for are applied by the subsystem.
+//! Candidates stored before scrubbing was introduced have no scrub record, so they are never
+//! scrubbed and just get pruned as usual. Scrub records which fail to decode are removed, leaving
+//! their candidates in the same position.
+
+use super::*;
+
+use futures::future::FusedFuture;
+use polkadot_node_subsystem::messages::AvailabilityDistributionMessage;
+use polkadot_primitives::v2::{BlakeTwo256, HashT};
+
+/// The number of leading bytes of the candidate hash a batch seeks to, see [`next_batch`].
+const CURSOR_SEEK_DEPTH: usize = 2;
+
+/// The outcome of scrubbing a single candidate.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Scrubbed {
+	/// The number of chunks checked.
+	pub chunks: usize,
+	/// Whether the available data was corrupt, and is to be deleted.
+	pub corrupt_available_data: bool,
+	/// The corrupt chunks.
+	pub corrupt_chunks: Vec<ValidatorIndex>,
+	/// The corrupt chunks restored from the available data, to be written.
+	pub restored_chunks: Vec<ErasureChunk>,
+	/// The corrupt chunks which could not be restored from the available data, to be deleted.
+	pub deleted_chunks: Vec<ValidatorIndex>,
+}
+
+impl Scrubbed {
+	fn is_corrupt(&self) -> bool {
+		self.corrupt_available_data || !self.corrupt_chunks.is_empty()
+	}
+}
+
+/// The outcome of scrubbing a batch of candidates.
+pub(crate) struct ScrubbedBatch {
+	/// The candidate the next batch starts after, `None` to start over.
+	cursor: Option<CandidateHash>,
+	/// The candidates found in the store.
+	candidates: Vec<(CandidateHash, ScrubInfo, Scrubbed)>,
+	/// The candidates with a scrub record, but not in the store.
+	missing: Vec<CandidateHash>,
+	/// The candidates with a scrub record which fails to decode, e.g. one of an earlier version.
+	undecodable: Vec<CandidateHash>,
+}
+
+/// Scrub the next batch of candidates on a blocking task, unless the previous batch is still
+/// being scrubbed.
+#[overseer::contextbounds(AvailabilityStore, prefix = self::overseer)]
+pub(crate) fn start_next_batch<Context>(
+	ctx: &mut Context,
+	subsystem: &AvailabilityStoreSubsystem,
+	scrubbing: &mut future::Fuse<oneshot::Receiver<Result<ScrubbedBatch, Error>>>,
+) -> Result<(), Error> {
+	if !scrubbing.is_terminated() {
+		gum::debug!(target: LOG_TARGET, "Previous batch still being scrubbed");
+		return Ok(())
+	}
+
+	let (tx, rx) = oneshot::channel();
+	let db = subsystem.db.clone();
+	let config = subsystem.config.clone();
+	let metrics = subsystem.metrics.clone();
+	let cursor = subsystem.scrub_cursor;
+	let task = async move {
+		let _timer = metrics.time_scrubbing();
+		let _ = tx.send(scrub_batch(&db, &config, cursor));
+	};
+
+	ctx.spawn_blocking("av-store-scrubbing", task.boxed())?;
+	*scrubbing = rx.fuse();
+	Ok(())
+}
+
+/// Apply the repairs found necessary by scrubbing a batch, and fetch the chunks deleted in the
+/// process once more.
+#[overseer::contextbounds(AvailabilityStore, prefix = self::overseer)]
+pub(crate) async fn finish_batch<Context>(
+	ctx: &mut Context,
+	subsystem: &mut AvailabilityStoreSubsystem,
+	batch: ScrubbedBatch,
+) -> Result<(), Error> {
+	let now = subsystem.clock.now()?;
+	subsystem.scrub_cursor = batch.cursor;
+
+	if !batch.undecodable.is_empty() {
+		gum::debug!(
+			target: LOG_TARGET,
+			candidates = ?batch.undecodable,
+			"Removing undecodable scrub records, their candidates won't be scrubbed",
+		);
+		let mut tx = DBTransaction::new();
+		for candidate_hash in batch.undecodable {
+			delete_scrub_info(&mut tx, &subsystem.config, &candidate_hash);
+		}
+		subsystem.db.write(tx)?;
+	}
+
+	for candidate_hash in batch.missing {
+		// The candidate was included without being backed, so no meta was ever written.
+		if load_meta(&subsystem.db, &subsystem.config, &candidate_hash)?.is_none() {
+			let mut tx = DBTransaction::new();
+			delete_scrub_info(&mut tx, &subsystem.config, &candidate_hash);
+			subsystem.db.write(tx)?;
+		}
+	}
+
+	for (candidate_hash, info, scrubbed) in batch.candidates {
+		subsystem.metrics.on_chunks_scrubbed(scrubbed.chunks);
+		if !scrubbed.is_corrupt() {
+			continue
+		}
+
+		let state = match repair(&subsystem.db, &subsystem.config, candidate_hash, &scrubbed)? {
+			Some(state) => state,
+			// Pruned in the meantime.
+			None => continue,
+		};
+
+		if scrubbed.corrupt_available_data {
+			subsystem.metrics.on_corrupt_available_data();
+		}
+		subsystem.metrics.on_corrupt_chunks(scrubbed.corrupt_chunks.len());
+		gum::warn!(
+			target: LOG_TARGET,
+			?candidate_hash,
+			corrupt_available_data = scrubbed.corrupt_available_data,
+			corrupt_chunks = ?scrubbed.corrupt_chunks,
+			deleted_chunks = ?scrubbed.deleted_chunks,
+			"Found corrupt data in the availability store",
+		);
+
+		// Once the candidate is out of the availability window, the backers may not have the
+		// chunks anymore either.
+		if !in_availability_window(&state, now, &subsystem.config.pruning) {
+			continue
+		}
+
+		subsystem.metrics.on_chunks_refetched(scrubbed.deleted_chunks.len());
+		for index in scrubbed.deleted_chunks {
+			ctx.send_message(AvailabilityDistributionMessage::FetchChunk {
+				relay_parent: info.relay_parent,
+				session_index: info.session_index,
+				group_index: info.group_index,
+				candidate_hash,
+				erasure_root: info.erasure_root,
+				index,
+			})
+			.await;
+		}
+	}
+
+	Ok(())
+}
+
+/// Whether the data of a candidate in the given state is still kept, by us and by the other
+/// validators.
+fn in_availability_window(state: &State, now: Duration, pruning: &PruningConfig) -> bool {
+	match *state {
+		State::Unavailable(at) => {
+			let observed_at: Duration = at.into();
+			now < observed_at + pruning.keep_unavailable_for
+		},
+		// Kept until the block including it is either finalized or abandoned.
+		State::Unfinalized(..) => true,
+		State::Finalized(at) => {
+			let finalized_at: Duration = at.into();
+			now < finalized_at + pruning.keep_finalized_for
+		},
+	}
+}
+
+/// Scrub the candidates after `cursor` in the order of their hashes.
+fn scrub_batch(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	cursor: Option<CandidateHash>,
+) -> Result<ScrubbedBatch, Error> {
+	let batch_size = config.scrubbing.batch_size;
+	let batch = next_batch(db, config, cursor, batch_size)?;
+
+	let mut scrubbed_batch = ScrubbedBatch {
+		// Start over once all candidates have been scrubbed.
+		cursor: if batch.len() < batch_size { None } else { batch.last().map(|(hash, _)| *hash) },
+		candidates: Vec::with_capacity(batch.len()),
+		missing: Vec::new(),
+		undecodable: Vec::new(),
+	};
+
+	for (candidate_hash, info) in batch {
+		let info = match info {
+			Some(info) => info,
+			None => {
+				scrubbed_batch.undecodable.push(candidate_hash);
+				continue
+			},
+		};
+
+		match scrub_candidate(db, config, candidate_hash, &info)? {
+			Some(scrubbed) => scrubbed_batch.candidates.push((candidate_hash, info, scrubbed)),
+			None => scrubbed_batch.missing.push(candidate_hash),
+		}
+	}
+
+	Ok(scrubbed_batch)
+}
+
+/// Load the candidates to scrub next, the ones after `cursor` in the order of their hashes, along
+/// with their scrub record if it decodes.
+pub(crate) fn next_batch(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	cursor: Option<CandidateHash>,
+	batch_size: usize,
+) -> Result<Vec<(CandidateHash, Option<ScrubInfo>)>, Error> {
+	let mut batch = Vec::with_capacity(batch_size);
+	let mut load = |prefix: &[u8], after: Option<&[u8]>| -> Result<bool, Error> {
+		for r in db.iter_with_prefix(config.col_meta, prefix) {
+			let (k, v) = r?;
+			if after.map_or(false, |after| &k[..] <= after) {
+				continue
+			}
+
+			let candidate_hash = CandidateHash::decode(&mut &k[SCRUB_PREFIX.len()..])?;
+			batch.push((candidate_hash, ScrubInfo::decode(&mut &v[..]).ok()));
+			if batch.len() == batch_size {
+				return Ok(true)
+			}
+		}
+		Ok(false)
+	};
+
+	let cursor_key = match cursor {
+		Some(cursor) => (SCRUB_PREFIX, cursor).encode(),
+		None => {
+			load(SCRUB_PREFIX, None)?;
+			return Ok(batch)
+		},
+	};
+
+	// The database can only iterate by prefix, so the keys after the cursor are loaded by the
+	// prefixes following the one of the cursor, rather than skipping all the keys before it. Only
+	// the few keys sharing the first `CURSOR_SEEK_DEPTH` bytes of the hash with the cursor are
+	// skipped.
+	let seek_prefix_len = SCRUB_PREFIX.len() + CURSOR_SEEK_DEPTH;
+	if load(&cursor_key[..seek_prefix_len], Some(&cursor_key))? {
+		return Ok(batch)
+	}
+	for prefix_len in (SCRUB_PREFIX.len()..seek_prefix_len).rev() {
+		for byte in (cursor_key[prefix_len]..=u8::MAX).skip(1) {
+			let mut prefix = cursor_key[..prefix_len].to_vec();
+			prefix.push(byte);
+			if load(&prefix, None)? {
+				return Ok(batch)
+			}
+		}
+	}
+
+	Ok(batch)
+}
+
+/// Check the stored available data and chunks of a candidate and work out how to restore or
+/// delete whatever is corrupt, without changing the store.
+///
+/// Returns `None` if the candidate is not in the store.
+pub(crate) fn scrub_candidate(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	candidate_hash: CandidateHash,
+	info: &ScrubInfo,
+) -> Result<Option<Scrubbed>, Error> {
+	let meta = match load_meta(db, config, &candidate_hash)? {
+		Some(meta) => meta,
+		None => return Ok(None),
+	};
+
+	let mut scrubbed = Scrubbed::default();
+	let n_validators = meta.chunks_stored.len();
+
+	let available_data = if meta.data_available {
+		let available_data = load_or_corrupt(load_available_data(db, config, &candidate_hash))?
+			.filter(|data| {
+				data.pov.hash() == info.pov_hash &&
+					data.validation_data.hash() == info.persisted_validation_data_hash
+			});
+		scrubbed.corrupt_available_data = available_data.is_none();
+		available_data
+	} else {
+		None
+	};
+
+	for index in meta.chunks_stored.iter_ones() {
+		let validator_index = ValidatorIndex(index as _);
+		scrubbed.chunks += 1;
+
+		let chunk = load_or_corrupt(load_chunk(db, config, &candidate_hash, validator_index))?;
+		let intact = chunk.map_or(false, |chunk| {
+			chunk.index == validator_index && is_valid_chunk(&info.erasure_root, &chunk)
+		});
+		if !intact {
+			scrubbed.corrupt_chunks.push(validator_index);
+		}
+	}
+
+	if scrubbed.corrupt_chunks.is_empty() {
+		return Ok(Some(scrubbed))
+	}
+
+	// Only re-encode the available data if there are chunks to restore from it.
+	let reencoded = available_data.and_then(|data| {
+		let chunks = erasure::obtain_chunks_v1(n_validators, &data).ok()?;
+		let branches = erasure::branches(&chunks);
+		if branches.root() == info.erasure_root {
+			Some(branches.map(|(proof, chunk)| (proof, chunk.to_vec())).collect::<Vec<_>>())
+		} else {
+			None
+		}
+	});
+	if reencoded.is_none() && meta.data_available && !scrubbed.corrupt_available_data {
+		// Matches the hashes in the descriptor but not the erasure root, so the candidate itself
+		// is bad. The data can't be served either way.
+		scrubbed.corrupt_available_data = true;
+	}
+
+	for &validator_index in &scrubbed.corrupt_chunks {
+		match reencoded {
+			Some(ref reencoded) => {
+				let (proof, chunk) = reencoded[validator_index.0 as usize].clone();
+				scrubbed.restored_chunks.push(ErasureChunk {
+					chunk,
+					proof,
+					index: validator_index,
+				});
+			},
+			None => scrubbed.deleted_chunks.push(validator_index),
+		}
+	}
+
+	Ok(Some(scrubbed))
+}
+
+/// Apply the repairs found necessary by scrubbing a candidate, and return the state of the
+/// candidate.
+///
+/// Returns `None` if the candidate is not in the store anymore.
+pub(crate) fn repair(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	candidate_hash: CandidateHash,
+	scrubbed: &Scrubbed,
+) -> Result<Option<State>, Error> {
+	let mut meta = match load_meta(db, config, &candidate_hash)? {
+		Some(meta) => meta,
+		None => return Ok(None),
+	};
+
+	let mut tx = DBTransaction::new();
+	if scrubbed.corrupt_available_data && meta.data_available {
+		delete_available_data(&mut tx, config, &candidate_hash);
+		meta.data_available = false;
+	}
+
+	for chunk in &scrubbed.restored_chunks {
+		if meta.chunks_stored.get(chunk.index.0 as usize).map_or(false, |stored| *stored) {
+			write_chunk(&mut tx, config, &candidate_hash, chunk.index, chunk);
+		}
+	}
+
+	for &validator_index in &scrubbed.deleted_chunks {
+		let index = validator_index.0 as usize;
+		if meta.chunks_stored.get(index).map_or(false, |stored| *stored) {
+			delete_chunk(&mut tx, config, &candidate_hash, validator_index);
+			meta.chunks_stored.set(index, false);
+		}
+	}
+
+	write_meta(&mut tx, config, &candidate_hash, &meta);
+	db.write(tx)?;
+
+	Ok(Some(meta.state))
+}
+
+/// Treat entries which fail to decode the same as missing ones.
+fn load_or_corrupt<T>(loaded: Result<Option<T>, Error>) -> Result<Option<T>, Error> {
+	match loaded {
+		Err(Error::Codec(_)) => Ok(None),
+		loaded => loaded,
+	}
+}
+
+fn is_valid_chunk(erasure_root: &Hash, chunk: &ErasureChunk) -> bool {
+	match erasure::branch_hash(erasure_root, &chunk.proof, chunk.index.0 as usize) {
+		Ok(hash) => hash == BlakeTwo256::hash(&chunk.chunk),
+		Err(_) => false,
+	}
+}
@@ -25,13 +25,15 @@ use polkadot_node_primitives::{AvailableData, BlockData, PoV, Proof};
 use polkadot_node_subsystem::{
 	errors::RuntimeApiError,
 	jaeger,
-	messages::{AllMessages, RuntimeApiMessage, RuntimeApiRequest},
+	messages::{
+		AllMessages, AvailabilityDistributionMessage, RuntimeApiMessage, RuntimeApiRequest,
+	},
 	ActivatedLeaf, ActiveLeavesUpdate, LeafStatus,
 };
 use polkadot_node_subsystem_test_helpers as test_helpers;
 use polkadot_node_subsystem_util::{database::Database, TimeoutExt};
 use polkadot_primitives::v2::{
-	CandidateHash, CandidateReceipt, CoreIndex, GroupIndex, HeadData, Header,
+	CandidateHash, CandidateReceipt, CoreIndex, GroupIndex, HeadData, Header, Id as ParaId,
 	PersistedValidationData, ValidatorId,
 };
 use sp_keyring::Sr25519Keyring;
@@ -42,7 +44,17 @@ mod columns {
 	pub const NUM_COLUMNS: u32 = 2;
 }
 
-const TEST_CONFIG: Config = Config { col_data: columns::DATA, col_meta: columns::META };
+const TEST_CONFIG: Config = Config {
+	col_data: columns::DATA,
+	col_meta: columns::META,
+	pruning: PruningConfig {
+		keep_unavailable_for: Duration::from_secs(1),
+		keep_finalized_for: Duration::from_secs(2),
+		pruning_interval: Duration::from_millis(250),
+		keep_finalized_paras: Vec::new(),
+	},
+	scrubbing: ScrubbingConfig { interval: Duration::from_secs(60 * 60), batch_size: 128 },
+};
 
 type VirtualOverseer = test_helpers::TestSubsystemContextHandle<AvailabilityStoreMessage>;
 
@@ -71,6 +83,7 @@ impl Clock for TestClock {
 struct TestState {
 	persisted_validation_data: PersistedValidationData,
 	pruning_config: PruningConfig,
+	scrubbing_config: ScrubbingConfig,
 	clock: TestClock,
 }
 
@@ -91,15 +104,12 @@ impl Default for TestState {
 			relay_parent_storage_root: Default::default(),
 		};
 
-		let pruning_config = PruningConfig {
-			keep_unavailable_for: Duration::from_secs(1),
-			keep_finalized_for: Duration::from_secs(2),
-			pruning_interval: Duration::from_millis(250),
-		};
+		let pruning_config = TEST_CONFIG.pruning;
+		let scrubbing_config = TEST_CONFIG.scrubbing;
 
 		let clock = TestClock { inner: Arc::new(Mutex::new(Duration::from_secs(0))) };
 
-		Self { persisted_validation_data, pruning_config, clock }
+		Self { persisted_validation_data, pruning_config, scrubbing_config, clock }
 	}
 }
 
@@ -117,10 +127,13 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 	let pool = sp_core::testing::TaskExecutor::new();
 	let (context, virtual_overseer) = test_helpers::make_subsystem_context(pool.clone());
 
-	let subsystem = AvailabilityStoreSubsystem::with_pruning_config_and_clock(
+	let subsystem = AvailabilityStoreSubsystem::with_clock(
 		store,
-		TEST_CONFIG,
-		state.pruning_config.clone(),
+		Config {
+			pruning: state.pruning_config.clone(),
+			scrubbing: state.scrubbing_config.clone(),
+			..TEST_CONFIG
+		},
 		Box::new(state.clock),
 		Metrics::default(),
 	);
@@ -452,6 +465,66 @@ fn store_block_works() {
 	});
 }
 
+#[test]
+fn inspect_lists_stored_candidates() {
+	let store = test_store();
+	let test_state = TestState::default();
+
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		let candidate_hash = CandidateHash(Hash::repeat_byte(1));
+		let n_validators = 10;
+
+		let available_data = AvailableData {
+			pov: Arc::new(PoV { block_data: BlockData(vec![4, 5, 6]) }),
+			validation_data: test_state.persisted_validation_data.clone(),
+		};
+
+		let (tx, rx) = oneshot::channel();
+		let block_msg = AvailabilityStoreMessage::StoreAvailableData {
+			candidate_hash,
+			n_validators,
+			available_data: available_data.clone(),
+			tx,
+		};
+
+		virtual_overseer.send(FromOrchestra::Communication { msg: block_msg }).await;
+		rx.await.unwrap().unwrap();
+
+		let candidates = inspect::candidates(&store, &TEST_CONFIG).unwrap();
+		assert_eq!(candidates.len(), 1);
+
+		let info = &candidates[0];
+		assert_eq!(info.candidate_hash, candidate_hash);
+		assert_eq!(
+			info.state,
+			inspect::CandidateState::Unavailable { observed_at: test_state.clock.now() }
+		);
+		assert!(info.data_available);
+		assert_eq!(info.chunks_stored.count_ones(), n_validators);
+		assert_eq!(
+			info.prune_at,
+			Some(test_state.clock.now() + test_state.pruning_config.keep_unavailable_for)
+		);
+		assert!(!info.kept);
+
+		assert_eq!(
+			inspect::available_data(&store, &TEST_CONFIG, candidate_hash).unwrap(),
+			Some(available_data),
+		);
+		assert_eq!(
+			inspect::chunk(&store, &TEST_CONFIG, candidate_hash, ValidatorIndex(5))
+				.unwrap()
+				.map(|chunk| chunk.index),
+			Some(ValidatorIndex(5)),
+		);
+		assert!(inspect::candidate(&store, &TEST_CONFIG, CandidateHash(Hash::repeat_byte(2)))
+			.unwrap()
+			.is_none());
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn store_pov_and_query_chunk_works() {
 	let store = test_store();
@@ -726,6 +799,87 @@ fn stored_data_kept_until_finalized() {
 	});
 }
 
+#[test]
+fn finalized_data_of_kept_paras_is_never_pruned() {
+	let store = test_store();
+	let mut test_state = TestState::default();
+	let kept_para = ParaId::from(5);
+	test_state.pruning_config.keep_finalized_paras = vec![kept_para];
+
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		let n_validators = 10;
+
+		let pov = PoV { block_data: BlockData(vec![4, 5, 6]) };
+		let pov_hash = pov.hash();
+
+		let available_data = AvailableData {
+			pov: Arc::new(pov),
+			validation_data: test_state.persisted_validation_data.clone(),
+		};
+
+		let kept =
+			TestCandidateBuilder { para_id: kept_para, pov_hash, ..Default::default() }.build();
+		let pruned =
+			TestCandidateBuilder { para_id: 6.into(), pov_hash, ..Default::default() }.build();
+
+		for candidate in [&kept, &pruned] {
+			let (tx, rx) = oneshot::channel();
+			let block_msg = AvailabilityStoreMessage::StoreAvailableData {
+				candidate_hash: candidate.hash(),
+				n_validators,
+				available_data: available_data.clone(),
+				tx,
+			};
+
+			virtual_overseer.send(FromOrchestra::Communication { msg: block_msg }).await;
+			rx.await.unwrap().unwrap();
+		}
+
+		let parent = Hash::repeat_byte(2);
+		let block_number = 10;
+
+		let new_leaf = import_leaf(
+			&mut virtual_overseer,
+			parent,
+			block_number,
+			vec![candidate_included(kept.clone()), candidate_included(pruned.clone())],
+			(0..n_validators).map(|_| Sr25519Keyring::Alice.public().into()).collect(),
+		)
+		.await;
+
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::BlockFinalized(new_leaf, block_number),
+		)
+		.await;
+
+		// Wait until finalized data would definitely be pruned.
+		test_state.clock.inc(test_state.pruning_config.keep_finalized_for * 10);
+		test_state.wait_for_pruning().await;
+
+		assert_eq!(
+			query_available_data(&mut virtual_overseer, kept.hash()).await.unwrap(),
+			available_data,
+		);
+		assert!(has_all_chunks(&mut virtual_overseer, kept.hash(), n_validators, true).await);
+
+		assert!(query_available_data(&mut virtual_overseer, pruned.hash()).await.is_none());
+		assert!(has_all_chunks(&mut virtual_overseer, pruned.hash(), n_validators, false).await);
+		virtual_overseer
+	});
+}
+
+#[test]
+fn finalized_data_must_be_kept_for_the_dispute_window() {
+	let pruning_config = TEST_CONFIG.pruning;
+
+	assert!(pruning_config.check_dispute_window(pruning_config.keep_finalized_for).is_ok());
+	assert_matches!(
+		pruning_config.check_dispute_window(pruning_config.keep_finalized_for * 2),
+		Err(Error::RetentionShorterThanDisputeWindow { .. })
+	);
+}
+
 #[test]
 fn we_dont_miss_anything_if_import_notifications_are_missed() {
 	let store = test_store();
@@ -996,6 +1150,203 @@ fn forkfullness_works() {
 	});
 }
 
+#[test]
+fn scrubbing_restores_corrupt_chunks_from_available_data() {
+	let store = test_store();
+	let test_state = TestState::default();
+
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		let n_validators = 10;
+
+		let available_data = AvailableData {
+			pov: Arc::new(PoV { block_data: BlockData(vec![4, 5, 6]) }),
+			validation_data: test_state.persisted_validation_data.clone(),
+		};
+		let chunks = erasure::obtain_chunks_v1(n_validators, &available_data).unwrap();
+		let erasure_root = erasure::branches(&chunks).root();
+		let candidate_hash = CandidateHash(Hash::repeat_byte(1));
+
+		let (tx, rx) = oneshot::channel();
+		let block_msg = AvailabilityStoreMessage::StoreAvailableData {
+			candidate_hash,
+			n_validators: n_validators as _,
+			available_data: available_data.clone(),
+			tx,
+		};
+
+		virtual_overseer.send(FromOrchestra::Communication { msg: block_msg }).await;
+		rx.await.unwrap().unwrap();
+
+		let chunk = query_chunk(&mut virtual_overseer, candidate_hash, ValidatorIndex(2))
+			.await
+			.unwrap();
+		with_tx(&store, |tx| {
+			let key = (CHUNK_PREFIX, candidate_hash, ValidatorIndex(2)).encode();
+			tx.put_vec(TEST_CONFIG.col_data, &key, vec![1, 2, 3]);
+		});
+
+		let info = ScrubInfo {
+			erasure_root,
+			pov_hash: available_data.pov.hash(),
+			persisted_validation_data_hash: available_data.validation_data.hash(),
+			relay_parent: Hash::repeat_byte(2),
+			session_index: 1,
+			group_index: GroupIndex(0),
+		};
+		let scrubbed = scrub::scrub_candidate(&store, &TEST_CONFIG, candidate_hash, &info)
+			.unwrap()
+			.unwrap();
+
+		assert_eq!(
+			scrubbed,
+			scrub::Scrubbed {
+				chunks: n_validators,
+				corrupt_available_data: false,
+				corrupt_chunks: vec![ValidatorIndex(2)],
+				restored_chunks: vec![chunk.clone()],
+				deleted_chunks: Vec::new(),
+			},
+		);
+		scrub::repair(&store, &TEST_CONFIG, candidate_hash, &scrubbed).unwrap().unwrap();
+		assert_eq!(
+			query_chunk(&mut virtual_overseer, candidate_hash, ValidatorIndex(2)).await,
+			Some(chunk),
+		);
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn scrubbing_fetches_corrupt_chunks_once_more() {
+	let store = test_store();
+	let mut test_state = TestState::default();
+	test_state.scrubbing_config.interval = Duration::from_millis(50);
+
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		let n_validators = 10;
+
+		let available_data = AvailableData {
+			pov: Arc::new(PoV { block_data: BlockData(vec![4, 5, 6]) }),
+			validation_data: test_state.persisted_validation_data.clone(),
+		};
+		let chunks = erasure::obtain_chunks_v1(n_validators, &available_data).unwrap();
+		let erasure_root = erasure::branches(&chunks).root();
+
+		let mut candidate =
+			TestCandidateBuilder { pov_hash: available_data.pov.hash(), ..Default::default() }
+				.build();
+		candidate.descriptor.erasure_root = erasure_root;
+		candidate.descriptor.persisted_validation_data_hash = available_data.validation_data.hash();
+		let candidate_hash = candidate.hash();
+		let relay_parent = candidate.descriptor.relay_parent;
+
+		let (tx, rx) = oneshot::channel();
+		let block_msg = AvailabilityStoreMessage::StoreAvailableData {
+			candidate_hash,
+			n_validators: n_validators as _,
+			available_data: available_data.clone(),
+			tx,
+		};
+
+		virtual_overseer.send(FromOrchestra::Communication { msg: block_msg }).await;
+		rx.await.unwrap().unwrap();
+
+		import_leaf(
+			&mut virtual_overseer,
+			Hash::repeat_byte(2),
+			10,
+			vec![candidate_included(candidate)],
+			(0..n_validators).map(|_| Sr25519Keyring::Alice.public().into()).collect(),
+		)
+		.await;
+
+		// Neither the data nor the chunk can be restored locally.
+		with_tx(&store, |tx| {
+			let key = (AVAILABLE_PREFIX, candidate_hash).encode();
+			tx.put_vec(TEST_CONFIG.col_data, &key, vec![1, 2, 3]);
+			let key = (CHUNK_PREFIX, candidate_hash, ValidatorIndex(3)).encode();
+			tx.put_vec(TEST_CONFIG.col_data, &key, vec![1, 2, 3]);
+		});
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::AvailabilityDistribution(AvailabilityDistributionMessage::FetchChunk {
+				relay_parent: r,
+				session_index: 1,
+				group_index: GroupIndex(0),
+				candidate_hash: c,
+				erasure_root: e,
+				index: ValidatorIndex(3),
+			}) => {
+				assert_eq!(r, relay_parent);
+				assert_eq!(c, candidate_hash);
+				assert_eq!(e, erasure_root);
+			}
+		);
+
+		assert!(query_available_data(&mut virtual_overseer, candidate_hash).await.is_none());
+		assert!(query_chunk(&mut virtual_overseer, candidate_hash, ValidatorIndex(3))
+			.await
+			.is_none());
+		assert!(query_chunk(&mut virtual_overseer, candidate_hash, ValidatorIndex(4))
+			.await
+			.is_some());
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn scrubbing_batches_follow_the_cursor() {
+	let store = test_store();
+	let info = ScrubInfo {
+		erasure_root: Hash::zero(),
+		pov_hash: Hash::zero(),
+		persisted_validation_data_hash: Hash::zero(),
+		relay_parent: Hash::zero(),
+		session_index: 0,
+		group_index: GroupIndex(0),
+	};
+
+	// Hashes sharing leading bytes with each other to different depths.
+	let mut hashes = Vec::new();
+	for first in [0x00, 0x01, 0x7f, 0xff] {
+		for second in [0x00, 0x10, 0xff] {
+			for last in [0x00, 0x42] {
+				let mut hash = Hash::repeat_byte(0x33);
+				hash.0[0] = first;
+				hash.0[1] = second;
+				hash.0[31] = last;
+				hashes.push(CandidateHash(hash));
+			}
+		}
+	}
+	with_tx(&store, |tx| {
+		for hash in &hashes {
+			write_scrub_info(tx, &TEST_CONFIG, hash, &info);
+		}
+	});
+	hashes.sort();
+
+	for batch_size in [1, 2, 5, hashes.len()] {
+		let mut cursor = None;
+		let mut scrubbed = Vec::new();
+		loop {
+			let batch = scrub::next_batch(&store, &TEST_CONFIG, cursor, batch_size).unwrap();
+			scrubbed.extend(batch.iter().map(|(hash, info)| {
+				assert!(info.is_some());
+				*hash
+			}));
+			if batch.len() < batch_size {
+				break
+			}
+			cursor = batch.last().map(|(hash, _)| *hash);
+		}
+		assert_eq!(scrubbed, hashes);
+	}
+}
+
 async fn query_available_data(
 	virtual_overseer: &mut VirtualOverseer,
 	candidate_hash: CandidateHash,
@@ -1052,6 +1403,7 @@ async fn import_leaf(
 		digest: Default::default(),
 	};
 	let new_leaf = header.hash();
+	let has_events = !events.is_empty();
 
 	overseer_signal(
 		virtual_overseer,
@@ -1097,5 +1449,18 @@ async fn import_leaf(
 		}
 	);
 
+	if has_events {
+		assert_matches!(
+			overseer_recv(virtual_overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				relay_parent,
+				RuntimeApiRequest::SessionIndexForChild(tx),
+			)) => {
+				assert_eq!(relay_parent, parent_hash);
+				tx.send(Ok(1)).unwrap();
+			}
+		);
+	}
+
 	new_leaf
 }
@@ -35,6 +35,7 @@ use polkadot_node_primitives::{
 	BACKING_EXECUTION_TIMEOUT,
 };
 use polkadot_node_subsystem::{
+	errors::RuntimeApiError,
 	jaeger,
 	messages::{
 		AvailabilityDistributionMessage, AvailabilityStoreMessage, CandidateBackingMessage,
@@ -45,8 +46,8 @@ use polkadot_node_subsystem::{
 	Stage, SubsystemError,
 };
 use polkadot_node_subsystem_util::{
-	self as util, request_from_runtime, request_session_index_for_child, request_validator_groups,
-	request_validators, Validator,
+	self as util, request_disabled_validators, request_from_runtime,
+	request_session_index_for_child, request_validator_groups, request_validators, Validator,
 };
 use polkadot_primitives::v2::{
 	BackedCandidate, CandidateCommitments, CandidateHash, CandidateReceipt, CollatorId,
@@ -303,7 +304,7 @@ async fn handle_active_leaves_update<Context>(
 	let span = PerLeafSpan::new(leaf.span, "backing");
 	let _span = span.child("runtime-apis");
 
-	let (validators, groups, session_index, cores) = futures::try_join!(
+	let (validators, groups, session_index, cores, disabled_validators) = futures::try_join!(
 		request_validators(parent, ctx.sender()).await,
 		request_validator_groups(parent, ctx.sender()).await,
 		request_session_index_for_child(parent, ctx.sender()).await,
@@ -311,6 +312,7 @@ async fn handle_active_leaves_update<Context>(
 			RuntimeApiRequest::AvailabilityCores(tx)
 		},)
 		.await,
+		request_disabled_validators(parent, ctx.sender()).await,
 	)
 	.map_err(Error::JoinMultiple)?;
 
@@ -318,6 +320,11 @@ async fn handle_active_leaves_update<Context>(
 	let (validator_groups, group_rotation_info) = try_runtime_api!(groups);
 	let session_index = try_runtime_api!(session_index);
 	let cores = try_runtime_api!(cores);
+	// Nobody is disabled on runtimes which don't support disabling yet.
+	let disabled_validators = try_runtime_api!(match disabled_validators {
+		Err(RuntimeApiError::NotSupported { .. }) => Ok(Vec::new()),
+		result => result,
+	});
 
 	drop(_span);
 	let _span = span.child("validator-construction");
@@ -391,6 +398,7 @@ async fn handle_active_leaves_update<Context>(
 		keystore: keystore.clone(),
 		table: Table::default(),
 		table_context,
+		disabled_validators: disabled_validators.into_iter().collect(),
 		background_validation_tx: background_validation_tx.clone(),
 		metrics: metrics.clone(),
 		_marker: std::marker::PhantomData,
@@ -430,6 +438,8 @@ struct CandidateBackingJob<Context> {
 	keystore: SyncCryptoStorePtr,
 	table: Table<TableContext>,
 	table_context: TableContext,
+	/// Validators disabled for having lost a dispute, whose statements are ignored.
+	disabled_validators: HashSet<ValidatorIndex>,
 	background_validation_tx: mpsc::Sender<(Hash, ValidatedCandidateCommand)>,
 	metrics: Metrics,
 	_marker: std::marker::PhantomData<Context>,
@@ -598,10 +608,15 @@ async fn make_pov_available(
 	{
 		let _span = span.as_ref().map(|s| s.child("erasure-coding").with_candidate(candidate_hash));
 
-		let chunks = erasure_coding::obtain_chunks_v1(n_validators, &available_data)?;
+		let chunks = erasure_coding::obtain_chunks_v1_parallel(n_validators, &available_data)?;
 
-		let branches = erasure_coding::branches(chunks.as_ref());
-		let erasure_root = branches.root();
+		// Only the root is needed here, so just the chunk hashes are kept for building it. The
+		// chunks themselves are all in memory until then, as the encoding fills them in parallel.
+		let mut branches = erasure_coding::BranchesBuilder::with_capacity(chunks.len());
+		for chunk in chunks {
+			branches.push(&chunk);
+		}
+		let erasure_root = branches.build().root();
 
 		if erasure_root != expected_erasure_root {
 			return Ok(Err(InvalidErasureRoot))
@@ -645,6 +660,7 @@ async fn request_candidate_validation(
 	sender: &mut impl overseer::CandidateBackingSenderTrait,
 	candidate_receipt: CandidateReceipt,
 	pov: Arc<PoV>,
+	span: jaeger::Span,
 ) -> Result<ValidationResult, Error> {
 	let (tx, rx) = oneshot::channel();
 
@@ -653,6 +669,7 @@ async fn request_candidate_validation(
 			candidate_receipt,
 			pov,
 			BACKING_EXECUTION_TIMEOUT,
+			span,
 			tx,
 		))
 		.await;
@@ -726,12 +743,13 @@ async fn validate_and_make_available(
 	};
 
 	let v = {
-		let _span = span.as_ref().map(|s| {
+		let validation_span = span.as_ref().map_or(jaeger::Span::Disabled, |s| {
 			s.child("request-validation")
 				.with_pov(&pov)
 				.with_para_id(candidate.descriptor().para_id)
 		});
-		request_candidate_validation(&mut sender, candidate.clone(), pov.clone()).await?
+		request_candidate_validation(&mut sender, candidate.clone(), pov.clone(), validation_span)
+			.await?
 	};
 
 	let res = match v {
@@ -1132,6 +1150,17 @@ impl<Context> CandidateBackingJob<Context> {
 		statement: SignedFullStatement,
 	) -> Result<(), Error> {
 		let _timer = self.metrics.time_process_statement();
+
+		if self.disabled_validators.contains(&statement.validator_index()) {
+			gum::debug!(
+				target: LOG_TARGET,
+				validator_index = statement.validator_index().0,
+				candidate_hash = ?statement.payload().candidate_hash(),
+				"Ignoring statement of disabled validator",
+			);
+			return Ok(())
+		}
+
 		let _span = root_span
 			.child("statement")
 			.with_stage(jaeger::Stage::CandidateBacking)
@@ -64,6 +64,7 @@ struct TestState {
 	head_data: HashMap<ParaId, HeadData>,
 	signing_context: SigningContext,
 	relay_parent: Hash,
+	disabled_validators: Vec<ValidatorIndex>,
 }
 
 impl Default for TestState {
@@ -136,6 +137,7 @@ impl Default for TestState {
 			validation_data,
 			signing_context,
 			relay_parent,
+			disabled_validators: Vec::new(),
 		}
 	}
 }
@@ -265,6 +267,16 @@ async fn test_startup(virtual_overseer: &mut VirtualOverseer, test_state: &TestS
 			tx.send(Ok(test_state.availability_cores.clone())).unwrap();
 		}
 	);
+
+	// Check that subsystem job issues a request for the disabled validators.
+	assert_matches!(
+		virtual_overseer.recv().await,
+		AllMessages::RuntimeApi(
+			RuntimeApiMessage::Request(parent, RuntimeApiRequest::DisabledValidators(tx))
+		) if parent == test_state.relay_parent => {
+			tx.send(Ok(test_state.disabled_validators.clone())).unwrap();
+		}
+	);
 }
 
 // Test that a `CandidateBackingMessage::Second` issues validation work
@@ -305,6 +317,7 @@ fn backing_second_works() {
 					candidate_receipt,
 					pov,
 					timeout,
+					_,
 					tx,
 				)
 			) if pov == pov && &candidate_receipt.descriptor == candidate.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT &&  candidate.commitments.hash() == candidate_receipt.commitments_hash => {
@@ -451,6 +464,7 @@ fn backing_works() {
 					c,
 					pov,
 					timeout,
+					_,
 					tx,
 				)
 			) if pov == pov && c.descriptor() == candidate_a.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT && c.commitments_hash == candidate_a_commitments_hash=> {
@@ -623,6 +637,7 @@ fn backing_works_while_validation_ongoing() {
 					c,
 					pov,
 					timeout,
+					_,
 					tx,
 				)
 			) if pov == pov && c.descriptor() == candidate_a.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT && candidate_a_commitments_hash == c.commitments_hash => {
@@ -775,6 +790,7 @@ fn backing_misbehavior_works() {
 					c,
 					pov,
 					timeout,
+					_,
 					tx,
 				)
 			) if pov == pov && c.descriptor() == candidate_a.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT && candidate_a_commitments_hash == c.commitments_hash => {
@@ -919,6 +935,7 @@ fn backing_dont_second_invalid() {
 					c,
 					pov,
 					timeout,
+					_,
 					tx,
 				)
 			) if pov == pov && c.descriptor() == candidate_a.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT => {
@@ -948,6 +965,7 @@ fn backing_dont_second_invalid() {
 					c,
 					pov,
 					timeout,
+					_,
 					tx,
 				)
 			) if pov == pov && c.descriptor() == candidate_b.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT => {
@@ -1063,6 +1081,7 @@ fn backing_second_after_first_fails_works() {
 					c,
 					pov,
 					timeout,
+					_,
 					tx,
 				)
 			) if pov == pov && c.descriptor() == candidate.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT && c.commitments_hash == candidate.commitments.hash() => {
@@ -1112,6 +1131,7 @@ fn backing_second_after_first_fails_works() {
 					pov,
 					_,
 					_,
+					_,
 				)
 			) => {
 				assert_eq!(&*pov, &pov_to_second);
@@ -1189,6 +1209,7 @@ fn backing_works_after_failed_validation() {
 					c,
 					pov,
 					timeout,
+					_,
 					tx,
 				)
 			) if pov == pov && c.descriptor() == candidate.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT && c.commitments_hash == candidate.commitments.hash() => {
@@ -1325,6 +1346,104 @@ fn validation_work_ignores_wrong_collator() {
 	});
 }
 
+#[test]
+fn statements_of_disabled_validators_are_ignored() {
+	let mut test_state = TestState::default();
+	test_state.disabled_validators = vec![ValidatorIndex(2)];
+
+	test_harness(test_state.keystore.clone(), |mut virtual_overseer| async move {
+		test_startup(&mut virtual_overseer, &test_state).await;
+
+		let pov = PoV { block_data: BlockData(vec![1, 2, 3]) };
+
+		let expected_head_data = test_state.head_data.get(&test_state.chain_ids[0]).unwrap();
+
+		let candidate_a = TestCandidateBuilder {
+			para_id: test_state.chain_ids[0],
+			relay_parent: test_state.relay_parent,
+			pov_hash: pov.hash(),
+			head_data: expected_head_data.clone(),
+			erasure_root: make_erasure_root(&test_state, pov.clone()),
+			..Default::default()
+		}
+		.build();
+
+		let public2 = CryptoStore::sr25519_generate_new(
+			&*test_state.keystore,
+			ValidatorId::ID,
+			Some(&test_state.validators[2].to_seed()),
+		)
+		.await
+		.expect("Insert key into keystore");
+		let public3 = CryptoStore::sr25519_generate_new(
+			&*test_state.keystore,
+			ValidatorId::ID,
+			Some(&test_state.validators[3].to_seed()),
+		)
+		.await
+		.expect("Insert key into keystore");
+
+		let seconding_disabled = SignedFullStatement::sign(
+			&test_state.keystore,
+			Statement::Seconded(candidate_a.clone()),
+			&test_state.signing_context,
+			ValidatorIndex(2),
+			&public2.into(),
+		)
+		.await
+		.ok()
+		.flatten()
+		.expect("should be signed");
+
+		let seconding = SignedFullStatement::sign(
+			&test_state.keystore,
+			Statement::Seconded(candidate_a.clone()),
+			&test_state.signing_context,
+			ValidatorIndex(3),
+			&public3.into(),
+		)
+		.await
+		.ok()
+		.flatten()
+		.expect("should be signed");
+
+		virtual_overseer
+			.send(FromOrchestra::Communication {
+				msg: CandidateBackingMessage::Statement(
+					test_state.relay_parent,
+					seconding_disabled,
+				),
+			})
+			.await;
+		virtual_overseer
+			.send(FromOrchestra::Communication {
+				msg: CandidateBackingMessage::Statement(test_state.relay_parent, seconding),
+			})
+			.await;
+
+		// Validation only starts with the statement of the validator which is not disabled.
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::AvailabilityDistribution(
+				AvailabilityDistributionMessage::FetchPoV {
+					relay_parent,
+					from_validator,
+					..
+				}
+			) if relay_parent == test_state.relay_parent => {
+				assert_eq!(from_validator, ValidatorIndex(3));
+			}
+		);
+
+		virtual_overseer
+			.send(FromOrchestra::Signal(OverseerSignal::ActiveLeaves(
+				ActiveLeavesUpdate::stop_work(test_state.relay_parent),
+			)))
+			.await;
+		virtual_overseer
+	});
+}
+
 #[test]
 fn candidate_backing_reorders_votes() {
 	use sp_core::Encode;
@@ -1542,6 +1661,7 @@ fn retry_works() {
 					c,
 					pov,
 					timeout,
+					_,
 					_tx,
 				)
 			) if pov == pov && c.descriptor() == candidate.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT && c.commitments_hash == candidate.commitments.hash()
@@ -15,6 +15,9 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 //! The bitfield signing subsystem produces `SignedAvailabilityBitfield`s once per block.
+//!
+//! Bitfields missing chunks of occupied cores get re-signed later in the same relay-parent
+//! window, as more chunks arrive.
 
 #![deny(unused_crate_dependencies)]
 #![warn(missing_docs)]
@@ -49,6 +52,12 @@ mod tests;
 
 /// Delay between starting a bitfield signing job and its attempting to create a bitfield.
 const SPAWNED_TASK_DELAY: Duration = Duration::from_millis(1500);
+/// Interval at which a bitfield missing chunks of occupied cores is constructed once more, to be
+/// re-signed if more chunks arrived in the meantime.
+const RESIGN_INTERVAL: Duration = Duration::from_millis(1000);
+/// Time after starting a bitfield signing job until which bitfields get re-signed. Later ones
+/// are unlikely to be gossiped in time for the next block.
+const RESIGN_UNTIL: Duration = Duration::from_millis(4500);
 const LOG_TARGET: &str = "parachain::bitfield-signing";
 
 // TODO: use `fatality` (https://github.com/paritytech/polkadot/issues/5540).
@@ -135,22 +144,17 @@ async fn get_availability_cores(
 	}
 }
 
-/// - get the list of core states from the runtime
-/// - for each core, concurrently determine chunk availability (see `get_core_availability`)
+/// - for each of the given cores, concurrently determine chunk availability (see
+///   `get_core_availability`)
 /// - return the bitfield if there were no errors at any point in this process
 ///   (otherwise, it's prone to false negatives)
 async fn construct_availability_bitfield(
 	relay_parent: Hash,
+	availability_cores: &[CoreState],
 	span: &jaeger::Span,
 	validator_idx: ValidatorIndex,
 	sender: &mut impl SubsystemSender<overseer::BitfieldSigningOutgoingMessages>,
 ) -> Result<AvailabilityBitfield, Error> {
-	// get the set of availability cores from the runtime
-	let availability_cores = {
-		let _span = span.child("get-availability-cores");
-		get_availability_cores(relay_parent, sender).await?
-	};
-
 	// Wrap the sender in a Mutex to share it between the futures.
 	//
 	// We use a `Mutex` here to not `clone` the sender inside the future, because
@@ -283,52 +287,97 @@ where
 	Delay::new_at(wait_until).await?;
 
 	// this timer does not appear at the head of the function because we don't want to include
-	// SPAWNED_TASK_DELAY each time.
-	let _timer = metrics.time_run();
+	// SPAWNED_TASK_DELAY each time. It stops once the first bitfield is sent, so re-signing is not
+	// included either.
+	let mut timer = metrics.time_run();
 
 	drop(span_delay);
-	let span_availability = span.child("availability");
 
-	let bitfield = match construct_availability_bitfield(
-		leaf.hash,
-		&span_availability,
-		validator.index(),
-		&mut sender,
-	)
-	.await
-	{
-		Err(Error::Runtime(runtime_err)) => {
-			// Don't take down the node on runtime API errors.
-			gum::warn!(target: LOG_TARGET, err = ?runtime_err, "Encountered a runtime API error");
-			return Ok(())
-		},
-		Err(err) => return Err(err),
-		Ok(bitfield) => bitfield,
-	};
-
-	drop(span_availability);
-	let span_signing = span.child("signing");
-
-	let signed_bitfield =
-		match validator.sign(keystore, bitfield).await.map_err(|e| Error::Keystore(e))? {
-			Some(b) => b,
-			None => {
-				gum::error!(
-					target: LOG_TARGET,
-					"Key was found at construction, but while signing it could not be found.",
-				);
+	// get the set of availability cores from the runtime
+	let availability_cores = {
+		let _span = span.child("get-availability-cores");
+		match get_availability_cores(leaf.hash, &mut sender).await {
+			Err(Error::Runtime(runtime_err)) => {
+				// Don't take down the node on runtime API errors.
+				gum::warn!(target: LOG_TARGET, err = ?runtime_err, "Encountered a runtime API error");
 				return Ok(())
 			},
-		};
+			Err(err) => return Err(err),
+			Ok(cores) => cores,
+		}
+	};
+	let occupied_cores = availability_cores
+		.iter()
+		.filter(|core| matches!(core, CoreState::Occupied(_)))
+		.count();
 
-	metrics.on_bitfield_signed();
+	let resign_until = wait_until - SPAWNED_TASK_DELAY + RESIGN_UNTIL;
+	let mut signed: Option<AvailabilityBitfield> = None;
 
-	drop(span_signing);
-	let _span_gossip = span.child("gossip");
+	loop {
+		let span_availability = span.child("availability");
+		let bitfield = construct_availability_bitfield(
+			leaf.hash,
+			&availability_cores,
+			&span_availability,
+			validator.index(),
+			&mut sender,
+		)
+		.await?;
+		drop(span_availability);
+
+		// Only a strict superset of the bitfield signed before replaces it.
+		let is_update = signed.as_ref().map_or(true, |old| bitfield.is_strict_superset_of(old));
+		if is_update {
+			let is_resign = signed.is_some();
+			signed = Some(bitfield.clone());
+
+			let span_signing = span.child("signing");
+			let signed_bitfield =
+				match validator.sign(keystore.clone(), bitfield).await.map_err(Error::Keystore)? {
+					Some(b) => b,
+					None => {
+						gum::error!(
+							target: LOG_TARGET,
+							"Key was found at construction, but while signing it could not be found.",
+						);
+						return Ok(())
+					},
+				};
+
+			if is_resign {
+				metrics.on_bitfield_resigned();
+			} else {
+				metrics.on_bitfield_signed();
+			}
+
+			drop(span_signing);
+			let _span_gossip = span.child("gossip");
+
+			sender
+				.send_message(BitfieldDistributionMessage::DistributeBitfield(
+					leaf.hash,
+					signed_bitfield,
+				))
+				.await;
+			drop(timer.take());
+		}
 
-	sender
-		.send_message(BitfieldDistributionMessage::DistributeBitfield(leaf.hash, signed_bitfield))
-		.await;
+		// Stop once the chunks of all occupied cores are there, or it is too late for an update
+		// to make it into the next block.
+		let available = signed.as_ref().map_or(0, |bitfield| bitfield.0.count_ones());
+		let next_attempt = Instant::now() + RESIGN_INTERVAL;
+		if available >= occupied_cores || next_attempt > resign_until {
+			return Ok(())
+		}
 
-	Ok(())
+		gum::trace!(
+			target: LOG_TARGET,
+			relay_parent = ?leaf.hash,
+			available,
+			occupied_cores,
+			"Waiting for more chunks to re-sign the bitfield",
+		);
+		Delay::new_at(next_attempt).await?;
+	}
 }
@@ -19,6 +19,7 @@ use polkadot_node_subsystem_util::metrics::{self, prometheus};
 #[derive(Clone)]
 pub(crate) struct MetricsInner {
 	pub(crate) bitfields_signed_total: prometheus::Counter<prometheus::U64>,
+	pub(crate) bitfields_resigned_total: prometheus::Counter<prometheus::U64>,
 	pub(crate) run: prometheus::Histogram,
 }
 
@@ -33,6 +34,12 @@ impl Metrics {
 		}
 	}
 
+	pub fn on_bitfield_resigned(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.bitfields_resigned_total.inc();
+		}
+	}
+
 	/// Provide a timer for `prune_povs` which observes on drop.
 	pub fn time_run(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.run.start_timer())
@@ -49,6 +56,13 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			bitfields_resigned_total: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_bitfields_resigned_total",
+					"Number of bitfields re-signed with more chunks available.",
+				)?,
+				registry,
+			)?,
 			run: prometheus::register(
 				prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
 					"polkadot_parachain_bitfield_signing_run",
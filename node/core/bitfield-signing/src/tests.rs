@@ -39,9 +39,14 @@ fn construct_availability_bitfield_works() {
 		let relay_parent = Hash::default();
 		let validator_index = ValidatorIndex(1u32);
 
+		let hash_a = CandidateHash(Hash::repeat_byte(1));
+		let hash_b = CandidateHash(Hash::repeat_byte(2));
+		let cores = vec![CoreState::Free, occupied_core(1, hash_a), occupied_core(2, hash_b)];
+
 		let (mut sender, mut receiver) = polkadot_node_subsystem_test_helpers::sender_receiver();
 		let future = construct_availability_bitfield(
 			relay_parent,
+			&cores,
 			&jaeger::Span::Disabled,
 			validator_index,
 			&mut sender,
@@ -49,18 +54,9 @@ fn construct_availability_bitfield_works() {
 		.fuse();
 		pin_mut!(future);
 
-		let hash_a = CandidateHash(Hash::repeat_byte(1));
-		let hash_b = CandidateHash(Hash::repeat_byte(2));
-
 		loop {
 			futures::select! {
 				m = receiver.next() => match m.unwrap() {
-					AllMessages::RuntimeApi(
-						RuntimeApiMessage::Request(rp, RuntimeApiRequest::AvailabilityCores(tx)),
-					) => {
-						assert_eq!(relay_parent, rp);
-						tx.send(Ok(vec![CoreState::Free, occupied_core(1, hash_a), occupied_core(2, hash_b)])).unwrap();
-					}
 					AllMessages::AvailabilityStore(
 						AvailabilityStoreMessage::QueryChunkAvailability(c_hash, vidx, tx),
 					) => {
@@ -24,13 +24,15 @@
 #![warn(missing_docs)]
 
 use polkadot_node_core_pvf::{
-	InvalidCandidate as WasmInvalidCandidate, PrepareError, Pvf, ValidationError, ValidationHost,
+	ExecutionPhases, InvalidCandidate as WasmInvalidCandidate, PrepareError, Pvf, ValidationError,
+	ValidationHost,
 };
 use polkadot_node_primitives::{
 	BlockData, InvalidCandidate, PoV, ValidationResult, POV_BOMB_LIMIT, VALIDATION_CODE_BOMB_LIMIT,
 };
 use polkadot_node_subsystem::{
 	errors::RuntimeApiError,
+	jaeger,
 	messages::{
 		CandidateValidationMessage, PreCheckOutcome, RuntimeApiMessage, RuntimeApiRequest,
 		ValidationFailed,
@@ -38,17 +40,27 @@ use polkadot_node_subsystem::{
 	overseer, FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError, SubsystemResult,
 	SubsystemSender,
 };
+use polkadot_node_subsystem_util::runtime::{self, RuntimeInfo};
 use polkadot_parachain::primitives::{ValidationParams, ValidationResult as WasmValidationResult};
-use polkadot_primitives::v2::{
-	CandidateCommitments, CandidateDescriptor, CandidateReceipt, Hash, OccupiedCoreAssumption,
-	PersistedValidationData, ValidationCode, ValidationCodeHash,
+use polkadot_primitives::{
+	v2::{
+		CandidateCommitments, CandidateDescriptor, CandidateReceipt, CoreIndex, Hash, Id as ParaId,
+		OccupiedCoreAssumption, PersistedValidationData, SessionIndex, ValidationCode,
+		ValidationCodeHash,
+	},
+	vstaging::ExecutorParams,
 };
 
 use parity_scale_codec::Encode;
 
-use futures::{channel::oneshot, prelude::*};
+use futures::{
+	channel::{mpsc, oneshot},
+	prelude::*,
+};
+
+use sp_keystore::SyncCryptoStorePtr;
 
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 
@@ -60,6 +72,10 @@ mod tests;
 
 const LOG_TARGET: &'static str = "parachain::candidate-validation";
 
+/// The number of leaves that may wait for the future code of the assigned paras to be prewarmed.
+/// Further leaves are skipped until the prewarming catches up.
+const PREWARM_QUEUE_SIZE: usize = 4;
+
 /// Configuration for the candidate validation subsystem
 #[derive(Clone)]
 pub struct Config {
@@ -68,6 +84,11 @@ pub struct Config {
 	/// The path to the executable which can be used for spawning PVF compilation & validation
 	/// workers.
 	pub program_path: PathBuf,
+	/// Whether the PVF workers should be sandboxed.
+	pub enable_pvf_sandbox: bool,
+	/// The maximum total size in bytes of the compiled artifacts kept in the cache. `None` means
+	/// unlimited.
+	pub artifacts_cache_max_size: Option<u64>,
 }
 
 /// The candidate validation subsystem.
@@ -77,34 +98,33 @@ pub struct CandidateValidationSubsystem {
 	#[allow(missing_docs)]
 	pub pvf_metrics: polkadot_node_core_pvf::Metrics,
 	config: Config,
+	keystore: SyncCryptoStorePtr,
 }
 
 impl CandidateValidationSubsystem {
 	/// Create a new `CandidateValidationSubsystem` with the given task spawner and isolation
 	/// strategy.
 	///
+	/// The keystore is used to find out which paras the validator is assigned to, so that their
+	/// upcoming code upgrades can be prepared in advance.
+	///
 	/// Check out [`IsolationStrategy`] to get more details.
 	pub fn with_config(
 		config: Config,
+		keystore: SyncCryptoStorePtr,
 		metrics: Metrics,
 		pvf_metrics: polkadot_node_core_pvf::Metrics,
 	) -> Self {
-		CandidateValidationSubsystem { config, metrics, pvf_metrics }
+		CandidateValidationSubsystem { config, keystore, metrics, pvf_metrics }
 	}
 }
 
 #[overseer::subsystem(CandidateValidation, error=SubsystemError, prefix=self::overseer)]
 impl<Context> CandidateValidationSubsystem {
 	fn start(self, ctx: Context) -> SpawnedSubsystem {
-		let future = run(
-			ctx,
-			self.metrics,
-			self.pvf_metrics,
-			self.config.artifacts_cache_path,
-			self.config.program_path,
-		)
-		.map_err(|e| SubsystemError::with_origin("candidate-validation", e))
-		.boxed();
+		let future = run(ctx, self.keystore, self.metrics, self.pvf_metrics, self.config)
+			.map_err(|e| SubsystemError::with_origin("candidate-validation", e))
+			.boxed();
 		SpawnedSubsystem { name: "candidate-validation-subsystem", future }
 	}
 }
@@ -112,20 +132,43 @@ impl<Context> CandidateValidationSubsystem {
 #[overseer::contextbounds(CandidateValidation, prefix = self::overseer)]
 async fn run<Context>(
 	mut ctx: Context,
+	keystore: SyncCryptoStorePtr,
 	metrics: Metrics,
 	pvf_metrics: polkadot_node_core_pvf::Metrics,
-	cache_path: PathBuf,
-	program_path: PathBuf,
+	config: Config,
 ) -> SubsystemResult<()> {
-	let (validation_host, task) = polkadot_node_core_pvf::start(
-		polkadot_node_core_pvf::Config::new(cache_path, program_path),
-		pvf_metrics,
-	);
+	let mut pvf_config =
+		polkadot_node_core_pvf::Config::new(config.artifacts_cache_path, config.program_path);
+	pvf_config.enable_sandbox = config.enable_pvf_sandbox;
+	pvf_config.artifact_cache_max_size = config.artifacts_cache_max_size;
+	let (validation_host, task) = polkadot_node_core_pvf::start(pvf_config, pvf_metrics);
 	ctx.spawn_blocking("pvf-validation-host", task.boxed())?;
 
+	let (mut prewarm_tx, prewarm_rx) = mpsc::channel(PREWARM_QUEUE_SIZE);
+	let prewarm = {
+		let sender = ctx.sender().clone();
+		prewarm_task(prewarm_rx, sender, validation_host.clone(), keystore)
+	};
+	ctx.spawn("prewarm-future-code", prewarm.boxed())?;
+
+	// The state of the relay parents of old candidates may be pruned already, so the runtime is
+	// queried at the most recent leaf instead where possible.
+	let mut recent_leaf = None;
+
 	loop {
 		match ctx.recv().await? {
-			FromOrchestra::Signal(OverseerSignal::ActiveLeaves(_)) => {},
+			FromOrchestra::Signal(OverseerSignal::ActiveLeaves(update)) =>
+				if let Some(activated) = update.activated {
+					recent_leaf = Some(activated.hash);
+					if let Err(err) = prewarm_tx.try_send(activated.hash) {
+						gum::debug!(
+							target: LOG_TARGET,
+							leaf = ?activated.hash,
+							?err,
+							"Skipping prewarming of the future code",
+						);
+					}
+				},
 			FromOrchestra::Signal(OverseerSignal::BlockFinalized(..)) => {},
 			FromOrchestra::Signal(OverseerSignal::Conclude) => return Ok(()),
 			FromOrchestra::Communication { msg } => match msg {
@@ -133,6 +176,7 @@ async fn run<Context>(
 					candidate_receipt,
 					pov,
 					timeout,
+					span,
 					response_sender,
 				) => {
 					let bg = {
@@ -142,6 +186,9 @@ async fn run<Context>(
 
 						async move {
 							let _timer = metrics.time_validate_from_chain_state();
+							let span = span
+								.child("validate-from-chain-state")
+								.with_para_id(candidate_receipt.descriptor.para_id);
 							let res = validate_from_chain_state(
 								&mut sender,
 								validation_host,
@@ -149,6 +196,7 @@ async fn run<Context>(
 								pov,
 								timeout,
 								&metrics,
+								&span,
 							)
 							.await;
 
@@ -164,25 +212,46 @@ async fn run<Context>(
 					validation_code,
 					candidate_receipt,
 					pov,
+					session_index,
 					timeout,
+					span,
 					response_sender,
 				) => {
 					let bg = {
+						let mut sender = ctx.sender().clone();
 						let metrics = metrics.clone();
 						let validation_host = validation_host.clone();
+						let block_hash =
+							recent_leaf.unwrap_or(candidate_receipt.descriptor.relay_parent);
 
 						async move {
 							let _timer = metrics.time_validate_from_exhaustive();
-							let res = validate_candidate_exhaustive(
-								validation_host,
-								persisted_validation_data,
-								validation_code,
-								candidate_receipt,
-								pov,
-								timeout,
-								&metrics,
+							let span = span
+								.child("validate-from-exhaustive")
+								.with_para_id(candidate_receipt.descriptor.para_id);
+							let res = match request_executor_params_by_session(
+								&mut sender,
+								block_hash,
+								session_index,
 							)
-							.await;
+							.await
+							{
+								Ok(executor_params) =>
+									validate_candidate_exhaustive(
+										validation_host,
+										persisted_validation_data,
+										validation_code,
+										candidate_receipt,
+										pov,
+										executor_params,
+										timeout,
+										&metrics,
+										&span,
+									)
+									.await,
+								Err(RuntimeRequestFailed) =>
+									Err(ValidationFailed("Executor params: Bad request".into())),
+							};
 
 							metrics.on_validation_event(&res);
 							let _ = response_sender.send(res);
@@ -274,6 +343,263 @@ where
 	.await
 }
 
+/// Requests the executor parameters agreed on-chain for the session of the given relay parent.
+///
+/// Falls back to the default parameters if the runtime doesn't expose them yet.
+async fn request_executor_params<Sender>(
+	sender: &mut Sender,
+	relay_parent: Hash,
+) -> Result<ExecutorParams, RuntimeRequestFailed>
+where
+	Sender: SubsystemSender<RuntimeApiMessage>,
+{
+	let (tx, rx) = oneshot::channel();
+	let session_index =
+		runtime_api_request(sender, relay_parent, RuntimeApiRequest::SessionIndexForChild(tx), rx)
+			.await?;
+
+	request_executor_params_by_session(sender, relay_parent, session_index).await
+}
+
+/// Requests the executor parameters agreed on-chain for the given session, as stored in the state
+/// of the given block.
+///
+/// Falls back to the default parameters if the runtime doesn't expose them yet.
+async fn request_executor_params_by_session<Sender>(
+	sender: &mut Sender,
+	block_hash: Hash,
+	session_index: SessionIndex,
+) -> Result<ExecutorParams, RuntimeRequestFailed>
+where
+	Sender: SubsystemSender<RuntimeApiMessage>,
+{
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(
+			RuntimeApiMessage::Request(
+				block_hash,
+				RuntimeApiRequest::SessionExecutorParams(session_index, tx),
+			)
+			.into(),
+		)
+		.await;
+
+	match rx.await {
+		Ok(Ok(Some(executor_params))) => Ok(executor_params),
+		Ok(Ok(None)) | Ok(Err(RuntimeApiError::NotSupported { .. })) => {
+			gum::debug!(
+				target: LOG_TARGET,
+				?block_hash,
+				?session_index,
+				"Executor params are not available, using the default ones",
+			);
+			Ok(ExecutorParams::default())
+		},
+		Ok(Err(e)) => {
+			gum::debug!(
+				target: LOG_TARGET,
+				?block_hash,
+				err = ?e,
+				"Runtime API request internal error"
+			);
+			Err(RuntimeRequestFailed)
+		},
+		Err(_) => {
+			gum::debug!(target: LOG_TARGET, ?block_hash, "Runtime API request dropped");
+			Err(RuntimeRequestFailed)
+		},
+	}
+}
+
+/// The validation code a para is scheduled to upgrade to, prepared for the given session.
+struct FutureCode {
+	code_hash: ValidationCodeHash,
+	session_index: SessionIndex,
+}
+
+/// Prewarms the future code of the assigned paras for every leaf received, in the background of the
+/// main loop, since that requires a few runtime API requests per leaf.
+async fn prewarm_task<Sender>(
+	mut leaves: mpsc::Receiver<Hash>,
+	mut sender: Sender,
+	mut validation_backend: impl ValidationBackend,
+	keystore: SyncCryptoStorePtr,
+) where
+	Sender: SubsystemSender<RuntimeApiMessage>,
+{
+	let mut runtime_info = RuntimeInfo::new(Some(keystore));
+	let mut future_code = HashMap::new();
+
+	while let Some(leaf) = leaves.next().await {
+		prewarm_future_code(
+			&mut sender,
+			&mut validation_backend,
+			&mut runtime_info,
+			&mut future_code,
+			leaf,
+		)
+		.await;
+	}
+}
+
+/// Issues a heads up for the scheduled code upgrades of the paras our group is assigned to at the
+/// given leaf, so that the first candidate after an upgrade is not delayed by the compilation.
+///
+/// `future_code` keeps the future code of the paras we were assigned to at the previous leaf. The
+/// code that is already known for the session is not fetched nor prewarmed again. The heads up is
+/// repeated once per session though, so that the prepared artifact isn't pruned before the upgrade
+/// is enacted.
+async fn prewarm_future_code<Sender>(
+	sender: &mut Sender,
+	validation_backend: &mut impl ValidationBackend,
+	runtime_info: &mut RuntimeInfo,
+	future_code: &mut HashMap<ParaId, FutureCode>,
+	leaf: Hash,
+) where
+	Sender: SubsystemSender<RuntimeApiMessage>,
+{
+	let (paras, session_index) = match assigned_paras(sender, runtime_info, leaf).await {
+		Ok(Some(assigned)) => assigned,
+		Ok(None) => {
+			// We are not a para validator in this session.
+			future_code.clear();
+			return
+		},
+		Err(err) => {
+			gum::debug!(target: LOG_TARGET, ?leaf, ?err, "Cannot determine the assigned paras");
+			return
+		},
+	};
+	future_code.retain(|para_id, _| paras.contains(para_id));
+
+	let mut executor_params = None;
+	let mut pvfs = Vec::new();
+	for para_id in paras {
+		let (tx, rx) = oneshot::channel();
+		let code_hash = match runtime_api_request(
+			sender,
+			leaf,
+			RuntimeApiRequest::FutureValidationCodeHash(para_id, tx),
+			rx,
+		)
+		.await
+		{
+			Ok(Some(code_hash)) => code_hash,
+			Ok(None) => {
+				future_code.remove(&para_id);
+				continue
+			},
+			// Most likely the runtime doesn't support the request yet, so there is no point in
+			// asking about the other paras.
+			Err(RuntimeRequestFailed) => return,
+		};
+
+		match future_code.get(&para_id) {
+			Some(known) if known.code_hash == code_hash && known.session_index == session_index =>
+				continue,
+			_ => {},
+		}
+
+		let raw_code = match request_validation_code_by_hash(sender, leaf, code_hash).await {
+			Ok(Some(code)) =>
+				sp_maybe_compressed_blob::decompress(&code.0, VALIDATION_CODE_BOMB_LIMIT)
+					.map(|code| code.into_owned()),
+			_ => {
+				gum::debug!(
+					target: LOG_TARGET,
+					?leaf,
+					?para_id,
+					?code_hash,
+					"Cannot fetch the future validation code",
+				);
+				continue
+			},
+		};
+		let raw_code = match raw_code {
+			Ok(raw_code) => raw_code,
+			Err(err) => {
+				// Such code will be rejected by pre-checking, no need to prepare it.
+				gum::debug!(
+					target: LOG_TARGET,
+					?para_id,
+					?code_hash,
+					?err,
+					"Cannot decompress the future validation code",
+				);
+				continue
+			},
+		};
+
+		let params = match executor_params {
+			Some(ref params) => params.clone(),
+			None => match request_executor_params(sender, leaf).await {
+				Ok(params) => {
+					executor_params = Some(params.clone());
+					params
+				},
+				Err(RuntimeRequestFailed) => return,
+			},
+		};
+
+		gum::debug!(
+			target: LOG_TARGET,
+			?para_id,
+			?code_hash,
+			"Preparing the validation code of an upcoming upgrade",
+		);
+		let pvf = Pvf::from_code(raw_code, params);
+		pvfs.push(pvf);
+		future_code.insert(para_id, FutureCode { code_hash, session_index });
+	}
+
+	if pvfs.is_empty() {
+		return
+	}
+
+	if let Err(err) = validation_backend.heads_up(pvfs).await {
+		gum::warn!(target: LOG_TARGET, ?err, "Cannot send a heads up to the validation host");
+	}
+}
+
+/// Returns the paras whose cores are assigned to our group at the given leaf together with the
+/// session index of the leaf's children.
+///
+/// Returns `None` if we are not a member of any group in the session.
+async fn assigned_paras<Sender>(
+	sender: &mut Sender,
+	runtime_info: &mut RuntimeInfo,
+	leaf: Hash,
+) -> Result<Option<(Vec<ParaId>, SessionIndex)>, runtime::Error>
+where
+	Sender: SubsystemSender<RuntimeApiMessage>,
+{
+	let session_index = runtime_info.get_session_index_for_child(sender, leaf).await?;
+	let our_group = match runtime_info
+		.get_session_info_by_index(sender, leaf, session_index)
+		.await?
+		.validator_info
+		.our_group
+	{
+		Some(our_group) => our_group,
+		None => return Ok(None),
+	};
+
+	let cores = runtime::get_availability_cores(sender, leaf).await?;
+	let group_rotation_info = runtime::get_group_rotation_info(sender, leaf).await?;
+	let n_cores = cores.len();
+
+	let assigned_paras = cores
+		.iter()
+		.enumerate()
+		.filter(|(core_index, _)| {
+			group_rotation_info.group_for_core(CoreIndex(*core_index as u32), n_cores) == our_group
+		})
+		.filter_map(|(_, core)| core.para_id())
+		.collect();
+
+	Ok(Some((assigned_paras, session_index)))
+}
+
 async fn precheck_pvf<Sender>(
 	sender: &mut Sender,
 	mut validation_backend: impl ValidationBackend,
@@ -301,25 +627,42 @@ where
 			},
 		};
 
-	let validation_code = match sp_maybe_compressed_blob::decompress(
+	let raw_validation_code = match sp_maybe_compressed_blob::decompress(
 		&validation_code.0,
 		VALIDATION_CODE_BOMB_LIMIT,
 	) {
-		Ok(code) => Pvf::from_code(code.into_owned()),
+		Ok(code) => code.into_owned(),
 		Err(e) => {
 			gum::debug!(target: LOG_TARGET, err=?e, "precheck: cannot decompress validation code");
 			return PreCheckOutcome::Invalid
 		},
 	};
 
+	// The PVF is prepared with the executor parameters of the relay parent's session.
+	let executor_params = match request_executor_params(sender, relay_parent).await {
+		Ok(executor_params) => executor_params,
+		Err(RuntimeRequestFailed) => {
+			gum::warn!(
+				target: LOG_TARGET,
+				?relay_parent,
+				?validation_code_hash,
+				"precheck: cannot obtain the executor params",
+			);
+			return PreCheckOutcome::Failed
+		},
+	};
+	let validation_code = Pvf::from_code(raw_validation_code, executor_params);
+
 	match validation_backend.precheck_pvf(validation_code).await {
 		Ok(_) => PreCheckOutcome::Valid,
-		Err(prepare_err) => match prepare_err {
-			PrepareError::Prevalidation(_) |
-			PrepareError::Preparation(_) |
-			PrepareError::Panic(_) => PreCheckOutcome::Invalid,
-			PrepareError::TimedOut | PrepareError::DidNotMakeIt => PreCheckOutcome::Failed,
-		},
+		// Only deterministic errors warrant voting against the PVF. The non-deterministic ones
+		// don't tell anything about the PVF itself.
+		Err(prepare_err) =>
+			if prepare_err.is_deterministic() {
+				PreCheckOutcome::Invalid
+			} else {
+				PreCheckOutcome::Failed
+			},
 	}
 }
 
@@ -438,6 +781,7 @@ async fn validate_from_chain_state<Sender>(
 	pov: Arc<PoV>,
 	timeout: Duration,
 	metrics: &Metrics,
+	span: &jaeger::Span,
 ) -> Result<ValidationResult, ValidationFailed>
 where
 	Sender: SubsystemSender<RuntimeApiMessage>,
@@ -449,14 +793,23 @@ where
 			None => return Ok(ValidationResult::Invalid(InvalidCandidate::BadParent)),
 		};
 
+	let executor_params =
+		request_executor_params(sender, candidate_receipt.descriptor.relay_parent)
+			.await
+			.map_err(|RuntimeRequestFailed| {
+				ValidationFailed("Executor params: Bad request".into())
+			})?;
+
 	let validation_result = validate_candidate_exhaustive(
 		validation_host,
 		validation_data,
 		validation_code,
 		candidate_receipt.clone(),
 		pov,
+		executor_params,
 		timeout,
 		metrics,
+		span,
 	)
 	.await;
 
@@ -489,19 +842,23 @@ where
 	validation_result
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn validate_candidate_exhaustive(
 	mut validation_backend: impl ValidationBackend,
 	persisted_validation_data: PersistedValidationData,
 	validation_code: ValidationCode,
 	candidate_receipt: CandidateReceipt,
 	pov: Arc<PoV>,
+	executor_params: ExecutorParams,
 	timeout: Duration,
 	metrics: &Metrics,
+	parent_span: &jaeger::Span,
 ) -> Result<ValidationResult, ValidationFailed> {
 	let _timer = metrics.time_validate_candidate_exhaustive();
 
 	let validation_code_hash = validation_code.hash();
 	let para_id = candidate_receipt.descriptor.para_id.clone();
+	let span = parent_span.child("validate-candidate");
 	gum::debug!(
 		target: LOG_TARGET,
 		?validation_code_hash,
@@ -550,16 +907,25 @@ async fn validate_candidate_exhaustive(
 		relay_parent_storage_root: persisted_validation_data.relay_parent_storage_root,
 	};
 
-	let result = validation_backend
-		.validate_candidate(raw_validation_code.to_vec(), timeout, params)
-		.await;
+	let pvf = Pvf::from_code(raw_validation_code.to_vec(), executor_params);
+	let execution_span = span.child("pvf-execution");
+	let result = validation_backend.validate_candidate(pvf, timeout, params).await;
 
 	if let Err(ref error) = result {
 		gum::info!(target: LOG_TARGET, ?para_id, ?error, "Failed to validate candidate",);
 	}
 
+	let result = result.map(|(res, phases)| {
+		metrics.observe_execution_phases(&phases);
+		record_execution_phases(&execution_span, &phases);
+		res
+	});
+	drop(execution_span);
+
 	match result {
 		Err(ValidationError::InternalError(e)) => Err(ValidationFailed(e)),
+		Err(ValidationError::WallClockTimeout) =>
+			Err(ValidationFailed("execution exceeded the wall-clock timeout".to_string())),
 
 		Err(ValidationError::InvalidCandidate(WasmInvalidCandidate::HardTimeout)) =>
 			Ok(ValidationResult::Invalid(InvalidCandidate::Timeout)),
@@ -601,30 +967,46 @@ async fn validate_candidate_exhaustive(
 	}
 }
 
+/// Records each phase of a PVF execution as a child of the execution span.
+///
+/// The phases are only reported by the worker once the execution is over, so each child carries
+/// the measured duration of its phase as a tag.
+fn record_execution_phases(execution_span: &jaeger::Span, phases: &ExecutionPhases) {
+	let as_micros = |duration: Duration| duration.as_micros().try_into().unwrap_or(u64::MAX);
+	for (phase, duration) in phases.iter() {
+		let _phase_span =
+			execution_span.child(phase).with_uint_tag("duration-us", as_micros(duration));
+	}
+}
+
 #[async_trait]
 trait ValidationBackend {
+	/// Executes the PVF and returns the result along with the time spent in each phase of the
+	/// execution.
 	async fn validate_candidate(
 		&mut self,
-		raw_validation_code: Vec<u8>,
+		pvf: Pvf,
 		timeout: Duration,
 		params: ValidationParams,
-	) -> Result<WasmValidationResult, ValidationError>;
+	) -> Result<(WasmValidationResult, ExecutionPhases), ValidationError>;
 
 	async fn precheck_pvf(&mut self, pvf: Pvf) -> Result<(), PrepareError>;
+
+	async fn heads_up(&mut self, active_pvfs: Vec<Pvf>) -> Result<(), String>;
 }
 
 #[async_trait]
 impl ValidationBackend for ValidationHost {
 	async fn validate_candidate(
 		&mut self,
-		raw_validation_code: Vec<u8>,
+		pvf: Pvf,
 		timeout: Duration,
 		params: ValidationParams,
-	) -> Result<WasmValidationResult, ValidationError> {
+	) -> Result<(WasmValidationResult, ExecutionPhases), ValidationError> {
 		let (tx, rx) = oneshot::channel();
 		if let Err(err) = self
 			.execute_pvf(
-				Pvf::from_code(raw_validation_code),
+				pvf,
 				timeout,
 				params.encode(),
 				polkadot_node_core_pvf::Priority::Normal,
@@ -655,6 +1037,10 @@ impl ValidationBackend for ValidationHost {
 
 		precheck_result
 	}
+
+	async fn heads_up(&mut self, active_pvfs: Vec<Pvf>) -> Result<(), String> {
+		ValidationHost::heads_up(self, active_pvfs).await
+	}
 }
 
 /// Does basic checks of a candidate. Provide the encoded PoV-block. Returns `Ok` if basic checks
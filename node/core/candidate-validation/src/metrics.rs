@@ -15,6 +15,7 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::{ValidationFailed, ValidationResult};
+use polkadot_node_core_pvf::ExecutionPhases;
 use polkadot_node_subsystem_util::metrics::{self, prometheus};
 
 #[derive(Clone)]
@@ -23,6 +24,7 @@ pub(crate) struct MetricsInner {
 	pub(crate) validate_from_chain_state: prometheus::Histogram,
 	pub(crate) validate_from_exhaustive: prometheus::Histogram,
 	pub(crate) validate_candidate_exhaustive: prometheus::Histogram,
+	pub(crate) execution_phases: prometheus::HistogramVec,
 }
 
 /// Candidate validation metrics.
@@ -68,6 +70,18 @@ impl Metrics {
 			.as_ref()
 			.map(|metrics| metrics.validate_candidate_exhaustive.start_timer())
 	}
+
+	/// Observe the time spent in each phase of a successful PVF execution.
+	pub fn observe_execution_phases(&self, phases: &ExecutionPhases) {
+		if let Some(metrics) = &self.0 {
+			for (phase, duration) in phases.iter() {
+				metrics
+					.execution_phases
+					.with_label_values(&[phase])
+					.observe(duration.as_secs_f64());
+			}
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -104,6 +118,20 @@ impl metrics::Metrics for Metrics {
 				))?,
 				registry,
 			)?,
+			execution_phases: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_candidate_validation_execution_phase_time",
+						"Time spent in each phase of a successful PVF execution",
+					)
+					.buckets(vec![
+						0.0001, 0.0005, 0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.0,
+						5.0,
+					]),
+					&["phase"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
@@ -21,10 +21,18 @@ use futures::executor;
 use polkadot_node_core_pvf::PrepareError;
 use polkadot_node_subsystem::messages::AllMessages;
 use polkadot_node_subsystem_test_helpers as test_helpers;
-use polkadot_node_subsystem_util::reexports::SubsystemContext;
-use polkadot_primitives::v2::{HeadData, Id as ParaId, UpwardMessage};
+use polkadot_node_subsystem_util::{
+	metrics::{self, prometheus},
+	reexports::SubsystemContext,
+};
+use polkadot_primitives::v2::{
+	CoreState, GroupRotationInfo, HeadData, Id as ParaId, ScheduledCore, SessionInfo,
+	UpwardMessage, ValidatorId, ValidatorIndex,
+};
+use sp_application_crypto::AppKey;
 use sp_core::testing::TaskExecutor;
 use sp_keyring::Sr25519Keyring;
+use sp_keystore::SyncCryptoStore;
 
 #[test]
 fn correctly_checks_included_assumption() {
@@ -346,11 +354,17 @@ fn check_does_not_match() {
 
 struct MockValidateCandidateBackend {
 	result: Result<WasmValidationResult, ValidationError>,
+	phases: ExecutionPhases,
 }
 
 impl MockValidateCandidateBackend {
 	fn with_hardcoded_result(result: Result<WasmValidationResult, ValidationError>) -> Self {
-		Self { result }
+		Self { result, phases: Default::default() }
+	}
+
+	fn with_phases(mut self, phases: ExecutionPhases) -> Self {
+		self.phases = phases;
+		self
 	}
 }
 
@@ -358,16 +372,19 @@ impl MockValidateCandidateBackend {
 impl ValidationBackend for MockValidateCandidateBackend {
 	async fn validate_candidate(
 		&mut self,
-		_raw_validation_code: Vec<u8>,
+		_pvf: Pvf,
 		_timeout: Duration,
 		_params: ValidationParams,
-	) -> Result<WasmValidationResult, ValidationError> {
-		self.result.clone()
+	) -> Result<(WasmValidationResult, ExecutionPhases), ValidationError> {
+		self.result.clone().map(|result| (result, self.phases))
 	}
 
 	async fn precheck_pvf(&mut self, _pvf: Pvf) -> Result<(), PrepareError> {
 		unreachable!()
 	}
+	async fn heads_up(&mut self, _active_pvfs: Vec<Pvf>) -> Result<(), String> {
+		unreachable!()
+	}
 }
 
 #[test]
@@ -423,8 +440,10 @@ fn candidate_validation_ok_is_ok() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		ExecutorParams::default(),
 		Duration::from_secs(0),
 		&Default::default(),
+		&jaeger::Span::Disabled,
 	))
 	.unwrap();
 
@@ -438,6 +457,96 @@ fn candidate_validation_ok_is_ok() {
 	});
 }
 
+#[test]
+fn candidate_validation_ok_observes_execution_phases() {
+	let validation_data = PersistedValidationData { max_pov_size: 1024, ..Default::default() };
+
+	let pov = PoV { block_data: BlockData(vec![1; 32]) };
+	let head_data = HeadData(vec![1, 1, 1]);
+	let validation_code = ValidationCode(vec![2; 16]);
+
+	let descriptor = make_valid_candidate_descriptor(
+		ParaId::from(1_u32),
+		dummy_hash(),
+		validation_data.hash(),
+		pov.hash(),
+		validation_code.hash(),
+		head_data.hash(),
+		dummy_hash(),
+		Sr25519Keyring::Alice,
+	);
+
+	let validation_result = WasmValidationResult {
+		head_data,
+		new_validation_code: None,
+		upward_messages: Vec::new(),
+		horizontal_messages: Vec::new(),
+		processed_downward_messages: 0,
+		hrmp_watermark: 0,
+	};
+
+	let commitments = CandidateCommitments {
+		head_data: validation_result.head_data.clone(),
+		upward_messages: validation_result.upward_messages.clone(),
+		horizontal_messages: validation_result.horizontal_messages.clone(),
+		new_validation_code: validation_result.new_validation_code.clone(),
+		processed_downward_messages: validation_result.processed_downward_messages,
+		hrmp_watermark: validation_result.hrmp_watermark,
+	};
+
+	let candidate_receipt = CandidateReceipt { descriptor, commitments_hash: commitments.hash() };
+
+	let phases = ExecutionPhases {
+		artifact_load: Duration::from_millis(1),
+		instantiation: Duration::from_millis(2),
+		call: Duration::from_millis(30),
+		result_decoding: Duration::from_micros(5),
+		ipc: Duration::from_millis(1),
+	};
+
+	let registry = prometheus::Registry::new();
+	let metrics = <Metrics as metrics::Metrics>::try_register(&registry).unwrap();
+
+	let v = executor::block_on(validate_candidate_exhaustive(
+		MockValidateCandidateBackend::with_hardcoded_result(Ok(validation_result))
+			.with_phases(phases),
+		validation_data,
+		validation_code,
+		candidate_receipt,
+		Arc::new(pov),
+		ExecutorParams::default(),
+		Duration::from_secs(0),
+		&metrics,
+		&jaeger::Span::Disabled,
+	))
+	.unwrap();
+	assert_matches!(v, ValidationResult::Valid(_, _));
+
+	let gathered = registry.gather();
+	let family = gathered
+		.iter()
+		.find(|family| {
+			family.get_name() == "polkadot_parachain_candidate_validation_execution_phase_time"
+		})
+		.expect("execution phases are observed");
+	let observed: HashMap<String, f64> = family
+		.get_metric()
+		.iter()
+		.map(|metric| {
+			let labels: HashMap<_, _> =
+				metric.get_label().iter().map(|l| (l.get_name(), l.get_value())).collect();
+			assert_eq!(labels["para_id"], "1");
+			assert_eq!(metric.get_histogram().get_sample_count(), 1);
+			(labels["phase"].to_string(), metric.get_histogram().get_sample_sum())
+		})
+		.collect();
+
+	assert_eq!(observed.len(), 5);
+	for (phase, duration) in phases.iter() {
+		assert_eq!(observed[phase], duration.as_secs_f64());
+	}
+}
+
 #[test]
 fn candidate_validation_bad_return_is_invalid() {
 	let validation_data = PersistedValidationData { max_pov_size: 1024, ..Default::default() };
@@ -474,8 +583,10 @@ fn candidate_validation_bad_return_is_invalid() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		ExecutorParams::default(),
 		Duration::from_secs(0),
 		&Default::default(),
+		&jaeger::Span::Disabled,
 	))
 	.unwrap();
 
@@ -518,13 +629,88 @@ fn candidate_validation_timeout_is_internal_error() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		ExecutorParams::default(),
 		Duration::from_secs(0),
 		&Default::default(),
+		&jaeger::Span::Disabled,
 	));
 
 	assert_matches!(v, Ok(ValidationResult::Invalid(InvalidCandidate::Timeout)));
 }
 
+#[test]
+fn candidate_validation_wall_clock_timeout_is_internal_error() {
+	let validation_data = PersistedValidationData { max_pov_size: 1024, ..Default::default() };
+
+	let pov = PoV { block_data: BlockData(vec![1; 32]) };
+	let validation_code = ValidationCode(vec![2; 16]);
+
+	let descriptor = make_valid_candidate_descriptor(
+		ParaId::from(1_u32),
+		dummy_hash(),
+		validation_data.hash(),
+		pov.hash(),
+		validation_code.hash(),
+		dummy_hash(),
+		dummy_hash(),
+		Sr25519Keyring::Alice,
+	);
+
+	let candidate_receipt = CandidateReceipt { descriptor, commitments_hash: Hash::zero() };
+
+	let v = executor::block_on(validate_candidate_exhaustive(
+		MockValidateCandidateBackend::with_hardcoded_result(Err(ValidationError::WallClockTimeout)),
+		validation_data,
+		validation_code,
+		candidate_receipt,
+		Arc::new(pov),
+		ExecutorParams::default(),
+		Duration::from_secs(0),
+		&Default::default(),
+		&jaeger::Span::Disabled,
+	));
+
+	assert_matches!(v, Err(ValidationFailed(_)));
+}
+
+#[test]
+fn candidate_validation_forbidden_syscall_is_internal_error() {
+	let validation_data = PersistedValidationData { max_pov_size: 1024, ..Default::default() };
+
+	let pov = PoV { block_data: BlockData(vec![1; 32]) };
+	let validation_code = ValidationCode(vec![2; 16]);
+
+	let descriptor = make_valid_candidate_descriptor(
+		ParaId::from(1_u32),
+		dummy_hash(),
+		validation_data.hash(),
+		pov.hash(),
+		validation_code.hash(),
+		dummy_hash(),
+		dummy_hash(),
+		Sr25519Keyring::Alice,
+	);
+
+	let candidate_receipt = CandidateReceipt { descriptor, commitments_hash: Hash::zero() };
+
+	let v = executor::block_on(validate_candidate_exhaustive(
+		// The error the PVF host reports for a worker killed for a forbidden syscall.
+		MockValidateCandidateBackend::with_hardcoded_result(Err(ValidationError::from(
+			PrepareError::ForbiddenSyscall,
+		))),
+		validation_data,
+		validation_code,
+		candidate_receipt,
+		Arc::new(pov),
+		ExecutorParams::default(),
+		Duration::from_secs(0),
+		&Default::default(),
+		&jaeger::Span::Disabled,
+	));
+
+	assert_matches!(v, Err(ValidationFailed(_)));
+}
+
 #[test]
 fn candidate_validation_commitment_hash_mismatch_is_invalid() {
 	let validation_data = PersistedValidationData { max_pov_size: 1024, ..Default::default() };
@@ -562,8 +748,10 @@ fn candidate_validation_commitment_hash_mismatch_is_invalid() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		ExecutorParams::default(),
 		Duration::from_secs(0),
 		&Default::default(),
+		&jaeger::Span::Disabled,
 	))
 	.unwrap();
 
@@ -607,8 +795,10 @@ fn candidate_validation_code_mismatch_is_invalid() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		ExecutorParams::default(),
 		Duration::from_secs(0),
 		&Default::default(),
+		&jaeger::Span::Disabled,
 	))
 	.unwrap();
 
@@ -663,8 +853,10 @@ fn compressed_code_works() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		ExecutorParams::default(),
 		Duration::from_secs(0),
 		&Default::default(),
+		&jaeger::Span::Disabled,
 	));
 
 	assert_matches!(v, Ok(ValidationResult::Valid(_, _)));
@@ -710,8 +902,10 @@ fn code_decompression_failure_is_invalid() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		ExecutorParams::default(),
 		Duration::from_secs(0),
 		&Default::default(),
+		&jaeger::Span::Disabled,
 	));
 
 	assert_matches!(v, Ok(ValidationResult::Invalid(InvalidCandidate::CodeDecompressionFailure)));
@@ -758,8 +952,10 @@ fn pov_decompression_failure_is_invalid() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		ExecutorParams::default(),
 		Duration::from_secs(0),
 		&Default::default(),
+		&jaeger::Span::Disabled,
 	));
 
 	assert_matches!(v, Ok(ValidationResult::Invalid(InvalidCandidate::PoVDecompressionFailure)));
@@ -779,16 +975,48 @@ impl MockPreCheckBackend {
 impl ValidationBackend for MockPreCheckBackend {
 	async fn validate_candidate(
 		&mut self,
-		_raw_validation_code: Vec<u8>,
+		_pvf: Pvf,
 		_timeout: Duration,
 		_params: ValidationParams,
-	) -> Result<WasmValidationResult, ValidationError> {
+	) -> Result<(WasmValidationResult, ExecutionPhases), ValidationError> {
 		unreachable!()
 	}
 
 	async fn precheck_pvf(&mut self, _pvf: Pvf) -> Result<(), PrepareError> {
 		self.result.clone()
 	}
+	async fn heads_up(&mut self, _active_pvfs: Vec<Pvf>) -> Result<(), String> {
+		unreachable!()
+	}
+}
+
+async fn expect_executor_params_request(
+	ctx_handle: &mut test_helpers::TestSubsystemContextHandle<AllMessages>,
+	relay_parent: Hash,
+	executor_params: Result<Option<ExecutorParams>, RuntimeApiError>,
+) {
+	let session_index = 1;
+	assert_matches!(
+		ctx_handle.recv().await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			rp,
+			RuntimeApiRequest::SessionIndexForChild(tx),
+		)) => {
+			assert_eq!(rp, relay_parent);
+			let _ = tx.send(Ok(session_index));
+		}
+	);
+	assert_matches!(
+		ctx_handle.recv().await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			rp,
+			RuntimeApiRequest::SessionExecutorParams(session, tx),
+		)) => {
+			assert_eq!(rp, relay_parent);
+			assert_eq!(session, session_index);
+			let _ = tx.send(executor_params);
+		}
+	);
 }
 
 #[test]
@@ -825,6 +1053,7 @@ fn precheck_works() {
 				let _ = tx.send(Ok(Some(validation_code.clone())));
 			}
 		);
+		expect_executor_params_request(&mut ctx_handle, relay_parent, Ok(None)).await;
 		assert_matches!(check_result.await, PreCheckOutcome::Valid);
 	};
 
@@ -913,6 +1142,12 @@ fn precheck_properly_classifies_outcomes() {
 					let _ = tx.send(Ok(Some(validation_code.clone())));
 				}
 			);
+			expect_executor_params_request(
+				&mut ctx_handle,
+				relay_parent,
+				Ok(Some(ExecutorParams::default())),
+			)
+			.await;
 			assert_eq!(check_result.await, precheck_outcome);
 		};
 
@@ -926,4 +1161,231 @@ fn precheck_properly_classifies_outcomes() {
 
 	inner(Err(PrepareError::TimedOut), PreCheckOutcome::Failed);
 	inner(Err(PrepareError::DidNotMakeIt), PreCheckOutcome::Failed);
+	inner(Err(PrepareError::OutOfMemory(1 << 32)), PreCheckOutcome::Failed);
+	inner(Err(PrepareError::ForbiddenSyscall), PreCheckOutcome::Failed);
+}
+
+#[test]
+fn precheck_falls_back_to_default_executor_params() {
+	let inner = |executor_params, precheck_outcome| {
+		let relay_parent = [3; 32].into();
+		let validation_code = ValidationCode(vec![3; 16]);
+		let validation_code_hash = validation_code.hash();
+
+		let pool = TaskExecutor::new();
+		let (mut ctx, mut ctx_handle) =
+			test_helpers::make_subsystem_context::<AllMessages, _>(pool.clone());
+
+		let (check_fut, check_result) = precheck_pvf(
+			ctx.sender(),
+			MockPreCheckBackend::with_hardcoded_result(Ok(())),
+			relay_parent,
+			validation_code_hash,
+		)
+		.remote_handle();
+
+		let test_fut = async move {
+			assert_matches!(
+				ctx_handle.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_,
+					RuntimeApiRequest::ValidationCodeByHash(_, tx),
+				)) => {
+					let _ = tx.send(Ok(Some(validation_code.clone())));
+				}
+			);
+			expect_executor_params_request(&mut ctx_handle, relay_parent, executor_params).await;
+			assert_eq!(check_result.await, precheck_outcome);
+		};
+
+		let test_fut = future::join(test_fut, check_fut);
+		executor::block_on(test_fut);
+	};
+
+	// The runtime doesn't know about the executor params yet.
+	inner(
+		Err(RuntimeApiError::NotSupported { runtime_api_name: "session_executor_params" }),
+		PreCheckOutcome::Valid,
+	);
+	// The session predates the executor params.
+	inner(Ok(None), PreCheckOutcome::Valid);
+	// The params cannot be obtained, so we cannot tell anything about the PVF.
+	inner(
+		Err(RuntimeApiError::Execution {
+			runtime_api_name: "session_executor_params",
+			source: std::sync::Arc::new(std::fmt::Error),
+		}),
+		PreCheckOutcome::Failed,
+	);
+}
+
+#[derive(Default)]
+struct MockHeadsUpBackend {
+	heads_ups: Vec<Vec<Pvf>>,
+}
+
+#[async_trait]
+impl ValidationBackend for MockHeadsUpBackend {
+	async fn validate_candidate(
+		&mut self,
+		_pvf: Pvf,
+		_timeout: Duration,
+		_params: ValidationParams,
+	) -> Result<(WasmValidationResult, ExecutionPhases), ValidationError> {
+		unreachable!()
+	}
+
+	async fn precheck_pvf(&mut self, _pvf: Pvf) -> Result<(), PrepareError> {
+		unreachable!()
+	}
+
+	async fn heads_up(&mut self, active_pvfs: Vec<Pvf>) -> Result<(), String> {
+		self.heads_ups.push(active_pvfs);
+		Ok(())
+	}
+}
+
+#[test]
+fn prewarms_future_code_of_assigned_paras() {
+	let keystore = Arc::new(sc_keystore::LocalKeystore::in_memory());
+	SyncCryptoStore::sr25519_generate_new(
+		&*keystore,
+		ValidatorId::ID,
+		Some(&Sr25519Keyring::Alice.to_seed()),
+	)
+	.expect("Generating keys for our node failed");
+
+	let session_index = 1;
+	let session_info = SessionInfo {
+		active_validator_indices: vec![ValidatorIndex(0), ValidatorIndex(1)],
+		random_seed: [0; 32],
+		dispute_period: 6,
+		validators: vec![
+			Sr25519Keyring::Alice.public().into(),
+			Sr25519Keyring::Bob.public().into(),
+		],
+		discovery_keys: vec![],
+		assignment_keys: vec![],
+		validator_groups: vec![vec![ValidatorIndex(0)], vec![ValidatorIndex(1)]],
+		n_cores: 2,
+		zeroth_delay_tranche_width: 1,
+		relay_vrf_modulo_samples: 1,
+		n_delay_tranches: 1,
+		no_show_slots: 1,
+		needed_approvals: 1,
+	};
+	// With no rotations yet, Alice's group is assigned to the first core.
+	let assigned_para = ParaId::from(1_u32);
+	let cores = vec![
+		CoreState::Scheduled(ScheduledCore { para_id: assigned_para, collator: None }),
+		CoreState::Scheduled(ScheduledCore { para_id: ParaId::from(2_u32), collator: None }),
+	];
+	let group_rotation_info =
+		GroupRotationInfo { session_start_block: 0, group_rotation_frequency: 10, now: 1 };
+
+	let raw_code = vec![3; 16];
+	let future_code = ValidationCode(raw_code.clone());
+	let future_code_hash = future_code.hash();
+
+	let pool = TaskExecutor::new();
+	let (mut ctx, mut ctx_handle) =
+		test_helpers::make_subsystem_context::<AllMessages, _>(pool.clone());
+
+	let mut backend = MockHeadsUpBackend::default();
+	let mut runtime_info = RuntimeInfo::new(Some(keystore));
+	let mut future_code_cache = HashMap::new();
+	let leaves: [Hash; 2] = [[1; 32].into(), [2; 32].into()];
+
+	let prewarm_fut = async {
+		for leaf in leaves {
+			prewarm_future_code(
+				ctx.sender(),
+				&mut backend,
+				&mut runtime_info,
+				&mut future_code_cache,
+				leaf,
+			)
+			.await;
+		}
+	};
+
+	let test_fut = async move {
+		for (i, leaf) in leaves.into_iter().enumerate() {
+			assert_matches!(
+				ctx_handle.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					rp,
+					RuntimeApiRequest::SessionIndexForChild(tx),
+				)) => {
+					assert_eq!(rp, leaf);
+					let _ = tx.send(Ok(session_index));
+				}
+			);
+			// The session info is cached after the first leaf.
+			if i == 0 {
+				assert_matches!(
+					ctx_handle.recv().await,
+					AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_,
+						RuntimeApiRequest::SessionInfo(index, tx),
+					)) => {
+						assert_eq!(index, session_index);
+						let _ = tx.send(Ok(Some(session_info.clone())));
+					}
+				);
+			}
+			assert_matches!(
+				ctx_handle.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_,
+					RuntimeApiRequest::AvailabilityCores(tx),
+				)) => {
+					let _ = tx.send(Ok(cores.clone()));
+				}
+			);
+			assert_matches!(
+				ctx_handle.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_,
+					RuntimeApiRequest::ValidatorGroups(tx),
+				)) => {
+					let _ = tx.send(Ok((
+						session_info.validator_groups.clone(),
+						group_rotation_info.clone(),
+					)));
+				}
+			);
+			// Only the para assigned to our group is queried.
+			assert_matches!(
+				ctx_handle.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_,
+					RuntimeApiRequest::FutureValidationCodeHash(para_id, tx),
+				)) => {
+					assert_eq!(para_id, assigned_para);
+					let _ = tx.send(Ok(Some(future_code_hash)));
+				}
+			);
+			// The code is fetched only once.
+			if i == 0 {
+				assert_matches!(
+					ctx_handle.recv().await,
+					AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_,
+						RuntimeApiRequest::ValidationCodeByHash(hash, tx),
+					)) => {
+						assert_eq!(hash, future_code_hash);
+						let _ = tx.send(Ok(Some(future_code.clone())));
+					}
+				);
+				expect_executor_params_request(&mut ctx_handle, leaf, Ok(None)).await;
+			}
+		}
+	};
+
+	executor::block_on(future::join(test_fut, prewarm_fut));
+
+	// The code is prewarmed only once per session.
+	let expected_pvf = Pvf::from_code(raw_code, ExecutorParams::default());
+	assert_eq!(backend.heads_ups, vec![vec![expected_pvf]]);
 }
@@ -16,7 +16,10 @@
 
 //! Dispute coordinator subsystem in initialized state (after first active leaf is received).
 
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+	collections::{BTreeMap, HashMap, HashSet},
+	sync::Arc,
+};
 
 use futures::{
 	channel::{mpsc, oneshot},
@@ -26,8 +29,8 @@ use futures::{
 use sc_keystore::LocalKeystore;
 
 use polkadot_node_primitives::{
-	CandidateVotes, DisputeMessage, DisputeMessageCheckError, DisputeStatus,
-	SignedDisputeStatement, Timestamp, DISPUTE_WINDOW,
+	dispute_is_inactive, CandidateVotes, DisputeInfo, DisputeMessage, DisputeMessageCheckError,
+	DisputeStatus, DisputesFilter, SignedDisputeStatement, Timestamp, DISPUTE_WINDOW,
 };
 use polkadot_node_subsystem::{
 	messages::{
@@ -36,13 +39,20 @@ use polkadot_node_subsystem::{
 	},
 	overseer, ActivatedLeaf, ActiveLeavesUpdate, FromOrchestra, OverseerSignal,
 };
-use polkadot_node_subsystem_util::rolling_session_window::{
-	RollingSessionWindow, SessionWindowUpdate, SessionsUnavailable,
+use polkadot_node_subsystem_util::{
+	rolling_session_window::{RollingSessionWindow, SessionWindowUpdate, SessionsUnavailable},
+	runtime::{
+		get_disabled_validators, get_unapplied_slashes, key_ownership_proof,
+		submit_report_dispute_lost,
+	},
 };
-use polkadot_primitives::v2::{
-	BlockNumber, CandidateHash, CandidateReceipt, CompactStatement, DisputeStatement,
-	DisputeStatementSet, Hash, ScrapedOnChainVotes, SessionIndex, SessionInfo,
-	ValidDisputeStatementKind, ValidatorId, ValidatorIndex,
+use polkadot_primitives::{
+	v2::{
+		BlockNumber, CandidateHash, CandidateReceipt, CompactStatement, DisputeStatement,
+		DisputeStatementSet, Hash, Id as ParaId, ScrapedOnChainVotes, SessionIndex, SessionInfo,
+		ValidDisputeStatementKind, ValidatorId, ValidatorIndex,
+	},
+	vstaging::slashing,
 };
 
 use crate::{
@@ -57,14 +67,22 @@ use super::{
 	backend::Backend,
 	db,
 	participation::{
-		self, Participation, ParticipationPriority, ParticipationRequest, ParticipationStatement,
-		WorkerMessageReceiver,
+		self, Participation, ParticipationOutcome, ParticipationPriority, ParticipationRequest,
+		ParticipationStatement, WorkerMessageReceiver,
 	},
 	scraping::ChainScraper,
 	spam_slots::SpamSlots,
 	OverlayedBackend,
 };
 
+/// The number of blocks after which a report of a lost dispute is submitted again, if the slash is
+/// still unapplied by then. The report may have been dropped from the transaction pool.
+pub(crate) const SLASH_REPORT_RESUBMISSION_BLOCKS: BlockNumber = 10;
+
+/// The maximum number of disputes returned on a `DisputeCoordinatorMessage::QueryDisputes`, the
+/// ones of the most recent sessions.
+pub(crate) const MAX_QUERIED_DISPUTES: usize = 256;
+
 /// After the first active leaves update we transition to `Initialized` state.
 ///
 /// Before the first active leaves update we can't really do much. We cannot check incoming
@@ -78,6 +96,16 @@ pub struct Initialized {
 	participation: Participation,
 	scraper: ChainScraper,
 	participation_receiver: WorkerMessageReceiver,
+	/// Pending slashes we already submitted a report for, along with the number of the block the
+	/// report was submitted at. The reports stay in the transaction pool until they get included,
+	/// so they are only submitted again after `SLASH_REPORT_RESUBMISSION_BLOCKS`.
+	reported_slashes: HashMap<(SessionIndex, CandidateHash, ValidatorIndex), BlockNumber>,
+	/// Outcomes of our participations in disputes within the session window, for introspection.
+	/// Only our votes get persisted, so this covers the participations since startup only.
+	participation_outcomes: HashMap<(SessionIndex, CandidateHash), ParticipationOutcome>,
+	/// The paras of the disputed candidates within the session window, so disputes can be
+	/// filtered by para without loading their votes. Filled in on demand after a restart.
+	dispute_paras: HashMap<(SessionIndex, CandidateHash), ParaId>,
 	metrics: Metrics,
 	// This tracks only rolling session window failures.
 	// It can be a `Vec` if the need to track more arises.
@@ -93,10 +121,11 @@ impl Initialized {
 		spam_slots: SpamSlots,
 		scraper: ChainScraper,
 	) -> Self {
-		let DisputeCoordinatorSubsystem { config: _, store: _, keystore, metrics } = subsystem;
+		let DisputeCoordinatorSubsystem { config, store: _, keystore, metrics } = subsystem;
 
 		let (participation_sender, participation_receiver) = mpsc::channel(1);
-		let participation = Participation::new(participation_sender);
+		let participation =
+			Participation::new(participation_sender, config.participation_queues, metrics.clone());
 		let highest_session = rolling_session_window.latest_session();
 
 		Self {
@@ -107,6 +136,9 @@ impl Initialized {
 			scraper,
 			participation,
 			participation_receiver,
+			reported_slashes: HashMap::new(),
+			participation_outcomes: HashMap::new(),
+			dispute_paras: HashMap::new(),
 			metrics,
 			error: None,
 		}
@@ -208,7 +240,9 @@ impl Initialized {
 							candidate_receipt,
 							outcome,
 						} = self.participation.get_participation_result(ctx, msg).await?;
-						if let Some(valid) = outcome.validity() {
+						let validity = outcome.validity();
+						self.participation_outcomes.insert((session, candidate_hash), outcome);
+						if let Some(valid) = validity {
 							gum::trace!(
 								target: LOG_TARGET,
 								?session,
@@ -269,6 +303,23 @@ impl Initialized {
 		update: ActiveLeavesUpdate,
 		now: u64,
 	) -> Result<()> {
+		let mut disabled_validators = None;
+		if let Some(new_leaf) = &update.activated {
+			self.process_unapplied_slashes(ctx, new_leaf.hash, new_leaf.number).await;
+
+			match get_disabled_validators(ctx.sender(), new_leaf.hash).await {
+				Ok(disabled) => disabled_validators = Some(disabled),
+				Err(error) => {
+					gum::debug!(
+						target: LOG_TARGET,
+						?error,
+						relay_parent = ?new_leaf.hash,
+						"Failed to fetch disabled validators",
+					);
+				},
+			}
+		}
+
 		let on_chain_votes =
 			self.scraper.process_active_leaves_update(ctx.sender(), &update).await?;
 		self.participation.process_active_leaves_update(ctx, &update).await?;
@@ -301,11 +352,18 @@ impl Initialized {
 
 						db::v1::note_current_session(overlay_db, session)?;
 						self.spam_slots.prune_old(new_window_start);
+						self.participation_outcomes
+							.retain(|(session, _), _| *session >= new_window_start);
+						self.dispute_paras.retain(|(session, _), _| *session >= new_window_start);
 					}
 				},
 				Ok(SessionWindowUpdate::Unchanged) => {},
 			};
 
+			if let Some((session, disabled)) = disabled_validators {
+				self.spam_slots.set_disabled(session, disabled);
+			}
+
 			// The `runtime-api` subsystem has an internal queue which serializes the execution,
 			// so there is no point in running these in parallel.
 			for votes in on_chain_votes {
@@ -324,6 +382,173 @@ impl Initialized {
 		Ok(())
 	}
 
+	/// Report the validators who lost a dispute about a candidate of a past session, so they get
+	/// slashed.
+	///
+	/// Disputes about a candidate of the current session are slashed by the runtime right away,
+	/// for past sessions it needs a proof of the offenders' session keys. Such a proof gets
+	/// generated and submitted along with the report as an unsigned transaction.
+	async fn process_unapplied_slashes<Context>(
+		&mut self,
+		ctx: &mut Context,
+		relay_parent: Hash,
+		relay_parent_number: BlockNumber,
+	) {
+		let unapplied_slashes = match get_unapplied_slashes(ctx.sender(), relay_parent).await {
+			Ok(unapplied_slashes) => unapplied_slashes,
+			Err(error) => {
+				gum::debug!(
+					target: LOG_TARGET,
+					?error,
+					?relay_parent,
+					"Failed to fetch unapplied slashes",
+				);
+				return
+			},
+		};
+
+		// Slashes which got applied, or are too old to be applied anymore, are gone from the
+		// runtime and need not be remembered either.
+		let pending: HashSet<_> = unapplied_slashes
+			.iter()
+			.flat_map(|(session_index, candidate_hash, pending)| {
+				pending
+					.keys
+					.keys()
+					.map(move |validator_index| (*session_index, *candidate_hash, *validator_index))
+			})
+			.collect();
+		self.reported_slashes.retain(|slash, _| pending.contains(slash));
+
+		for (session_index, candidate_hash, pending) in unapplied_slashes {
+			for (validator_index, validator_id) in pending.keys {
+				let slash = (session_index, candidate_hash, validator_index);
+				let due = self.reported_slashes.get(&slash).map_or(true, |reported_at| {
+					relay_parent_number >= reported_at + SLASH_REPORT_RESUBMISSION_BLOCKS
+				});
+				if !due {
+					continue
+				}
+
+				gum::debug!(
+					target: LOG_TARGET,
+					?session_index,
+					?candidate_hash,
+					?validator_index,
+					kind = ?pending.kind,
+					"Reporting lost dispute of a past session",
+				);
+
+				let reported = self
+					.report_dispute_lost(
+						ctx,
+						relay_parent,
+						slashing::DisputeProof {
+							time_slot: slashing::DisputesTimeSlot::new(
+								session_index,
+								candidate_hash,
+							),
+							kind: pending.kind,
+							validator_index,
+							validator_id,
+						},
+					)
+					.await;
+				if reported {
+					self.reported_slashes.insert(slash, relay_parent_number);
+				} else {
+					// Try again on the next leaf.
+					self.reported_slashes.remove(&slash);
+				}
+			}
+		}
+	}
+
+	/// Generate the key ownership proof of the offender and submit the report of the lost
+	/// dispute. Returns whether the report got submitted, or can't ever be.
+	async fn report_dispute_lost<Context>(
+		&mut self,
+		ctx: &mut Context,
+		relay_parent: Hash,
+		dispute_proof: slashing::DisputeProof,
+	) -> bool {
+		let time_slot = dispute_proof.time_slot.clone();
+		let validator_index = dispute_proof.validator_index;
+
+		let key_ownership_proof = match key_ownership_proof(
+			ctx.sender(),
+			relay_parent,
+			dispute_proof.validator_id.clone(),
+		)
+		.await
+		{
+			Ok(Some(key_ownership_proof)) => key_ownership_proof,
+			Ok(None) => {
+				// The validator is not part of the current validator set anymore.
+				gum::debug!(
+					target: LOG_TARGET,
+					?time_slot,
+					?validator_index,
+					"No key ownership proof for the offender of a lost dispute",
+				);
+				self.metrics.on_slashing_report_failed();
+				return true
+			},
+			Err(error) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					?error,
+					?time_slot,
+					?validator_index,
+					"Failed to generate key ownership proof",
+				);
+				self.metrics.on_slashing_report_failed();
+				return false
+			},
+		};
+
+		match submit_report_dispute_lost(
+			ctx.sender(),
+			relay_parent,
+			dispute_proof,
+			key_ownership_proof,
+		)
+		.await
+		{
+			Ok(Some(())) => {
+				gum::info!(
+					target: LOG_TARGET,
+					?time_slot,
+					?validator_index,
+					"Submitted report of lost dispute",
+				);
+				self.metrics.on_slashing_report_submitted();
+				true
+			},
+			Ok(None) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					?time_slot,
+					?validator_index,
+					"Runtime rejected the report of lost dispute",
+				);
+				self.metrics.on_slashing_report_failed();
+				true
+			},
+			Err(error) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					?error,
+					?time_slot,
+					?validator_index,
+					"Failed to submit report of lost dispute",
+				);
+				self.metrics.on_slashing_report_failed();
+				false
+			},
+		}
+	}
+
 	/// Scrapes on-chain votes (backing votes and concluded disputes) for a active leaf of the
 	/// relay chain.
 	async fn process_on_chain_votes<Context>(
@@ -643,6 +868,15 @@ impl Initialized {
 				}
 				let _ = tx.send(query_output);
 			},
+			DisputeCoordinatorMessage::QueryDisputes(filter, tx) => {
+				// Return error if session information is missing.
+				self.ensure_available_session_info()?;
+
+				gum::trace!(target: LOG_TARGET, ?filter, "DisputeCoordinatorMessage::QueryDisputes");
+
+				let disputes = self.query_disputes(overlay_db, &filter, now)?;
+				let _ = tx.send(disputes);
+			},
 			DisputeCoordinatorMessage::IssueLocalStatement(
 				session,
 				candidate_hash,
@@ -687,6 +921,77 @@ impl Initialized {
 		Ok(Box::new(|| Ok(())))
 	}
 
+	/// Collect what we know about the recent disputes passing the filter, at most
+	/// `MAX_QUERIED_DISPUTES` of them, most recent sessions first.
+	fn query_disputes(
+		&mut self,
+		overlay_db: &mut OverlayedBackend<'_, impl Backend>,
+		filter: &DisputesFilter,
+		now: Timestamp,
+	) -> Result<Vec<DisputeInfo>> {
+		let recent_disputes = overlay_db.load_recent_disputes()?.unwrap_or_default();
+
+		let mut disputes = Vec::new();
+		for ((session, candidate_hash), status) in recent_disputes.into_iter().rev() {
+			if disputes.len() >= MAX_QUERIED_DISPUTES {
+				break
+			}
+			if filter.session.map_or(false, |s| s != session) {
+				continue
+			}
+			let active = !dispute_is_inactive(&status, &now);
+			if filter.active_only && !active {
+				continue
+			}
+			if let (Some(wanted), Some(para_id)) =
+				(filter.para_id, self.dispute_paras.get(&(session, candidate_hash)))
+			{
+				if *para_id != wanted {
+					continue
+				}
+			}
+
+			let votes: CandidateVotes =
+				match overlay_db.load_candidate_votes(session, &candidate_hash)? {
+					Some(votes) => votes.into(),
+					None => {
+						gum::debug!(
+							target: LOG_TARGET,
+							?candidate_hash,
+							session,
+							"No votes found for recent dispute",
+						);
+						continue
+					},
+				};
+			let para_id = votes.candidate_receipt.descriptor.para_id;
+			self.dispute_paras.insert((session, candidate_hash), para_id);
+			if !filter.matches(session, para_id) {
+				continue
+			}
+
+			let mut own_validator_indices: Vec<_> =
+				CandidateEnvironment::new(&*self.keystore, &self.rolling_session_window, session)
+					.map(|env| env.controlled_indices().iter().copied().collect())
+					.unwrap_or_default();
+			own_validator_indices.sort();
+
+			disputes.push(DisputeInfo {
+				session,
+				candidate_hash,
+				para_id,
+				status,
+				active,
+				valid_votes: votes.valid.into_iter().map(|(i, (kind, _))| (i, kind)).collect(),
+				invalid_votes: votes.invalid.into_iter().map(|(i, (kind, _))| (i, kind)).collect(),
+				own_validator_indices,
+				participation: self.participation_outcomes.get(&(session, candidate_hash)).cloned(),
+			});
+		}
+
+		Ok(disputes)
+	}
+
 	// Helper function for checking subsystem errors in message processing.
 	fn ensure_available_session_info(&self) -> Result<()> {
 		if let Some(subsystem_error) = self.error.clone() {
@@ -892,7 +1197,8 @@ impl Initialized {
 				.queue_participation(
 					ctx,
 					priority,
-					ParticipationRequest::new(new_state.candidate_receipt().clone(), session),
+					ParticipationRequest::new(new_state.candidate_receipt().clone(), session)
+						.with_reporter_from(new_state.votes()),
 				)
 				.await;
 			log_error(r)?;
@@ -952,6 +1258,10 @@ impl Initialized {
 
 		// All good, update recent disputes if state has changed:
 		if import_result.dispute_state_changed() {
+			self.dispute_paras.insert(
+				(session, candidate_hash),
+				new_state.candidate_receipt().descriptor.para_id,
+			);
 			let mut recent_disputes = overlay_db.load_recent_disputes()?.unwrap_or_default();
 
 			let status = recent_disputes.entry((session, candidate_hash)).or_insert_with(|| {
@@ -50,9 +50,11 @@ use fatality::Split;
 
 use self::{
 	participation::{ParticipationPriority, ParticipationRequest},
-	spam_slots::{SpamSlots, UnconfirmedDisputes},
+	spam_slots::{SpamCount, SpamSlots, UnconfirmedDisputes},
 };
 
+pub use self::{participation::QueueConfig, spam_slots::DEFAULT_MAX_SPAM_VOTES};
+
 pub(crate) mod backend;
 pub(crate) mod db;
 pub(crate) mod error;
@@ -118,6 +120,10 @@ pub struct DisputeCoordinatorSubsystem {
 pub struct Config {
 	/// The data column in the store to use for dispute data.
 	pub col_data: u32,
+	/// Limits of the participation queues.
+	pub participation_queues: QueueConfig,
+	/// How many unconfirmed disputes a validator is allowed to import per session.
+	pub max_spam_votes: SpamCount,
 }
 
 impl Config {
@@ -334,7 +340,8 @@ impl DisputeCoordinatorSubsystem {
 			if missing_local_statement {
 				participation_requests.push((
 					ParticipationPriority::with_priority_if(is_included),
-					ParticipationRequest::new(votes.candidate_receipt.clone(), session),
+					ParticipationRequest::new(votes.candidate_receipt.clone(), session)
+						.with_reporter_from(&votes),
 				));
 			}
 		}
@@ -342,7 +349,7 @@ impl DisputeCoordinatorSubsystem {
 		Ok((
 			participation_requests,
 			votes,
-			SpamSlots::recover_from_state(unconfirmed_disputes),
+			SpamSlots::recover_from_state(unconfirmed_disputes, self.config.max_spam_votes),
 			scraper,
 		))
 	}
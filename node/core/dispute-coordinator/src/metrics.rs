@@ -28,8 +28,12 @@ struct MetricsInner {
 	concluded: prometheus::CounterVec<prometheus::U64>,
 	/// Number of participations that have been queued.
 	queued_participations: prometheus::CounterVec<prometheus::U64>,
+	/// Number of participations currently waiting in the queues.
+	participation_queue_depths: prometheus::GaugeVec<prometheus::U64>,
 	/// How long vote cleanup batches take.
 	vote_cleanup_time: prometheus::Histogram,
+	/// Reports of lost disputes about candidates of past sessions, by outcome.
+	slashing_reports: prometheus::CounterVec<prometheus::U64>,
 }
 
 /// Candidate validation metrics.
@@ -85,9 +89,34 @@ impl Metrics {
 		}
 	}
 
+	pub(crate) fn on_participation_queue_depths(&self, priority: usize, best_effort: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics
+				.participation_queue_depths
+				.with_label_values(&["priority"])
+				.set(priority as _);
+			metrics
+				.participation_queue_depths
+				.with_label_values(&["best-effort"])
+				.set(best_effort as _);
+		}
+	}
+
 	pub(crate) fn time_vote_cleanup(&self) -> Option<prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.vote_cleanup_time.start_timer())
 	}
+
+	pub(crate) fn on_slashing_report_submitted(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.slashing_reports.with_label_values(&["submitted"]).inc();
+		}
+	}
+
+	pub(crate) fn on_slashing_report_failed(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.slashing_reports.with_label_values(&["failed"]).inc();
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -137,6 +166,16 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			participation_queue_depths: prometheus::register(
+				prometheus::GaugeVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_dispute_participation_queue_depth",
+						"Number of participations waiting in the queues, grouped by priority and best-effort.",
+					),
+					&["priority"],
+				)?,
+				registry,
+			)?,
 			vote_cleanup_time: prometheus::register(
 				prometheus::Histogram::with_opts(
 					prometheus::HistogramOpts::new(
@@ -147,6 +186,16 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			slashing_reports: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_dispute_slashing_reports_total",
+						"Reports of lost disputes about candidates of past sessions, by whether they got `submitted` or `failed`.",
+					),
+					&["outcome"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
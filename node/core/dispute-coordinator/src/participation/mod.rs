@@ -25,15 +25,20 @@ use futures::{
 #[cfg(test)]
 use futures_timer::Delay;
 
+pub use polkadot_node_primitives::ParticipationOutcome;
 use polkadot_node_primitives::{ValidationResult, APPROVAL_EXECUTION_TIMEOUT};
 use polkadot_node_subsystem::{
-	messages::{AvailabilityRecoveryMessage, CandidateValidationMessage},
+	jaeger,
+	messages::{
+		AvailabilityRecoveryMessage, CandidateValidationMessage, RecoveryPolicy,
+		RecoveryStrategyKind,
+	},
 	overseer, ActiveLeavesUpdate, RecoveryError,
 };
 use polkadot_node_subsystem_util::runtime::get_validation_code_by_hash;
 use polkadot_primitives::v2::{BlockNumber, CandidateHash, CandidateReceipt, Hash, SessionIndex};
 
-use crate::LOG_TARGET;
+use crate::{metrics::Metrics, LOG_TARGET};
 
 use crate::error::{FatalError, FatalResult, Result};
 
@@ -44,7 +49,7 @@ pub use tests::{participation_full_happy_path, participation_missing_availabilit
 
 mod queues;
 use queues::Queues;
-pub use queues::{ParticipationPriority, ParticipationRequest, QueueError};
+pub use queues::{ParticipationPriority, ParticipationRequest, QueueConfig, QueueError};
 
 /// How many participation processes do we want to run in parallel the most.
 ///
@@ -66,6 +71,8 @@ pub struct Participation {
 	worker_sender: WorkerMessageSender,
 	/// Some recent block for retrieving validation code from chain.
 	recent_block: Option<(BlockNumber, Hash)>,
+	/// For reporting the depths of the queues.
+	metrics: Metrics,
 }
 
 /// Message from worker tasks.
@@ -91,30 +98,6 @@ pub struct ParticipationStatement {
 	pub outcome: ParticipationOutcome,
 }
 
-/// Outcome of the validation process.
-#[derive(Copy, Clone, Debug)]
-pub enum ParticipationOutcome {
-	/// Candidate was found to be valid.
-	Valid,
-	/// Candidate was found to be invalid.
-	Invalid,
-	/// Candidate was found to be unavailable.
-	Unavailable,
-	/// Something went wrong (bug), details can be found in the logs.
-	Error,
-}
-
-impl ParticipationOutcome {
-	/// If validation was successful, get whether the candidate was valid or invalid.
-	pub fn validity(self) -> Option<bool> {
-		match self {
-			Self::Valid => Some(true),
-			Self::Invalid => Some(false),
-			Self::Unavailable | Self::Error => None,
-		}
-	}
-}
-
 impl WorkerMessage {
 	fn from_request(req: ParticipationRequest, outcome: ParticipationOutcome) -> Self {
 		let session = req.session();
@@ -130,12 +113,13 @@ impl Participation {
 	/// The passed in sender will be used by background workers to communicate back their results.
 	/// The calling context should make sure to call `Participation::on_worker_message()` for the
 	/// received messages.
-	pub fn new(sender: WorkerMessageSender) -> Self {
+	pub fn new(sender: WorkerMessageSender, config: QueueConfig, metrics: Metrics) -> Self {
 		Self {
 			running_participations: HashSet::new(),
-			queue: Queues::new(),
+			queue: Queues::new(config),
 			worker_sender: sender,
 			recent_block: None,
+			metrics,
 		}
 	}
 
@@ -163,7 +147,9 @@ impl Participation {
 			}
 		}
 		// Out of capacity/no recent block yet - queue:
-		self.queue.queue(ctx.sender(), priority, req).await
+		let r = self.queue.queue(ctx.sender(), priority, req).await;
+		self.report_queue_depths();
+		r
 	}
 
 	/// Message from a worker task was received - get the outcome.
@@ -225,9 +211,15 @@ impl Participation {
 				break
 			}
 		}
+		self.report_queue_depths();
 		Ok(())
 	}
 
+	fn report_queue_depths(&self) {
+		self.metrics
+			.on_participation_queue_depths(self.queue.priority_len(), self.queue.best_effort_len());
+	}
+
 	/// Fork a participation task in the background.
 	fn fork_participation<Context>(
 		&mut self,
@@ -264,6 +256,13 @@ async fn participate(
 			req.candidate_receipt().clone(),
 			req.session(),
 			None,
+			// The backers of a disputed candidate are not to be relied upon, so we go for the
+			// chunks right away.
+			RecoveryPolicy::Custom(vec![
+				RecoveryStrategyKind::LocalStore,
+				RecoveryStrategyKind::SystematicChunks,
+				RecoveryStrategyKind::Chunks,
+			]),
 			recover_available_data_tx,
 		))
 		.await;
@@ -282,7 +281,7 @@ async fn participate(
 		Ok(Err(RecoveryError::Invalid)) => {
 			// the available data was recovered but it is invalid, therefore we'll
 			// vote negatively for the candidate dispute
-			send_result(&mut result_sender, req, ParticipationOutcome::Invalid).await;
+			send_result(&mut result_sender, req, ParticipationOutcome::Invalid(None)).await;
 			return
 		},
 		Ok(Err(RecoveryError::Unavailable)) => {
@@ -325,6 +324,10 @@ async fn participate(
 	// We use the approval execution timeout because this is intended to
 	// be run outside of backing and therefore should be subject to the
 	// same level of leeway.
+	let candidate_hash = *req.candidate_hash();
+	let span = jaeger::Span::new(candidate_hash, "participation-validation")
+		.with_candidate(candidate_hash)
+		.with_para_id(req.candidate_receipt().descriptor.para_id);
 	let (validation_tx, validation_rx) = oneshot::channel();
 	sender
 		.send_message(CandidateValidationMessage::ValidateFromExhaustive(
@@ -332,7 +335,9 @@ async fn participate(
 			validation_code,
 			req.candidate_receipt().clone(),
 			available_data.pov,
+			req.session(),
 			APPROVAL_EXECUTION_TIMEOUT,
+			span,
 			validation_tx,
 		))
 		.await;
@@ -357,7 +362,7 @@ async fn participate(
 				err,
 			);
 
-			send_result(&mut result_sender, req, ParticipationOutcome::Invalid).await;
+			send_result(&mut result_sender, req, ParticipationOutcome::Invalid(None)).await;
 		},
 
 		Ok(Ok(ValidationResult::Invalid(invalid))) => {
@@ -368,7 +373,8 @@ async fn participate(
 				invalid,
 			);
 
-			send_result(&mut result_sender, req, ParticipationOutcome::Invalid).await;
+			send_result(&mut result_sender, req, ParticipationOutcome::Invalid(Some(invalid)))
+				.await;
 		},
 		Ok(Ok(ValidationResult::Valid(_, _))) => {
 			send_result(&mut result_sender, req, ParticipationOutcome::Valid).await;
@@ -20,8 +20,11 @@ use std::{
 };
 
 use futures::channel::oneshot;
+use polkadot_node_primitives::CandidateVotes;
 use polkadot_node_subsystem::{messages::ChainApiMessage, overseer};
-use polkadot_primitives::v2::{BlockNumber, CandidateHash, CandidateReceipt, Hash, SessionIndex};
+use polkadot_primitives::v2::{
+	BlockNumber, CandidateHash, CandidateReceipt, Hash, Id as ParaId, SessionIndex, ValidatorIndex,
+};
 
 use crate::{
 	error::{FatalError, FatalResult, Result},
@@ -31,33 +34,50 @@ use crate::{
 #[cfg(test)]
 mod tests;
 
-/// How many potential garbage disputes we want to queue, before starting to drop requests.
-#[cfg(not(test))]
-const BEST_EFFORT_QUEUE_SIZE: usize = 100;
-#[cfg(test)]
-const BEST_EFFORT_QUEUE_SIZE: usize = 3;
-
-/// How many priority disputes can be queued.
-///
-/// Once the queue exceeds that size, we will start to drop the newest participation requests in
-/// the queue. Note that for each vote import the request will be re-added, if there is free
-/// capacity. This limit just serves as a safe guard, it is not expected to ever really be reached.
-///
-/// For 100 parachains, this would allow for every single candidate in 100 blocks on
-/// two forks to get disputed, which should be plenty to deal with any realistic attack.
-#[cfg(not(test))]
-const PRIORITY_QUEUE_SIZE: usize = 20_000;
-#[cfg(test)]
-const PRIORITY_QUEUE_SIZE: usize = 2;
-
 /// Type for counting how often a candidate was added to the best effort queue.
 type BestEffortCount = u32;
 
+/// Limits of the participation queues.
+#[derive(Debug, Clone, Copy)]
+pub struct QueueConfig {
+	/// How many potential garbage disputes we want to queue, before starting to drop requests.
+	pub best_effort_queue_size: usize,
+	/// How many best effort participations about candidates of a single para can be queued.
+	///
+	/// So disputes about one para can't crowd out the disputes about all other paras.
+	pub best_effort_per_para: usize,
+	/// How many best effort participations in disputes raised by a single validator can be
+	/// queued.
+	///
+	/// So a validator raising lots of disputes can't crowd out the disputes raised by others.
+	pub best_effort_per_validator: usize,
+	/// How many priority disputes can be queued.
+	///
+	/// Once the queue exceeds that size, we will start to drop the newest participation requests
+	/// in the queue. Note that for each vote import the request will be re-added, if there is free
+	/// capacity. This limit just serves as a safe guard, it is not expected to ever really be
+	/// reached.
+	pub priority_queue_size: usize,
+}
+
+impl Default for QueueConfig {
+	fn default() -> Self {
+		Self {
+			best_effort_queue_size: 100,
+			best_effort_per_para: 20,
+			best_effort_per_validator: 10,
+			// For 100 parachains, this would allow for every single candidate in 100 blocks on
+			// two forks to get disputed, which should be plenty to deal with any realistic attack.
+			priority_queue_size: 20_000,
+		}
+	}
+}
+
 /// Queues for dispute participation.
 pub struct Queues {
 	/// Set of best effort participation requests.
 	///
-	/// Note that as size is limited to `BEST_EFFORT_QUEUE_SIZE` we simply do a linear search for
+	/// Note that as size is limited to `best_effort_queue_size` we simply do a linear search for
 	/// the entry with the highest `added_count` to determine what dispute to participate next in.
 	///
 	/// This mechanism leads to an amplifying effect - the more validators already participated,
@@ -70,6 +90,9 @@ pub struct Queues {
 	/// In the priority queue, we have a strict ordering of candidates and participation will
 	/// happen in that order.
 	priority: BTreeMap<CandidateComparator, ParticipationRequest>,
+
+	/// Limits of the above queues.
+	config: QueueConfig,
 }
 
 /// A dispute participation request that can be queued.
@@ -78,6 +101,8 @@ pub struct ParticipationRequest {
 	candidate_hash: CandidateHash,
 	candidate_receipt: CandidateReceipt,
 	session: SessionIndex,
+	/// The validator who raised the dispute, if known.
+	reporter: Option<ValidatorIndex>,
 }
 
 /// Whether a `ParticipationRequest` should be put on best-effort or the priority queue.
@@ -115,6 +140,14 @@ impl ParticipationPriority {
 pub enum QueueError {
 	#[error("Request could not be queued, because best effort queue was already full.")]
 	BestEffortFull,
+	#[error(
+		"Request could not be queued, because best effort queue was already full for the para."
+	)]
+	BestEffortParaFull,
+	#[error(
+		"Request could not be queued, because best effort queue was already full for the reporting validator."
+	)]
+	BestEffortValidatorFull,
 	#[error("Request could not be queued, because priority queue was already full.")]
 	PriorityFull,
 }
@@ -122,7 +155,26 @@ pub enum QueueError {
 impl ParticipationRequest {
 	/// Create a new `ParticipationRequest` to be queued.
 	pub fn new(candidate_receipt: CandidateReceipt, session: SessionIndex) -> Self {
-		Self { candidate_hash: candidate_receipt.hash(), candidate_receipt, session }
+		Self {
+			candidate_hash: candidate_receipt.hash(),
+			candidate_receipt,
+			session,
+			reporter: None,
+		}
+	}
+
+	/// Note the validator who raised the dispute, which is accounted for in the best effort
+	/// queue.
+	pub fn with_reporter(self, reporter: Option<ValidatorIndex>) -> Self {
+		Self { reporter, ..self }
+	}
+
+	/// Note the invalid voter with the lowest index as the validator who raised the dispute.
+	///
+	/// The order votes got imported in is not persisted, so the reporter is derived from the votes
+	/// alone. This way requests recovered on startup are attributed the same way as live ones.
+	pub fn with_reporter_from(self, votes: &CandidateVotes) -> Self {
+		self.with_reporter(votes.invalid.keys().next().copied())
 	}
 
 	pub fn candidate_receipt(&'_ self) -> &'_ CandidateReceipt {
@@ -134,6 +186,9 @@ impl ParticipationRequest {
 	pub fn session(&self) -> SessionIndex {
 		self.session
 	}
+	pub fn para_id(&self) -> ParaId {
+		self.candidate_receipt.descriptor.para_id
+	}
 	pub fn into_candidate_info(self) -> (CandidateHash, CandidateReceipt) {
 		let Self { candidate_hash, candidate_receipt, .. } = self;
 		(candidate_hash, candidate_receipt)
@@ -142,8 +197,18 @@ impl ParticipationRequest {
 
 impl Queues {
 	/// Create new `Queues`.
-	pub fn new() -> Self {
-		Self { best_effort: HashMap::new(), priority: BTreeMap::new() }
+	pub fn new(config: QueueConfig) -> Self {
+		Self { best_effort: HashMap::new(), priority: BTreeMap::new(), config }
+	}
+
+	/// Number of requests in the best effort queue.
+	pub fn best_effort_len(&self) -> usize {
+		self.best_effort.len()
+	}
+
+	/// Number of requests in the priority queue.
+	pub fn priority_len(&self) -> usize {
+		self.priority.len()
 	}
 
 	/// Will put message in queue, either priority or best effort depending on priority.
@@ -188,22 +253,53 @@ impl Queues {
 		req: ParticipationRequest,
 	) -> std::result::Result<(), QueueError> {
 		if let Some(comparator) = comparator {
-			if self.priority.len() >= PRIORITY_QUEUE_SIZE {
+			if self.priority.len() >= self.config.priority_queue_size {
 				return Err(QueueError::PriorityFull)
 			}
 			// Remove any best effort entry:
 			self.best_effort.remove(&req.candidate_hash);
 			self.priority.insert(comparator, req);
 		} else {
-			if self.best_effort.len() >= BEST_EFFORT_QUEUE_SIZE {
-				return Err(QueueError::BestEffortFull)
-			}
 			// Note: The request might have been added to priority in a previous call already, we
 			// take care of that case in `dequeue` (more efficient).
+			if let Some(entry) = self.best_effort.get_mut(&req.candidate_hash) {
+				entry.added_count += 1;
+				return Ok(())
+			}
+			self.check_best_effort_capacity(&req)?;
 			self.best_effort
-				.entry(req.candidate_hash)
-				.or_insert(BestEffortEntry { req, added_count: 0 })
-				.added_count += 1;
+				.insert(req.candidate_hash, BestEffortEntry { req, added_count: 1 });
+		}
+		Ok(())
+	}
+
+	/// Check whether a new request can be put on the best effort queue.
+	///
+	/// Apart from the overall size of the queue, this limits the number of requests per para and
+	/// per reporting validator, so a single noisy para or validator can't starve participation for
+	/// everybody else.
+	fn check_best_effort_capacity(
+		&self,
+		req: &ParticipationRequest,
+	) -> std::result::Result<(), QueueError> {
+		if self.best_effort.len() >= self.config.best_effort_queue_size {
+			return Err(QueueError::BestEffortFull)
+		}
+		let para_id = req.para_id();
+		let para_count =
+			self.best_effort.values().filter(|entry| entry.req.para_id() == para_id).count();
+		if para_count >= self.config.best_effort_per_para {
+			return Err(QueueError::BestEffortParaFull)
+		}
+		if let Some(reporter) = req.reporter {
+			let reporter_count = self
+				.best_effort
+				.values()
+				.filter(|entry| entry.req.reporter == Some(reporter))
+				.count();
+			if reporter_count >= self.config.best_effort_per_validator {
+				return Err(QueueError::BestEffortValidatorFull)
+			}
 		}
 		Ok(())
 	}
@@ -16,9 +16,18 @@
 
 use ::test_helpers::{dummy_candidate_receipt, dummy_hash};
 use assert_matches::assert_matches;
-use polkadot_primitives::v2::{BlockNumber, Hash};
+use polkadot_primitives::v2::{BlockNumber, Hash, Id as ParaId, ValidatorIndex};
 
-use super::{CandidateComparator, ParticipationRequest, QueueError, Queues};
+use super::{CandidateComparator, ParticipationRequest, QueueConfig, QueueError, Queues};
+
+fn test_config() -> QueueConfig {
+	QueueConfig {
+		best_effort_queue_size: 3,
+		best_effort_per_para: 3,
+		best_effort_per_validator: 3,
+		priority_queue_size: 2,
+	}
+}
 
 /// Make a `ParticipationRequest` based on the given commitments hash.
 fn make_participation_request(hash: Hash) -> ParticipationRequest {
@@ -28,6 +37,19 @@ fn make_participation_request(hash: Hash) -> ParticipationRequest {
 	ParticipationRequest::new(receipt, 1)
 }
 
+/// Make a `ParticipationRequest` about a candidate of the given para, raised by the given
+/// validator.
+fn make_reported_participation_request(
+	hash: Hash,
+	para_id: ParaId,
+	reporter: ValidatorIndex,
+) -> ParticipationRequest {
+	let mut receipt = dummy_candidate_receipt(dummy_hash());
+	receipt.commitments_hash = hash;
+	receipt.descriptor.para_id = para_id;
+	ParticipationRequest::new(receipt, 1).with_reporter(Some(reporter))
+}
+
 /// Make dummy comparator for request, based on the given block number.
 fn make_dummy_comparator(
 	req: &ParticipationRequest,
@@ -42,7 +64,7 @@ fn make_dummy_comparator(
 /// processed in order. Best effort items, based on how often they have been added.
 #[test]
 fn ordering_works_as_expected() {
-	let mut queue = Queues::new();
+	let mut queue = Queues::new(test_config());
 	let req1 = make_participation_request(Hash::repeat_byte(0x01));
 	let req_prio = make_participation_request(Hash::repeat_byte(0x02));
 	let req3 = make_participation_request(Hash::repeat_byte(0x03));
@@ -83,7 +105,7 @@ fn ordering_works_as_expected() {
 /// No matter how often a candidate gets queued, it should only ever get dequeued once.
 #[test]
 fn candidate_is_only_dequeued_once() {
-	let mut queue = Queues::new();
+	let mut queue = Queues::new(test_config());
 	let req1 = make_participation_request(Hash::repeat_byte(0x01));
 	let req_prio = make_participation_request(Hash::repeat_byte(0x02));
 	let req_best_effort_then_prio = make_participation_request(Hash::repeat_byte(0x03));
@@ -128,3 +150,61 @@ fn candidate_is_only_dequeued_once() {
 	assert_eq!(queue.dequeue(), Some(req1));
 	assert_eq!(queue.dequeue(), None);
 }
+
+/// A single para or validator can only occupy part of the best effort queue.
+#[test]
+fn best_effort_queue_is_shared_fairly() {
+	let mut queue = Queues::new(QueueConfig {
+		best_effort_queue_size: 4,
+		best_effort_per_para: 2,
+		best_effort_per_validator: 2,
+		priority_queue_size: 2,
+	});
+	let noisy_para = ParaId::from(1);
+	let noisy_validator = ValidatorIndex(0);
+
+	let req1 =
+		make_reported_participation_request(Hash::repeat_byte(0x01), noisy_para, ValidatorIndex(1));
+	let req2 =
+		make_reported_participation_request(Hash::repeat_byte(0x02), noisy_para, ValidatorIndex(2));
+	let req_para_full =
+		make_reported_participation_request(Hash::repeat_byte(0x03), noisy_para, ValidatorIndex(3));
+	queue.queue_with_comparator(None, req1).unwrap();
+	queue.queue_with_comparator(None, req2.clone()).unwrap();
+	assert_matches!(
+		queue.queue_with_comparator(None, req_para_full),
+		Err(QueueError::BestEffortParaFull)
+	);
+	// Requests already queued can still be bumped:
+	queue.queue_with_comparator(None, req2.clone()).unwrap();
+
+	let req3 = make_reported_participation_request(
+		Hash::repeat_byte(0x04),
+		ParaId::from(2),
+		noisy_validator,
+	);
+	let req4 = make_reported_participation_request(
+		Hash::repeat_byte(0x05),
+		ParaId::from(3),
+		noisy_validator,
+	);
+	let req_validator_full = make_reported_participation_request(
+		Hash::repeat_byte(0x06),
+		ParaId::from(4),
+		noisy_validator,
+	);
+	queue.queue_with_comparator(None, req3).unwrap();
+	queue.queue_with_comparator(None, req4).unwrap();
+	assert_matches!(
+		queue.queue_with_comparator(None, req_validator_full),
+		Err(QueueError::BestEffortValidatorFull)
+	);
+	assert_eq!(queue.best_effort_len(), 4);
+
+	assert_eq!(queue.dequeue(), Some(req2));
+	// Space for other paras got freed up:
+	let req5 =
+		make_reported_participation_request(Hash::repeat_byte(0x07), noisy_para, ValidatorIndex(3));
+	queue.queue_with_comparator(None, req5).unwrap();
+	assert_eq!(queue.best_effort_len(), 4);
+}
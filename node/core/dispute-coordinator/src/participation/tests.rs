@@ -116,7 +116,7 @@ pub async fn participation_full_happy_path(
 	assert_matches!(
 	ctx_handle.recv().await,
 	AllMessages::CandidateValidation(
-		CandidateValidationMessage::ValidateFromExhaustive(_, _, candidate_receipt, _, timeout, tx)
+		CandidateValidationMessage::ValidateFromExhaustive(_, _, candidate_receipt, _, _, timeout, _, tx)
 		) if timeout == APPROVAL_EXECUTION_TIMEOUT => {
 			if expected_commitments_hash != candidate_receipt.commitments_hash {
 				tx.send(Ok(ValidationResult::Invalid(InvalidCandidate::CommitmentsHashMismatch))).unwrap();
@@ -133,7 +133,7 @@ pub async fn participation_missing_availability(ctx_handle: &mut VirtualOverseer
 	assert_matches!(
 		ctx_handle.recv().await,
 		AllMessages::AvailabilityRecovery(
-			AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, tx)
+			AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, _, tx)
 		) => {
 			tx.send(Err(RecoveryError::Unavailable)).unwrap();
 		},
@@ -152,7 +152,7 @@ async fn recover_available_data(virtual_overseer: &mut VirtualOverseer) {
 	assert_matches!(
 		virtual_overseer.recv().await,
 		AllMessages::AvailabilityRecovery(
-			AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, tx)
+			AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, _, tx)
 		) => {
 			tx.send(Ok(available_data)).unwrap();
 		},
@@ -186,7 +186,8 @@ fn same_req_wont_get_queued_if_participation_is_already_running() {
 		let (mut ctx, mut ctx_handle) = make_our_subsystem_context(TaskExecutor::new());
 
 		let (sender, mut worker_receiver) = mpsc::channel(1);
-		let mut participation = Participation::new(sender);
+		let mut participation =
+			Participation::new(sender, QueueConfig::default(), Metrics::default());
 		activate_leaf(&mut ctx, &mut participation, 10).await.unwrap();
 		participate(&mut ctx, &mut participation).await.unwrap();
 		for _ in 0..MAX_PARALLEL_PARTICIPATIONS {
@@ -196,7 +197,7 @@ fn same_req_wont_get_queued_if_participation_is_already_running() {
 		assert_matches!(
 			ctx_handle.recv().await,
 			AllMessages::AvailabilityRecovery(
-				AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, tx)
+				AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, _, tx)
 			) => {
 				tx.send(Err(RecoveryError::Unavailable)).unwrap();
 			},
@@ -225,7 +226,8 @@ fn reqs_get_queued_when_out_of_capacity() {
 		let (mut ctx, mut ctx_handle) = make_our_subsystem_context(TaskExecutor::new());
 
 		let (sender, mut worker_receiver) = mpsc::channel(1);
-		let mut participation = Participation::new(sender);
+		let mut participation =
+			Participation::new(sender, QueueConfig::default(), Metrics::default());
 		activate_leaf(&mut ctx, &mut participation, 10).await.unwrap();
 		participate(&mut ctx, &mut participation).await.unwrap();
 		for i in 0..MAX_PARALLEL_PARTICIPATIONS {
@@ -242,7 +244,7 @@ fn reqs_get_queued_when_out_of_capacity() {
 			assert_matches!(
 				ctx_handle.recv().await,
 				AllMessages::AvailabilityRecovery(
-					AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, tx)
+					AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, _, tx)
 				) => {
 					tx.send(Err(RecoveryError::Unavailable)).unwrap();
 				},
@@ -272,7 +274,8 @@ fn reqs_get_queued_on_no_recent_block() {
 		let (mut ctx, mut ctx_handle) = make_our_subsystem_context(TaskExecutor::new());
 
 		let (sender, _worker_receiver) = mpsc::channel(1);
-		let mut participation = Participation::new(sender);
+		let mut participation =
+			Participation::new(sender, QueueConfig::default(), Metrics::default());
 		participate(&mut ctx, &mut participation).await.unwrap();
 		assert!(ctx_handle.recv().timeout(Duration::from_millis(10)).await.is_none());
 		activate_leaf(&mut ctx, &mut participation, 10).await.unwrap();
@@ -297,14 +300,15 @@ fn cannot_participate_if_cannot_recover_available_data() {
 		let (mut ctx, mut ctx_handle) = make_our_subsystem_context(TaskExecutor::new());
 
 		let (sender, mut worker_receiver) = mpsc::channel(1);
-		let mut participation = Participation::new(sender);
+		let mut participation =
+			Participation::new(sender, QueueConfig::default(), Metrics::default());
 		activate_leaf(&mut ctx, &mut participation, 10).await.unwrap();
 		participate(&mut ctx, &mut participation).await.unwrap();
 
 		assert_matches!(
 			ctx_handle.recv().await,
 			AllMessages::AvailabilityRecovery(
-				AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, tx)
+				AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, _, tx)
 			) => {
 				tx.send(Err(RecoveryError::Unavailable)).unwrap();
 			},
@@ -327,7 +331,8 @@ fn cannot_participate_if_cannot_recover_validation_code() {
 		let (mut ctx, mut ctx_handle) = make_our_subsystem_context(TaskExecutor::new());
 
 		let (sender, mut worker_receiver) = mpsc::channel(1);
-		let mut participation = Participation::new(sender);
+		let mut participation =
+			Participation::new(sender, QueueConfig::default(), Metrics::default());
 		activate_leaf(&mut ctx, &mut participation, 10).await.unwrap();
 		participate(&mut ctx, &mut participation).await.unwrap();
 
@@ -364,14 +369,15 @@ fn cast_invalid_vote_if_available_data_is_invalid() {
 		let (mut ctx, mut ctx_handle) = make_our_subsystem_context(TaskExecutor::new());
 
 		let (sender, mut worker_receiver) = mpsc::channel(1);
-		let mut participation = Participation::new(sender);
+		let mut participation =
+			Participation::new(sender, QueueConfig::default(), Metrics::default());
 		activate_leaf(&mut ctx, &mut participation, 10).await.unwrap();
 		participate(&mut ctx, &mut participation).await.unwrap();
 
 		assert_matches!(
 			ctx_handle.recv().await,
 			AllMessages::AvailabilityRecovery(
-				AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, tx)
+				AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, _, tx)
 			) => {
 				tx.send(Err(RecoveryError::Invalid)).unwrap();
 			},
@@ -384,7 +390,7 @@ fn cast_invalid_vote_if_available_data_is_invalid() {
 			.unwrap();
 		assert_matches!(
 			result.outcome,
-			ParticipationOutcome::Invalid => {}
+			ParticipationOutcome::Invalid(None) => {}
 		);
 	})
 }
@@ -395,7 +401,8 @@ fn cast_invalid_vote_if_validation_fails_or_is_invalid() {
 		let (mut ctx, mut ctx_handle) = make_our_subsystem_context(TaskExecutor::new());
 
 		let (sender, mut worker_receiver) = mpsc::channel(1);
-		let mut participation = Participation::new(sender);
+		let mut participation =
+			Participation::new(sender, QueueConfig::default(), Metrics::default());
 		activate_leaf(&mut ctx, &mut participation, 10).await.unwrap();
 		participate(&mut ctx, &mut participation).await.unwrap();
 
@@ -408,7 +415,7 @@ fn cast_invalid_vote_if_validation_fails_or_is_invalid() {
 		assert_matches!(
 			ctx_handle.recv().await,
 			AllMessages::CandidateValidation(
-				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, tx)
+				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, _, timeout, _, tx)
 			) if timeout == APPROVAL_EXECUTION_TIMEOUT => {
 				tx.send(Ok(ValidationResult::Invalid(InvalidCandidate::Timeout))).unwrap();
 			},
@@ -421,7 +428,7 @@ fn cast_invalid_vote_if_validation_fails_or_is_invalid() {
 			.unwrap();
 		assert_matches!(
 			result.outcome,
-			ParticipationOutcome::Invalid => {}
+			ParticipationOutcome::Invalid(Some(InvalidCandidate::Timeout)) => {}
 		);
 	})
 }
@@ -432,7 +439,8 @@ fn cast_invalid_vote_if_commitments_dont_match() {
 		let (mut ctx, mut ctx_handle) = make_our_subsystem_context(TaskExecutor::new());
 
 		let (sender, mut worker_receiver) = mpsc::channel(1);
-		let mut participation = Participation::new(sender);
+		let mut participation =
+			Participation::new(sender, QueueConfig::default(), Metrics::default());
 		activate_leaf(&mut ctx, &mut participation, 10).await.unwrap();
 		participate(&mut ctx, &mut participation).await.unwrap();
 
@@ -445,7 +453,7 @@ fn cast_invalid_vote_if_commitments_dont_match() {
 		assert_matches!(
 			ctx_handle.recv().await,
 			AllMessages::CandidateValidation(
-				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, tx)
+				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, _, timeout, _, tx)
 			) if timeout == APPROVAL_EXECUTION_TIMEOUT => {
 				tx.send(Ok(ValidationResult::Invalid(InvalidCandidate::CommitmentsHashMismatch))).unwrap();
 			},
@@ -458,7 +466,7 @@ fn cast_invalid_vote_if_commitments_dont_match() {
 			.unwrap();
 		assert_matches!(
 			result.outcome,
-			ParticipationOutcome::Invalid => {}
+			ParticipationOutcome::Invalid(Some(InvalidCandidate::CommitmentsHashMismatch)) => {}
 		);
 	})
 }
@@ -469,7 +477,8 @@ fn cast_valid_vote_if_validation_passes() {
 		let (mut ctx, mut ctx_handle) = make_our_subsystem_context(TaskExecutor::new());
 
 		let (sender, mut worker_receiver) = mpsc::channel(1);
-		let mut participation = Participation::new(sender);
+		let mut participation =
+			Participation::new(sender, QueueConfig::default(), Metrics::default());
 		activate_leaf(&mut ctx, &mut participation, 10).await.unwrap();
 		participate(&mut ctx, &mut participation).await.unwrap();
 
@@ -482,7 +491,7 @@ fn cast_valid_vote_if_validation_passes() {
 		assert_matches!(
 			ctx_handle.recv().await,
 			AllMessages::CandidateValidation(
-				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, tx)
+				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, _, timeout, _, tx)
 			) if timeout == APPROVAL_EXECUTION_TIMEOUT => {
 				tx.send(Ok(ValidationResult::Valid(dummy_candidate_commitments(None), PersistedValidationData::default()))).unwrap();
 			},
@@ -14,43 +14,50 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use polkadot_primitives::v2::{CandidateHash, SessionIndex, ValidatorIndex};
 
 use crate::LOG_TARGET;
 
 /// Type used for counting potential spam votes.
-type SpamCount = u32;
+pub type SpamCount = u32;
 
-/// How many unconfirmed disputes a validator is allowed to import (per session).
+/// How many unconfirmed disputes a validator is allowed to import (per session), unless
+/// configured otherwise.
 ///
 /// Unconfirmed means: Node has not seen the candidate be included on any chain, it has not cast a
 /// vote itself on that dispute, the dispute has not yet reached more than a third of
 /// validator's votes and the including relay chain block has not yet been finalized.
 ///
-/// Exact number of `MAX_SPAM_VOTES` is not that important here. It is important that the number is
-/// low enough to not cause resource exhaustion (disk & memory) on the importing validator, even if
-/// multiple validators fully make use of their assigned spam slots.
+/// Exact number of `DEFAULT_MAX_SPAM_VOTES` is not that important here. It is important that the
+/// number is low enough to not cause resource exhaustion (disk & memory) on the importing
+/// validator, even if multiple validators fully make use of their assigned spam slots.
 ///
 /// Also if things are working properly, this number cannot really be too low either, as all
 /// relevant disputes _should_ have been seen as included by enough validators. (Otherwise the
 /// candidate would not have been available in the first place and could not have been included.)
 /// So this is really just a fallback mechanism if things go terribly wrong.
-#[cfg(not(test))]
-const MAX_SPAM_VOTES: SpamCount = 50;
-#[cfg(test)]
-const MAX_SPAM_VOTES: SpamCount = 1;
+pub const DEFAULT_MAX_SPAM_VOTES: SpamCount = 50;
 
 /// Spam slots for raised disputes concerning unknown candidates.
 pub struct SpamSlots {
 	/// Counts per validator and session.
 	///
-	/// Must not exceed `MAX_SPAM_VOTES`.
+	/// Must not exceed `max_spam_votes`.
 	slots: HashMap<(SessionIndex, ValidatorIndex), SpamCount>,
 
+	/// How many unconfirmed disputes a validator is allowed to import per session.
+	max_spam_votes: SpamCount,
+
 	/// All unconfirmed candidates we are aware of right now.
 	unconfirmed: UnconfirmedDisputes,
+
+	/// Validators per session which got disabled for losing a dispute.
+	///
+	/// They don't get any spam slots, so they can't raise disputes about unconfirmed candidates
+	/// on their own.
+	disabled: HashMap<SessionIndex, HashSet<ValidatorIndex>>,
 }
 
 /// Unconfirmed disputes to be passed at initialization.
@@ -60,13 +67,16 @@ impl SpamSlots {
 	/// Recover `SpamSlots` from state on startup.
 	///
 	/// Initialize based on already existing active disputes.
-	pub fn recover_from_state(unconfirmed_disputes: UnconfirmedDisputes) -> Self {
+	pub fn recover_from_state(
+		unconfirmed_disputes: UnconfirmedDisputes,
+		max_spam_votes: SpamCount,
+	) -> Self {
 		let mut slots: HashMap<(SessionIndex, ValidatorIndex), SpamCount> = HashMap::new();
 		for ((session, _), validators) in unconfirmed_disputes.iter() {
 			for validator in validators {
 				let spam_vote_count = slots.entry((*session, *validator)).or_default();
 				*spam_vote_count += 1;
-				if *spam_vote_count > MAX_SPAM_VOTES {
+				if *spam_vote_count > max_spam_votes {
 					gum::debug!(
 						target: LOG_TARGET,
 						?session,
@@ -78,7 +88,19 @@ impl SpamSlots {
 			}
 		}
 
-		Self { slots, unconfirmed: unconfirmed_disputes }
+		Self { slots, max_spam_votes, unconfirmed: unconfirmed_disputes, disabled: HashMap::new() }
+	}
+
+	/// Set the validators of a session disabled by the runtime.
+	///
+	/// Replaces any previously noted set, as validators can get re-enabled, e.g. on a fork which
+	/// did not see the dispute concluding.
+	pub fn set_disabled(
+		&mut self,
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		self.disabled.insert(session, validators.into_iter().collect());
 	}
 
 	/// Increase a "voting invalid" validator's spam slot.
@@ -86,15 +108,23 @@ impl SpamSlots {
 	/// This function should get called for any validator's invalidity vote for any not yet
 	/// confirmed dispute.
 	///
-	/// Returns: `true` if validator still had vacant spam slots, `false` otherwise.
+	/// Returns: `true` if validator still had vacant spam slots, `false` otherwise. Disabled
+	/// validators never have any.
 	pub fn add_unconfirmed(
 		&mut self,
 		session: SessionIndex,
 		candidate: CandidateHash,
 		validator: ValidatorIndex,
 	) -> bool {
+		if self
+			.disabled
+			.get(&session)
+			.map_or(false, |disabled| disabled.contains(&validator))
+		{
+			return false
+		}
 		let spam_vote_count = self.slots.entry((session, validator)).or_default();
-		if *spam_vote_count >= MAX_SPAM_VOTES {
+		if *spam_vote_count >= self.max_spam_votes {
 			return false
 		}
 		let validators = self.unconfirmed.entry((session, candidate)).or_default();
@@ -131,5 +161,6 @@ impl SpamSlots {
 	pub fn prune_old(&mut self, oldest_index: SessionIndex) {
 		self.unconfirmed.retain(|(session, _), _| *session >= oldest_index);
 		self.slots.retain(|(session, _), _| *session >= oldest_index);
+		self.disabled.retain(|session, _| *session >= oldest_index);
 	}
 }
@@ -29,9 +29,13 @@ use futures::{
 	future::{self, BoxFuture},
 };
 
+use parity_scale_codec::Encode;
 use polkadot_node_subsystem_util::database::Database;
 
-use polkadot_node_primitives::{SignedDisputeStatement, SignedFullStatement, Statement};
+use polkadot_node_primitives::{
+	DisputesFilter, InvalidCandidate, ParticipationOutcome, SignedDisputeStatement,
+	SignedFullStatement, Statement,
+};
 use polkadot_node_subsystem::{
 	messages::{
 		ApprovalVotingMessage, ChainApiMessage, DisputeCoordinatorMessage,
@@ -56,19 +60,23 @@ use polkadot_node_subsystem::{
 	ActivatedLeaf, ActiveLeavesUpdate, LeafStatus,
 };
 use polkadot_node_subsystem_test_helpers::{make_subsystem_context, TestSubsystemContextHandle};
-use polkadot_primitives::v2::{
-	ApprovalVote, BlockNumber, CandidateCommitments, CandidateHash, CandidateReceipt,
-	DisputeStatement, Hash, Header, MultiDisputeStatementSet, ScrapedOnChainVotes, SessionIndex,
-	SessionInfo, SigningContext, ValidDisputeStatementKind, ValidatorId, ValidatorIndex,
-	ValidatorSignature,
+use polkadot_primitives::{
+	v2::{
+		ApprovalVote, BlockNumber, CandidateCommitments, CandidateHash, CandidateReceipt,
+		DisputeStatement, Hash, Header, MultiDisputeStatementSet, ScrapedOnChainVotes,
+		SessionIndex, SessionInfo, SigningContext, ValidDisputeStatementKind, ValidatorId,
+		ValidatorIndex, ValidatorSignature,
+	},
+	vstaging::slashing,
 };
 
 use crate::{
 	backend::Backend,
+	initialized::SLASH_REPORT_RESUBMISSION_BLOCKS,
 	metrics::Metrics,
 	participation::{participation_full_happy_path, participation_missing_availability},
 	status::Clock,
-	Config, DisputeCoordinatorSubsystem,
+	Config, DisputeCoordinatorSubsystem, QueueConfig,
 };
 
 use super::db::v1::DbBackend;
@@ -136,6 +144,12 @@ struct TestState {
 	last_block: Hash,
 	// last session the subsystem knows about.
 	known_session: Option<SessionIndex>,
+	// slashes the runtime reports as unapplied.
+	unapplied_slashes: Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>,
+	// reports of lost disputes the subsystem submitted.
+	reported_slashes: Vec<slashing::DisputeProof>,
+	// validators the runtime reports as disabled.
+	disabled_validators: Vec<ValidatorIndex>,
 }
 
 impl Default for TestState {
@@ -176,7 +190,17 @@ impl Default for TestState {
 		let db = kvdb_memorydb::create(1);
 		let db = polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(db, &[]);
 		let db = Arc::new(db);
-		let config = Config { col_data: 0 };
+		// Small limits, so tests can run into them.
+		let config = Config {
+			col_data: 0,
+			participation_queues: QueueConfig {
+				best_effort_queue_size: 3,
+				best_effort_per_para: 3,
+				best_effort_per_validator: 3,
+				priority_queue_size: 2,
+			},
+			max_spam_votes: 1,
+		};
 
 		let genesis_header = Header {
 			parent_hash: Hash::zero(),
@@ -202,6 +226,9 @@ impl Default for TestState {
 			headers,
 			last_block,
 			known_session: None,
+			unapplied_slashes: Vec::new(),
+			reported_slashes: Vec::new(),
+			disabled_validators: Vec::new(),
 		}
 	}
 }
@@ -368,6 +395,56 @@ impl TestState {
 					);
 					gum::trace!("After answering runtime API request (votes)");
 				},
+				// Not requested for the leaf the subsystem starts with.
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					h,
+					RuntimeApiRequest::UnappliedSlashes(tx),
+				)) => {
+					assert_eq!(h, block_hash);
+					tx.send(Ok(self.unapplied_slashes.clone())).unwrap();
+				},
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					h,
+					RuntimeApiRequest::KeyOwnershipProof(validator_id, tx),
+				)) => {
+					assert_eq!(h, block_hash);
+					let proof = slashing::OpaqueKeyOwnershipProof::new(validator_id.encode());
+					tx.send(Ok(Some(proof))).unwrap();
+				},
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					h,
+					RuntimeApiRequest::SubmitReportDisputeLost(
+						dispute_proof,
+						key_ownership_proof,
+						tx,
+					),
+				)) => {
+					assert_eq!(h, block_hash);
+					assert_eq!(
+						key_ownership_proof.decode::<ValidatorId>(),
+						Some(dispute_proof.validator_id.clone())
+					);
+					self.reported_slashes.push(dispute_proof);
+					tx.send(Ok(Some(()))).unwrap();
+				},
+				// Not requested for the leaf the subsystem starts with.
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					h,
+					RuntimeApiRequest::DisabledValidators(tx),
+				)) => {
+					assert_eq!(h, block_hash);
+					tx.send(Ok(self.disabled_validators.clone())).unwrap();
+					assert_matches!(
+						overseer_recv(virtual_overseer).await,
+						AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+							h,
+							RuntimeApiRequest::SessionIndexForChild(tx),
+						)) => {
+							assert_eq!(h, block_hash);
+							let _ = tx.send(Ok(session));
+						}
+					);
+				},
 				msg => {
 					panic!("Received unexpected message in `handle_sync_queries`: {:?}", msg);
 				},
@@ -2780,3 +2857,264 @@ fn redundant_votes_ignored() {
 		})
 	});
 }
+
+#[test]
+fn unapplied_slashes_get_reported_once() {
+	test_harness(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			let session = 1;
+
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			let candidate_hash = CandidateHash(Hash::repeat_byte(1));
+			let validator_id = test_state.validator_public[3].clone();
+			test_state.unapplied_slashes = vec![(
+				session,
+				candidate_hash,
+				slashing::PendingSlashes {
+					keys: [(ValidatorIndex(3), validator_id.clone())].into_iter().collect(),
+					kind: slashing::SlashingOffenceKind::ForInvalid,
+				},
+			)];
+
+			test_state.activate_leaf_at_session(&mut virtual_overseer, session, 1).await;
+			// The report is still in the transaction pool.
+			test_state.activate_leaf_at_session(&mut virtual_overseer, session, 2).await;
+
+			assert_eq!(
+				test_state.reported_slashes,
+				vec![slashing::DisputeProof {
+					time_slot: slashing::DisputesTimeSlot::new(session, candidate_hash),
+					kind: slashing::SlashingOffenceKind::ForInvalid,
+					validator_index: ValidatorIndex(3),
+					validator_id,
+				}],
+			);
+
+			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+			assert!(virtual_overseer.try_recv().await.is_none());
+
+			test_state
+		})
+	});
+}
+
+#[test]
+fn unapplied_slashes_get_reported_again_if_not_included() {
+	test_harness(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			let session = 1;
+
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			let candidate_hash = CandidateHash(Hash::repeat_byte(1));
+			let validator_id = test_state.validator_public[3].clone();
+			test_state.unapplied_slashes = vec![(
+				session,
+				candidate_hash,
+				slashing::PendingSlashes {
+					keys: [(ValidatorIndex(3), validator_id.clone())].into_iter().collect(),
+					kind: slashing::SlashingOffenceKind::ForInvalid,
+				},
+			)];
+
+			// The report got dropped from the transaction pool.
+			for block_number in 1..=1 + SLASH_REPORT_RESUBMISSION_BLOCKS {
+				test_state
+					.activate_leaf_at_session(&mut virtual_overseer, session, block_number)
+					.await;
+			}
+
+			let dispute_proof = slashing::DisputeProof {
+				time_slot: slashing::DisputesTimeSlot::new(session, candidate_hash),
+				kind: slashing::SlashingOffenceKind::ForInvalid,
+				validator_index: ValidatorIndex(3),
+				validator_id,
+			};
+			assert_eq!(test_state.reported_slashes, vec![dispute_proof.clone(), dispute_proof]);
+
+			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+			assert!(virtual_overseer.try_recv().await.is_none());
+
+			test_state
+		})
+	});
+}
+
+#[test]
+fn disputes_raised_by_disabled_validators_are_considered_spam() {
+	test_harness(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			let session = 1;
+
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			test_state.disabled_validators = vec![ValidatorIndex(1)];
+			test_state.activate_leaf_at_session(&mut virtual_overseer, session, 1).await;
+
+			let candidate_receipt = make_valid_candidate_receipt();
+			let candidate_hash = candidate_receipt.hash();
+
+			let valid_vote = test_state
+				.issue_backing_statement_with_index(ValidatorIndex(3), candidate_hash, session)
+				.await;
+
+			let invalid_vote = test_state
+				.issue_explicit_statement_with_index(
+					ValidatorIndex(1),
+					candidate_hash,
+					session,
+					false,
+				)
+				.await;
+
+			let (pending_confirmation, confirmation_rx) = oneshot::channel();
+			virtual_overseer
+				.send(FromOrchestra::Communication {
+					msg: DisputeCoordinatorMessage::ImportStatements {
+						candidate_receipt: candidate_receipt.clone(),
+						session,
+						statements: vec![
+							(valid_vote, ValidatorIndex(3)),
+							(invalid_vote, ValidatorIndex(1)),
+						],
+						pending_confirmation: Some(pending_confirmation),
+					},
+				})
+				.await;
+
+			handle_approval_vote_request(&mut virtual_overseer, &candidate_hash, HashMap::new())
+				.await;
+
+			// The disabled validator has no spam slots, despite never having used any.
+			assert_matches!(confirmation_rx.await, Ok(ImportStatementsResult::InvalidImport));
+
+			{
+				let (tx, rx) = oneshot::channel();
+				virtual_overseer
+					.send(FromOrchestra::Communication {
+						msg: DisputeCoordinatorMessage::ActiveDisputes(tx),
+					})
+					.await;
+
+				assert!(rx.await.unwrap().is_empty());
+			}
+
+			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+			assert!(virtual_overseer.try_recv().await.is_none());
+
+			test_state
+		})
+	});
+}
+
+#[test]
+fn query_disputes_returns_votes_and_participation_outcome() {
+	test_harness(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			let session = 1;
+
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			let candidate_receipt = make_invalid_candidate_receipt();
+			let candidate_hash = candidate_receipt.hash();
+			let para_id = candidate_receipt.descriptor.para_id;
+
+			test_state.activate_leaf_at_session(&mut virtual_overseer, session, 1).await;
+
+			let valid_vote = test_state
+				.issue_explicit_statement_with_index(
+					ValidatorIndex(3),
+					candidate_hash,
+					session,
+					true,
+				)
+				.await;
+
+			let invalid_vote = test_state
+				.issue_explicit_statement_with_index(
+					ValidatorIndex(1),
+					candidate_hash,
+					session,
+					false,
+				)
+				.await;
+
+			virtual_overseer
+				.send(FromOrchestra::Communication {
+					msg: DisputeCoordinatorMessage::ImportStatements {
+						candidate_receipt: candidate_receipt.clone(),
+						session,
+						statements: vec![
+							(valid_vote, ValidatorIndex(3)),
+							(invalid_vote, ValidatorIndex(1)),
+						],
+						pending_confirmation: None,
+					},
+				})
+				.await;
+			handle_approval_vote_request(&mut virtual_overseer, &candidate_hash, HashMap::new())
+				.await;
+
+			participation_with_distribution(
+				&mut virtual_overseer,
+				&candidate_hash,
+				CandidateCommitments::default().hash(),
+			)
+			.await;
+
+			{
+				let (tx, rx) = oneshot::channel();
+				virtual_overseer
+					.send(FromOrchestra::Communication {
+						msg: DisputeCoordinatorMessage::QueryDisputes(
+							DisputesFilter { para_id: Some(para_id), ..Default::default() },
+							tx,
+						),
+					})
+					.await;
+
+				let disputes = rx.await.unwrap();
+				assert_eq!(disputes.len(), 1);
+				let dispute = &disputes[0];
+				assert_eq!((dispute.session, dispute.candidate_hash), (session, candidate_hash));
+				assert!(dispute.active);
+				assert_eq!(
+					dispute.valid_votes.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+					vec![ValidatorIndex(3)],
+				);
+				// Alice, at index 0, is us.
+				assert_eq!(
+					dispute.invalid_votes.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+					vec![ValidatorIndex(0), ValidatorIndex(1)],
+				);
+				assert_eq!(dispute.own_validator_indices, vec![ValidatorIndex(0)]);
+				assert_matches!(
+					dispute.participation,
+					Some(ParticipationOutcome::Invalid(Some(
+						InvalidCandidate::CommitmentsHashMismatch
+					)))
+				);
+			}
+
+			{
+				let (tx, rx) = oneshot::channel();
+				virtual_overseer
+					.send(FromOrchestra::Communication {
+						msg: DisputeCoordinatorMessage::QueryDisputes(
+							DisputesFilter { session: Some(session + 1), ..Default::default() },
+							tx,
+						),
+					})
+					.await;
+
+				assert!(rx.await.unwrap().is_empty());
+			}
+
+			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+			assert!(virtual_overseer.try_recv().await.is_none());
+
+			test_state
+		})
+	});
+}
@@ -0,0 +1,70 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Embeds the version of wasmtime the artifacts get compiled with, as locked for this build, so
+//! that artifacts of a different wasmtime are never loaded.
+
+use std::{env, fs, path::PathBuf};
+
+fn main() {
+	// Falling back to a placeholder would give all such builds the same fingerprint and have them
+	// load each other's artifacts, so refuse to build instead.
+	let version = locked_wasmtime_version()
+		.expect("the version of wasmtime must be locked in the Cargo.lock of the build");
+	println!("cargo:rustc-env=PVF_WASMTIME_VERSION={}", version);
+}
+
+/// Finds the `Cargo.lock` of the build and returns the versions of the locked `wasmtime` packages.
+///
+/// The lock file is looked up above the manifest of this crate and, for builds of this crate as a
+/// dependency from outside of its workspace, above the output directory of the build.
+fn locked_wasmtime_version() -> Option<String> {
+	["CARGO_MANIFEST_DIR", "OUT_DIR"]
+		.iter()
+		.filter_map(|var| env::var_os(var))
+		.find_map(|dir| wasmtime_versions(&lock_file_above(PathBuf::from(dir))?))
+}
+
+/// Returns the contents of the first `Cargo.lock` found in `dir` or any of its ancestors.
+fn lock_file_above(mut dir: PathBuf) -> Option<String> {
+	loop {
+		let lock_file = dir.join("Cargo.lock");
+		if lock_file.is_file() {
+			println!("cargo:rerun-if-changed={}", lock_file.display());
+			return fs::read_to_string(lock_file).ok()
+		}
+		if !dir.pop() {
+			return None
+		}
+	}
+}
+
+fn wasmtime_versions(lock: &str) -> Option<String> {
+	let mut lines = lock.lines();
+	let mut versions = Vec::new();
+	while let Some(line) = lines.next() {
+		if line.trim() == r#"name = "wasmtime""# {
+			let version = lines.next()?.trim().strip_prefix("version = ")?.trim_matches('"');
+			versions.push(version.to_owned());
+		}
+	}
+
+	if versions.is_empty() {
+		None
+	} else {
+		Some(versions.join("+"))
+	}
+}
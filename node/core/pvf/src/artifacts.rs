@@ -14,13 +14,21 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::{error::PrepareError, host::PrepareResultSender};
+use crate::{error::PrepareError, executor_intf, host::PrepareResultSender, LOG_TARGET};
 use always_assert::always;
-use async_std::path::{Path, PathBuf};
+use async_std::{
+	io,
+	path::{Path, PathBuf},
+};
+use futures::StreamExt as _;
+use once_cell::sync::Lazy;
+use parity_scale_codec::{Decode, Encode};
 use polkadot_parachain::primitives::ValidationCodeHash;
+use polkadot_primitives::vstaging::ExecutorParamsHash;
 use std::{
 	collections::HashMap,
-	time::{Duration, SystemTime},
+	sync::Arc,
+	time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 pub struct CompiledArtifact(Vec<u8>);
@@ -37,37 +45,121 @@ impl AsRef<[u8]> for CompiledArtifact {
 	}
 }
 
-/// Identifier of an artifact. Right now it only encodes a code hash of the PVF. But if we get to
-/// multiple engine implementations the artifact ID should include the engine type as well.
+/// The version of wasmtime the artifacts are compiled with, as locked for this build.
+const WASMTIME_VERSION: &str = env!("PVF_WASMTIME_VERSION");
+
+/// Returns the fingerprint of the executor that produces the artifacts.
+///
+/// Compiled artifacts are only meaningful for the exact wasmtime version and executor semantics
+/// that produced them. Both are hashed into the fingerprint that is embedded into the file names
+/// of the artifacts. Artifacts carrying a different fingerprint are considered incompatible and
+/// are discarded on startup.
+fn executor_fingerprint() -> &'static str {
+	static FINGERPRINT: Lazy<String> = Lazy::new(|| {
+		let executor = format!(
+			"wasmtime-{};{}",
+			WASMTIME_VERSION,
+			executor_intf::default_semantics_description()
+		);
+		format!("{:016x}", u64::from_be_bytes(sp_core::hashing::blake2_64(executor.as_bytes())))
+	});
+
+	&FINGERPRINT
+}
+
+/// Returns the path of the file holding the checksum of the artifact at the given path.
+///
+/// The checksum is written by the host before the artifact is promoted to its final path, and is
+/// verified when the artifact is discovered on startup.
+pub fn checksum_path(artifact_path: &Path) -> PathBuf {
+	let mut path = artifact_path.as_os_str().to_owned();
+	path.push(ArtifactId::CHECKSUM_SUFFIX);
+	path.into()
+}
+
+/// Computes the checksum of the artifact at the given path.
+pub async fn artifact_checksum(artifact_path: &Path) -> io::Result<[u8; 32]> {
+	let artifact = async_std::fs::read(artifact_path).await?;
+	Ok(sp_core::hashing::blake2_256(&artifact))
+}
+
+/// Computes the checksum of the artifact at the given path and stores it next to the artifact
+/// under the given final path of the artifact.
+pub async fn write_checksum(tmp_artifact_path: &Path, artifact_path: &Path) -> io::Result<()> {
+	let checksum = artifact_checksum(tmp_artifact_path).await?;
+	async_std::fs::write(checksum_path(artifact_path), checksum).await
+}
+
+/// Identifier of an artifact. Encodes the code hash of the PVF and the hash of the executor
+/// parameters it was prepared with, since the same code prepared with different parameters
+/// results in different artifacts. But if we get to multiple engine implementations the artifact
+/// ID should include the engine type as well.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ArtifactId {
 	pub(crate) code_hash: ValidationCodeHash,
+	pub(crate) executor_params_hash: ExecutorParamsHash,
 }
 
 impl ArtifactId {
 	const PREFIX: &'static str = "wasmtime_";
+	const FAILURE_RECORD_SUFFIX: &'static str = ".failed";
+	const CHECKSUM_SUFFIX: &'static str = ".checksum";
 
-	/// Creates a new artifact ID with the given hash.
-	pub fn new(code_hash: ValidationCodeHash) -> Self {
-		Self { code_hash }
+	/// Creates a new artifact ID with the given hashes.
+	pub fn new(code_hash: ValidationCodeHash, executor_params_hash: ExecutorParamsHash) -> Self {
+		Self { code_hash, executor_params_hash }
 	}
 
 	/// Tries to recover the artifact id from the given file name.
-	#[cfg(test)]
+	///
+	/// Returns `None` if the file name is not recognized as an artifact or if it was produced by
+	/// an executor with a different fingerprint.
 	pub fn from_file_name(file_name: &str) -> Option<Self> {
 		use polkadot_core_primitives::Hash;
 		use std::str::FromStr as _;
 
 		let file_name = file_name.strip_prefix(Self::PREFIX)?;
-		let code_hash = Hash::from_str(file_name).ok()?.into();
+		let file_name = file_name.strip_prefix(executor_fingerprint())?.strip_prefix('_')?;
+		let (code_hash, executor_params_hash) = file_name.split_once('_')?;
+		let code_hash = Hash::from_str(code_hash).ok()?.into();
+		let executor_params_hash = Hash::from_str(executor_params_hash).ok()?.into();
+
+		Some(Self { code_hash, executor_params_hash })
+	}
+
+	/// Tries to recover the artifact id from the file name of a failure record.
+	///
+	/// Returns `None` if the file name is not recognized as a failure record.
+	pub fn from_failure_record_file_name(file_name: &str) -> Option<Self> {
+		Self::from_file_name(file_name.strip_suffix(Self::FAILURE_RECORD_SUFFIX)?)
+	}
 
-		Some(Self { code_hash })
+	/// Tries to recover the artifact id from the file name of an artifact checksum.
+	///
+	/// Returns `None` if the file name is not recognized as an artifact checksum.
+	pub fn from_checksum_file_name(file_name: &str) -> Option<Self> {
+		Self::from_file_name(file_name.strip_suffix(Self::CHECKSUM_SUFFIX)?)
 	}
 
 	/// Returns the expected path to this artifact given the root of the cache.
 	pub fn path(&self, cache_path: &Path) -> PathBuf {
-		let file_name = format!("{}{:#x}", Self::PREFIX, self.code_hash);
-		cache_path.join(file_name)
+		cache_path.join(self.file_name())
+	}
+
+	/// Returns the path to the record of the failed preparation of this artifact given the root of
+	/// the cache.
+	pub fn failure_record_path(&self, cache_path: &Path) -> PathBuf {
+		cache_path.join(format!("{}{}", self.file_name(), Self::FAILURE_RECORD_SUFFIX))
+	}
+
+	fn file_name(&self) -> String {
+		format!(
+			"{}{}_{:#x}_{:#x}",
+			Self::PREFIX,
+			executor_fingerprint(),
+			self.code_hash,
+			self.executor_params_hash
+		)
 	}
 }
 
@@ -78,15 +170,31 @@ impl ArtifactId {
 /// - While we can derive the artifact path from the artifact id, it makes sense to carry it around
 /// sometimes to avoid extra work.
 /// - At the same time, carrying only path limiting the ability for logging.
+///
+/// It also carries a lease of the artifact, which keeps it from being evicted from the cache while
+/// it is waiting for or undergoing the execution.
 #[derive(Debug, Clone)]
 pub struct ArtifactPathId {
 	pub(crate) id: ArtifactId,
 	pub(crate) path: PathBuf,
+	_lease: ArtifactLease,
 }
 
 impl ArtifactPathId {
-	pub(crate) fn new(artifact_id: ArtifactId, cache_path: &Path) -> Self {
-		Self { path: artifact_id.path(cache_path), id: artifact_id }
+	pub(crate) fn new(artifact_id: ArtifactId, cache_path: &Path, lease: ArtifactLease) -> Self {
+		Self { path: artifact_id.path(cache_path), id: artifact_id, _lease: lease }
+	}
+}
+
+/// A lease of a prepared artifact. The artifact keeps one and every pending execution of the
+/// artifact holds a clone of it.
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactLease(Arc<()>);
+
+impl ArtifactLease {
+	/// Returns whether there are any executions holding this lease.
+	fn is_held(&self) -> bool {
+		Arc::strong_count(&self.0) > 1
 	}
 }
 
@@ -101,12 +209,57 @@ pub enum ArtifactState {
 		/// This is updated when we get the heads up for this artifact or when we just discover
 		/// this file.
 		last_time_needed: SystemTime,
+		/// The size of the artifact file in bytes.
+		size: u64,
+		/// The lease handed out to the executions of this artifact.
+		lease: ArtifactLease,
 	},
 	/// A task to prepare this artifact is scheduled.
-	Preparing { waiting_for_response: Vec<PrepareResultSender> },
-	/// The code couldn't be compiled due to an error. Such artifacts
-	/// never reach the executor and stay in the host's memory.
-	FailedToProcess(PrepareError),
+	Preparing {
+		waiting_for_response: Vec<PrepareResultSender>,
+		/// The number of times the preparation of this artifact has failed before.
+		num_failures: u32,
+	},
+	/// The code couldn't be compiled due to an error. Such artifacts never reach the executor.
+	///
+	/// The failure is recorded on disk next to the artifacts, so it survives restarts. Depending on
+	/// the error, the preparation may be retried later.
+	FailedToProcess {
+		/// The time when the preparation failed the last time.
+		last_time_failed: SystemTime,
+		/// The number of times the preparation has failed.
+		num_failures: u32,
+		/// The error of the last failed preparation.
+		error: PrepareError,
+	},
+}
+
+/// The record of a failed preparation, as it is stored on disk.
+#[derive(Debug, Encode, Decode)]
+struct FailureRecord {
+	error: PrepareError,
+	num_failures: u32,
+	/// Seconds since the UNIX epoch.
+	last_time_failed: u64,
+}
+
+/// Persists the failed state of the preparation of the given artifact.
+pub async fn write_failure_record(
+	cache_path: &Path,
+	artifact_id: &ArtifactId,
+	error: &PrepareError,
+	num_failures: u32,
+	last_time_failed: SystemTime,
+) -> io::Result<()> {
+	let last_time_failed =
+		last_time_failed.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+	let record = FailureRecord { error: error.clone(), num_failures, last_time_failed };
+	async_std::fs::write(artifact_id.failure_record_path(cache_path), record.encode()).await
+}
+
+/// Removes the record of the failed preparation of the given artifact, if any.
+pub async fn remove_failure_record(cache_path: &Path, artifact_id: &ArtifactId) {
+	let _ = async_std::fs::remove_file(artifact_id.failure_record_path(cache_path)).await;
 }
 
 /// A container of all known artifact ids and their states.
@@ -115,17 +268,34 @@ pub struct Artifacts {
 }
 
 impl Artifacts {
-	/// Initialize a blank cache at the given path. This will clear everything present at the
-	/// given path, to be populated over time.
+	/// Initialize the cache at the given path, picking up the artifacts that survived from the
+	/// previous run.
 	///
-	/// The recognized artifacts will be filled in the table and unrecognized will be removed.
+	/// The recognized artifacts will be filled in the table as prepared and unrecognized will be
+	/// removed. That includes the artifacts produced by an incompatible executor, as well as the
+	/// leftovers of the preparations that were interrupted.
 	pub async fn new(cache_path: &Path) -> Self {
 		// Make sure that the cache path directory and all it's parents are created.
-		// First delete the entire cache. Nodes are long-running so this should populate shortly.
-		let _ = async_std::fs::remove_dir_all(cache_path).await;
 		let _ = async_std::fs::create_dir_all(cache_path).await;
 
-		Self { artifacts: HashMap::new() }
+		let artifacts = match scan_for_known_artifacts(cache_path).await {
+			Ok(artifacts) => artifacts,
+			Err(err) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					"failed to scan the artifact cache at {}, clearing it: {:?}",
+					cache_path.display(),
+					err,
+				);
+
+				// Start from scratch. Nodes are long-running so this should populate shortly.
+				let _ = async_std::fs::remove_dir_all(cache_path).await;
+				let _ = async_std::fs::create_dir_all(cache_path).await;
+				HashMap::new()
+			},
+		};
+
+		Self { artifacts }
 	}
 
 	#[cfg(test)]
@@ -150,7 +320,7 @@ impl Artifacts {
 		// See the precondition.
 		always!(self
 			.artifacts
-			.insert(artifact_id, ArtifactState::Preparing { waiting_for_response })
+			.insert(artifact_id, ArtifactState::Preparing { waiting_for_response, num_failures: 0 })
 			.is_none());
 	}
 
@@ -159,14 +329,50 @@ impl Artifacts {
 	/// This function must be used only for brand-new artifacts and should never be used for
 	/// replacing existing ones.
 	#[cfg(test)]
-	pub fn insert_prepared(&mut self, artifact_id: ArtifactId, last_time_needed: SystemTime) {
+	pub fn insert_prepared(
+		&mut self,
+		artifact_id: ArtifactId,
+		last_time_needed: SystemTime,
+		size: u64,
+	) {
+		// See the precondition.
+		always!(self
+			.artifacts
+			.insert(
+				artifact_id,
+				ArtifactState::Prepared { last_time_needed, size, lease: ArtifactLease::default() }
+			)
+			.is_none());
+	}
+
+	/// Insert an artifact with the given ID as "failed to process".
+	///
+	/// This function must be used only for brand-new artifacts and should never be used for
+	/// replacing existing ones.
+	#[cfg(test)]
+	pub fn insert_failed(
+		&mut self,
+		artifact_id: ArtifactId,
+		last_time_failed: SystemTime,
+		num_failures: u32,
+		error: PrepareError,
+	) {
 		// See the precondition.
 		always!(self
 			.artifacts
-			.insert(artifact_id, ArtifactState::Prepared { last_time_needed })
+			.insert(
+				artifact_id,
+				ArtifactState::FailedToProcess { last_time_failed, num_failures, error }
+			)
 			.is_none());
 	}
 
+	/// Returns the number of artifacts known to the table.
+	#[cfg(test)]
+	pub(crate) fn len(&self) -> usize {
+		self.artifacts.len()
+	}
+
 	/// Remove and retrieve the artifacts from the table that are older than the supplied Time-To-Live.
 	pub fn prune(&mut self, artifact_ttl: Duration) -> Vec<ArtifactId> {
 		let now = SystemTime::now();
@@ -190,29 +396,306 @@ impl Artifacts {
 
 		to_remove
 	}
+
+	/// Remove and retrieve the failed preparations from the table that are older than the supplied
+	/// Time-To-Live. Their failure records on disk should be removed as well, so that the
+	/// preparation is attempted anew if the PVF is needed again.
+	///
+	/// Only the transient failures are pruned. A deterministic failure would be reproduced by
+	/// another attempt anyway, and it is kept for as long as the artifact id is valid so that the
+	/// pre-checking vote on the PVF can't flip.
+	pub fn prune_failures(&mut self, failure_ttl: Duration) -> Vec<ArtifactId> {
+		let now = SystemTime::now();
+
+		let to_remove = self
+			.artifacts
+			.iter()
+			.filter_map(|(artifact_id, state)| match state {
+				ArtifactState::FailedToProcess { last_time_failed, error, .. }
+					if !error.is_deterministic() &&
+						now.duration_since(*last_time_failed)
+							.map(|age| age > failure_ttl)
+							.unwrap_or(false) =>
+					Some(artifact_id.clone()),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+
+		for artifact in &to_remove {
+			self.artifacts.remove(artifact);
+		}
+
+		to_remove
+	}
+
+	/// Returns the total size of the prepared artifacts in bytes.
+	pub fn total_size(&self) -> u64 {
+		self.artifacts
+			.values()
+			.map(|state| match state {
+				ArtifactState::Prepared { size, .. } => *size,
+				_ => 0,
+			})
+			.sum()
+	}
+
+	/// Remove and retrieve the least recently used artifacts until the total size of the prepared
+	/// artifacts fits into the supplied budget.
+	///
+	/// The artifacts that are leased to executions are never evicted, so the total size may still
+	/// exceed the budget afterwards.
+	pub fn evict_to_fit(&mut self, max_size: u64) -> Vec<ArtifactId> {
+		let mut total_size = self.total_size();
+		if total_size <= max_size {
+			return Vec::new()
+		}
+
+		let mut candidates = self
+			.artifacts
+			.iter()
+			.filter_map(|(id, state)| match state {
+				ArtifactState::Prepared { last_time_needed, size, lease } if !lease.is_held() =>
+					Some((*last_time_needed, *size, id.clone())),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		candidates.sort_by_key(|(last_time_needed, _, _)| *last_time_needed);
+
+		let mut to_remove = vec![];
+		for (_, size, artifact_id) in candidates {
+			if total_size <= max_size {
+				break
+			}
+			total_size -= size;
+			self.artifacts.remove(&artifact_id);
+			to_remove.push(artifact_id);
+		}
+
+		to_remove
+	}
+}
+
+/// Goes over all files in the given cache directory and registers the compatible artifacts that
+/// match their checksums as prepared and the recorded failures as failed to process. Everything
+/// else is removed.
+async fn scan_for_known_artifacts(
+	cache_path: &Path,
+) -> io::Result<HashMap<ArtifactId, ArtifactState>> {
+	let mut artifacts = HashMap::new();
+	let mut found_artifacts = Vec::new();
+	let mut checksums = HashMap::new();
+	let mut failures = Vec::new();
+	let now = SystemTime::now();
+
+	let mut dir = async_std::fs::read_dir(cache_path).await?;
+	while let Some(entry) = dir.next().await {
+		let entry = entry?;
+		let path = entry.path();
+		let file_name = entry.file_name();
+
+		if let Some(artifact_id) =
+			file_name.to_str().and_then(ArtifactId::from_failure_record_file_name)
+		{
+			match read_failure_record(&path).await {
+				Some(state) => failures.push((artifact_id, state)),
+				None => {
+					gum::debug!(
+						target: LOG_TARGET,
+						validation_code_hash = ?artifact_id.code_hash,
+						"removing invalid failure record: {}",
+						path.display(),
+					);
+					remove_stale_entry(&path).await;
+				},
+			}
+			continue
+		}
+
+		if let Some(artifact_id) = file_name.to_str().and_then(ArtifactId::from_checksum_file_name)
+		{
+			checksums.insert(artifact_id, path);
+			continue
+		}
+
+		let artifact_id = match file_name.to_str().and_then(ArtifactId::from_file_name) {
+			Some(artifact_id) => artifact_id,
+			None => {
+				gum::debug!(
+					target: LOG_TARGET,
+					"removing unrecognized file from the artifact cache: {}",
+					path.display(),
+				);
+				remove_stale_entry(&path).await;
+				continue
+			},
+		};
+
+		let size = match entry.metadata().await {
+			Ok(metadata) if metadata.is_file() => metadata.len(),
+			_ => 0,
+		};
+		found_artifacts.push((artifact_id, path, size));
+	}
+
+	for (artifact_id, path, size) in found_artifacts {
+		// Verify that the artifact is exactly what the prepare worker has written. The artifacts
+		// are promoted from a temporary file by a rename, so a partially written artifact is not
+		// expected here, but the file could have been corrupted or tampered with externally.
+		let checksum = checksums.remove(&artifact_id);
+		let valid = match &checksum {
+			Some(checksum_path) if size > 0 =>
+				verify_checksum(&path, checksum_path).await.unwrap_or(false),
+			_ => false,
+		};
+		if !valid {
+			gum::debug!(
+				target: LOG_TARGET,
+				validation_code_hash = ?artifact_id.code_hash,
+				"removing invalid artifact: {}",
+				path.display(),
+			);
+			remove_stale_entry(&path).await;
+			if let Some(checksum_path) = checksum {
+				remove_stale_entry(&checksum_path).await;
+			}
+			continue
+		}
+
+		gum::debug!(
+			target: LOG_TARGET,
+			validation_code_hash = ?artifact_id.code_hash,
+			"discovered a prepared artifact: {}",
+			path.display(),
+		);
+		artifacts.insert(
+			artifact_id,
+			ArtifactState::Prepared {
+				last_time_needed: now,
+				size,
+				lease: ArtifactLease::default(),
+			},
+		);
+	}
+
+	for (_, checksum_path) in checksums {
+		gum::debug!(
+			target: LOG_TARGET,
+			"removing checksum of a missing artifact: {}",
+			checksum_path.display(),
+		);
+		remove_stale_entry(&checksum_path).await;
+	}
+
+	let num_prepared = artifacts.len();
+	for (artifact_id, state) in failures {
+		if artifacts.contains_key(&artifact_id) {
+			// The preparation succeeded after all, but the node stopped before the record of the
+			// earlier failure was removed.
+			remove_stale_entry(&artifact_id.failure_record_path(cache_path)).await;
+			continue
+		}
+		artifacts.insert(artifact_id, state);
+	}
+
+	gum::info!(
+		target: LOG_TARGET,
+		"recovered {} prepared artifacts and {} failed preparations from {}",
+		num_prepared,
+		artifacts.len() - num_prepared,
+		cache_path.display(),
+	);
+
+	Ok(artifacts)
+}
+
+/// Checks that the artifact at the given path matches the checksum stored at the given path.
+async fn verify_checksum(artifact_path: &Path, checksum_path: &Path) -> io::Result<bool> {
+	let expected = async_std::fs::read(checksum_path).await?;
+	Ok(artifact_checksum(artifact_path).await?[..] == expected[..])
+}
+
+/// Reads the failure record at the given path. Returns `None` if the record is not readable.
+async fn read_failure_record(path: &Path) -> Option<ArtifactState> {
+	let bytes = async_std::fs::read(path).await.ok()?;
+	let FailureRecord { error, num_failures, last_time_failed } =
+		FailureRecord::decode(&mut &bytes[..]).ok()?;
+	Some(ArtifactState::FailedToProcess {
+		last_time_failed: UNIX_EPOCH + Duration::from_secs(last_time_failed),
+		num_failures,
+		error,
+	})
+}
+
+async fn remove_stale_entry(path: &Path) {
+	let result = if path.is_dir().await {
+		async_std::fs::remove_dir_all(path).await
+	} else {
+		async_std::fs::remove_file(path).await
+	};
+	if let Err(err) = result {
+		gum::warn!(
+			target: LOG_TARGET,
+			"failed to remove {} from the artifact cache: {:?}",
+			path.display(),
+			err,
+		);
+	}
 }
 
 #[cfg(test)]
 mod tests {
-	use super::{ArtifactId, Artifacts};
+	use super::{
+		checksum_path, executor_fingerprint, write_failure_record, ArtifactId, ArtifactState,
+		Artifacts,
+	};
+	use crate::error::PrepareError;
 	use async_std::path::Path;
+	use polkadot_primitives::vstaging::ExecutorParams;
 	use sp_core::H256;
-	use std::str::FromStr;
+	use std::{
+		str::FromStr,
+		time::{Duration, SystemTime, UNIX_EPOCH},
+	};
+
+	/// Writes the artifact along with its checksum, the way the host does.
+	fn write_artifact(path: &Path, artifact: &[u8]) {
+		std::fs::write(path, artifact).unwrap();
+		std::fs::write(checksum_path(path), sp_core::hashing::blake2_256(artifact)).unwrap();
+	}
 
 	#[test]
 	fn from_file_name() {
 		assert!(ArtifactId::from_file_name("").is_none());
 		assert!(ArtifactId::from_file_name("junk").is_none());
+		assert!(ArtifactId::from_file_name(
+			"wasmtime_0x0022800000000000000000000000000000000000000000000000000000000000"
+		)
+		.is_none());
+		assert!(ArtifactId::from_file_name(
+			"wasmtime_0.0.0-incompatible_0x0022800000000000000000000000000000000000000000000000000000000000_0x0000000000000000000000000000000000000000000000000000000000000000"
+		)
+		.is_none());
+		// Missing the executor parameters hash.
+		assert!(ArtifactId::from_file_name(&format!(
+			"wasmtime_{}_0x0022800000000000000000000000000000000000000000000000000000000000",
+			executor_fingerprint(),
+		))
+		.is_none());
 
 		assert_eq!(
-			ArtifactId::from_file_name(
-				"wasmtime_0x0022800000000000000000000000000000000000000000000000000000000000"
-			),
+			ArtifactId::from_file_name(&format!(
+				"wasmtime_{}_0x0022800000000000000000000000000000000000000000000000000000000000_0x4321000000000000000000000000000000000000000000000000000000000000",
+				executor_fingerprint(),
+			)),
 			Some(ArtifactId::new(
 				hex_literal::hex![
 					"0022800000000000000000000000000000000000000000000000000000000000"
 				]
-				.into()
+				.into(),
+				hex_literal::hex![
+					"4321000000000000000000000000000000000000000000000000000000000000"
+				]
+				.into(),
 			)),
 		);
 	}
@@ -225,37 +708,216 @@ mod tests {
 				.unwrap()
 				.into();
 
+		let executor_params_hash =
+			H256::from_str("4321000000000000000000000000000000000000000000000000000000000000")
+				.unwrap()
+				.into();
+
 		assert_eq!(
-			ArtifactId::new(hash).path(path).to_str(),
-			Some(
-				"/test/wasmtime_0x1234567890123456789012345678901234567890123456789012345678901234"
-			),
+			ArtifactId::new(hash, executor_params_hash).path(path).to_str().map(ToOwned::to_owned),
+			Some(format!(
+				"/test/wasmtime_{}_0x1234567890123456789012345678901234567890123456789012345678901234_0x4321000000000000000000000000000000000000000000000000000000000000",
+				executor_fingerprint(),
+			)),
 		);
 	}
 
 	#[test]
-	fn artifacts_removes_cache_on_startup() {
+	fn artifacts_recovered_on_startup() {
 		let fake_cache_path = async_std::task::block_on(async move {
 			crate::worker_common::tmpfile("test-cache").await.unwrap()
 		});
-		let fake_artifact_path = {
-			let mut p = fake_cache_path.clone();
-			p.push("wasmtime_0x1234567890123456789012345678901234567890123456789012345678901234");
-			p
-		};
+		let hash: polkadot_parachain::primitives::ValidationCodeHash =
+			H256::from_str("1234567890123456789012345678901234567890123456789012345678901234")
+				.unwrap()
+				.into();
+		let compatible_artifact_path =
+			ArtifactId::new(hash, ExecutorParams::default().hash()).path(&fake_cache_path);
+		let incompatible_artifact_path = fake_cache_path.join(
+			"wasmtime_0.0.0-incompatible_0x1234567890123456789012345678901234567890123456789012345678901234",
+		);
+		let tmp_artifact_path = fake_cache_path.join("prepare-artifact-0123456789");
 
-		// create a tmp cache with 1 artifact.
+		// create a tmp cache with a compatible artifact, an incompatible one and a leftover
+		// of an interrupted preparation.
 
 		std::fs::create_dir_all(&fake_cache_path).unwrap();
-		std::fs::File::create(fake_artifact_path).unwrap();
+		write_artifact(&compatible_artifact_path, b"artifact");
+		write_artifact(&incompatible_artifact_path, b"artifact");
+		std::fs::write(&tmp_artifact_path, b"artifact").unwrap();
 
-		// this should remove it and re-create.
+		// this should keep only the compatible artifact and register it as prepared.
 
 		let p = &fake_cache_path;
-		async_std::task::block_on(async { Artifacts::new(p).await });
+		let mut artifacts = async_std::task::block_on(async { Artifacts::new(p).await });
+
+		assert_eq!(artifacts.len(), 1);
+		assert!(matches!(
+			artifacts.artifact_state_mut(&ArtifactId::new(hash, ExecutorParams::default().hash())),
+			Some(ArtifactState::Prepared { .. })
+		));
+
+		let mut remaining = std::fs::read_dir(&fake_cache_path)
+			.unwrap()
+			.map(|entry| entry.unwrap().file_name())
+			.collect::<Vec<_>>();
+		remaining.sort();
+		let mut expected = vec![
+			compatible_artifact_path.file_name().unwrap().to_owned(),
+			checksum_path(&compatible_artifact_path).file_name().unwrap().to_owned(),
+		];
+		expected.sort();
+		assert_eq!(remaining, expected);
+
+		std::fs::remove_dir_all(fake_cache_path).unwrap();
+	}
 
+	#[test]
+	fn corrupted_artifacts_removed_on_startup() {
+		let fake_cache_path = async_std::task::block_on(async move {
+			crate::worker_common::tmpfile("test-cache").await.unwrap()
+		});
+		let artifact_id =
+			|n: u8| ArtifactId::new(H256::repeat_byte(n).into(), ExecutorParams::default().hash());
+
+		std::fs::create_dir_all(&fake_cache_path).unwrap();
+		// An artifact that was modified after it had been written.
+		let corrupted_path = artifact_id(1).path(&fake_cache_path);
+		write_artifact(&corrupted_path, b"artifact");
+		std::fs::write(&corrupted_path, b"artefact").unwrap();
+		// An artifact without a checksum.
+		std::fs::write(artifact_id(2).path(&fake_cache_path), b"artifact").unwrap();
+		// A checksum without an artifact.
+		std::fs::write(checksum_path(&artifact_id(3).path(&fake_cache_path)), [0u8; 32]).unwrap();
+
+		let p = &fake_cache_path;
+		let artifacts = async_std::task::block_on(async { Artifacts::new(p).await });
+
+		assert_eq!(artifacts.len(), 0);
+		assert_eq!(std::fs::read_dir(&fake_cache_path).unwrap().count(), 0);
+
+		std::fs::remove_dir_all(fake_cache_path).unwrap();
+	}
+
+	#[test]
+	fn empty_artifacts_removed_on_startup() {
+		let fake_cache_path = async_std::task::block_on(async move {
+			crate::worker_common::tmpfile("test-cache").await.unwrap()
+		});
+		let hash =
+			H256::from_str("1234567890123456789012345678901234567890123456789012345678901234")
+				.unwrap()
+				.into();
+
+		std::fs::create_dir_all(&fake_cache_path).unwrap();
+		std::fs::File::create(
+			ArtifactId::new(hash, ExecutorParams::default().hash()).path(&fake_cache_path),
+		)
+		.unwrap();
+
+		let p = &fake_cache_path;
+		let artifacts = async_std::task::block_on(async { Artifacts::new(p).await });
+
+		assert_eq!(artifacts.len(), 0);
 		assert_eq!(std::fs::read_dir(&fake_cache_path).unwrap().count(), 0);
 
 		std::fs::remove_dir_all(fake_cache_path).unwrap();
 	}
+
+	#[test]
+	fn failure_records_recovered_on_startup() {
+		let fake_cache_path = async_std::task::block_on(async move {
+			crate::worker_common::tmpfile("test-cache").await.unwrap()
+		});
+		let failed_hash =
+			H256::from_str("1234567890123456789012345678901234567890123456789012345678901234")
+				.unwrap()
+				.into();
+		let prepared_hash =
+			H256::from_str("0022800000000000000000000000000000000000000000000000000000000000")
+				.unwrap()
+				.into();
+		let failed = ArtifactId::new(failed_hash, ExecutorParams::default().hash());
+		let prepared = ArtifactId::new(prepared_hash, ExecutorParams::default().hash());
+		let last_time_failed = UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+		std::fs::create_dir_all(&fake_cache_path).unwrap();
+		let p = &fake_cache_path;
+		async_std::task::block_on(async {
+			write_failure_record(p, &failed, &PrepareError::TimedOut, 2, last_time_failed)
+				.await
+				.unwrap();
+			// A stale record of an artifact that was prepared successfully afterwards.
+			write_failure_record(p, &prepared, &PrepareError::TimedOut, 1, last_time_failed)
+				.await
+				.unwrap();
+		});
+		write_artifact(&prepared.path(&fake_cache_path), b"artifact");
+		// A corrupted record.
+		std::fs::write(
+			fake_cache_path.join(format!(
+				"wasmtime_{}_0x0000000000000000000000000000000000000000000000000000000000000000_0x0000000000000000000000000000000000000000000000000000000000000000.failed",
+				executor_fingerprint(),
+			)),
+			b"junk",
+		)
+		.unwrap();
+
+		let mut artifacts = async_std::task::block_on(async { Artifacts::new(p).await });
+
+		assert_eq!(artifacts.len(), 2);
+		assert!(matches!(
+			artifacts.artifact_state_mut(&prepared),
+			Some(ArtifactState::Prepared { .. })
+		));
+		match artifacts.artifact_state_mut(&failed) {
+			Some(ArtifactState::FailedToProcess {
+				last_time_failed: recovered_last_time_failed,
+				num_failures: 2,
+				error: PrepareError::TimedOut,
+			}) => assert_eq!(*recovered_last_time_failed, last_time_failed),
+			_ => panic!("the failure record was not recovered"),
+		}
+
+		let mut remaining = std::fs::read_dir(&fake_cache_path)
+			.unwrap()
+			.map(|entry| entry.unwrap().file_name())
+			.collect::<Vec<_>>();
+		remaining.sort();
+		let mut expected = vec![
+			prepared.path(&fake_cache_path).file_name().unwrap().to_owned(),
+			checksum_path(&prepared.path(&fake_cache_path)).file_name().unwrap().to_owned(),
+			failed.failure_record_path(&fake_cache_path).file_name().unwrap().to_owned(),
+		];
+		expected.sort();
+		assert_eq!(remaining, expected);
+
+		std::fs::remove_dir_all(fake_cache_path).unwrap();
+	}
+
+	#[test]
+	fn least_recently_used_artifacts_are_evicted_first() {
+		let artifact_id =
+			|n: u8| ArtifactId::new(H256::repeat_byte(n).into(), ExecutorParams::default().hash());
+		let now = SystemTime::now();
+
+		let mut artifacts = Artifacts::empty();
+		artifacts.insert_prepared(artifact_id(1), now - Duration::from_secs(3), 100);
+		artifacts.insert_prepared(artifact_id(2), now - Duration::from_secs(2), 100);
+		artifacts.insert_prepared(artifact_id(3), now - Duration::from_secs(1), 100);
+		assert_eq!(artifacts.total_size(), 300);
+		assert!(artifacts.evict_to_fit(300).is_empty());
+
+		// The least recently used artifact is leased to an execution, so it has to stay.
+		let lease = match artifacts.artifact_state_mut(&artifact_id(1)) {
+			Some(ArtifactState::Prepared { lease, .. }) => lease.clone(),
+			_ => panic!("the artifact is not prepared"),
+		};
+		assert_eq!(artifacts.evict_to_fit(150), vec![artifact_id(2), artifact_id(3)]);
+		assert_eq!(artifacts.total_size(), 100);
+
+		drop(lease);
+		assert_eq!(artifacts.evict_to_fit(50), vec![artifact_id(1)]);
+		assert_eq!(artifacts.len(), 0);
+	}
 }
@@ -34,6 +34,37 @@ pub enum PrepareError {
 	/// This state indicates that the process assigned to prepare the artifact wasn't responsible
 	/// or were killed. This state is reported by the validation host (not by the worker).
 	DidNotMakeIt,
+	/// The preparation exceeded the memory limit and was aborted by the worker. The value is the
+	/// peak memory usage in bytes observed by the worker.
+	///
+	/// The usage is sampled from the resident set size of the worker, which depends on the
+	/// allocator and the memory pressure on the machine as much as on the PVF, so this error is
+	/// not deterministic.
+	OutOfMemory(u64),
+	/// The sandboxed preparation worker made a forbidden syscall and was terminated.
+	///
+	/// This may be caused by the PVF exploiting the compiler, but just as well by a bug in the node
+	/// or an unexpected syscall made by a system library, so it is not attributed to the PVF.
+	ForbiddenSyscall,
+}
+
+impl PrepareError {
+	/// Returns whether this is a deterministic error, i.e. one that would be reliably reproduced
+	/// should the preparation of the same PVF be retried.
+	///
+	/// Non-deterministic errors can happen spuriously, typically due to resource starvation, and
+	/// thus the preparation can be retried.
+	pub fn is_deterministic(&self) -> bool {
+		match self {
+			PrepareError::Prevalidation(_) |
+			PrepareError::Preparation(_) |
+			PrepareError::Panic(_) => true,
+			PrepareError::TimedOut |
+			PrepareError::DidNotMakeIt |
+			PrepareError::OutOfMemory(_) |
+			PrepareError::ForbiddenSyscall => false,
+		}
+	}
 }
 
 /// A error raised during validation of the candidate.
@@ -43,6 +74,10 @@ pub enum ValidationError {
 	InvalidCandidate(InvalidCandidate),
 	/// This error is raised due to inability to serve the request.
 	InternalError(String),
+	/// The execution did not finish within the wall-clock backstop timeout, even though it did not
+	/// exceed the CPU time limit. This most likely means that the machine is overloaded and thus
+	/// cannot be attributed to the candidate.
+	WallClockTimeout,
 }
 
 /// A description of an error raised during executing a PVF and can be attributed to the combination
@@ -73,7 +108,7 @@ pub enum InvalidCandidate {
 	/// validator. On the other hand, if the worker died because of (b) we would have better chances
 	/// to stop the attack.
 	AmbiguousWorkerDeath,
-	/// PVF execution (compilation is not included) took more time than was allotted.
+	/// PVF execution (compilation is not included) consumed more CPU time than was allotted.
 	HardTimeout,
 }
 
@@ -106,6 +141,10 @@ impl From<PrepareError> for ValidationError {
 			PrepareError::TimedOut => ValidationError::InternalError("prepare: timeout".to_owned()),
 			PrepareError::DidNotMakeIt =>
 				ValidationError::InternalError("prepare: did not make it".to_owned()),
+			PrepareError::OutOfMemory(peak) =>
+				ValidationError::InternalError(format!("prepare: out of memory: {} bytes", peak)),
+			PrepareError::ForbiddenSyscall =>
+				ValidationError::InternalError("prepare: forbidden syscall".to_owned()),
 		}
 	}
 }
@@ -25,3 +25,35 @@ mod worker;
 
 pub use queue::{start, ToQueue};
 pub use worker::worker_entrypoint;
+
+use std::time::Duration;
+
+/// The wall-clock time spent in each phase of a successful PVF execution.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionPhases {
+	/// Loading the compiled artifact from the disk and creating the runtime out of it.
+	pub artifact_load: Duration,
+	/// Instantiating the runtime.
+	pub instantiation: Duration,
+	/// Calling `validate_block`.
+	pub call: Duration,
+	/// Decoding the validation result returned by the PVF.
+	pub result_decoding: Duration,
+	/// Everything the host observed on top of the phases above: sending the request, receiving
+	/// the response and the worker bookkeeping in between.
+	pub ipc: Duration,
+}
+
+impl ExecutionPhases {
+	/// Returns the name and the duration of each phase, in execution order.
+	pub fn iter(&self) -> impl Iterator<Item = (&'static str, Duration)> {
+		[
+			("artifact_load", self.artifact_load),
+			("instantiation", self.instantiation),
+			("call", self.call),
+			("result_decoding", self.result_decoding),
+			("ipc", self.ipc),
+		]
+		.into_iter()
+	}
+}
@@ -21,6 +21,7 @@ use crate::{
 	artifacts::{ArtifactId, ArtifactPathId},
 	host::ResultSender,
 	metrics::Metrics,
+	sandbox::SandboxConfig,
 	worker_common::{IdleWorker, WorkerHandle},
 	InvalidCandidate, ValidationError, LOG_TARGET,
 };
@@ -31,8 +32,9 @@ use futures::{
 	stream::{FuturesUnordered, StreamExt as _},
 	Future, FutureExt,
 };
+use polkadot_primitives::vstaging::ExecutorParams;
 use slotmap::HopSlotMap;
-use std::{collections::VecDeque, fmt, time::Duration};
+use std::{collections::VecDeque, fmt, sync::Arc, time::Duration};
 
 slotmap::new_key_type! { struct Worker; }
 
@@ -40,6 +42,7 @@ slotmap::new_key_type! { struct Worker; }
 pub enum ToQueue {
 	Enqueue {
 		artifact: ArtifactPathId,
+		executor_params: Arc<ExecutorParams>,
 		execution_timeout: Duration,
 		params: Vec<u8>,
 		result_tx: ResultSender,
@@ -48,6 +51,7 @@ pub enum ToQueue {
 
 struct ExecuteJob {
 	artifact: ArtifactPathId,
+	executor_params: Arc<ExecutorParams>,
 	execution_timeout: Duration,
 	params: Vec<u8>,
 	result_tx: ResultSender,
@@ -109,6 +113,7 @@ struct Queue {
 
 	program_path: PathBuf,
 	spawn_timeout: Duration,
+	sandbox: Option<SandboxConfig>,
 
 	/// The queue of jobs that are waiting for a worker to pick up.
 	queue: VecDeque<ExecuteJob>,
@@ -122,12 +127,14 @@ impl Queue {
 		program_path: PathBuf,
 		worker_capacity: usize,
 		spawn_timeout: Duration,
+		sandbox: Option<SandboxConfig>,
 		to_queue_rx: mpsc::Receiver<ToQueue>,
 	) -> Self {
 		Self {
 			metrics,
 			program_path,
 			spawn_timeout,
+			sandbox,
 			to_queue_rx,
 			queue: VecDeque::new(),
 			mux: Mux::new(),
@@ -173,14 +180,15 @@ async fn purge_dead(metrics: &Metrics, workers: &mut Workers) {
 }
 
 fn handle_to_queue(queue: &mut Queue, to_queue: ToQueue) {
-	let ToQueue::Enqueue { artifact, execution_timeout, params, result_tx } = to_queue;
+	let ToQueue::Enqueue { artifact, executor_params, execution_timeout, params, result_tx } =
+		to_queue;
 	gum::debug!(
 		target: LOG_TARGET,
 		validation_code_hash = ?artifact.id.code_hash,
 		"enqueueing an artifact for execution",
 	);
 	queue.metrics.execute_enqueued();
-	let job = ExecuteJob { artifact, execution_timeout, params, result_tx };
+	let job = ExecuteJob { artifact, executor_params, execution_timeout, params, result_tx };
 
 	if let Some(available) = queue.workers.find_available() {
 		assign(queue, available, job);
@@ -225,11 +233,11 @@ fn handle_job_finish(
 	result_tx: ResultSender,
 ) {
 	let (idle_worker, result) = match outcome {
-		Outcome::Ok { result_descriptor, duration_ms, idle_worker } => {
+		Outcome::Ok { result_descriptor, duration_ms, phases, idle_worker } => {
 			// TODO: propagate the soft timeout
 			drop(duration_ms);
 
-			(Some(idle_worker), Ok(result_descriptor))
+			(Some(idle_worker), Ok((result_descriptor, phases)))
 		},
 		Outcome::InvalidCandidate { err, idle_worker } => (
 			Some(idle_worker),
@@ -239,6 +247,11 @@ fn handle_job_finish(
 			(Some(idle_worker), Err(ValidationError::InternalError(err))),
 		Outcome::HardTimeout =>
 			(None, Err(ValidationError::InvalidCandidate(InvalidCandidate::HardTimeout))),
+		Outcome::WallClockTimeout => (None, Err(ValidationError::WallClockTimeout)),
+		// The syscall may as well have been made by the node itself, so it is not attributed to
+		// the candidate.
+		Outcome::ForbiddenSyscall =>
+			(None, Err(ValidationError::InternalError("execute: forbidden syscall".to_owned()))),
 		Outcome::IoErr =>
 			(None, Err(ValidationError::InvalidCandidate(InvalidCandidate::AmbiguousWorkerDeath))),
 	};
@@ -290,17 +303,22 @@ fn spawn_extra_worker(queue: &mut Queue) {
 	queue.metrics.execute_worker().on_begin_spawn();
 	gum::debug!(target: LOG_TARGET, "spawning an extra worker");
 
-	queue
-		.mux
-		.push(spawn_worker_task(queue.program_path.clone(), queue.spawn_timeout).boxed());
+	queue.mux.push(
+		spawn_worker_task(queue.program_path.clone(), queue.spawn_timeout, queue.sandbox.clone())
+			.boxed(),
+	);
 	queue.workers.spawn_inflight += 1;
 }
 
-async fn spawn_worker_task(program_path: PathBuf, spawn_timeout: Duration) -> QueueEvent {
+async fn spawn_worker_task(
+	program_path: PathBuf,
+	spawn_timeout: Duration,
+	sandbox: Option<SandboxConfig>,
+) -> QueueEvent {
 	use futures_timer::Delay;
 
 	loop {
-		match super::worker::spawn(&program_path, spawn_timeout).await {
+		match super::worker::spawn(&program_path, spawn_timeout, sandbox.clone()).await {
 			Ok((idle, handle)) => break QueueEvent::Spawn(idle, handle),
 			Err(err) => {
 				gum::warn!(target: LOG_TARGET, "failed to spawn an execute worker: {:?}", err);
@@ -335,6 +353,7 @@ fn assign(queue: &mut Queue, worker: Worker, job: ExecuteJob) {
 			let outcome = super::worker::start_work(
 				idle,
 				job.artifact.clone(),
+				job.executor_params,
 				job.execution_timeout,
 				job.params,
 			)
@@ -350,8 +369,11 @@ pub fn start(
 	program_path: PathBuf,
 	worker_capacity: usize,
 	spawn_timeout: Duration,
+	sandbox: Option<SandboxConfig>,
 ) -> (mpsc::Sender<ToQueue>, impl Future<Output = ()>) {
 	let (to_queue_tx, to_queue_rx) = mpsc::channel(20);
-	let run = Queue::new(metrics, program_path, worker_capacity, spawn_timeout, to_queue_rx).run();
+	let run =
+		Queue::new(metrics, program_path, worker_capacity, spawn_timeout, sandbox, to_queue_rx)
+			.run();
 	(to_queue_tx, run)
 }
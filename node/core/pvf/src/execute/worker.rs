@@ -14,9 +14,11 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
+use super::ExecutionPhases;
 use crate::{
 	artifacts::ArtifactPathId,
-	executor_intf::Executor,
+	executor_intf::{Executor, ThreadCpuClock},
+	sandbox::SandboxConfig,
 	worker_common::{
 		bytes_to_path, framed_recv, framed_send, path_to_bytes, spawn_with_program_path,
 		worker_event_loop, IdleWorker, SpawnErr, WorkerHandle,
@@ -28,35 +30,66 @@ use async_std::{
 	os::unix::net::UnixStream,
 	path::{Path, PathBuf},
 };
-use futures::FutureExt;
+use futures::{channel::oneshot, FutureExt};
 use futures_timer::Delay;
 use parity_scale_codec::{Decode, Encode};
 use polkadot_parachain::primitives::ValidationResult;
-use std::time::{Duration, Instant};
+use polkadot_primitives::vstaging::ExecutorParams;
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// The execution timeout is enforced in terms of the CPU time consumed by the execute worker.
+/// The host additionally enforces a wall-clock timeout as a backstop in case the worker becomes
+/// unresponsive. The backstop is this many times the execution timeout.
+///
+/// It is deliberately generous: hitting the backstop means the machine is overloaded rather than
+/// the candidate is invalid.
+pub const EXECUTION_TIMEOUT_WALL_CLOCK_FACTOR: u32 = 4;
+
+/// The interval at which the execute worker checks the CPU time consumed by the execution.
+const CPU_TIME_POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 /// Spawns a new worker with the given program path that acts as the worker and the spawn timeout.
 ///
 /// The program should be able to handle `<program-path> execute-worker <socket-path>` invocation.
+///
+/// If `sandbox` is `Some`, the worker will be sandboxed.
 pub async fn spawn(
 	program_path: &Path,
 	spawn_timeout: Duration,
+	sandbox: Option<SandboxConfig>,
 ) -> Result<(IdleWorker, WorkerHandle), SpawnErr> {
-	spawn_with_program_path("execute", program_path, &["execute-worker"], spawn_timeout).await
+	spawn_with_program_path("execute", program_path, &["execute-worker"], spawn_timeout, sandbox)
+		.await
 }
 
 /// Outcome of PVF execution.
 pub enum Outcome {
-	/// PVF execution completed successfully and the result is returned. The worker is ready for
-	/// another job.
-	Ok { result_descriptor: ValidationResult, duration_ms: u64, idle_worker: IdleWorker },
+	/// PVF execution completed successfully and the result is returned along with the time spent
+	/// in each phase of the execution. The worker is ready for another job.
+	Ok {
+		result_descriptor: ValidationResult,
+		duration_ms: u64,
+		phases: ExecutionPhases,
+		idle_worker: IdleWorker,
+	},
 	/// The candidate validation failed. It may be for example because the wasm execution triggered a trap.
 	/// Errors related to the preparation process are not expected to be encountered by the execution workers.
 	InvalidCandidate { err: String, idle_worker: IdleWorker },
 	/// An internal error happened during the validation. Such an error is most likely related to
 	/// some transient glitch.
 	InternalError { err: String, idle_worker: IdleWorker },
-	/// The execution time exceeded the hard limit. The worker is terminated.
+	/// The execution exceeded the CPU time limit. This is reported by the worker, which then
+	/// terminates itself.
 	HardTimeout,
+	/// The worker did not respond within the wall-clock backstop timeout. The worker is
+	/// terminated.
+	WallClockTimeout,
+	/// The sandboxed worker made a forbidden syscall during the execution. The worker terminates
+	/// itself.
+	ForbiddenSyscall,
 	/// An I/O error happened during communication with the worker. This may mean that the worker
 	/// process already died. The token is not returned in any case.
 	IoErr,
@@ -67,6 +100,7 @@ pub enum Outcome {
 pub async fn start_work(
 	worker: IdleWorker,
 	artifact: ArtifactPathId,
+	executor_params: Arc<ExecutorParams>,
 	execution_timeout: Duration,
 	validation_params: Vec<u8>,
 ) -> Outcome {
@@ -80,7 +114,16 @@ pub async fn start_work(
 		artifact.path.display(),
 	);
 
-	if let Err(error) = send_request(&mut stream, &artifact.path, &validation_params).await {
+	let start = Instant::now();
+	if let Err(error) = send_request(
+		&mut stream,
+		&artifact.path,
+		&executor_params,
+		&validation_params,
+		execution_timeout,
+	)
+	.await
+	{
 		gum::warn!(
 			target: LOG_TARGET,
 			worker_pid = %pid,
@@ -107,37 +150,73 @@ pub async fn start_work(
 				Ok(response) => response,
 			}
 		},
-		_ = Delay::new(execution_timeout).fuse() => {
+		_ = Delay::new(execution_timeout * EXECUTION_TIMEOUT_WALL_CLOCK_FACTOR).fuse() => {
 			gum::warn!(
 				target: LOG_TARGET,
 				worker_pid = %pid,
 				validation_code_hash = ?artifact.id.code_hash,
-				"execution worker exceeded alloted time for execution",
+				"execution worker exceeded the wall-clock backstop timeout",
 			);
-			return Outcome::HardTimeout;
+			return Outcome::WallClockTimeout;
 		},
 	};
 
 	match response {
-		Response::Ok { result_descriptor, duration_ms } =>
-			Outcome::Ok { result_descriptor, duration_ms, idle_worker: IdleWorker { stream, pid } },
+		Response::Ok { result_descriptor, duration_ms, phases } => {
+			let mut phases = phases.into_phases();
+			// Whatever the worker didn't account for was spent on the way to and from it.
+			phases.ipc = start.elapsed().saturating_sub(
+				phases.artifact_load + phases.instantiation + phases.call + phases.result_decoding,
+			);
+			Outcome::Ok {
+				result_descriptor,
+				duration_ms,
+				phases,
+				idle_worker: IdleWorker { stream, pid },
+			}
+		},
 		Response::InvalidCandidate(err) =>
 			Outcome::InvalidCandidate { err, idle_worker: IdleWorker { stream, pid } },
 		Response::InternalError(err) =>
 			Outcome::InternalError { err, idle_worker: IdleWorker { stream, pid } },
+		Response::TimedOut => {
+			gum::warn!(
+				target: LOG_TARGET,
+				worker_pid = %pid,
+				validation_code_hash = ?artifact.id.code_hash,
+				"execution worker exceeded alloted CPU time for execution",
+			);
+			Outcome::HardTimeout
+		},
+		Response::ForbiddenSyscall => {
+			gum::warn!(
+				target: LOG_TARGET,
+				worker_pid = %pid,
+				validation_code_hash = ?artifact.id.code_hash,
+				"execution worker made a forbidden syscall",
+			);
+			Outcome::ForbiddenSyscall
+		},
 	}
 }
 
 async fn send_request(
 	stream: &mut UnixStream,
 	artifact_path: &Path,
+	executor_params: &ExecutorParams,
 	validation_params: &[u8],
+	execution_timeout: Duration,
 ) -> io::Result<()> {
 	framed_send(stream, path_to_bytes(artifact_path)).await?;
-	framed_send(stream, validation_params).await
+	framed_send(stream, &executor_params.encode()).await?;
+	framed_send(stream, validation_params).await?;
+	framed_send(stream, &(execution_timeout.as_secs(), execution_timeout.subsec_nanos()).encode())
+		.await
 }
 
-async fn recv_request(stream: &mut UnixStream) -> io::Result<(PathBuf, Vec<u8>)> {
+async fn recv_request(
+	stream: &mut UnixStream,
+) -> io::Result<(PathBuf, ExecutorParams, Vec<u8>, Duration)> {
 	let artifact_path = framed_recv(stream).await?;
 	let artifact_path = bytes_to_path(&artifact_path).ok_or_else(|| {
 		io::Error::new(
@@ -145,8 +224,22 @@ async fn recv_request(stream: &mut UnixStream) -> io::Result<(PathBuf, Vec<u8>)>
 			"execute pvf recv_request: non utf-8 artifact path".to_string(),
 		)
 	})?;
+	let executor_params = framed_recv(stream).await?;
+	let executor_params = ExecutorParams::decode(&mut &executor_params[..]).map_err(|_| {
+		io::Error::new(
+			io::ErrorKind::Other,
+			"execute pvf recv_request: failed to decode executor params".to_string(),
+		)
+	})?;
 	let params = framed_recv(stream).await?;
-	Ok((artifact_path, params))
+	let execution_timeout = framed_recv(stream).await?;
+	let (secs, nanos) = <(u64, u32)>::decode(&mut &execution_timeout[..]).map_err(|_| {
+		io::Error::new(
+			io::ErrorKind::Other,
+			"execute pvf recv_request: failed to decode execution timeout".to_string(),
+		)
+	})?;
+	Ok((artifact_path, executor_params, params, Duration::new(secs, nanos)))
 }
 
 async fn send_response(stream: &mut UnixStream, response: Response) -> io::Result<()> {
@@ -163,11 +256,52 @@ async fn recv_response(stream: &mut UnixStream) -> io::Result<Response> {
 	})
 }
 
+/// The phases of the execution measured by the worker, in nanoseconds.
+#[derive(Encode, Decode)]
+struct WorkerPhases {
+	artifact_load: u64,
+	instantiation: u64,
+	call: u64,
+	result_decoding: u64,
+}
+
+impl WorkerPhases {
+	fn from_phases(phases: &ExecutionPhases) -> Self {
+		let nanos = |d: Duration| d.as_nanos().try_into().unwrap_or(u64::MAX);
+		Self {
+			artifact_load: nanos(phases.artifact_load),
+			instantiation: nanos(phases.instantiation),
+			call: nanos(phases.call),
+			result_decoding: nanos(phases.result_decoding),
+		}
+	}
+
+	fn into_phases(self) -> ExecutionPhases {
+		ExecutionPhases {
+			artifact_load: Duration::from_nanos(self.artifact_load),
+			instantiation: Duration::from_nanos(self.instantiation),
+			call: Duration::from_nanos(self.call),
+			result_decoding: Duration::from_nanos(self.result_decoding),
+			ipc: Duration::ZERO,
+		}
+	}
+}
+
 #[derive(Encode, Decode)]
 enum Response {
-	Ok { result_descriptor: ValidationResult, duration_ms: u64 },
+	/// `duration_ms` is the CPU time consumed by the execution.
+	Ok {
+		result_descriptor: ValidationResult,
+		duration_ms: u64,
+		phases: WorkerPhases,
+	},
 	InvalidCandidate(String),
 	InternalError(String),
+	/// The execution exceeded the CPU time limit.
+	TimedOut,
+	/// The sandboxed worker made a forbidden syscall. This response is sent by the sandbox
+	/// rather than the event loop.
+	ForbiddenSyscall,
 }
 
 impl Response {
@@ -183,31 +317,123 @@ impl Response {
 /// The entrypoint that the spawned execute worker should start with. The `socket_path` specifies
 /// the path to the socket used to communicate with the host.
 pub fn worker_entrypoint(socket_path: &str) {
-	worker_event_loop("execute", socket_path, |mut stream| async move {
-		let executor = Executor::new().map_err(|e| {
-			io::Error::new(io::ErrorKind::Other, format!("cannot create executor: {}", e))
-		})?;
+	// Sent to the host by the sandbox should the worker make a forbidden syscall.
+	let forbidden_syscall = Response::ForbiddenSyscall.encode();
+	worker_event_loop("execute", socket_path, forbidden_syscall, |mut stream| async move {
+		// The executor is configured with the executor parameters, so it is only recreated when
+		// a request comes with different ones, e.g. after a session change.
+		let mut current: Option<(ExecutorParams, Arc<Executor>)> = None;
 		loop {
-			let (artifact_path, params) = recv_request(&mut stream).await?;
+			let (artifact_path, executor_params, params, execution_timeout) =
+				recv_request(&mut stream).await?;
 			gum::debug!(
 				target: LOG_TARGET,
 				worker_pid = %std::process::id(),
 				"worker: validating artifact {}",
 				artifact_path.display(),
 			);
-			let response = validate_using_artifact(&artifact_path, &params, &executor).await;
+
+			let reusable = match &current {
+				Some((current_params, executor)) if *current_params == executor_params =>
+					Some(executor.clone()),
+				_ => None,
+			};
+			let executor = match reusable {
+				Some(executor) => executor,
+				None => match Executor::new(&executor_params) {
+					Ok(executor) => {
+						let executor = Arc::new(executor);
+						current = Some((executor_params, executor.clone()));
+						executor
+					},
+					Err(err) => {
+						let response =
+							Response::InternalError(format!("cannot create executor: {}", err));
+						send_response(&mut stream, response).await?;
+						continue
+					},
+				},
+			};
+
+			let response =
+				validate_with_cpu_time_limit(executor, artifact_path, params, execution_timeout)
+					.await;
+			let timed_out = matches!(response, Response::TimedOut);
 			send_response(&mut stream, response).await?;
+
+			if timed_out {
+				// The execution thread cannot be interrupted and is still running. Shut down the
+				// worker, taking the thread down with it.
+				return Err(io::Error::new(
+					io::ErrorKind::Other,
+					"execution exceeded the CPU time limit".to_string(),
+				))
+			}
 		}
 	});
 }
 
-async fn validate_using_artifact(
+/// Runs the validation on a separate thread while watching the CPU time consumed by the executor.
+///
+/// Returns [`Response::TimedOut`] as soon as the consumed CPU time exceeds `execution_timeout`,
+/// without waiting for the execution to finish.
+async fn validate_with_cpu_time_limit(
+	executor: Arc<Executor>,
+	artifact_path: PathBuf,
+	params: Vec<u8>,
+	execution_timeout: Duration,
+) -> Response {
+	let cpu_clock = executor.cpu_clock();
+	let cpu_time_start = match cpu_clock.elapsed() {
+		Ok(cpu_time) => cpu_time,
+		Err(err) => return Response::InternalError(format!("cannot read CPU time: {}", err)),
+	};
+
+	let (response_tx, response_rx) = oneshot::channel();
+	let spawn_result =
+		std::thread::Builder::new().name("pvf-execute".to_string()).spawn(move || {
+			let response = validate_using_artifact(
+				&artifact_path,
+				&params,
+				&executor,
+				cpu_clock,
+				cpu_time_start,
+			);
+			let _ = response_tx.send(response);
+		});
+	if let Err(err) = spawn_result {
+		return Response::InternalError(format!("cannot spawn execution thread: {}", err))
+	}
+
+	let mut response_rx = response_rx.fuse();
+	loop {
+		futures::select! {
+			response = response_rx => {
+				return response.unwrap_or_else(|_| {
+					Response::InternalError("execution thread terminated".to_string())
+				})
+			},
+			_ = Delay::new(CPU_TIME_POLL_INTERVAL).fuse() => {
+				match cpu_clock.elapsed() {
+					Ok(cpu_time) if cpu_time.saturating_sub(cpu_time_start) > execution_timeout =>
+						return Response::TimedOut,
+					Ok(_) => {},
+					Err(err) =>
+						return Response::InternalError(format!("cannot read CPU time: {}", err)),
+				}
+			},
+		}
+	}
+}
+
+fn validate_using_artifact(
 	artifact_path: &Path,
 	params: &[u8],
 	executor: &Executor,
+	cpu_clock: ThreadCpuClock,
+	cpu_time_start: Duration,
 ) -> Response {
-	let validation_started_at = Instant::now();
-	let descriptor_bytes = match unsafe {
+	let (descriptor_bytes, mut phases) = match unsafe {
 		// SAFETY: this should be safe since the compiled artifact passed here comes from the
 		//         file created by the prepare workers. These files are obtained by calling
 		//         [`executor_intf::prepare`].
@@ -217,13 +443,18 @@ async fn validate_using_artifact(
 		Ok(d) => d,
 	};
 
-	let duration_ms = validation_started_at.elapsed().as_millis() as u64;
+	let duration_ms = match cpu_clock.elapsed() {
+		Ok(cpu_time) => cpu_time.saturating_sub(cpu_time_start).as_millis() as u64,
+		Err(err) => return Response::InternalError(format!("cannot read CPU time: {}", err)),
+	};
 
+	let start = Instant::now();
 	let result_descriptor = match ValidationResult::decode(&mut &descriptor_bytes[..]) {
 		Err(err) =>
 			return Response::InvalidCandidate(format!("validation result decoding failed: {}", err)),
 		Ok(r) => r,
 	};
+	phases.result_decoding = start.elapsed();
 
-	Response::Ok { result_descriptor, duration_ms }
+	Response::Ok { result_descriptor, duration_ms, phases: WorkerPhases::from_phases(&phases) }
 }
@@ -16,6 +16,8 @@
 
 //! Interface to the Substrate Executor
 
+use crate::execute::ExecutionPhases;
+use polkadot_primitives::vstaging::{ExecutionEnvironment, ExecutorParam, ExecutorParams};
 use sc_executor_common::{
 	runtime_blob::RuntimeBlob,
 	wasm_runtime::{InvokeMethod, WasmModule as _},
@@ -26,6 +28,7 @@ use sp_externalities::MultiRemovalResults;
 use std::{
 	any::{Any, TypeId},
 	path::Path,
+	time::{Duration, Instant},
 };
 
 // Memory configuration
@@ -43,17 +46,23 @@ use std::{
 const DEFAULT_HEAP_PAGES_ESTIMATE: u64 = 32;
 const EXTRA_HEAP_PAGES: u64 = 2048;
 
+/// The size of a WASM page in bytes.
+const WASM_PAGE_SIZE: u64 = 65536;
+
 /// The number of bytes devoted for the stack during wasm execution of a PVF.
 const NATIVE_STACK_MAX: u32 = 256 * 1024 * 1024;
 
-const CONFIG: Config = Config {
+/// The executor configuration used when no executor parameters override it.
+pub const DEFAULT_CONFIG: Config = Config {
 	allow_missing_func_imports: true,
 	cache_path: None,
 	semantics: Semantics {
 		extra_heap_pages: EXTRA_HEAP_PAGES,
 
 		// NOTE: This is specified in bytes, so we multiply by WASM page size.
-		max_memory_size: Some(((DEFAULT_HEAP_PAGES_ESTIMATE + EXTRA_HEAP_PAGES) * 65536) as usize),
+		max_memory_size: Some(
+			((DEFAULT_HEAP_PAGES_ESTIMATE + EXTRA_HEAP_PAGES) * WASM_PAGE_SIZE) as usize,
+		),
 
 		instantiation_strategy:
 			sc_executor_wasmtime::InstantiationStrategy::RecreateInstanceCopyOnWrite,
@@ -85,6 +94,26 @@ const CONFIG: Config = Config {
 	},
 };
 
+/// Describes the semantics of [`DEFAULT_CONFIG`]. The executor parameters are applied on top of
+/// those, so together with the wasmtime version they determine the artifacts the node produces.
+pub fn default_semantics_description() -> String {
+	let semantics = &DEFAULT_CONFIG.semantics;
+	let stack_limit = semantics
+		.deterministic_stack_limit
+		.as_ref()
+		.map(|limit| (limit.logical_max, limit.native_stack_max));
+	format!(
+		"extra_heap_pages={};max_memory_size={:?};instantiation_strategy={:?};\
+		 deterministic_stack_limit={:?};canonicalize_nans={};parallel_compilation={}",
+		semantics.extra_heap_pages,
+		semantics.max_memory_size,
+		semantics.instantiation_strategy,
+		stack_limit,
+		semantics.canonicalize_nans,
+		semantics.parallel_compilation,
+	)
+}
+
 /// Runs the prevalidation on the given code. Returns a [`RuntimeBlob`] if it succeeds.
 pub fn prevalidate(code: &[u8]) -> Result<RuntimeBlob, sc_executor_common::error::WasmError> {
 	let blob = RuntimeBlob::new(code)?;
@@ -95,19 +124,71 @@ pub fn prevalidate(code: &[u8]) -> Result<RuntimeBlob, sc_executor_common::error
 	Ok(blob)
 }
 
+/// Converts the executor parameters agreed on-chain into the wasmtime semantics, starting from the
+/// semantics of [`DEFAULT_CONFIG`].
+///
+/// Returns an error if the parameters cannot be applied, e.g. because they target an execution
+/// environment this node doesn't know about.
+pub fn params_to_wasmtime_semantics(executor_params: &ExecutorParams) -> Result<Semantics, String> {
+	match executor_params.environment() {
+		ExecutionEnvironment::WasmtimeGeneric => {},
+	}
+
+	let mut semantics = DEFAULT_CONFIG.semantics;
+	let mut stack_limit = semantics
+		.deterministic_stack_limit
+		.take()
+		.ok_or_else(|| "no default deterministic stack limit set".to_string())?;
+	let mut max_memory_pages = None;
+
+	for param in executor_params.iter() {
+		match param {
+			ExecutorParam::ExtraHeapPages(pages) => semantics.extra_heap_pages = *pages,
+			ExecutorParam::MaxMemoryPages(pages) => max_memory_pages = Some(*pages as u64),
+			ExecutorParam::StackLogicalMax(max) => stack_limit.logical_max = *max,
+			ExecutorParam::StackNativeMax(max) => stack_limit.native_stack_max = *max,
+		}
+	}
+
+	// If the maximum memory is not given explicitly, keep it in line with the extra heap pages the
+	// same way the default configuration does.
+	let max_memory_pages = max_memory_pages
+		.unwrap_or_else(|| DEFAULT_HEAP_PAGES_ESTIMATE.saturating_add(semantics.extra_heap_pages));
+	let max_memory_size = max_memory_pages
+		.checked_mul(WASM_PAGE_SIZE)
+		.and_then(|size| usize::try_from(size).ok())
+		.ok_or_else(|| format!("max memory of {} pages is too large", max_memory_pages))?;
+
+	semantics.max_memory_size = Some(max_memory_size);
+	semantics.deterministic_stack_limit = Some(stack_limit);
+	Ok(semantics)
+}
+
 /// Runs preparation on the given runtime blob. If successful, it returns a serialized compiled
-/// artifact which can then be used to pass into [`execute`] after writing it to the disk.
-pub fn prepare(blob: RuntimeBlob) -> Result<Vec<u8>, sc_executor_common::error::WasmError> {
-	sc_executor_wasmtime::prepare_runtime_artifact(blob, &CONFIG.semantics)
+/// artifact which can then be used to pass into [`Executor::execute`] after writing it to the disk.
+///
+/// The `semantics` must be the same ones the artifact is going to be executed with, see
+/// [`params_to_wasmtime_semantics`].
+pub fn prepare(
+	blob: RuntimeBlob,
+	semantics: &Semantics,
+) -> Result<Vec<u8>, sc_executor_common::error::WasmError> {
+	sc_executor_wasmtime::prepare_runtime_artifact(blob, semantics)
 }
 
 pub struct Executor {
 	thread_pool: rayon::ThreadPool,
 	spawner: TaskSpawner,
+	cpu_clock: ThreadCpuClock,
+	config: Config,
 }
 
 impl Executor {
-	pub fn new() -> Result<Self, String> {
+	/// Creates an executor that runs artifacts with the given executor parameters.
+	pub fn new(executor_params: &ExecutorParams) -> Result<Self, String> {
+		let config =
+			Config { semantics: params_to_wasmtime_semantics(executor_params)?, ..DEFAULT_CONFIG };
+
 		// Wasmtime powers the Substrate Executor. It compiles the wasm bytecode into native code.
 		// That native code does not create any stacks and just reuses the stack of the thread that
 		// wasmtime was invoked from.
@@ -133,7 +214,7 @@ impl Executor {
 		//
 		// Typically on Linux the main thread gets the stack size specified by the `ulimit` and
 		// typically it's configured to 8 MiB. Rust's spawned threads are 2 MiB. OTOH, the
-		// NATIVE_STACK_MAX is set to 256 MiB. Not nearly enough.
+		// NATIVE_STACK_MAX is set to 256 MiB by default. Not nearly enough.
 		//
 		// Hence we need to increase it.
 		//
@@ -143,8 +224,14 @@ impl Executor {
 		//
 		// The reasoning why we pick this particular size is:
 		//
-		// The default Rust thread stack limit 2 MiB + 256 MiB wasm stack.
-		let thread_stack_size = 2 * 1024 * 1024 + NATIVE_STACK_MAX as usize;
+		// The default Rust thread stack limit 2 MiB + the wasm stack, 256 MiB unless overridden by
+		// the executor parameters.
+		let native_stack_max = config
+			.semantics
+			.deterministic_stack_limit
+			.as_ref()
+			.map_or(NATIVE_STACK_MAX, |stack_limit| stack_limit.native_stack_max);
+		let thread_stack_size = 2 * 1024 * 1024 + native_stack_max as usize;
 		let thread_pool = rayon::ThreadPoolBuilder::new()
 			.num_threads(1)
 			.stack_size(thread_stack_size)
@@ -154,16 +241,28 @@ impl Executor {
 		let spawner =
 			TaskSpawner::new().map_err(|e| format!("cannot create task spawner: {}", e))?;
 
-		Ok(Self { thread_pool, spawner })
+		// The pool consists of a single long-living thread, so it's enough to obtain the clock
+		// once.
+		let cpu_clock = thread_pool
+			.install(ThreadCpuClock::current)
+			.map_err(|e| format!("cannot obtain the execution thread CPU clock: {}", e))?;
+
+		Ok(Self { thread_pool, spawner, cpu_clock, config })
+	}
+
+	/// Returns the clock measuring the CPU time consumed by the thread executing the PVFs.
+	pub fn cpu_clock(&self) -> ThreadCpuClock {
+		self.cpu_clock
 	}
 
 	/// Executes the given PVF in the form of a compiled artifact and returns the result of execution
-	/// upon success.
+	/// upon success, along with the time spent loading, instantiating and calling it. The remaining
+	/// phases of [`ExecutionPhases`] are left for the caller to fill in.
 	///
 	/// # Safety
 	///
 	/// The caller must ensure that the compiled artifact passed here was:
-	///   1) produced by [`prepare`],
+	///   1) produced by [`prepare`] with the same executor parameters as this executor's,
 	///   2) written to the disk as a file,
 	///   3) was not modified,
 	///   4) will not be modified while any runtime using this artifact is alive, or is being
@@ -174,8 +273,9 @@ impl Executor {
 		&self,
 		compiled_artifact_path: &Path,
 		params: &[u8],
-	) -> Result<Vec<u8>, String> {
+	) -> Result<(Vec<u8>, ExecutionPhases), String> {
 		let spawner = self.spawner.clone();
+		let config = self.config.clone();
 		let mut result = None;
 		self.thread_pool.scope({
 			let result = &mut result;
@@ -183,7 +283,7 @@ impl Executor {
 				s.spawn(move |_| {
 					// spawn does not return a value, so we need to use a variable to pass the result.
 					*result = Some(
-						do_execute(compiled_artifact_path, params, spawner)
+						do_execute(compiled_artifact_path, config, params, spawner)
 							.map_err(|err| format!("execute error: {:?}", err)),
 					);
 				});
@@ -195,9 +295,10 @@ impl Executor {
 
 unsafe fn do_execute(
 	compiled_artifact_path: &Path,
+	config: Config,
 	params: &[u8],
 	spawner: impl sp_core::traits::SpawnNamed + 'static,
-) -> Result<Vec<u8>, sc_executor_common::error::Error> {
+) -> Result<(Vec<u8>, ExecutionPhases), sc_executor_common::error::Error> {
 	let mut extensions = sp_externalities::Extensions::new();
 
 	extensions.register(sp_core::traits::TaskExecutorExt::new(spawner));
@@ -205,13 +306,71 @@ unsafe fn do_execute(
 
 	let mut ext = ValidationExternalities(extensions);
 
-	sc_executor::with_externalities_safe(&mut ext, || {
+	let mut phases = ExecutionPhases::default();
+	let result = sc_executor::with_externalities_safe(&mut ext, || {
+		let start = Instant::now();
 		let runtime = sc_executor_wasmtime::create_runtime_from_artifact::<HostFunctions>(
 			compiled_artifact_path,
-			CONFIG,
+			config,
 		)?;
-		runtime.new_instance()?.call(InvokeMethod::Export("validate_block"), params)
-	})?
+		phases.artifact_load = start.elapsed();
+
+		let start = Instant::now();
+		let mut instance = runtime.new_instance()?;
+		phases.instantiation = start.elapsed();
+
+		let start = Instant::now();
+		let result = instance.call(InvokeMethod::Export("validate_block"), params);
+		phases.call = start.elapsed();
+		result
+	})??;
+	Ok((result, phases))
+}
+
+/// A clock measuring the CPU time consumed by a particular thread. The clock can be read from any
+/// thread of the process.
+#[derive(Clone, Copy)]
+pub struct ThreadCpuClock(#[cfg(target_os = "linux")] libc::clockid_t);
+
+impl ThreadCpuClock {
+	/// Returns the clock of the calling thread.
+	#[cfg(target_os = "linux")]
+	fn current() -> Result<Self, String> {
+		let mut clock_id: libc::clockid_t = 0;
+		// SAFETY: `pthread_self` always returns a valid handle of the calling thread and the
+		//         out-pointer is valid for writes.
+		let ret = unsafe { libc::pthread_getcpuclockid(libc::pthread_self(), &mut clock_id) };
+		if ret != 0 {
+			return Err(format!("pthread_getcpuclockid: {}", std::io::Error::from_raw_os_error(ret)))
+		}
+		Ok(Self(clock_id))
+	}
+
+	/// Returns the CPU time consumed by the thread so far.
+	#[cfg(target_os = "linux")]
+	pub fn elapsed(&self) -> Result<Duration, String> {
+		let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+		// SAFETY: the out-pointer is valid for writes.
+		let ret = unsafe { libc::clock_gettime(self.0, &mut ts) };
+		if ret != 0 {
+			return Err(format!("clock_gettime: {}", std::io::Error::last_os_error()))
+		}
+		Ok(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
+	}
+
+	/// Per-thread CPU clocks are only supported on Linux.
+	#[cfg(not(target_os = "linux"))]
+	fn current() -> Result<Self, String> {
+		Ok(Self())
+	}
+
+	/// Per-thread CPU clocks are only supported on Linux. Elsewhere, the CPU time cannot be
+	/// measured and no time is reported as consumed, leaving the wall-clock timeout as the only
+	/// limit.
+	#[cfg(not(target_os = "linux"))]
+	pub fn elapsed(&self) -> Result<Duration, String> {
+		Ok(Duration::ZERO)
+	}
 }
 
 type HostFunctions = (
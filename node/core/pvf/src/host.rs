@@ -21,10 +21,12 @@
 //! [`ValidationHost`], that allows communication with that event-loop.
 
 use crate::{
-	artifacts::{ArtifactId, ArtifactPathId, ArtifactState, Artifacts},
-	execute,
+	artifacts::{self, ArtifactId, ArtifactLease, ArtifactPathId, ArtifactState, Artifacts},
+	execute::{self, ExecutionPhases},
 	metrics::Metrics,
-	prepare, PrepareResult, Priority, Pvf, ValidationError, LOG_TARGET,
+	prepare,
+	sandbox::{self, SandboxConfig, SandboxSupport},
+	PrepareError, PrepareResult, Priority, Pvf, ValidationError, LOG_TARGET,
 };
 use always_assert::never;
 use async_std::path::{Path, PathBuf};
@@ -33,8 +35,10 @@ use futures::{
 	Future, FutureExt, SinkExt, StreamExt,
 };
 use polkadot_parachain::primitives::ValidationResult;
+use polkadot_primitives::vstaging::ExecutorParams;
 use std::{
 	collections::HashMap,
+	sync::Arc,
 	time::{Duration, SystemTime},
 };
 
@@ -48,8 +52,22 @@ pub const PRECHECK_COMPILATION_TIMEOUT: Duration = Duration::from_secs(60);
 // NOTE: If you change this make sure to fix the buckets of `pvf_preparation_time` metric.
 pub const EXECUTE_COMPILATION_TIMEOUT: Duration = Duration::from_secs(180);
 
+/// The default limit on the memory a prepare worker may use while compiling a PVF. The preparation
+/// is aborted with [`PrepareError::OutOfMemory`](crate::PrepareError::OutOfMemory) if the worker
+/// exceeds it.
+// NOTE: If you change this make sure to fix the buckets of `pvf_preparation_peak_memory` metric.
+pub const DEFAULT_PREPARE_WORKER_MAX_MEMORY: u64 = 2 * 1024 * 1024 * 1024;
+
+/// The time to wait before retrying a preparation that failed with a non-deterministic error. The
+/// delay doubles with every subsequent failure.
+pub const PREPARE_FAILURE_COOLDOWN: Duration = Duration::from_secs(15 * 60);
+
+/// The number of times a preparation that failed with a non-deterministic error is retried.
+pub const NUM_PREPARE_RETRIES: u32 = 5;
+
 /// An alias to not spell the type for the oneshot sender for the PVF execution result.
-pub(crate) type ResultSender = oneshot::Sender<Result<ValidationResult, ValidationError>>;
+pub(crate) type ResultSender =
+	oneshot::Sender<Result<(ValidationResult, ExecutionPhases), ValidationError>>;
 
 /// Transmission end used for sending the PVF preparation result.
 pub(crate) type PrepareResultSender = oneshot::Sender<PrepareResult>;
@@ -81,7 +99,11 @@ impl ValidationHost {
 	}
 
 	/// Execute PVF with the given code, execution timeout, parameters and priority.
-	/// The result of execution will be sent to the provided result sender.
+	/// The result of execution will be sent to the provided result sender, along with the time
+	/// spent in each phase of the execution.
+	///
+	/// The execution timeout limits the CPU time spent executing the PVF. The wall-clock time is
+	/// only limited by a generous multiple of the timeout, as a backstop.
 	///
 	/// This is async to accommodate the possibility of back-pressure. In the vast majority of
 	/// situations this function should return immediately.
@@ -145,12 +167,23 @@ pub struct Config {
 	pub prepare_workers_soft_max_num: usize,
 	/// The absolute number of workers that can be spawned in the prepare pool.
 	pub prepare_workers_hard_max_num: usize,
+	/// The maximum amount of memory in bytes a prepare worker may use while compiling a PVF.
+	/// `None` disables the limit.
+	pub prepare_worker_max_memory: Option<u64>,
 	/// The path to the program that can be used to spawn the execute workers.
 	pub execute_worker_program_path: PathBuf,
 	/// The time allotted for an execute worker to spawn and report to the host.
 	pub execute_worker_spawn_timeout: Duration,
 	/// The maximum number of execute workers that can run at the same time.
 	pub execute_workers_max_num: usize,
+	/// Whether the workers should be sandboxed. The sandbox restricts the file system access of
+	/// the workers to the cache directory and forbids them from using the network.
+	///
+	/// If the running system doesn't support sandboxing, the workers run unsandboxed.
+	pub enable_sandbox: bool,
+	/// The maximum total size in bytes of the prepared artifacts kept in the cache. When the cache
+	/// grows beyond it, the least recently used artifacts are removed. `None` disables the limit.
+	pub artifact_cache_max_size: Option<u64>,
 }
 
 impl Config {
@@ -166,9 +199,12 @@ impl Config {
 			prepare_worker_spawn_timeout: Duration::from_secs(3),
 			prepare_workers_soft_max_num: 1,
 			prepare_workers_hard_max_num: 1,
+			prepare_worker_max_memory: Some(DEFAULT_PREPARE_WORKER_MAX_MEMORY),
 			execute_worker_program_path: program_path,
 			execute_worker_spawn_timeout: Duration::from_secs(3),
 			execute_workers_max_num: 2,
+			enable_sandbox: false,
+			artifact_cache_max_size: None,
 		}
 	}
 }
@@ -186,39 +222,47 @@ pub fn start(config: Config, metrics: Metrics) -> (ValidationHost, impl Future<O
 
 	let validation_host = ValidationHost { to_host_tx };
 
-	let (to_prepare_pool, from_prepare_pool, run_prepare_pool) = prepare::start_pool(
-		metrics.clone(),
-		config.prepare_worker_program_path.clone(),
-		config.cache_path.clone(),
-		config.prepare_worker_spawn_timeout,
-	);
+	let task = async move {
+		let artifacts = Artifacts::new(&config.cache_path).await;
 
-	let (to_prepare_queue_tx, from_prepare_queue_rx, run_prepare_queue) = prepare::start_queue(
-		metrics.clone(),
-		config.prepare_workers_soft_max_num,
-		config.prepare_workers_hard_max_num,
-		config.cache_path.clone(),
-		to_prepare_pool,
-		from_prepare_pool,
-	);
+		// No worker is spawned before it is known whether the workers can be sandboxed. The
+		// requests sent to the host in the meantime wait in its channel.
+		let sandbox = sandbox_config(&config).await;
+
+		let (to_prepare_pool, from_prepare_pool, run_prepare_pool) = prepare::start_pool(
+			metrics.clone(),
+			config.prepare_worker_program_path.clone(),
+			config.cache_path.clone(),
+			config.prepare_worker_spawn_timeout,
+			config.prepare_worker_max_memory,
+			sandbox.clone(),
+		);
 
-	let (to_execute_queue_tx, run_execute_queue) = execute::start(
-		metrics.clone(),
-		config.execute_worker_program_path.to_owned(),
-		config.execute_workers_max_num,
-		config.execute_worker_spawn_timeout,
-	);
+		let (to_prepare_queue_tx, from_prepare_queue_rx, run_prepare_queue) = prepare::start_queue(
+			metrics.clone(),
+			config.prepare_workers_soft_max_num,
+			config.prepare_workers_hard_max_num,
+			config.cache_path.clone(),
+			to_prepare_pool,
+			from_prepare_pool,
+		);
 
-	let (to_sweeper_tx, to_sweeper_rx) = mpsc::channel(100);
-	let run_sweeper = sweeper_task(to_sweeper_rx);
+		let (to_execute_queue_tx, run_execute_queue) = execute::start(
+			metrics.clone(),
+			config.execute_worker_program_path.to_owned(),
+			config.execute_workers_max_num,
+			config.execute_worker_spawn_timeout,
+			sandbox,
+		);
 
-	let run_host = async move {
-		let artifacts = Artifacts::new(&config.cache_path).await;
+		let (to_sweeper_tx, to_sweeper_rx) = mpsc::channel(100);
+		let run_sweeper = sweeper_task(to_sweeper_rx);
 
-		run(Inner {
+		let run_host = run(Inner {
 			cache_path: config.cache_path,
 			cleanup_pulse_interval: Duration::from_secs(3600),
 			artifact_ttl: Duration::from_secs(3600 * 24),
+			artifact_cache_max_size: config.artifact_cache_max_size,
 			artifacts,
 			to_host_rx,
 			to_prepare_queue_tx,
@@ -226,11 +270,9 @@ pub fn start(config: Config, metrics: Metrics) -> (ValidationHost, impl Future<O
 			to_execute_queue_tx,
 			to_sweeper_tx,
 			awaiting_prepare: AwaitingPrepare::default(),
-		})
-		.await
-	};
+			metrics,
+		});
 
-	let task = async move {
 		// Bundle the sub-components' tasks together into a single future.
 		futures::select! {
 			_ = run_host.fuse() => {},
@@ -244,10 +286,64 @@ pub fn start(config: Config, metrics: Metrics) -> (ValidationHost, impl Future<O
 	(validation_host, task)
 }
 
+/// Returns the sandbox configuration for the workers, if sandboxing is enabled and supported by
+/// the running system.
+///
+/// As a self-test, a sandboxed prepare worker is spawned and given a job. Only if it gets through
+/// the job inside of the sandbox, the workers are sandboxed.
+async fn sandbox_config(config: &Config) -> Option<SandboxConfig> {
+	if !config.enable_sandbox {
+		return None
+	}
+
+	let landlock_abi = match sandbox::check_support() {
+		SandboxSupport::Available { landlock_abi } => landlock_abi,
+		SandboxSupport::Unavailable(reason) => {
+			gum::warn!(
+				target: LOG_TARGET,
+				%reason,
+				"PVF worker sandboxing is enabled but not supported by this system. The workers will run unsandboxed.",
+			);
+			return None
+		},
+	};
+
+	let sandbox = SandboxConfig::new(config.cache_path.as_ref());
+	let self_test = prepare::prepare_in_sandbox(
+		&config.prepare_worker_program_path,
+		&["prepare-worker"],
+		config.prepare_worker_spawn_timeout,
+		&config.cache_path,
+		sandbox.clone(),
+	)
+	.await;
+	match self_test {
+		// The job is an empty PVF, which a working worker rejects.
+		Ok(Err(PrepareError::Prevalidation(_))) => {
+			gum::info!(
+				target: LOG_TARGET,
+				%landlock_abi,
+				"PVF workers will be sandboxed to {}",
+				config.cache_path.display(),
+			);
+			Some(sandbox)
+		},
+		result => {
+			gum::warn!(
+				target: LOG_TARGET,
+				?result,
+				"The PVF worker sandbox self-test failed. The workers will run unsandboxed.",
+			);
+			None
+		},
+	}
+}
+
 /// An execution request that should execute the PVF (known in the context) and send the results
 /// to the given result sender.
 #[derive(Debug)]
 struct PendingExecutionRequest {
+	executor_params: Arc<ExecutorParams>,
 	execution_timeout: Duration,
 	params: Vec<u8>,
 	result_tx: ResultSender,
@@ -259,18 +355,8 @@ struct PendingExecutionRequest {
 struct AwaitingPrepare(HashMap<ArtifactId, Vec<PendingExecutionRequest>>);
 
 impl AwaitingPrepare {
-	fn add(
-		&mut self,
-		artifact_id: ArtifactId,
-		execution_timeout: Duration,
-		params: Vec<u8>,
-		result_tx: ResultSender,
-	) {
-		self.0.entry(artifact_id).or_default().push(PendingExecutionRequest {
-			execution_timeout,
-			params,
-			result_tx,
-		});
+	fn add(&mut self, artifact_id: ArtifactId, request: PendingExecutionRequest) {
+		self.0.entry(artifact_id).or_default().push(request);
 	}
 
 	fn take(&mut self, artifact_id: &ArtifactId) -> Vec<PendingExecutionRequest> {
@@ -282,6 +368,7 @@ struct Inner {
 	cache_path: PathBuf,
 	cleanup_pulse_interval: Duration,
 	artifact_ttl: Duration,
+	artifact_cache_max_size: Option<u64>,
 	artifacts: Artifacts,
 
 	to_host_rx: mpsc::Receiver<ToHost>,
@@ -293,6 +380,8 @@ struct Inner {
 	to_sweeper_tx: mpsc::Sender<PathBuf>,
 
 	awaiting_prepare: AwaitingPrepare,
+
+	metrics: Metrics,
 }
 
 #[derive(Debug)]
@@ -303,6 +392,7 @@ async fn run(
 		cache_path,
 		cleanup_pulse_interval,
 		artifact_ttl,
+		artifact_cache_max_size,
 		mut artifacts,
 		to_host_rx,
 		from_prepare_queue_rx,
@@ -310,6 +400,7 @@ async fn run(
 		mut to_execute_queue_tx,
 		mut to_sweeper_tx,
 		mut awaiting_prepare,
+		metrics,
 	}: Inner,
 ) {
 	macro_rules! break_if_fatal {
@@ -328,6 +419,20 @@ async fn run(
 		};
 	}
 
+	// The budget could have been lowered since the previous run.
+	if let Err(Fatal) = enforce_artifact_cache_budget(
+		&cache_path,
+		&mut to_sweeper_tx,
+		&mut artifacts,
+		artifact_cache_max_size,
+		&metrics,
+	)
+	.await
+	{
+		gum::error!(target: LOG_TARGET, "Fatal error occurred, terminating the host");
+		return
+	}
+
 	let cleanup_pulse = pulse_every(cleanup_pulse_interval).fuse();
 	futures::pin_mut!(cleanup_pulse);
 
@@ -349,6 +454,13 @@ async fn run(
 					&mut artifacts,
 					artifact_ttl,
 				).await);
+				break_if_fatal!(enforce_artifact_cache_budget(
+					&cache_path,
+					&mut to_sweeper_tx,
+					&mut artifacts,
+					artifact_cache_max_size,
+					&metrics,
+				).await);
 			},
 			to_host = to_host_rx.next() => {
 				let to_host = match to_host {
@@ -389,6 +501,13 @@ async fn run(
 					&mut awaiting_prepare,
 					from_queue,
 				).await);
+				break_if_fatal!(enforce_artifact_cache_budget(
+					&cache_path,
+					&mut to_sweeper_tx,
+					&mut artifacts,
+					artifact_cache_max_size,
+					&metrics,
+				).await);
 			},
 		}
 	}
@@ -442,15 +561,31 @@ async fn handle_precheck_pvf(
 
 	if let Some(state) = artifacts.artifact_state_mut(&artifact_id) {
 		match state {
-			ArtifactState::Prepared { last_time_needed } => {
+			ArtifactState::Prepared { last_time_needed, .. } => {
 				*last_time_needed = SystemTime::now();
 				let _ = result_sender.send(Ok(()));
 			},
-			ArtifactState::Preparing { waiting_for_response } =>
+			ArtifactState::Preparing { waiting_for_response, num_failures: _ } =>
 				waiting_for_response.push(result_sender),
-			ArtifactState::FailedToProcess(result) => {
-				let _ = result_sender.send(PrepareResult::Err(result.clone()));
-			},
+			ArtifactState::FailedToProcess { last_time_failed, num_failures, error } =>
+				if can_retry_prepare_after_failure(*last_time_failed, *num_failures, error) {
+					let num_failures = *num_failures;
+					*state = ArtifactState::Preparing {
+						waiting_for_response: vec![result_sender],
+						num_failures,
+					};
+					send_prepare(
+						prepare_queue,
+						prepare::ToQueue::Enqueue {
+							priority: Priority::Normal,
+							pvf,
+							compilation_timeout: PRECHECK_COMPILATION_TIMEOUT,
+						},
+					)
+					.await?;
+				} else {
+					let _ = result_sender.send(PrepareResult::Err(error.clone()));
+				},
 		}
 	} else {
 		artifacts.insert_preparing(artifact_id, vec![result_sender]);
@@ -485,16 +620,29 @@ async fn handle_execute_pvf(
 	result_tx: ResultSender,
 ) -> Result<(), Fatal> {
 	let artifact_id = pvf.as_artifact_id();
+	let request = PendingExecutionRequest {
+		executor_params: pvf.executor_params.clone(),
+		execution_timeout,
+		params,
+		result_tx,
+	};
 
 	if let Some(state) = artifacts.artifact_state_mut(&artifact_id) {
 		match state {
-			ArtifactState::Prepared { last_time_needed } => {
+			ArtifactState::Prepared { last_time_needed, lease, .. } => {
 				*last_time_needed = SystemTime::now();
 
+				let PendingExecutionRequest {
+					executor_params,
+					execution_timeout,
+					params,
+					result_tx,
+				} = request;
 				send_execute(
 					execute_queue,
 					execute::ToQueue::Enqueue {
-						artifact: ArtifactPathId::new(artifact_id, cache_path),
+						artifact: ArtifactPathId::new(artifact_id, cache_path, lease.clone()),
+						executor_params,
 						execution_timeout,
 						params,
 						result_tx,
@@ -502,12 +650,28 @@ async fn handle_execute_pvf(
 				)
 				.await?;
 			},
-			ArtifactState::Preparing { waiting_for_response: _ } => {
-				awaiting_prepare.add(artifact_id, execution_timeout, params, result_tx);
-			},
-			ArtifactState::FailedToProcess(error) => {
-				let _ = result_tx.send(Err(ValidationError::from(error.clone())));
+			ArtifactState::Preparing { .. } => {
+				awaiting_prepare.add(artifact_id, request);
 			},
+			ArtifactState::FailedToProcess { last_time_failed, num_failures, error } =>
+				if can_retry_prepare_after_failure(*last_time_failed, *num_failures, error) {
+					let num_failures = *num_failures;
+					*state =
+						ArtifactState::Preparing { waiting_for_response: Vec::new(), num_failures };
+					send_prepare(
+						prepare_queue,
+						prepare::ToQueue::Enqueue {
+							priority,
+							pvf,
+							compilation_timeout: EXECUTE_COMPILATION_TIMEOUT,
+						},
+					)
+					.await?;
+
+					awaiting_prepare.add(artifact_id, request);
+				} else {
+					let _ = request.result_tx.send(Err(ValidationError::from(error.clone())));
+				},
 		}
 	} else {
 		// Artifact is unknown: register it and enqueue a job with the corresponding priority and
@@ -523,7 +687,7 @@ async fn handle_execute_pvf(
 		)
 		.await?;
 
-		awaiting_prepare.add(artifact_id, execution_timeout, params, result_tx);
+		awaiting_prepare.add(artifact_id, request);
 	}
 
 	return Ok(())
@@ -543,10 +707,26 @@ async fn handle_heads_up(
 				ArtifactState::Prepared { last_time_needed, .. } => {
 					*last_time_needed = now;
 				},
-				ArtifactState::Preparing { waiting_for_response: _ } => {
+				ArtifactState::Preparing { .. } => {
 					// The artifact is already being prepared, so we don't need to do anything.
 				},
-				ArtifactState::FailedToProcess(_) => {},
+				ArtifactState::FailedToProcess { last_time_failed, num_failures, error } =>
+					if can_retry_prepare_after_failure(*last_time_failed, *num_failures, error) {
+						let num_failures = *num_failures;
+						*state = ArtifactState::Preparing {
+							waiting_for_response: Vec::new(),
+							num_failures,
+						};
+						send_prepare(
+							prepare_queue,
+							prepare::ToQueue::Enqueue {
+								priority: Priority::Normal,
+								pvf: active_pvf,
+								compilation_timeout: EXECUTE_COMPILATION_TIMEOUT,
+							},
+						)
+						.await?;
+					},
 			}
 		} else {
 			// It's not in the artifacts, so we need to enqueue a job to prepare it.
@@ -596,25 +776,32 @@ async fn handle_prepare_done(
 			never!("the artifact is already prepared: {:?}", artifact_id);
 			return Ok(())
 		},
-		Some(ArtifactState::FailedToProcess(_)) => {
+		Some(ArtifactState::FailedToProcess { .. }) => {
 			// The reasoning is similar to the above, the artifact cannot be
 			// processed at this point.
 			never!("the artifact is already processed unsuccessfully: {:?}", artifact_id);
 			return Ok(())
 		},
-		Some(state @ ArtifactState::Preparing { waiting_for_response: _ }) => state,
+		Some(state @ ArtifactState::Preparing { .. }) => state,
 	};
 
-	if let ArtifactState::Preparing { waiting_for_response } = state {
+	let mut num_failures = 0;
+	if let ArtifactState::Preparing { waiting_for_response, num_failures: previous_failures } =
+		state
+	{
 		for result_sender in waiting_for_response.drain(..) {
 			let _ = result_sender.send(result.clone());
 		}
+		num_failures = *previous_failures;
 	}
 
 	// It's finally time to dispatch all the execution requests that were waiting for this artifact
 	// to be prepared.
+	let lease = ArtifactLease::default();
 	let pending_requests = awaiting_prepare.take(&artifact_id);
-	for PendingExecutionRequest { execution_timeout, params, result_tx } in pending_requests {
+	for PendingExecutionRequest { executor_params, execution_timeout, params, result_tx } in
+		pending_requests
+	{
 		if result_tx.is_canceled() {
 			// Preparation could've taken quite a bit of time and the requester may be not interested
 			// in execution anymore, in which case we just skip the request.
@@ -630,7 +817,8 @@ async fn handle_prepare_done(
 		send_execute(
 			execute_queue,
 			execute::ToQueue::Enqueue {
-				artifact: ArtifactPathId::new(artifact_id.clone(), cache_path),
+				artifact: ArtifactPathId::new(artifact_id.clone(), cache_path, lease.clone()),
+				executor_params,
 				execution_timeout,
 				params,
 				result_tx,
@@ -640,13 +828,63 @@ async fn handle_prepare_done(
 	}
 
 	*state = match result {
-		Ok(()) => ArtifactState::Prepared { last_time_needed: SystemTime::now() },
-		Err(error) => ArtifactState::FailedToProcess(error.clone()),
+		Ok(()) => ArtifactState::Prepared {
+			last_time_needed: SystemTime::now(),
+			size: artifact_size(cache_path, &artifact_id).await,
+			lease,
+		},
+		Err(ref error) => ArtifactState::FailedToProcess {
+			last_time_failed: SystemTime::now(),
+			num_failures: num_failures + 1,
+			error: error.clone(),
+		},
 	};
 
+	// Persist the outcome, so that a restart doesn't reset the failed preparations.
+	if let ArtifactState::FailedToProcess { last_time_failed, num_failures, error } = state {
+		if let Err(err) = artifacts::write_failure_record(
+			cache_path,
+			&artifact_id,
+			error,
+			*num_failures,
+			*last_time_failed,
+		)
+		.await
+		{
+			gum::warn!(
+				target: LOG_TARGET,
+				validation_code_hash = ?artifact_id.code_hash,
+				"failed to persist the failed preparation: {:?}",
+				err,
+			);
+		}
+	} else if num_failures > 0 {
+		artifacts::remove_failure_record(cache_path, &artifact_id).await;
+	}
+
 	Ok(())
 }
 
+/// Returns whether the preparation that failed with the given error can be retried.
+///
+/// Deterministic errors are never retried. The others are retried a bounded number of times, each
+/// time after an exponentially growing cooldown.
+fn can_retry_prepare_after_failure(
+	last_time_failed: SystemTime,
+	num_failures: u32,
+	error: &PrepareError,
+) -> bool {
+	if error.is_deterministic() || num_failures > NUM_PREPARE_RETRIES {
+		return false
+	}
+
+	let cooldown = PREPARE_FAILURE_COOLDOWN * 2u32.saturating_pow(num_failures.saturating_sub(1));
+	SystemTime::now()
+		.duration_since(last_time_failed)
+		.map(|elapsed| elapsed >= cooldown)
+		.unwrap_or(false)
+}
+
 async fn send_prepare(
 	prepare_queue: &mut mpsc::Sender<prepare::ToQueue>,
 	to_queue: prepare::ToQueue,
@@ -683,10 +921,67 @@ async fn handle_cleanup_pulse(
 		sweeper_tx.send(artifact_path).await.map_err(|_| Fatal)?;
 	}
 
+	// The transient failures expire after the same period, so that the records don't pile up on
+	// disk.
+	let to_remove = artifacts.prune_failures(artifact_ttl);
+	for artifact_id in to_remove {
+		gum::debug!(
+			target: LOG_TARGET,
+			validation_code_hash = ?artifact_id.code_hash,
+			"pruning failed preparation",
+		);
+		let failure_record_path = artifact_id.failure_record_path(cache_path);
+		sweeper_tx.send(failure_record_path).await.map_err(|_| Fatal)?;
+	}
+
 	Ok(())
 }
 
-/// A simple task which sole purpose is to delete files thrown at it.
+/// Removes the least recently used artifacts until the cache fits into the given budget, if any.
+async fn enforce_artifact_cache_budget(
+	cache_path: &Path,
+	sweeper_tx: &mut mpsc::Sender<PathBuf>,
+	artifacts: &mut Artifacts,
+	artifact_cache_max_size: Option<u64>,
+	metrics: &Metrics,
+) -> Result<(), Fatal> {
+	if let Some(max_size) = artifact_cache_max_size {
+		let to_remove = artifacts.evict_to_fit(max_size);
+		if !to_remove.is_empty() {
+			gum::debug!(
+				target: LOG_TARGET,
+				"PVF eviction: {} artifacts removed to fit the cache into {} bytes",
+				to_remove.len(),
+				max_size,
+			);
+			metrics.on_artifacts_evicted(to_remove.len());
+		}
+		for artifact_id in to_remove {
+			gum::debug!(
+				target: LOG_TARGET,
+				validation_code_hash = ?artifact_id.code_hash,
+				"evicting artifact",
+			);
+			let artifact_path = artifact_id.path(cache_path);
+			sweeper_tx.send(artifact_path).await.map_err(|_| Fatal)?;
+		}
+	}
+
+	metrics.artifact_cache_size(artifacts.total_size());
+
+	Ok(())
+}
+
+/// Returns the size of the prepared artifact file, or zero if it cannot be determined.
+async fn artifact_size(cache_path: &Path, artifact_id: &ArtifactId) -> u64 {
+	async_std::fs::metadata(artifact_id.path(cache_path))
+		.await
+		.map(|metadata| metadata.len())
+		.unwrap_or(0)
+}
+
+/// A simple task which sole purpose is to delete the files thrown at it. The checksum of an artifact
+/// is deleted along with the artifact.
 async fn sweeper_task(mut sweeper_rx: mpsc::Receiver<PathBuf>) {
 	loop {
 		match sweeper_rx.next().await {
@@ -699,6 +994,7 @@ async fn sweeper_task(mut sweeper_rx: mpsc::Receiver<PathBuf>) {
 					"Sweeping the artifact file {}",
 					condemned.display(),
 				);
+				let _ = async_std::fs::remove_file(artifacts::checksum_path(&condemned)).await;
 			},
 		}
 	}
@@ -750,6 +1046,7 @@ mod tests {
 	struct Builder {
 		cleanup_pulse_interval: Duration,
 		artifact_ttl: Duration,
+		artifact_cache_max_size: Option<u64>,
 		artifacts: Artifacts,
 	}
 
@@ -759,6 +1056,7 @@ mod tests {
 				// these are selected high to not interfere in tests in which pruning is irrelevant.
 				cleanup_pulse_interval: Duration::from_secs(3600),
 				artifact_ttl: Duration::from_secs(3600),
+				artifact_cache_max_size: None,
 
 				artifacts: Artifacts::empty(),
 			}
@@ -781,7 +1079,9 @@ mod tests {
 	}
 
 	impl Test {
-		fn new(Builder { cleanup_pulse_interval, artifact_ttl, artifacts }: Builder) -> Self {
+		fn new(
+			Builder { cleanup_pulse_interval, artifact_ttl, artifact_cache_max_size, artifacts }: Builder,
+		) -> Self {
 			let cache_path = PathBuf::from(std::env::temp_dir());
 
 			let (to_host_tx, to_host_rx) = mpsc::channel(10);
@@ -794,6 +1094,7 @@ mod tests {
 				cache_path,
 				cleanup_pulse_interval,
 				artifact_ttl,
+				artifact_cache_max_size,
 				artifacts,
 				to_host_rx,
 				to_prepare_queue_tx,
@@ -801,6 +1102,7 @@ mod tests {
 				to_execute_queue_tx,
 				to_sweeper_tx,
 				awaiting_prepare: AwaitingPrepare::default(),
+				metrics: Metrics::default(),
 			})
 			.boxed();
 
@@ -850,6 +1152,25 @@ mod tests {
 			.await
 		}
 
+		async fn poll_ensure_to_prepare_queue_is_empty(&mut self) {
+			use futures_timer::Delay;
+
+			let to_prepare_queue_rx = &mut self.to_prepare_queue_rx;
+			run_until(
+				&mut self.run,
+				async {
+					futures::select! {
+						_ = Delay::new(Duration::from_millis(500)).fuse() => (),
+						_ = to_prepare_queue_rx.next().fuse() => {
+							panic!("the prepare queue supposed to be empty")
+						}
+					}
+				}
+				.boxed(),
+			)
+			.await
+		}
+
 		async fn poll_ensure_to_sweeper_is_empty(&mut self) {
 			use futures_timer::Delay;
 
@@ -914,8 +1235,8 @@ mod tests {
 		let mut builder = Builder::default();
 		builder.cleanup_pulse_interval = Duration::from_millis(100);
 		builder.artifact_ttl = Duration::from_millis(500);
-		builder.artifacts.insert_prepared(artifact_id(1), mock_now);
-		builder.artifacts.insert_prepared(artifact_id(2), mock_now);
+		builder.artifacts.insert_prepared(artifact_id(1), mock_now, 1024);
+		builder.artifacts.insert_prepared(artifact_id(2), mock_now, 1024);
 		let mut test = builder.build();
 		let mut host = test.host_handle();
 
@@ -937,6 +1258,145 @@ mod tests {
 		test.poll_ensure_to_sweeper_is_empty().await;
 	}
 
+	#[async_std::test]
+	async fn pruning_failed_preparations() {
+		let mock_now = SystemTime::now() - Duration::from_millis(1000);
+
+		let mut builder = Builder::default();
+		builder.cleanup_pulse_interval = Duration::from_millis(100);
+		builder.artifact_ttl = Duration::from_millis(500);
+		builder
+			.artifacts
+			.insert_failed(artifact_id(1), mock_now, 1, PrepareError::TimedOut);
+		builder.artifacts.insert_failed(
+			artifact_id(2),
+			SystemTime::now() + Duration::from_secs(3600),
+			1,
+			PrepareError::TimedOut,
+		);
+		builder.artifacts.insert_failed(
+			artifact_id(3),
+			mock_now,
+			1,
+			PrepareError::Preparation("foo".to_owned()),
+		);
+		let mut test = builder.build();
+
+		let to_sweeper_rx = &mut test.to_sweeper_rx;
+		run_until(
+			&mut test.run,
+			async {
+				assert_eq!(
+					to_sweeper_rx.next().await.unwrap(),
+					artifact_id(1).failure_record_path(&PathBuf::from(std::env::temp_dir())),
+				);
+			}
+			.boxed(),
+		)
+		.await;
+
+		// The second failure is recent and the third one is deterministic, so they are kept.
+		test.poll_ensure_to_sweeper_is_empty().await;
+	}
+
+	#[async_std::test]
+	async fn least_recently_used_artifacts_are_evicted_over_budget() {
+		let mock_now = SystemTime::now() - Duration::from_millis(1000);
+
+		let mut builder = Builder::default();
+		builder.artifact_cache_max_size = Some(2048);
+		builder.artifacts.insert_prepared(artifact_id(1), mock_now, 1024);
+		builder.artifacts.insert_prepared(
+			artifact_id(2),
+			mock_now + Duration::from_millis(1),
+			1024,
+		);
+		builder.artifacts.insert_prepared(
+			artifact_id(3),
+			mock_now + Duration::from_millis(2),
+			1024,
+		);
+
+		// The least recently used artifact is being executed, so it must not be evicted.
+		let lease = match builder.artifacts.artifact_state_mut(&artifact_id(1)) {
+			Some(ArtifactState::Prepared { lease, .. }) => lease.clone(),
+			_ => panic!("the artifact is not prepared"),
+		};
+
+		let mut test = builder.build();
+		let to_sweeper_rx = &mut test.to_sweeper_rx;
+		run_until(
+			&mut test.run,
+			async {
+				assert_eq!(to_sweeper_rx.next().await.unwrap(), artifact_path(2));
+			}
+			.boxed(),
+		)
+		.await;
+		test.poll_ensure_to_sweeper_is_empty().await;
+
+		drop(lease);
+	}
+
+	#[async_std::test]
+	async fn artifacts_are_keyed_by_executor_params() {
+		use polkadot_primitives::vstaging::ExecutorParam;
+
+		let mut test = Builder::default().build();
+		let mut host = test.host_handle();
+
+		let code = b"pvf".to_vec();
+		let default_pvf = Pvf::from_code(code.clone(), ExecutorParams::default());
+		let custom_params = ExecutorParams::from(vec![ExecutorParam::StackLogicalMax(1024)]);
+		let custom_pvf = Pvf::from_code(code, custom_params.clone());
+		assert_ne!(default_pvf.as_artifact_id(), custom_pvf.as_artifact_id());
+
+		let (result_tx, _result_rx_default) = oneshot::channel();
+		host.execute_pvf(
+			default_pvf.clone(),
+			TEST_EXECUTION_TIMEOUT,
+			b"pvf".to_vec(),
+			Priority::Normal,
+			result_tx,
+		)
+		.await
+		.unwrap();
+
+		let (result_tx, _result_rx_custom) = oneshot::channel();
+		host.execute_pvf(
+			custom_pvf.clone(),
+			TEST_EXECUTION_TIMEOUT,
+			b"pvf".to_vec(),
+			Priority::Normal,
+			result_tx,
+		)
+		.await
+		.unwrap();
+
+		// Despite the same code, both PVFs are prepared.
+		assert_matches!(
+			test.poll_and_recv_to_prepare_queue().await,
+			prepare::ToQueue::Enqueue { pvf, .. } => assert_eq!(pvf, default_pvf)
+		);
+		assert_matches!(
+			test.poll_and_recv_to_prepare_queue().await,
+			prepare::ToQueue::Enqueue { pvf, .. } => assert_eq!(pvf, custom_pvf)
+		);
+
+		test.from_prepare_queue_tx
+			.send(prepare::FromQueue { artifact_id: custom_pvf.as_artifact_id(), result: Ok(()) })
+			.await
+			.unwrap();
+		assert_matches!(
+			test.poll_and_recv_to_execute_queue().await,
+			execute::ToQueue::Enqueue { artifact, executor_params, .. } => {
+				assert_eq!(artifact.id, custom_pvf.as_artifact_id());
+				assert_eq!(*executor_params, custom_params);
+			}
+		);
+		test.poll_ensure_to_execute_queue_is_empty().await;
+	}
+
 	#[async_std::test]
 	async fn execute_pvf_requests() {
 		let mut test = Builder::default().build();
@@ -1195,4 +1655,89 @@ mod tests {
 
 		test.poll_ensure_to_execute_queue_is_empty().await;
 	}
+
+	#[async_std::test]
+	async fn transient_prepare_failure_is_retried_after_cooldown() {
+		let mut builder = Builder::default();
+		// The cooldown has elapsed for the first artifact, but not for the second one.
+		builder.artifacts.insert_failed(
+			artifact_id(1),
+			SystemTime::now() - PREPARE_FAILURE_COOLDOWN - Duration::from_secs(1),
+			1,
+			PrepareError::TimedOut,
+		);
+		builder.artifacts.insert_failed(
+			artifact_id(2),
+			SystemTime::now(),
+			1,
+			PrepareError::TimedOut,
+		);
+		let mut test = builder.build();
+		let mut host = test.host_handle();
+
+		let (result_tx, result_rx) = oneshot::channel();
+		host.precheck_pvf(Pvf::from_discriminator(2), result_tx).await.unwrap();
+		test.poll_ensure_to_prepare_queue_is_empty().await;
+		assert_matches!(result_rx.now_or_never().unwrap().unwrap(), Err(PrepareError::TimedOut));
+
+		let (result_tx, result_rx) = oneshot::channel();
+		host.precheck_pvf(Pvf::from_discriminator(1), result_tx).await.unwrap();
+		assert_matches!(
+			test.poll_and_recv_to_prepare_queue().await,
+			prepare::ToQueue::Enqueue { .. }
+		);
+		test.from_prepare_queue_tx
+			.send(prepare::FromQueue { artifact_id: artifact_id(1), result: Ok(()) })
+			.await
+			.unwrap();
+		test.poll_ensure_to_execute_queue_is_empty().await;
+		assert_matches!(result_rx.now_or_never().unwrap().unwrap(), Ok(()));
+	}
+
+	#[async_std::test]
+	async fn prepare_failure_is_not_retried() {
+		let long_ago = SystemTime::now() - Duration::from_secs(3600 * 24 * 365);
+
+		let mut builder = Builder::default();
+		// A deterministic error.
+		builder.artifacts.insert_failed(
+			artifact_id(1),
+			long_ago,
+			1,
+			PrepareError::Prevalidation("foo".to_owned()),
+		);
+		// A transient error, but the retries are exhausted.
+		builder.artifacts.insert_failed(
+			artifact_id(2),
+			long_ago,
+			NUM_PREPARE_RETRIES + 1,
+			PrepareError::DidNotMakeIt,
+		);
+		let mut test = builder.build();
+		let mut host = test.host_handle();
+
+		let (result_tx, result_rx) = oneshot::channel();
+		host.execute_pvf(
+			Pvf::from_discriminator(1),
+			TEST_EXECUTION_TIMEOUT,
+			b"pvf1".to_vec(),
+			Priority::Normal,
+			result_tx,
+		)
+		.await
+		.unwrap();
+		test.poll_ensure_to_prepare_queue_is_empty().await;
+		assert_matches!(
+			result_rx.now_or_never().unwrap().unwrap(),
+			Err(ValidationError::InvalidCandidate(InvalidCandidate::PrepareError(_)))
+		);
+
+		let (result_tx, result_rx) = oneshot::channel();
+		host.precheck_pvf(Pvf::from_discriminator(2), result_tx).await.unwrap();
+		test.poll_ensure_to_prepare_queue_is_empty().await;
+		assert_matches!(
+			result_rx.now_or_never().unwrap().unwrap(),
+			Err(PrepareError::DidNotMakeIt)
+		);
+	}
 }
@@ -61,17 +61,33 @@
 //! Artifact is a final product of preparation. If the preparation succeeded, then the artifact will
 //! contain the compiled code usable for quick execution by a worker later on.
 //!
-//! If the preparation failed, then the worker will still write the artifact with the error message.
-//! We save the artifact with the error so that we don't try to prepare the artifacts that are broken
-//! repeatedly.
+//! If the preparation failed, then the host records the error on disk next to the artifacts. We
+//! save the error so that we don't try to prepare the artifacts that are broken repeatedly, even
+//! across restarts. A preparation that failed with a deterministic error is never retried. The
+//! other errors may be caused by a transient condition of the node, so such preparations are
+//! retried a bounded number of times after an exponentially growing cooldown.
 //!
 //! The artifact is saved on disk and is also tracked by an in memory table. This in memory table
 //! doesn't contain the artifact contents though, only a flag that the given artifact is compiled.
 //!
+//! Each [`Pvf`] carries the executor parameters agreed on-chain for the session in which it is
+//! used. Both preparation and execution use exactly these parameters, so an artifact is identified
+//! by the code hash together with the hash of the executor parameters. The same code used with
+//! different parameters results in different artifacts.
+//!
+//! The artifacts survive node restarts. On startup, the cache directory is scanned and all the
+//! artifacts that were produced by a compatible executor are registered in the table as prepared.
+//! The rest, including the artifacts produced by a different executor version, is removed.
+//!
 //! The execute workers will be fed by the requests from the execution queue, which is basically a
-//! combination of a path to the compiled artifact and the
+//! combination of a path to the compiled artifact, the executor parameters and the
 //! [`params`][`polkadot_parachain::primitives::ValidationParams`].
 //!
+//! Optionally, the workers can be sandboxed. A sandboxed worker can only access the cache directory,
+//! which is enforced by landlock, and cannot use the network. Sandboxing is only supported on Linux;
+//! on startup, the host checks whether the running system supports it and has a sandboxed worker
+//! run a job as a self-test. If either fails, it falls back to unsandboxed workers.
+//!
 //! Each fixed interval of time a pruning task will run. This task will remove all artifacts that
 //! weren't used or received a heads up signal for a while.
 
@@ -84,6 +100,7 @@ mod metrics;
 mod prepare;
 mod priority;
 mod pvf;
+mod sandbox;
 mod worker_common;
 
 #[doc(hidden)]
@@ -99,10 +116,10 @@ pub use pvf::Pvf;
 pub use host::{start, Config, ValidationHost};
 pub use metrics::Metrics;
 
-pub use execute::worker_entrypoint as execute_worker_entrypoint;
+pub use execute::{worker_entrypoint as execute_worker_entrypoint, ExecutionPhases};
 pub use prepare::worker_entrypoint as prepare_worker_entrypoint;
 
-pub use executor_intf::{prepare, prevalidate};
+pub use executor_intf::{params_to_wasmtime_semantics, prepare, prevalidate};
 
 pub use sc_executor_common;
 pub use sp_maybe_compressed_blob;
@@ -68,10 +68,31 @@ impl Metrics {
 		self.0.as_ref().map(|metrics| metrics.preparation_time.start_timer())
 	}
 
+	/// Observe the peak memory usage of a prepare worker during a preparation job.
+	pub(crate) fn observe_preparation_peak_memory(&self, peak_memory: u64) {
+		if let Some(metrics) = &self.0 {
+			metrics.preparation_peak_memory.observe(peak_memory as f64 / 1024.0);
+		}
+	}
+
 	/// Time between sending execution request to a worker to having the response.
 	pub(crate) fn time_execution(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.execution_time.start_timer())
 	}
+
+	/// Set the total size of the prepared artifacts in the cache.
+	pub(crate) fn artifact_cache_size(&self, size: u64) {
+		if let Some(metrics) = &self.0 {
+			metrics.artifact_cache_size.set(size);
+		}
+	}
+
+	/// When prepared artifacts were evicted from the cache to fit into its size budget.
+	pub(crate) fn on_artifacts_evicted(&self, count: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.artifacts_evicted.inc_by(count as u64);
+		}
+	}
 }
 
 #[derive(Clone)]
@@ -85,6 +106,9 @@ struct MetricsInner {
 	execute_finished: prometheus::Counter<prometheus::U64>,
 	preparation_time: prometheus::Histogram,
 	execution_time: prometheus::Histogram,
+	preparation_peak_memory: prometheus::Histogram,
+	artifact_cache_size: prometheus::Gauge<prometheus::U64>,
+	artifacts_evicted: prometheus::Counter<prometheus::U64>,
 }
 
 impl metrics::Metrics for Metrics {
@@ -193,6 +217,39 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			preparation_peak_memory: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_pvf_preparation_peak_memory",
+						"Peak memory usage observed by prepare workers during preparation in kilobytes",
+					).buckets(vec![
+						// This is synchronized with `DEFAULT_PREPARE_WORKER_MAX_MEMORY`=2GiB
+						// constant found in src/host.rs
+						65536.0,
+						131072.0,
+						262144.0,
+						524288.0,
+						1048576.0,
+						2097152.0,
+						4194304.0,
+					]),
+				)?,
+				registry,
+			)?,
+			artifact_cache_size: prometheus::register(
+				prometheus::Gauge::new(
+					"polkadot_pvf_artifact_cache_size",
+					"The total size of the prepared artifacts in the cache in bytes",
+				)?,
+				registry,
+			)?,
+			artifacts_evicted: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_pvf_artifacts_evicted_total",
+					"The total number of prepared artifacts evicted to fit the cache into its size budget",
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(inner)))
 	}
@@ -28,4 +28,4 @@ mod worker;
 
 pub use pool::start as start_pool;
 pub use queue::{start as start_queue, FromQueue, ToQueue};
-pub use worker::worker_entrypoint;
+pub use worker::{forbidden_syscall_worker_entrypoint, prepare_in_sandbox, worker_entrypoint};
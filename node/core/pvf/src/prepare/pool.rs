@@ -18,8 +18,9 @@ use super::worker::{self, Outcome};
 use crate::{
 	error::{PrepareError, PrepareResult},
 	metrics::Metrics,
+	sandbox::SandboxConfig,
 	worker_common::{IdleWorker, WorkerHandle},
-	LOG_TARGET,
+	Pvf, LOG_TARGET,
 };
 use always_assert::never;
 use assert_matches::assert_matches;
@@ -28,7 +29,7 @@ use futures::{
 	channel::mpsc, future::BoxFuture, stream::FuturesUnordered, Future, FutureExt, StreamExt,
 };
 use slotmap::HopSlotMap;
-use std::{fmt, sync::Arc, task::Poll, time::Duration};
+use std::{fmt, task::Poll, time::Duration};
 
 slotmap::new_key_type! { pub struct Worker; }
 
@@ -61,12 +62,7 @@ pub enum ToPool {
 	///
 	/// In either case, the worker is considered busy and no further `StartWork` messages should be
 	/// sent until either `Concluded` or `Rip` message is received.
-	StartWork {
-		worker: Worker,
-		code: Arc<Vec<u8>>,
-		artifact_path: PathBuf,
-		compilation_timeout: Duration,
-	},
+	StartWork { worker: Worker, pvf: Pvf, artifact_path: PathBuf, compilation_timeout: Duration },
 }
 
 /// A message sent from pool to its client.
@@ -108,10 +104,18 @@ enum PoolEvent {
 
 type Mux = FuturesUnordered<BoxFuture<'static, PoolEvent>>;
 
-struct Pool {
+/// The parameters used to spawn the workers of the pool.
+#[derive(Clone)]
+struct SpawnParams {
 	program_path: PathBuf,
-	cache_path: PathBuf,
 	spawn_timeout: Duration,
+	sandbox: Option<SandboxConfig>,
+}
+
+struct Pool {
+	spawn_params: SpawnParams,
+	cache_path: PathBuf,
+	max_memory: Option<u64>,
 	to_pool: mpsc::Receiver<ToPool>,
 	from_pool: mpsc::UnboundedSender<FromPool>,
 	spawned: HopSlotMap<Worker, WorkerData>,
@@ -124,9 +128,9 @@ struct Fatal;
 
 async fn run(
 	Pool {
-		program_path,
+		spawn_params,
 		cache_path,
-		spawn_timeout,
+		max_memory,
 		to_pool,
 		mut from_pool,
 		mut spawned,
@@ -151,9 +155,9 @@ async fn run(
 				let to_pool = break_if_fatal!(to_pool.ok_or(Fatal));
 				handle_to_pool(
 					&metrics,
-					&program_path,
+					&spawn_params,
 					&cache_path,
-					spawn_timeout,
+					max_memory,
 					&mut spawned,
 					&mut mux,
 					to_pool,
@@ -197,9 +201,9 @@ async fn purge_dead(
 
 fn handle_to_pool(
 	metrics: &Metrics,
-	program_path: &Path,
+	spawn_params: &SpawnParams,
 	cache_path: &Path,
-	spawn_timeout: Duration,
+	max_memory: Option<u64>,
 	spawned: &mut HopSlotMap<Worker, WorkerData>,
 	mux: &mut Mux,
 	to_pool: ToPool,
@@ -208,22 +212,27 @@ fn handle_to_pool(
 		ToPool::Spawn => {
 			gum::debug!(target: LOG_TARGET, "spawning a new prepare worker");
 			metrics.prepare_worker().on_begin_spawn();
-			mux.push(spawn_worker_task(program_path.to_owned(), spawn_timeout).boxed());
+			mux.push(spawn_worker_task(spawn_params.clone()).boxed());
 		},
-		ToPool::StartWork { worker, code, artifact_path, compilation_timeout } => {
+		ToPool::StartWork { worker, pvf, artifact_path, compilation_timeout } => {
 			if let Some(data) = spawned.get_mut(worker) {
 				if let Some(idle) = data.idle.take() {
 					let preparation_timer = metrics.time_preparation();
+					let cache_path = cache_path.to_owned();
 					mux.push(
-						start_work_task(
-							worker,
-							idle,
-							code,
-							cache_path.to_owned(),
-							artifact_path,
-							compilation_timeout,
-							preparation_timer,
-						)
+						async move {
+							let _timer = preparation_timer;
+							start_work_task(
+								worker,
+								idle,
+								pvf,
+								cache_path,
+								artifact_path,
+								compilation_timeout,
+								max_memory,
+							)
+							.await
+						}
 						.boxed(),
 					);
 				} else {
@@ -247,11 +256,12 @@ fn handle_to_pool(
 	}
 }
 
-async fn spawn_worker_task(program_path: PathBuf, spawn_timeout: Duration) -> PoolEvent {
+async fn spawn_worker_task(spawn_params: SpawnParams) -> PoolEvent {
 	use futures_timer::Delay;
 
+	let SpawnParams { program_path, spawn_timeout, sandbox } = spawn_params;
 	loop {
-		match worker::spawn(&program_path, spawn_timeout).await {
+		match worker::spawn(&program_path, spawn_timeout, sandbox.clone()).await {
 			Ok((idle, handle)) => break PoolEvent::Spawn(idle, handle),
 			Err(err) => {
 				gum::warn!(target: LOG_TARGET, "failed to spawn a prepare worker: {:?}", err);
@@ -263,17 +273,18 @@ async fn spawn_worker_task(program_path: PathBuf, spawn_timeout: Duration) -> Po
 	}
 }
 
-async fn start_work_task<Timer>(
+async fn start_work_task(
 	worker: Worker,
 	idle: IdleWorker,
-	code: Arc<Vec<u8>>,
+	pvf: Pvf,
 	cache_path: PathBuf,
 	artifact_path: PathBuf,
 	compilation_timeout: Duration,
-	_preparation_timer: Option<Timer>,
+	max_memory: Option<u64>,
 ) -> PoolEvent {
 	let outcome =
-		worker::start_work(idle, code, &cache_path, artifact_path, compilation_timeout).await;
+		worker::start_work(idle, pvf, &cache_path, artifact_path, compilation_timeout, max_memory)
+			.await;
 	PoolEvent::StartWork(worker, outcome)
 }
 
@@ -295,7 +306,23 @@ fn handle_mux(
 		},
 		PoolEvent::StartWork(worker, outcome) => {
 			match outcome {
-				Outcome::Concluded { worker: idle, result } => {
+				Outcome::Concluded { worker: idle, result, peak_memory } => {
+					if let Some(peak_memory) = peak_memory {
+						metrics.observe_preparation_peak_memory(peak_memory);
+					}
+
+					if let Err(PrepareError::OutOfMemory(_) | PrepareError::ForbiddenSyscall) =
+						result
+					{
+						// The worker shuts itself down after exceeding the memory limit or making
+						// a forbidden syscall, so it cannot be reused.
+						if attempt_retire(metrics, spawned, worker) {
+							reply(from_pool, FromPool::Concluded { worker, rip: true, result })?;
+						}
+
+						return Ok(())
+					}
+
 					let data = match spawned.get_mut(worker) {
 						None => {
 							// Perhaps the worker was killed meanwhile and the result is no longer
@@ -383,15 +410,17 @@ pub fn start(
 	program_path: PathBuf,
 	cache_path: PathBuf,
 	spawn_timeout: Duration,
+	max_memory: Option<u64>,
+	sandbox: Option<SandboxConfig>,
 ) -> (mpsc::Sender<ToPool>, mpsc::UnboundedReceiver<FromPool>, impl Future<Output = ()>) {
 	let (to_pool_tx, to_pool_rx) = mpsc::channel(10);
 	let (from_pool_tx, from_pool_rx) = mpsc::unbounded();
 
 	let run = run(Pool {
 		metrics,
-		program_path,
+		spawn_params: SpawnParams { program_path, spawn_timeout, sandbox },
 		cache_path,
-		spawn_timeout,
+		max_memory,
 		to_pool: to_pool_rx,
 		from_pool: from_pool_tx,
 		spawned: HopSlotMap::with_capacity_and_key(20),
@@ -437,7 +437,7 @@ async fn assign(queue: &mut Queue, worker: Worker, job: Job) -> Result<(), Fatal
 		&mut queue.to_pool_tx,
 		pool::ToPool::StartWork {
 			worker,
-			code: job_data.pvf.code.clone(),
+			pvf: job_data.pvf.clone(),
 			artifact_path,
 			compilation_timeout: job_data.compilation_timeout,
 		},
@@ -15,36 +15,88 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
-	artifacts::CompiledArtifact,
+	artifacts::{self, CompiledArtifact},
 	error::{PrepareError, PrepareResult},
+	sandbox::SandboxConfig,
 	worker_common::{
 		bytes_to_path, framed_recv, framed_send, path_to_bytes, spawn_with_program_path,
 		tmpfile_in, worker_event_loop, IdleWorker, SpawnErr, WorkerHandle,
 	},
-	LOG_TARGET,
+	Pvf, LOG_TARGET,
 };
 use async_std::{
 	io,
 	os::unix::net::UnixStream,
 	path::{Path, PathBuf},
 };
+use futures::{channel::oneshot, FutureExt as _};
+use futures_timer::Delay;
 use parity_scale_codec::{Decode, Encode};
+use polkadot_primitives::vstaging::ExecutorParams;
 use sp_core::hexdisplay::HexDisplay;
-use std::{panic, sync::Arc, time::Duration};
+use std::{panic, time::Duration};
+
+/// The interval at which the prepare worker samples its memory usage while compiling.
+const MEMORY_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The stack size of the thread that performs the compilation.
+///
+/// The compilation used to run on the main thread of the worker, so give it the same amount of
+/// stack as the main thread typically gets on Linux.
+const PREPARE_THREAD_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// The time allotted for the job of the sandbox self-test.
+const SANDBOX_SELF_TEST_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Spawns a new worker with the given program path that acts as the worker and the spawn timeout.
 ///
 /// The program should be able to handle `<program-path> prepare-worker <socket-path>` invocation.
+///
+/// If `sandbox` is `Some`, the worker will be sandboxed.
 pub async fn spawn(
 	program_path: &Path,
 	spawn_timeout: Duration,
+	sandbox: Option<SandboxConfig>,
 ) -> Result<(IdleWorker, WorkerHandle), SpawnErr> {
-	spawn_with_program_path("prepare", program_path, &["prepare-worker"], spawn_timeout).await
+	spawn_with_program_path("prepare", program_path, &["prepare-worker"], spawn_timeout, sandbox)
+		.await
+}
+
+/// Spawns a sandboxed worker and has it prepare an empty PVF, which it rejects in prevalidation
+/// if it works as it should.
+///
+/// `extra_args` are the arguments the program is invoked with in front of the socket path.
+///
+/// Return: The result the worker reported for the job, or why it did not report one.
+pub async fn prepare_in_sandbox(
+	program_path: &Path,
+	extra_args: &'static [&'static str],
+	spawn_timeout: Duration,
+	cache_path: &Path,
+	sandbox: SandboxConfig,
+) -> Result<PrepareResult, String> {
+	let (worker, _handle) =
+		spawn_with_program_path("prepare", program_path, extra_args, spawn_timeout, Some(sandbox))
+			.await
+			.map_err(|err| format!("failed to spawn the worker: {:?}", err))?;
+
+	let pvf = Pvf::from_code(Vec::new(), ExecutorParams::default());
+	let artifact_path = cache_path.join("sandbox-self-test");
+	match start_work(worker, pvf, cache_path, artifact_path, SANDBOX_SELF_TEST_TIMEOUT, None).await
+	{
+		Outcome::Concluded { result, .. } => Ok(result),
+		Outcome::Unreachable => Err("the worker was unreachable".to_string()),
+		Outcome::TimedOut => Err("the worker timed out".to_string()),
+		Outcome::DidNotMakeIt => Err("the worker did not make it".to_string()),
+	}
 }
 
 pub enum Outcome {
 	/// The worker has finished the work assigned to it.
-	Concluded { worker: IdleWorker, result: PrepareResult },
+	///
+	/// `peak_memory` is the highest memory usage in bytes observed by the worker during the job,
+	/// if the worker was able to measure it.
+	Concluded { worker: IdleWorker, result: PrepareResult, peak_memory: Option<u64> },
 	/// The host tried to reach the worker but failed. This is most likely because the worked was
 	/// killed by the system.
 	Unreachable,
@@ -62,10 +114,11 @@ pub enum Outcome {
 /// returns the outcome.
 pub async fn start_work(
 	worker: IdleWorker,
-	code: Arc<Vec<u8>>,
+	pvf: Pvf,
 	cache_path: &Path,
 	artifact_path: PathBuf,
 	compilation_timeout: Duration,
+	max_memory: Option<u64>,
 ) -> Outcome {
 	let IdleWorker { mut stream, pid } = worker;
 
@@ -77,7 +130,7 @@ pub async fn start_work(
 	);
 
 	with_tmp_file(pid, cache_path, |tmp_file| async move {
-		if let Err(err) = send_request(&mut stream, code, &tmp_file).await {
+		if let Err(err) = send_request(&mut stream, &pvf, &tmp_file, max_memory).await {
 			gum::warn!(
 				target: LOG_TARGET,
 				worker_pid = %pid,
@@ -94,7 +147,7 @@ pub async fn start_work(
 
 		#[derive(Debug)]
 		enum Selected {
-			Done(PrepareResult),
+			Done(PrepareResult, Option<u64>),
 			IoErr,
 			Deadline,
 		}
@@ -103,8 +156,11 @@ pub async fn start_work(
 			match async_std::future::timeout(compilation_timeout, framed_recv(&mut stream)).await {
 				Ok(Ok(response_bytes)) => {
 					// Received bytes from worker within the time limit.
-					// By convention we expect encoded `PrepareResult`.
-					if let Ok(result) = PrepareResult::decode(&mut response_bytes.as_slice()) {
+					// By convention we expect encoded `PrepareResult` followed by the peak memory
+					// usage.
+					if let Ok((result, peak_memory)) =
+						<(PrepareResult, Option<u64>)>::decode(&mut response_bytes.as_slice())
+					{
 						if result.is_ok() {
 							gum::debug!(
 								target: LOG_TARGET,
@@ -114,22 +170,29 @@ pub async fn start_work(
 								artifact_path.display(),
 							);
 
-							async_std::fs::rename(&tmp_file, &artifact_path)
-								.await
-								.map(|_| Selected::Done(result))
-								.unwrap_or_else(|err| {
+							// The checksum is written before the artifact is promoted, so that a
+							// promoted artifact never lacks one.
+							let promoted =
+								match artifacts::write_checksum(&tmp_file, &artifact_path).await {
+									Ok(()) =>
+										async_std::fs::rename(&tmp_file, &artifact_path).await,
+									Err(err) => Err(err),
+								};
+							promoted.map(|_| Selected::Done(result, peak_memory)).unwrap_or_else(
+								|err| {
 									gum::warn!(
 										target: LOG_TARGET,
 										worker_pid = %pid,
-										"failed to rename the artifact from {} to {}: {:?}",
+										"failed to promote the artifact from {} to {}: {:?}",
 										tmp_file.display(),
 										artifact_path.display(),
 										err,
 									);
 									Selected::IoErr
-								})
+								},
+							)
 						} else {
-							Selected::Done(result)
+							Selected::Done(result, peak_memory)
 						}
 					} else {
 						// We received invalid bytes from the worker.
@@ -160,8 +223,8 @@ pub async fn start_work(
 			};
 
 		match selected {
-			Selected::Done(result) =>
-				Outcome::Concluded { worker: IdleWorker { stream, pid }, result },
+			Selected::Done(result, peak_memory) =>
+				Outcome::Concluded { worker: IdleWorker { stream, pid }, result, peak_memory },
 			Selected::Deadline => Outcome::TimedOut,
 			Selected::IoErr => Outcome::DidNotMakeIt,
 		}
@@ -216,16 +279,28 @@ where
 
 async fn send_request(
 	stream: &mut UnixStream,
-	code: Arc<Vec<u8>>,
+	pvf: &Pvf,
 	tmp_file: &Path,
+	max_memory: Option<u64>,
 ) -> io::Result<()> {
-	framed_send(stream, &*code).await?;
+	framed_send(stream, &*pvf.code).await?;
+	framed_send(stream, &pvf.executor_params.encode()).await?;
 	framed_send(stream, path_to_bytes(tmp_file)).await?;
+	framed_send(stream, &max_memory.encode()).await?;
 	Ok(())
 }
 
-async fn recv_request(stream: &mut UnixStream) -> io::Result<(Vec<u8>, PathBuf)> {
+async fn recv_request(
+	stream: &mut UnixStream,
+) -> io::Result<(Vec<u8>, ExecutorParams, PathBuf, Option<u64>)> {
 	let code = framed_recv(stream).await?;
+	let executor_params = framed_recv(stream).await?;
+	let executor_params = ExecutorParams::decode(&mut &executor_params[..]).map_err(|_| {
+		io::Error::new(
+			io::ErrorKind::Other,
+			"prepare pvf recv_request: failed to decode executor params".to_string(),
+		)
+	})?;
 	let tmp_file = framed_recv(stream).await?;
 	let tmp_file = bytes_to_path(&tmp_file).ok_or_else(|| {
 		io::Error::new(
@@ -233,15 +308,23 @@ async fn recv_request(stream: &mut UnixStream) -> io::Result<(Vec<u8>, PathBuf)>
 			"prepare pvf recv_request: non utf-8 artifact path".to_string(),
 		)
 	})?;
-	Ok((code, tmp_file))
+	let max_memory = framed_recv(stream).await?;
+	let max_memory = Option::<u64>::decode(&mut &max_memory[..]).map_err(|_| {
+		io::Error::new(
+			io::ErrorKind::Other,
+			"prepare pvf recv_request: failed to decode memory limit".to_string(),
+		)
+	})?;
+	Ok((code, executor_params, tmp_file, max_memory))
 }
 
 /// The entrypoint that the spawned prepare worker should start with. The `socket_path` specifies
 /// the path to the socket used to communicate with the host.
 pub fn worker_entrypoint(socket_path: &str) {
-	worker_event_loop("prepare", socket_path, |mut stream| async move {
+	let forbidden_syscall = forbidden_syscall_response();
+	worker_event_loop("prepare", socket_path, forbidden_syscall, |mut stream| async move {
 		loop {
-			let (code, dest) = recv_request(&mut stream).await?;
+			let (code, executor_params, dest, max_memory) = recv_request(&mut stream).await?;
 
 			gum::debug!(
 				target: LOG_TARGET,
@@ -249,7 +332,30 @@ pub fn worker_entrypoint(socket_path: &str) {
 				"worker: preparing artifact",
 			);
 
-			let result = match prepare_artifact(&code) {
+			let outcome = prepare_with_memory_limit(code, executor_params, max_memory).await?;
+			let (result, peak_memory) = match outcome {
+				MemoryLimitedOutcome::Finished(result, peak_memory) => (result, peak_memory),
+				MemoryLimitedOutcome::LimitExceeded(observed) => {
+					gum::warn!(
+						target: LOG_TARGET,
+						worker_pid = %std::process::id(),
+						?max_memory,
+						"worker: preparation exceeded the memory limit: {} bytes",
+						observed,
+					);
+
+					// The compilation thread cannot be interrupted. Report the error and then
+					// shut down the worker, taking the thread down with it.
+					let result: PrepareResult = Err(PrepareError::OutOfMemory(observed));
+					framed_send(&mut stream, (result, Some(observed)).encode().as_slice()).await?;
+					return Err(io::Error::new(
+						io::ErrorKind::Other,
+						"preparation exceeded the memory limit".to_string(),
+					))
+				},
+			};
+
+			let result = match result {
 				Err(err) => {
 					// Serialized error will be written into the socket.
 					Err(err)
@@ -274,19 +380,137 @@ pub fn worker_entrypoint(socket_path: &str) {
 				},
 			};
 
-			framed_send(&mut stream, result.encode().as_slice()).await?;
+			framed_send(&mut stream, (result, peak_memory).encode().as_slice()).await?;
 		}
 	});
 }
 
-fn prepare_artifact(code: &[u8]) -> Result<CompiledArtifact, PrepareError> {
+/// The entrypoint of a prepare worker which opens a network socket instead of preparing the PVF
+/// it is given. Only used to test the sandbox, which forbids that.
+pub fn forbidden_syscall_worker_entrypoint(socket_path: &str) {
+	worker_event_loop(
+		"prepare",
+		socket_path,
+		forbidden_syscall_response(),
+		|mut stream| async move {
+			loop {
+				let _ = recv_request(&mut stream).await?;
+
+				// Unless the worker is sandboxed, the socket is opened just fine.
+				let result: PrepareResult = Err(PrepareError::Preparation(
+					match std::net::UdpSocket::bind("127.0.0.1:0") {
+						Ok(_) => "opened a socket".to_string(),
+						Err(err) => format!("failed to open a socket: {}", err),
+					},
+				));
+				framed_send(&mut stream, (result, None::<u64>).encode().as_slice()).await?;
+			}
+		},
+	);
+}
+
+/// The response sent to the host by the sandbox should the worker make a forbidden syscall.
+fn forbidden_syscall_response() -> Vec<u8> {
+	let result: PrepareResult = Err(PrepareError::ForbiddenSyscall);
+	(result, None::<u64>).encode()
+}
+
+enum MemoryLimitedOutcome {
+	/// The preparation finished. Contains the result and the peak memory usage observed during
+	/// the preparation, if it could be measured.
+	Finished(Result<CompiledArtifact, PrepareError>, Option<u64>),
+	/// The memory usage exceeded the limit. Contains the observed memory usage.
+	LimitExceeded(u64),
+}
+
+/// Runs the preparation on a separate thread while sampling the memory usage of the worker.
+///
+/// If the memory usage exceeds `max_memory`, returns immediately without waiting for the
+/// preparation to finish.
+async fn prepare_with_memory_limit(
+	code: Vec<u8>,
+	executor_params: ExecutorParams,
+	max_memory: Option<u64>,
+) -> io::Result<MemoryLimitedOutcome> {
+	let (result_tx, result_rx) = oneshot::channel();
+	std::thread::Builder::new()
+		.name("pvf-prepare".to_string())
+		.stack_size(PREPARE_THREAD_STACK_SIZE)
+		.spawn(move || {
+			let _ = result_tx.send(prepare_artifact(&code, &executor_params));
+		})?;
+
+	let mut peak_memory = resident_memory();
+	let mut result_rx = result_rx.fuse();
+	loop {
+		futures::select! {
+			result = result_rx => {
+				let result = result.unwrap_or_else(|_| {
+					// The thread catches the panics, so the sender is only dropped if the thread
+					// was torn down abruptly.
+					Err(PrepareError::Panic("preparation thread terminated".to_string()))
+				});
+				return Ok(MemoryLimitedOutcome::Finished(result, peak_memory))
+			},
+			_ = Delay::new(MEMORY_POLL_INTERVAL).fuse() => {
+				let current = match resident_memory() {
+					Some(current) => current,
+					// The memory usage cannot be measured on this platform.
+					None => continue,
+				};
+				let peak = peak_memory.map_or(current, |peak| peak.max(current));
+				peak_memory = Some(peak);
+
+				if max_memory.map_or(false, |max_memory| peak > max_memory) {
+					return Ok(MemoryLimitedOutcome::LimitExceeded(peak))
+				}
+			},
+		}
+	}
+}
+
+/// Returns the resident set size of the current process in bytes.
+///
+/// Returns `None` if the value is not available on this platform.
+#[cfg(target_os = "linux")]
+fn resident_memory() -> Option<u64> {
+	let status = std::fs::read_to_string("/proc/self/status").ok()?;
+	let kib = status
+		.lines()
+		.find_map(|line| line.strip_prefix("VmRSS:"))?
+		.trim()
+		.strip_suffix("kB")?
+		.trim()
+		.parse::<u64>()
+		.ok()?;
+	Some(kib * 1024)
+}
+
+/// Returns the resident set size of the current process in bytes.
+///
+/// Returns `None` if the value is not available on this platform.
+#[cfg(not(target_os = "linux"))]
+fn resident_memory() -> Option<u64> {
+	None
+}
+
+fn prepare_artifact(
+	code: &[u8],
+	executor_params: &ExecutorParams,
+) -> Result<CompiledArtifact, PrepareError> {
 	panic::catch_unwind(|| {
+		let semantics = match crate::executor_intf::params_to_wasmtime_semantics(executor_params) {
+			Err(err) =>
+				return Err(PrepareError::Preparation(format!("invalid executor params: {}", err))),
+			Ok(s) => s,
+		};
+
 		let blob = match crate::executor_intf::prevalidate(code) {
 			Err(err) => return Err(PrepareError::Prevalidation(format!("{:?}", err))),
 			Ok(b) => b,
 		};
 
-		match crate::executor_intf::prepare(blob) {
+		match crate::executor_intf::prepare(blob, &semantics) {
 			Ok(compiled_artifact) => Ok(CompiledArtifact::new(compiled_artifact)),
 			Err(err) => Err(PrepareError::Preparation(format!("{:?}", err))),
 		}
@@ -16,41 +16,60 @@
 
 use crate::artifacts::ArtifactId;
 use polkadot_parachain::primitives::ValidationCodeHash;
+use polkadot_primitives::vstaging::{ExecutorParams, ExecutorParamsHash};
 use sp_core::blake2_256;
 use std::{fmt, sync::Arc};
 
-/// A struct that carries code of a parachain validation function and it's hash.
+/// A struct that carries code of a parachain validation function, it's hash and the executor
+/// parameters it should be prepared and executed with.
 ///
 /// Should be cheap to clone.
 #[derive(Clone)]
 pub struct Pvf {
 	pub(crate) code: Arc<Vec<u8>>,
 	pub(crate) code_hash: ValidationCodeHash,
+	pub(crate) executor_params: Arc<ExecutorParams>,
+	pub(crate) executor_params_hash: ExecutorParamsHash,
 }
 
 impl fmt::Debug for Pvf {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		write!(f, "Pvf {{ code, code_hash: {:?} }}", self.code_hash)
+		write!(
+			f,
+			"Pvf {{ code, code_hash: {:?}, executor_params: {:?} }}",
+			self.code_hash, self.executor_params
+		)
 	}
 }
 
+impl PartialEq for Pvf {
+	fn eq(&self, other: &Self) -> bool {
+		// The hashes uniquely identify the code and the parameters, no need to compare the code.
+		self.code_hash == other.code_hash && self.executor_params_hash == other.executor_params_hash
+	}
+}
+
+impl Eq for Pvf {}
+
 impl Pvf {
-	/// Returns an instance of the PVF out of the given PVF code.
-	pub fn from_code(code: Vec<u8>) -> Self {
+	/// Returns an instance of the PVF out of the given PVF code and the executor parameters
+	/// agreed on-chain for the session.
+	pub fn from_code(code: Vec<u8>, executor_params: ExecutorParams) -> Self {
 		let code = Arc::new(code);
 		let code_hash = blake2_256(&code).into();
-		Self { code, code_hash }
+		let executor_params_hash = executor_params.hash();
+		Self { code, code_hash, executor_params: Arc::new(executor_params), executor_params_hash }
 	}
 
 	/// Creates a new PVF which artifact id can be uniquely identified by the given number.
 	#[cfg(test)]
 	pub(crate) fn from_discriminator(num: u32) -> Self {
 		let descriminator_buf = num.to_le_bytes().to_vec();
-		Pvf::from_code(descriminator_buf)
+		Pvf::from_code(descriminator_buf, ExecutorParams::default())
 	}
 
 	/// Returns the artifact ID that corresponds to this PVF.
 	pub(crate) fn as_artifact_id(&self) -> ArtifactId {
-		ArtifactId::new(self.code_hash)
+		ArtifactId::new(self.code_hash, self.executor_params_hash)
 	}
 }
@@ -0,0 +1,420 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sandboxing of the worker processes.
+//!
+//! The workers handle untrusted code, so should the code find a way to take control over a worker
+//! we would like to limit the damage it can do. A sandboxed worker:
+//!
+//! - can only access the files beneath the artifact cache directory. This is enforced with
+//!   landlock alone: the worker does not unshare any namespaces, as unprivileged user namespaces
+//!   are disabled on many systems.
+//! - cannot open network connections or spawn other programs. This is enforced by a seccomp filter.
+//!
+//! A worker that makes a forbidden syscall reports that to the host and terminates.
+//!
+//! Sandboxing is only supported on Linux running on x86-64 or aarch64. The host checks whether the
+//! running system supports it, see [`check_support`], and then has a sandboxed worker run a job as
+//! a self-test before spawning any other sandboxed workers.
+
+use parity_scale_codec::{Decode, Encode};
+use std::{ffi::OsStr, io, os::unix::ffi::OsStrExt as _, path::Path};
+
+/// The sandbox configuration that the host passes to a worker.
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct SandboxConfig {
+	/// The raw bytes of the only directory the worker is allowed to access. This is expected to be
+	/// the artifact cache directory.
+	cache_path: Vec<u8>,
+}
+
+impl SandboxConfig {
+	/// Creates the sandbox configuration restricting the worker to the given cache directory.
+	pub fn new(cache_path: &Path) -> Self {
+		Self { cache_path: cache_path.as_os_str().as_bytes().to_vec() }
+	}
+
+	/// The only directory the worker is allowed to access.
+	pub fn cache_path(&self) -> &Path {
+		Path::new(OsStr::from_bytes(&self.cache_path))
+	}
+}
+
+/// The result of the check whether sandboxing is supported by the running system.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SandboxSupport {
+	/// Sandboxing is supported. Contains the landlock ABI version supported by the kernel.
+	Available { landlock_abi: i32 },
+	/// Sandboxing is not supported. Contains the reason.
+	Unavailable(String),
+}
+
+impl SandboxSupport {
+	/// Returns `true` if sandboxing is supported.
+	pub fn is_available(&self) -> bool {
+		matches!(self, SandboxSupport::Available { .. })
+	}
+}
+
+/// Checks whether the running system supports all the features the sandbox relies upon.
+///
+/// This doesn't apply any restrictions to the calling process.
+pub fn check_support() -> SandboxSupport {
+	imp::check_support()
+}
+
+/// Applies the sandbox to the calling process. This is irreversible.
+///
+/// This must be called before the worker spawns any threads. The seccomp filter is synchronized
+/// across all the threads of the process, but the file system restrictions only apply to the
+/// calling thread and the threads it spawns afterwards.
+///
+/// `report_fd` is the file descriptor of the socket connected to the host and `report` is the
+/// encoded message that is sent over it if the worker makes a forbidden syscall, right before the
+/// worker terminates.
+pub fn enter(config: &SandboxConfig, report_fd: i32, report: &[u8]) -> io::Result<()> {
+	imp::enter(config, report_fd, report)
+}
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod imp {
+	use super::{SandboxConfig, SandboxSupport};
+	use std::{
+		ffi::CString,
+		io,
+		os::unix::ffi::OsStrExt as _,
+		path::Path,
+		sync::atomic::{AtomicI32, AtomicPtr, AtomicUsize, Ordering},
+	};
+
+	// The landlock syscalls have the same numbers on all architectures.
+	const SYS_LANDLOCK_CREATE_RULESET: libc::c_long = 444;
+	const SYS_LANDLOCK_ADD_RULE: libc::c_long = 445;
+	const SYS_LANDLOCK_RESTRICT_SELF: libc::c_long = 446;
+
+	const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1 << 0;
+	const LANDLOCK_RULE_PATH_BENEATH: libc::c_int = 1;
+
+	const LANDLOCK_ACCESS_FS_READ_FILE: u64 = 1 << 2;
+	const LANDLOCK_ACCESS_FS_READ_DIR: u64 = 1 << 3;
+	/// All the file system access rights defined by the first version of the landlock ABI.
+	const LANDLOCK_ACCESS_FS_ALL: u64 = (1 << 13) - 1;
+
+	#[repr(C)]
+	struct LandlockRulesetAttr {
+		handled_access_fs: u64,
+	}
+
+	#[repr(C, packed)]
+	struct LandlockPathBeneathAttr {
+		allowed_access: u64,
+		parent_fd: i32,
+	}
+
+	#[repr(C)]
+	struct SockFilter {
+		code: u16,
+		jt: u8,
+		jf: u8,
+		k: u32,
+	}
+
+	#[repr(C)]
+	struct SockFprog {
+		len: libc::c_ushort,
+		filter: *const SockFilter,
+	}
+
+	/// `BPF_LD | BPF_W | BPF_ABS`
+	const BPF_LD_W_ABS: u16 = 0x20;
+	/// `BPF_JMP | BPF_JEQ | BPF_K`
+	const BPF_JMP_JEQ_K: u16 = 0x15;
+	/// `BPF_RET | BPF_K`
+	const BPF_RET_K: u16 = 0x06;
+
+	const SECCOMP_SET_MODE_FILTER: libc::c_uint = 1;
+	const SECCOMP_FILTER_FLAG_TSYNC: libc::c_uint = 1 << 0;
+	const SECCOMP_RET_KILL_PROCESS: u32 = 0x8000_0000;
+	const SECCOMP_RET_TRAP: u32 = 0x0003_0000;
+	const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+
+	/// Offsets of the fields within `struct seccomp_data`.
+	const SECCOMP_DATA_NR_OFFSET: u32 = 0;
+	const SECCOMP_DATA_ARCH_OFFSET: u32 = 4;
+
+	#[cfg(target_arch = "x86_64")]
+	const AUDIT_ARCH: u32 = 0xc000_003e;
+	#[cfg(target_arch = "aarch64")]
+	const AUDIT_ARCH: u32 = 0xc000_00b7;
+
+	/// The syscalls a sandboxed worker is not allowed to make.
+	const FORBIDDEN_SYSCALLS: &[libc::c_long] = &[
+		libc::SYS_socket,
+		libc::SYS_socketpair,
+		libc::SYS_connect,
+		libc::SYS_bind,
+		libc::SYS_listen,
+		libc::SYS_accept,
+		libc::SYS_accept4,
+		libc::SYS_execve,
+		libc::SYS_execveat,
+		libc::SYS_ptrace,
+	];
+
+	static REPORT_FD: AtomicI32 = AtomicI32::new(-1);
+	static REPORT_PTR: AtomicPtr<u8> = AtomicPtr::new(std::ptr::null_mut());
+	static REPORT_LEN: AtomicUsize = AtomicUsize::new(0);
+
+	pub fn check_support() -> SandboxSupport {
+		// SAFETY: querying the seccomp mode of the calling thread has no side effects.
+		if unsafe { libc::prctl(libc::PR_GET_SECCOMP, 0, 0, 0, 0) } < 0 {
+			return SandboxSupport::Unavailable(format!(
+				"seccomp is not supported: {}",
+				io::Error::last_os_error()
+			))
+		}
+
+		match landlock_abi() {
+			Ok(landlock_abi) => SandboxSupport::Available { landlock_abi },
+			Err(err) => SandboxSupport::Unavailable(format!("landlock is not supported: {}", err)),
+		}
+	}
+
+	pub fn enter(config: &SandboxConfig, report_fd: i32, report: &[u8]) -> io::Result<()> {
+		install_report_handler(report_fd, report)?;
+
+		// SAFETY: this only affects the privileges of the calling process.
+		if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } < 0 {
+			return Err(io::Error::last_os_error())
+		}
+
+		restrict_file_system(&[
+			(config.cache_path(), LANDLOCK_ACCESS_FS_ALL),
+			// The worker inspects its own memory usage.
+			(Path::new("/proc/self"), LANDLOCK_ACCESS_FS_READ_FILE | LANDLOCK_ACCESS_FS_READ_DIR),
+		])?;
+		install_seccomp_filter()
+	}
+
+	fn landlock_abi() -> io::Result<i32> {
+		// SAFETY: with the version flag set, the syscall doesn't create a ruleset and only returns
+		// the highest supported ABI version.
+		let abi = unsafe {
+			libc::syscall(
+				SYS_LANDLOCK_CREATE_RULESET,
+				std::ptr::null::<LandlockRulesetAttr>(),
+				0usize,
+				LANDLOCK_CREATE_RULESET_VERSION,
+			)
+		};
+		if abi < 1 {
+			return Err(io::Error::last_os_error())
+		}
+		Ok(abi as i32)
+	}
+
+	/// Allows the process to access only the files beneath the given paths with the given access
+	/// rights.
+	fn restrict_file_system(rules: &[(&Path, u64)]) -> io::Result<()> {
+		let attr = LandlockRulesetAttr { handled_access_fs: LANDLOCK_ACCESS_FS_ALL };
+		// SAFETY: `attr` is a valid ruleset attribute and its size is passed along.
+		let ruleset_fd = unsafe {
+			libc::syscall(
+				SYS_LANDLOCK_CREATE_RULESET,
+				&attr as *const LandlockRulesetAttr,
+				std::mem::size_of::<LandlockRulesetAttr>(),
+				0u32,
+			)
+		};
+		if ruleset_fd < 0 {
+			return Err(io::Error::last_os_error())
+		}
+		let ruleset_fd = ruleset_fd as libc::c_int;
+
+		let result = (|| {
+			for (path, allowed_access) in rules {
+				add_path_rule(ruleset_fd, path, *allowed_access)?;
+			}
+			// SAFETY: `ruleset_fd` is a valid landlock ruleset.
+			if unsafe { libc::syscall(SYS_LANDLOCK_RESTRICT_SELF, ruleset_fd, 0u32) } < 0 {
+				return Err(io::Error::last_os_error())
+			}
+			Ok(())
+		})();
+
+		// SAFETY: `ruleset_fd` is owned by this function.
+		unsafe { libc::close(ruleset_fd) };
+		result
+	}
+
+	fn add_path_rule(ruleset_fd: libc::c_int, path: &Path, allowed_access: u64) -> io::Result<()> {
+		let c_path = CString::new(path.as_os_str().as_bytes())
+			.map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+		// SAFETY: `c_path` is a valid nul-terminated string.
+		let parent_fd = unsafe { libc::open(c_path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC) };
+		if parent_fd < 0 {
+			return Err(io::Error::last_os_error())
+		}
+
+		let attr = LandlockPathBeneathAttr { allowed_access, parent_fd };
+		// SAFETY: `attr` is a valid path beneath rule that refers to an open file descriptor.
+		let result = unsafe {
+			libc::syscall(
+				SYS_LANDLOCK_ADD_RULE,
+				ruleset_fd,
+				LANDLOCK_RULE_PATH_BENEATH,
+				&attr as *const LandlockPathBeneathAttr,
+				0u32,
+			)
+		};
+		let result = if result < 0 { Err(io::Error::last_os_error()) } else { Ok(()) };
+
+		// SAFETY: `parent_fd` is owned by this function.
+		unsafe { libc::close(parent_fd) };
+		result
+	}
+
+	/// Installs a seccomp filter that traps the forbidden syscalls on all the threads of the
+	/// process. The syscalls made on behalf of a foreign architecture kill the process right away.
+	fn install_seccomp_filter() -> io::Result<()> {
+		let mut filter = vec![
+			SockFilter { code: BPF_LD_W_ABS, jt: 0, jf: 0, k: SECCOMP_DATA_ARCH_OFFSET },
+			SockFilter { code: BPF_JMP_JEQ_K, jt: 1, jf: 0, k: AUDIT_ARCH },
+			SockFilter { code: BPF_RET_K, jt: 0, jf: 0, k: SECCOMP_RET_KILL_PROCESS },
+			SockFilter { code: BPF_LD_W_ABS, jt: 0, jf: 0, k: SECCOMP_DATA_NR_OFFSET },
+		];
+		for syscall in FORBIDDEN_SYSCALLS {
+			filter.push(SockFilter { code: BPF_JMP_JEQ_K, jt: 0, jf: 1, k: *syscall as u32 });
+			filter.push(SockFilter { code: BPF_RET_K, jt: 0, jf: 0, k: SECCOMP_RET_TRAP });
+		}
+		filter.push(SockFilter { code: BPF_RET_K, jt: 0, jf: 0, k: SECCOMP_RET_ALLOW });
+
+		let prog = SockFprog { len: filter.len() as libc::c_ushort, filter: filter.as_ptr() };
+		// SAFETY: `prog` points to a valid filter program that outlives the call.
+		let result = unsafe {
+			libc::syscall(
+				libc::SYS_seccomp,
+				SECCOMP_SET_MODE_FILTER,
+				SECCOMP_FILTER_FLAG_TSYNC,
+				&prog as *const SockFprog,
+			)
+		};
+		match result {
+			0 => Ok(()),
+			// With `SECCOMP_FILTER_FLAG_TSYNC`, a positive result is the ID of the thread that
+			// could not be synchronized.
+			tid if tid > 0 => Err(io::Error::new(
+				io::ErrorKind::Other,
+				format!("failed to apply the seccomp filter to thread {}", tid),
+			)),
+			_ => Err(io::Error::last_os_error()),
+		}
+	}
+
+	/// Installs the handler of `SIGSYS`, which is raised by the seccomp filter when a forbidden
+	/// syscall is made.
+	fn install_report_handler(report_fd: i32, report: &[u8]) -> io::Result<()> {
+		// The report is framed the same way as `framed_send` does it. It is leaked, since the
+		// handler may fire at any point until the process exits.
+		let mut framed = report.len().to_le_bytes().to_vec();
+		framed.extend_from_slice(report);
+		let framed = Box::leak(framed.into_boxed_slice());
+		REPORT_LEN.store(framed.len(), Ordering::SeqCst);
+		REPORT_PTR.store(framed.as_mut_ptr(), Ordering::SeqCst);
+		REPORT_FD.store(report_fd, Ordering::SeqCst);
+
+		// SAFETY: the handler only makes async-signal-safe calls.
+		unsafe {
+			let mut action: libc::sigaction = std::mem::zeroed();
+			action.sa_sigaction = on_forbidden_syscall as libc::sighandler_t;
+			action.sa_flags = libc::SA_SIGINFO;
+			libc::sigemptyset(&mut action.sa_mask);
+			if libc::sigaction(libc::SIGSYS, &action, std::ptr::null_mut()) < 0 {
+				return Err(io::Error::last_os_error())
+			}
+		}
+		Ok(())
+	}
+
+	extern "C" fn on_forbidden_syscall(
+		_signal: libc::c_int,
+		_info: *mut libc::siginfo_t,
+		_context: *mut libc::c_void,
+	) {
+		let fd = REPORT_FD.load(Ordering::SeqCst);
+		let ptr = REPORT_PTR.load(Ordering::SeqCst);
+		let len = REPORT_LEN.load(Ordering::SeqCst);
+
+		let mut written = 0;
+		while written < len {
+			// SAFETY: `ptr` points to the leaked report of `len` bytes.
+			let n =
+				unsafe { libc::write(fd, ptr.add(written) as *const libc::c_void, len - written) };
+			if n <= 0 {
+				break
+			}
+			written += n as usize;
+		}
+
+		// SAFETY: `_exit` is async-signal-safe. The process cannot carry on, since the forbidden
+		// syscall has not been performed.
+		unsafe { libc::_exit(1) }
+	}
+}
+
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+mod imp {
+	use super::{SandboxConfig, SandboxSupport};
+	use std::io;
+
+	pub fn check_support() -> SandboxSupport {
+		SandboxSupport::Unavailable(
+			"sandboxing is only supported on Linux running on x86-64 or aarch64".to_string(),
+		)
+	}
+
+	pub fn enter(_config: &SandboxConfig, _report_fd: i32, _report: &[u8]) -> io::Result<()> {
+		Err(io::Error::new(io::ErrorKind::Other, "sandboxing is not supported on this platform"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sandbox_config_roundtrip() {
+		let config = SandboxConfig::new(Path::new("/tmp/pvf-artifacts"));
+		let decoded = SandboxConfig::decode(&mut &config.encode()[..]).unwrap();
+		assert_eq!(decoded.cache_path(), Path::new("/tmp/pvf-artifacts"));
+	}
+
+	#[test]
+	fn sandbox_config_keeps_non_utf8_paths() {
+		let path = Path::new(OsStr::from_bytes(b"/tmp/pvf-\xff"));
+		let config = SandboxConfig::new(path);
+		let decoded = SandboxConfig::decode(&mut &config.encode()[..]).unwrap();
+		assert_eq!(decoded.cache_path(), path);
+	}
+
+	#[test]
+	fn check_support_does_not_restrict_the_process() {
+		let _ = check_support();
+		// The check must not have sandboxed the test process.
+		assert!(std::fs::read_dir("/").is_ok());
+		assert!(std::net::UdpSocket::bind("127.0.0.1:0").is_ok());
+	}
+}
@@ -23,25 +23,46 @@ pub mod worker_common {
 	pub use crate::worker_common::{spawn_with_program_path, SpawnErr};
 }
 
+pub mod sandbox {
+	pub use crate::{
+		prepare::prepare_in_sandbox,
+		sandbox::{check_support, SandboxConfig, SandboxSupport},
+	};
+}
+
+pub use crate::prepare::forbidden_syscall_worker_entrypoint as forbidden_syscall_prepare_worker_entrypoint;
+
 /// A function that emulates the stitches together behaviors of the preparation and the execution
 /// worker in a single synchronous function.
+///
+/// The default executor parameters are used.
 pub fn validate_candidate(
 	code: &[u8],
 	params: &[u8],
 ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
-	use crate::executor_intf::{prepare, prevalidate, Executor};
+	validate_candidate_with_executor_params(code, params, &Default::default())
+}
+
+/// Same as [`validate_candidate`], but prepares and executes the code with the given executor
+/// parameters.
+pub fn validate_candidate_with_executor_params(
+	code: &[u8],
+	params: &[u8],
+	executor_params: &polkadot_primitives::vstaging::ExecutorParams,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+	use crate::executor_intf::{params_to_wasmtime_semantics, prepare, prevalidate, Executor};
 
 	let code = sp_maybe_compressed_blob::decompress(code, 10 * 1024 * 1024)
 		.expect("Decompressing code failed");
 
 	let blob = prevalidate(&*code)?;
-	let artifact = prepare(blob)?;
+	let artifact = prepare(blob, &params_to_wasmtime_semantics(executor_params)?)?;
 	let tmpdir = tempfile::tempdir()?;
 	let artifact_path = tmpdir.path().join("blob");
 	std::fs::write(&artifact_path, &artifact)?;
 
-	let executor = Executor::new()?;
-	let result = unsafe {
+	let executor = Executor::new(executor_params)?;
+	let (result, _phases) = unsafe {
 		// SAFETY: This is trivially safe since the artifact is obtained by calling `prepare`
 		//         and is written into a temporary directory in an unmodified state.
 		executor.execute(&artifact_path, params)?
@@ -76,6 +97,10 @@ macro_rules! decl_puppet_worker_main {
 					let socket_path = &args[2];
 					$crate::execute_worker_entrypoint(socket_path);
 				},
+				"prepare-worker-forbidden-syscall" => {
+					let socket_path = &args[2];
+					$crate::testing::forbidden_syscall_prepare_worker_entrypoint(socket_path);
+				},
 				other => panic!("unknown subcommand: {}", other),
 			}
 		}
@@ -16,16 +16,20 @@
 
 //! Common logic for implementation of worker processes.
 
-use crate::LOG_TARGET;
+use crate::{sandbox::SandboxConfig, LOG_TARGET};
 use async_std::{
 	io,
-	os::unix::net::{UnixListener, UnixStream},
+	os::unix::{
+		io::AsRawFd as _,
+		net::{UnixListener, UnixStream},
+	},
 	path::{Path, PathBuf},
 };
 use futures::{
 	never::Never, AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _, FutureExt as _,
 };
 use futures_timer::Delay;
+use parity_scale_codec::{Decode, Encode};
 use pin_project::pin_project;
 use rand::Rng;
 use std::{
@@ -36,12 +40,16 @@ use std::{
 };
 
 /// This is publicly exposed only for integration tests.
+///
+/// Right after the worker connects, it is sent the sandbox configuration. If it is `Some`, the
+/// worker sandboxes itself before accepting any work.
 #[doc(hidden)]
 pub async fn spawn_with_program_path(
 	debug_id: &'static str,
 	program_path: impl Into<PathBuf>,
 	extra_args: &'static [&'static str],
 	spawn_timeout: Duration,
+	sandbox: Option<SandboxConfig>,
 ) -> Result<(IdleWorker, WorkerHandle), SpawnErr> {
 	let program_path = program_path.into();
 	with_transient_socket_path(debug_id, |socket_path| {
@@ -70,7 +78,7 @@ pub async fn spawn_with_program_path(
 
 			futures::select! {
 				accept_result = listener.accept().fuse() => {
-					let (stream, _) = accept_result.map_err(|err| {
+					let (mut stream, _) = accept_result.map_err(|err| {
 						gum::warn!(
 							target: LOG_TARGET,
 							%debug_id,
@@ -79,6 +87,15 @@ pub async fn spawn_with_program_path(
 						);
 						SpawnErr::Accept
 					})?;
+					framed_send(&mut stream, &sandbox.encode()).await.map_err(|err| {
+						gum::warn!(
+							target: LOG_TARGET,
+							%debug_id,
+							"cannot send the sandbox configuration to a worker: {:?}",
+							err,
+						);
+						SpawnErr::Accept
+					})?;
 					Ok((IdleWorker { stream, pid: handle.id() }, handle))
 				}
 				_ = Delay::new(spawn_timeout).fuse() => {
@@ -147,17 +164,54 @@ pub async fn tmpfile(prefix: &str) -> io::Result<PathBuf> {
 	tmpfile_in(prefix, &temp_dir).await
 }
 
-pub fn worker_event_loop<F, Fut>(debug_id: &'static str, socket_path: &str, mut event_loop: F)
-where
+/// Connects to the host and runs the given event loop.
+///
+/// If the host asks for it, the worker is sandboxed before the event loop starts. Should the
+/// sandboxed worker make a forbidden syscall, the encoded `forbidden_syscall_response` is sent to
+/// the host in place of the response to the job at hand and the worker terminates.
+pub fn worker_event_loop<F, Fut>(
+	debug_id: &'static str,
+	socket_path: &str,
+	forbidden_syscall_response: Vec<u8>,
+	mut event_loop: F,
+) where
 	F: FnMut(UnixStream) -> Fut,
 	Fut: futures::Future<Output = io::Result<Never>>,
 {
-	let err = async_std::task::block_on::<_, io::Result<Never>>(async move {
-		let stream = UnixStream::connect(socket_path).await?;
-		let _ = async_std::fs::remove_file(socket_path).await;
+	let err = (|| -> io::Result<Never> {
+		// The sandbox is entered before the async runtime is started, since the runtime spawns
+		// threads that would otherwise escape the file system restrictions. Hence the connection
+		// is set up in a blocking way on the current thread.
+		let mut stream = std::os::unix::net::UnixStream::connect(socket_path)?;
+		let _ = std::fs::remove_file(socket_path);
+
+		let sandbox = futures::executor::block_on(framed_recv(&mut futures::io::AllowStdIo::new(
+			&mut stream,
+		)))?;
+		let sandbox = Option::<SandboxConfig>::decode(&mut &sandbox[..]).map_err(|_| {
+			io::Error::new(
+				io::ErrorKind::Other,
+				"worker: failed to decode the sandbox configuration".to_string(),
+			)
+		})?;
+		if let Some(ref sandbox) = sandbox {
+			crate::sandbox::enter(sandbox, stream.as_raw_fd(), &forbidden_syscall_response)?;
+		}
 
-		event_loop(stream).await
-	})
+		async_std::task::block_on(async move {
+			if let Some(sandbox) = sandbox {
+				gum::debug!(
+					target: LOG_TARGET,
+					worker_pid = %std::process::id(),
+					"pvf worker ({}): sandboxed to {}",
+					debug_id,
+					sandbox.cache_path().display(),
+				);
+			}
+
+			event_loop(UnixStream::from(stream)).await
+		})
+	})()
 	.unwrap_err(); // it's never `Ok` because it's `Ok(Never)`
 
 	gum::debug!(
@@ -21,6 +21,7 @@ use polkadot_parachain::primitives::{
 	BlockData as GenericBlockData, HeadData as GenericHeadData, RelayChainBlockNumber,
 	ValidationParams,
 };
+use std::time::{Duration, Instant};
 
 #[async_std::test]
 async fn execute_good_on_parent() {
@@ -50,6 +51,73 @@ async fn execute_good_on_parent() {
 	assert_eq!(new_head.post_state, hash_state(512));
 }
 
+#[async_std::test]
+async fn execute_reports_phases() {
+	let parent_head = HeadData { number: 0, parent_hash: [0; 32], post_state: hash_state(0) };
+
+	let block_data = BlockData { state: 0, add: 512 };
+
+	let host = TestHost::new();
+
+	let started = Instant::now();
+	let (_, phases) = host
+		.validate_candidate_with_phases(
+			adder::wasm_binary_unwrap(),
+			ValidationParams {
+				parent_head: GenericHeadData(parent_head.encode()),
+				block_data: GenericBlockData(block_data.encode()),
+				relay_parent_number: 1,
+				relay_parent_storage_root: Default::default(),
+			},
+		)
+		.await
+		.unwrap();
+	let elapsed = started.elapsed();
+
+	assert!(phases.artifact_load > Duration::ZERO);
+	assert!(phases.instantiation > Duration::ZERO);
+	assert!(phases.call > Duration::ZERO);
+	// Decoding the few bytes of the result may take less than the resolution of the clock.
+	assert!(phases.result_decoding >= Duration::ZERO);
+	assert!(phases.ipc > Duration::ZERO);
+	// The phases are all part of the request.
+	assert!(phases.iter().map(|(_, duration)| duration).sum::<Duration>() <= elapsed);
+}
+
+#[async_std::test]
+async fn execute_good_on_parent_sandboxed() {
+	let parent_head = HeadData { number: 0, parent_hash: [0; 32], post_state: hash_state(0) };
+
+	let block_data = BlockData { state: 0, add: 512 };
+
+	// The host would fall back to unsandboxed workers.
+	if !crate::sandbox::sandbox_supported() {
+		return
+	}
+
+	let host = TestHost::new_with_config(|cfg| {
+		cfg.enable_sandbox = true;
+	});
+
+	let ret = host
+		.validate_candidate(
+			adder::wasm_binary_unwrap(),
+			ValidationParams {
+				parent_head: GenericHeadData(parent_head.encode()),
+				block_data: GenericBlockData(block_data.encode()),
+				relay_parent_number: 1,
+				relay_parent_storage_root: Default::default(),
+			},
+		)
+		.await
+		.unwrap();
+
+	let new_head = HeadData::decode(&mut &ret.head_data.0[..]).unwrap();
+
+	assert_eq!(new_head.number, 1);
+	assert_eq!(new_head.post_state, hash_state(512));
+}
+
 #[async_std::test]
 async fn execute_good_chain_on_parent() {
 	let mut number = 0;
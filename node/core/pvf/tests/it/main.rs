@@ -17,12 +17,13 @@
 use async_std::sync::Mutex;
 use parity_scale_codec::Encode as _;
 use polkadot_node_core_pvf::{
-	start, Config, InvalidCandidate, Metrics, Pvf, ValidationError, ValidationHost,
+	start, Config, ExecutionPhases, InvalidCandidate, Metrics, Pvf, ValidationError, ValidationHost,
 };
 use polkadot_parachain::primitives::{BlockData, ValidationParams, ValidationResult};
 use std::time::Duration;
 
 mod adder;
+mod sandbox;
 mod worker_common;
 
 const PUPPET_EXE: &str = env!("CARGO_BIN_EXE_puppet_worker");
@@ -56,6 +57,16 @@ impl TestHost {
 		code: &[u8],
 		params: ValidationParams,
 	) -> Result<ValidationResult, ValidationError> {
+		self.validate_candidate_with_phases(code, params)
+			.await
+			.map(|(result, _)| result)
+	}
+
+	async fn validate_candidate_with_phases(
+		&self,
+		code: &[u8],
+		params: ValidationParams,
+	) -> Result<(ValidationResult, ExecutionPhases), ValidationError> {
 		let (result_tx, result_rx) = futures::channel::oneshot::channel();
 
 		let code = sp_maybe_compressed_blob::decompress(code, 16 * 1024 * 1024)
@@ -65,7 +76,7 @@ impl TestHost {
 			.lock()
 			.await
 			.execute_pvf(
-				Pvf::from_code(code.into()),
+				Pvf::from_code(code.into(), Default::default()),
 				TEST_EXECUTION_TIMEOUT,
 				params.encode(),
 				polkadot_node_core_pvf::Priority::Normal,
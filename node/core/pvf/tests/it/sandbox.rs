@@ -0,0 +1,81 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::PUPPET_EXE;
+use async_std::path::Path;
+use polkadot_node_core_pvf::{
+	testing::sandbox::{check_support, prepare_in_sandbox, SandboxConfig, SandboxSupport},
+	PrepareError, ValidationError,
+};
+use std::time::Duration;
+
+const SPAWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Returns `false`, noting that the test is skipped, if the system doesn't support sandboxing.
+pub fn sandbox_supported() -> bool {
+	match check_support() {
+		SandboxSupport::Available { .. } => true,
+		SandboxSupport::Unavailable(reason) => {
+			eprintln!("skipping the test, sandboxing is not supported: {}", reason);
+			false
+		},
+	}
+}
+
+#[async_std::test]
+async fn sandboxed_worker_prepares() {
+	if !sandbox_supported() {
+		return
+	}
+
+	let cache_dir = tempfile::tempdir().unwrap();
+	let cache_path = Path::new(cache_dir.path());
+	let result = prepare_in_sandbox(
+		Path::new(PUPPET_EXE),
+		&["prepare-worker"],
+		SPAWN_TIMEOUT,
+		cache_path,
+		SandboxConfig::new(cache_dir.path()),
+	)
+	.await;
+
+	// The worker got through the job inside of the sandbox and rejected the empty PVF.
+	assert!(matches!(result, Ok(Err(PrepareError::Prevalidation(_)))), "{:?}", result);
+}
+
+#[async_std::test]
+async fn sandboxed_worker_making_forbidden_syscall_is_reported() {
+	if !sandbox_supported() {
+		return
+	}
+
+	let cache_dir = tempfile::tempdir().unwrap();
+	let cache_path = Path::new(cache_dir.path());
+	let result = prepare_in_sandbox(
+		Path::new(PUPPET_EXE),
+		&["prepare-worker-forbidden-syscall"],
+		SPAWN_TIMEOUT,
+		cache_path,
+		SandboxConfig::new(cache_dir.path()),
+	)
+	.await;
+
+	assert!(matches!(result, Ok(Err(PrepareError::ForbiddenSyscall))), "{:?}", result);
+	assert!(matches!(
+		ValidationError::from(PrepareError::ForbiddenSyscall),
+		ValidationError::InternalError(_)
+	));
+}
@@ -20,12 +20,15 @@ use memory_lru::{MemoryLruCache, ResidentSize};
 use parity_util_mem::{MallocSizeOf, MallocSizeOfExt};
 use sp_consensus_babe::Epoch;
 
-use polkadot_primitives::v2::{
-	AuthorityDiscoveryId, BlockNumber, CandidateCommitments, CandidateEvent, CandidateHash,
-	CommittedCandidateReceipt, CoreState, DisputeState, GroupRotationInfo, Hash, Id as ParaId,
-	InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption, PersistedValidationData,
-	PvfCheckStatement, ScrapedOnChainVotes, SessionIndex, SessionInfo, ValidationCode,
-	ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
+use polkadot_primitives::{
+	v2::{
+		AuthorityDiscoveryId, BlockNumber, CandidateCommitments, CandidateEvent, CandidateHash,
+		CommittedCandidateReceipt, CoreState, DisputeState, GroupRotationInfo, Hash, Id as ParaId,
+		InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption,
+		PersistedValidationData, PvfCheckStatement, ScrapedOnChainVotes, SessionIndex, SessionInfo,
+		ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
+	},
+	vstaging::{slashing, ExecutorParams},
 };
 
 const AUTHORITIES_CACHE_SIZE: usize = 128 * 1024;
@@ -48,6 +51,11 @@ const PVFS_REQUIRE_PRECHECK_SIZE: usize = 1024;
 const VALIDATION_CODE_HASH_CACHE_SIZE: usize = 64 * 1024;
 const VERSION_CACHE_SIZE: usize = 4 * 1024;
 const DISPUTES_CACHE_SIZE: usize = 64 * 1024;
+const EXECUTOR_PARAMS_CACHE_SIZE: usize = 64 * 1024;
+const FUTURE_VALIDATION_CODE_HASH_CACHE_SIZE: usize = 64 * 1024;
+const UNAPPLIED_SLASHES_CACHE_SIZE: usize = 64 * 1024;
+const KEY_OWNERSHIP_PROOF_CACHE_SIZE: usize = 64 * 1024;
+const DISABLED_VALIDATORS_CACHE_SIZE: usize = 64 * 1024;
 
 struct ResidentSizeOf<T>(T);
 
@@ -120,6 +128,18 @@ pub(crate) struct RequestResultCache {
 		Hash,
 		ResidentSizeOf<Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>>,
 	>,
+	session_executor_params: MemoryLruCache<SessionIndex, ResidentSizeOf<ExecutorParams>>,
+	future_validation_code_hash:
+		MemoryLruCache<(Hash, ParaId), ResidentSizeOf<Option<ValidationCodeHash>>>,
+	unapplied_slashes: MemoryLruCache<
+		Hash,
+		ResidentSizeOf<Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>>,
+	>,
+	key_ownership_proof: MemoryLruCache<
+		(Hash, ValidatorId),
+		ResidentSizeOf<Option<slashing::OpaqueKeyOwnershipProof>>,
+	>,
+	disabled_validators: MemoryLruCache<Hash, ResidentSizeOf<Vec<ValidatorIndex>>>,
 }
 
 impl Default for RequestResultCache {
@@ -148,6 +168,13 @@ impl Default for RequestResultCache {
 			validation_code_hash: MemoryLruCache::new(VALIDATION_CODE_HASH_CACHE_SIZE),
 			version: MemoryLruCache::new(VERSION_CACHE_SIZE),
 			disputes: MemoryLruCache::new(DISPUTES_CACHE_SIZE),
+			session_executor_params: MemoryLruCache::new(EXECUTOR_PARAMS_CACHE_SIZE),
+			future_validation_code_hash: MemoryLruCache::new(
+				FUTURE_VALIDATION_CODE_HASH_CACHE_SIZE,
+			),
+			unapplied_slashes: MemoryLruCache::new(UNAPPLIED_SLASHES_CACHE_SIZE),
+			key_ownership_proof: MemoryLruCache::new(KEY_OWNERSHIP_PROOF_CACHE_SIZE),
+			disabled_validators: MemoryLruCache::new(DISABLED_VALIDATORS_CACHE_SIZE),
 		}
 	}
 }
@@ -428,6 +455,81 @@ impl RequestResultCache {
 	) {
 		self.disputes.insert(relay_parent, ResidentSizeOf(value));
 	}
+
+	pub(crate) fn session_executor_params(
+		&mut self,
+		session_index: SessionIndex,
+	) -> Option<&ExecutorParams> {
+		self.session_executor_params.get(&session_index).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_session_executor_params(
+		&mut self,
+		session_index: SessionIndex,
+		value: ExecutorParams,
+	) {
+		self.session_executor_params.insert(session_index, ResidentSizeOf(value));
+	}
+
+	pub(crate) fn future_validation_code_hash(
+		&mut self,
+		key: (Hash, ParaId),
+	) -> Option<&Option<ValidationCodeHash>> {
+		self.future_validation_code_hash.get(&key).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_future_validation_code_hash(
+		&mut self,
+		key: (Hash, ParaId),
+		value: Option<ValidationCodeHash>,
+	) {
+		self.future_validation_code_hash.insert(key, ResidentSizeOf(value));
+	}
+
+	pub(crate) fn unapplied_slashes(
+		&mut self,
+		relay_parent: &Hash,
+	) -> Option<&Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>> {
+		self.unapplied_slashes.get(relay_parent).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_unapplied_slashes(
+		&mut self,
+		relay_parent: Hash,
+		value: Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>,
+	) {
+		self.unapplied_slashes.insert(relay_parent, ResidentSizeOf(value));
+	}
+
+	pub(crate) fn key_ownership_proof(
+		&mut self,
+		key: (Hash, ValidatorId),
+	) -> Option<&Option<slashing::OpaqueKeyOwnershipProof>> {
+		self.key_ownership_proof.get(&key).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_key_ownership_proof(
+		&mut self,
+		key: (Hash, ValidatorId),
+		value: Option<slashing::OpaqueKeyOwnershipProof>,
+	) {
+		self.key_ownership_proof.insert(key, ResidentSizeOf(value));
+	}
+
+	pub(crate) fn disabled_validators(
+		&mut self,
+		relay_parent: &Hash,
+	) -> Option<&Vec<ValidatorIndex>> {
+		self.disabled_validators.get(relay_parent).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_disabled_validators(
+		&mut self,
+		relay_parent: Hash,
+		value: Vec<ValidatorIndex>,
+	) {
+		self.disabled_validators.insert(relay_parent, ResidentSizeOf(value));
+	}
 }
 
 pub(crate) enum RequestResult {
@@ -464,4 +566,16 @@ pub(crate) enum RequestResult {
 	ValidationCodeHash(Hash, ParaId, OccupiedCoreAssumption, Option<ValidationCodeHash>),
 	Version(Hash, u32),
 	Disputes(Hash, Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>),
+	SessionExecutorParams(Hash, SessionIndex, Option<ExecutorParams>),
+	FutureValidationCodeHash(Hash, ParaId, Option<ValidationCodeHash>),
+	UnappliedSlashes(Hash, Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>),
+	KeyOwnershipProof(Hash, ValidatorId, Option<slashing::OpaqueKeyOwnershipProof>),
+	// This is a request with side-effects.
+	SubmitReportDisputeLost(
+		Hash,
+		slashing::DisputeProof,
+		slashing::OpaqueKeyOwnershipProof,
+		Option<()>,
+	),
+	DisabledValidators(Hash, Vec<ValidatorIndex>),
 }
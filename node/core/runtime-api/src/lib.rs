@@ -155,6 +155,22 @@ where
 				self.requests_cache.cache_version(relay_parent, version),
 			Disputes(relay_parent, disputes) =>
 				self.requests_cache.cache_disputes(relay_parent, disputes),
+			SessionExecutorParams(_relay_parent, session_index, executor_params) =>
+				if let Some(executor_params) = executor_params {
+					self.requests_cache
+						.cache_session_executor_params(session_index, executor_params);
+				},
+			FutureValidationCodeHash(relay_parent, para_id, hash) => self
+				.requests_cache
+				.cache_future_validation_code_hash((relay_parent, para_id), hash),
+			UnappliedSlashes(relay_parent, slashes) =>
+				self.requests_cache.cache_unapplied_slashes(relay_parent, slashes),
+			KeyOwnershipProof(relay_parent, validator_id, proof) => self
+				.requests_cache
+				.cache_key_ownership_proof((relay_parent, validator_id), proof),
+			SubmitReportDisputeLost(_, _, _, _) => {},
+			DisabledValidators(relay_parent, disabled) =>
+				self.requests_cache.cache_disabled_validators(relay_parent, disabled),
 		}
 	}
 
@@ -258,6 +274,31 @@ where
 					.map(|sender| Request::ValidationCodeHash(para, assumption, sender)),
 			Request::Disputes(sender) =>
 				query!(disputes(), sender).map(|sender| Request::Disputes(sender)),
+			Request::SessionExecutorParams(session_index, sender) => {
+				if let Some(executor_params) =
+					self.requests_cache.session_executor_params(session_index)
+				{
+					self.metrics.on_cached_request();
+					let _ = sender.send(Ok(Some(executor_params.clone())));
+					None
+				} else {
+					Some(Request::SessionExecutorParams(session_index, sender))
+				}
+			},
+			Request::FutureValidationCodeHash(para, sender) =>
+				query!(future_validation_code_hash(para), sender)
+					.map(|sender| Request::FutureValidationCodeHash(para, sender)),
+			Request::UnappliedSlashes(sender) =>
+				query!(unapplied_slashes(), sender).map(|sender| Request::UnappliedSlashes(sender)),
+			Request::KeyOwnershipProof(validator_id, sender) =>
+				query!(key_ownership_proof(validator_id), sender)
+					.map(|sender| Request::KeyOwnershipProof(validator_id, sender)),
+			request @ Request::SubmitReportDisputeLost(_, _, _) => {
+				// This request is side-effecting and thus cannot be cached.
+				Some(request)
+			},
+			Request::DisabledValidators(sender) => query!(disabled_validators(), sender)
+				.map(|sender| Request::DisabledValidators(sender)),
 		}
 	}
 
@@ -502,5 +543,41 @@ where
 			query!(ValidationCodeHash, validation_code_hash(para, assumption), ver = 2, sender),
 		Request::Disputes(sender) =>
 			query!(Disputes, disputes(), ver = Request::DISPUTES_RUNTIME_REQUIREMENT, sender),
+		Request::SessionExecutorParams(session_index, sender) => query!(
+			SessionExecutorParams,
+			session_executor_params(session_index),
+			ver = Request::EXECUTOR_PARAMS_RUNTIME_REQUIREMENT,
+			sender
+		),
+		Request::FutureValidationCodeHash(para, sender) => query!(
+			FutureValidationCodeHash,
+			future_validation_code_hash(para),
+			ver = Request::FUTURE_VALIDATION_CODE_HASH_RUNTIME_REQUIREMENT,
+			sender
+		),
+		Request::UnappliedSlashes(sender) => query!(
+			UnappliedSlashes,
+			unapplied_slashes(),
+			ver = Request::SLASHING_RUNTIME_REQUIREMENT,
+			sender
+		),
+		Request::KeyOwnershipProof(validator_id, sender) => query!(
+			KeyOwnershipProof,
+			key_ownership_proof(validator_id),
+			ver = Request::SLASHING_RUNTIME_REQUIREMENT,
+			sender
+		),
+		Request::SubmitReportDisputeLost(dispute_proof, key_ownership_proof, sender) => query!(
+			SubmitReportDisputeLost,
+			submit_report_dispute_lost(dispute_proof, key_ownership_proof),
+			ver = Request::SLASHING_RUNTIME_REQUIREMENT,
+			sender
+		),
+		Request::DisabledValidators(sender) => query!(
+			DisabledValidators,
+			disabled_validators(),
+			ver = Request::DISABLED_VALIDATORS_RUNTIME_REQUIREMENT,
+			sender
+		),
 	}
 }
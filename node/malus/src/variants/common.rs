@@ -223,7 +223,9 @@ where
 						validation_code,
 						candidate_receipt,
 						pov,
+						session_index,
 						timeout,
+						span,
 						sender,
 					),
 			} => {
@@ -238,7 +240,9 @@ where
 									validation_code,
 									candidate_receipt,
 									pov,
+									session_index,
 									timeout,
+									span,
 									sender,
 								),
 							})
@@ -275,7 +279,9 @@ where
 										validation_code,
 										candidate_receipt,
 										pov,
+										session_index,
 										timeout,
+										span,
 										sender,
 									),
 								})
@@ -313,7 +319,9 @@ where
 										validation_code,
 										candidate_receipt,
 										pov,
+										session_index,
 										timeout,
+										span,
 										sender,
 									),
 								})
@@ -327,7 +335,9 @@ where
 							validation_code,
 							candidate_receipt,
 							pov,
+							session_index,
 							timeout,
+							span,
 							sender,
 						),
 					}),
@@ -340,6 +350,7 @@ where
 						candidate_receipt,
 						pov,
 						timeout,
+						span,
 						response_sender,
 					),
 			} => {
@@ -353,6 +364,7 @@ where
 									candidate_receipt,
 									pov,
 									timeout,
+									span,
 									response_sender,
 								),
 							})
@@ -381,6 +393,7 @@ where
 									candidate_receipt,
 									pov,
 									timeout,
+									span,
 									response_sender,
 								),
 							}),
@@ -414,6 +427,7 @@ where
 										candidate_receipt,
 										pov,
 										timeout,
+										span,
 										response_sender,
 									),
 								})
@@ -425,6 +439,7 @@ where
 							candidate_receipt,
 							pov,
 							timeout,
+							span,
 							response_sender,
 						),
 					}),
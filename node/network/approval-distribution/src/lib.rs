@@ -257,6 +257,9 @@ struct BlockEntry {
 	candidates: Vec<CandidateEntry>,
 	/// The session index of this block.
 	session: SessionIndex,
+	/// Validators disabled for having lost a dispute. Their messages are still imported and
+	/// circulated along the grid, so finality doesn't suffer, but don't get routed randomly.
+	disabled_validators: HashSet<ValidatorIndex>,
 }
 
 #[derive(Debug)]
@@ -402,6 +405,7 @@ impl State {
 						knowledge: Knowledge::default(),
 						candidates,
 						session: meta.session,
+						disabled_validators: meta.disabled_validators.iter().copied().collect(),
 					});
 
 					self.topologies.inc_session_refs(meta.session);
@@ -880,10 +884,15 @@ impl State {
 			Some(candidate_entry) => {
 				// set the approval state for validator_index to Assigned
 				// unless the approval state is set already
+				let random_routing = if entry.disabled_validators.contains(&validator_index) {
+					RandomRouting::none()
+				} else {
+					Default::default()
+				};
 				candidate_entry.messages.entry(validator_index).or_insert_with(|| MessageState {
 					required_routing,
 					local,
-					random_routing: Default::default(),
+					random_routing,
 					approval_state: ApprovalState::Assigned(assignment.cert.clone()),
 				})
 			},
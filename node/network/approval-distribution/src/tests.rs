@@ -316,6 +316,7 @@ fn try_import_the_same_assignment() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
 		overseer_send(overseer, msg).await;
@@ -399,6 +400,7 @@ fn spam_attack_results_in_negative_reputation_change() {
 			candidates: vec![Default::default(); candidates_count],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
@@ -482,6 +484,7 @@ fn peer_sending_us_the_same_we_just_sent_them_is_ok() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
 		overseer_send(overseer, msg).await;
@@ -560,6 +563,7 @@ fn import_approval_happy_path() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
 		overseer_send(overseer, msg).await;
@@ -647,6 +651,7 @@ fn import_approval_bad() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
 		overseer_send(overseer, msg).await;
@@ -725,6 +730,7 @@ fn update_our_view() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 		let meta_b = BlockApprovalMeta {
 			hash: hash_b,
@@ -733,6 +739,7 @@ fn update_our_view() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 		let meta_c = BlockApprovalMeta {
 			hash: hash_c,
@@ -741,6 +748,7 @@ fn update_our_view() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta_a, meta_b, meta_c]);
@@ -801,6 +809,7 @@ fn update_peer_view() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 		let meta_b = BlockApprovalMeta {
 			hash: hash_b,
@@ -809,6 +818,7 @@ fn update_peer_view() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 		let meta_c = BlockApprovalMeta {
 			hash: hash_c,
@@ -817,6 +827,7 @@ fn update_peer_view() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta_a, meta_b, meta_c]);
@@ -955,6 +966,7 @@ fn import_remotely_then_locally() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
 		overseer_send(overseer, msg).await;
@@ -1040,6 +1052,7 @@ fn sends_assignments_even_when_state_is_approved() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
 		overseer_send(overseer, msg).await;
@@ -1127,6 +1140,7 @@ fn race_condition_in_local_vs_remote_view_update() {
 			candidates: vec![Default::default(); candidates_count],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 
 		// This will send a peer view that is ahead of our view
@@ -1217,6 +1231,7 @@ fn propagates_locally_generated_assignment_to_both_dimensions() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
@@ -1317,6 +1332,7 @@ fn propagates_assignments_along_unshared_dimension() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
@@ -1465,6 +1481,7 @@ fn propagates_to_required_after_connect() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
@@ -1590,6 +1607,7 @@ fn sends_to_more_peers_after_getting_topology() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
@@ -1714,6 +1732,116 @@ fn sends_to_more_peers_after_getting_topology() {
 	});
 }
 
+#[test]
+fn messages_of_disabled_validators_are_not_sent_to_random_peers() {
+	let parent_hash = Hash::repeat_byte(0xFF);
+	let hash = Hash::repeat_byte(0xAA);
+
+	let peers = make_peers_and_authority_ids(100);
+
+	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+
+		for (peer, _) in &peers {
+			setup_peer_with_view(overseer, peer, view![hash]).await;
+		}
+
+		let validator_index = ValidatorIndex(0);
+		let candidate_index = 0u32;
+
+		// new block `hash_a` with 1 candidates, in which our validator is disabled
+		let meta = BlockApprovalMeta {
+			hash,
+			parent_hash,
+			number: 1,
+			candidates: vec![Default::default(); 1],
+			slot: 1.into(),
+			session: 1,
+			disabled_validators: vec![validator_index],
+		};
+
+		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
+		overseer_send(overseer, msg).await;
+
+		// import an assignment and approval locally.
+		let cert = fake_assignment_cert(hash, validator_index);
+		let approval = IndirectSignedApprovalVote {
+			block_hash: hash,
+			candidate_index,
+			validator: validator_index,
+			signature: dummy_signature(),
+		};
+
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+		)
+		.await;
+
+		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.clone()))
+			.await;
+
+		// Nothing gets sent to random peers before the topology is known.
+		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
+
+		setup_gossip_topology(
+			overseer,
+			make_gossip_topology(1, &peers, &[0, 10, 20, 30], &[50, 51, 52, 53]),
+		)
+		.await;
+
+		let assignments = vec![(cert.clone(), candidate_index)];
+		let approvals = vec![approval.clone()];
+
+		let mut expected_indices_assignments = vec![0, 10, 20, 30, 50, 51, 52, 53];
+		let mut expected_indices_approvals = expected_indices_assignments.clone();
+
+		for _ in 0..expected_indices_assignments.len() {
+			assert_matches!(
+				overseer_recv(overseer).await,
+				AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+					sent_peers,
+					Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+						protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
+					))
+				)) => {
+					// Sends to the grid neighbors only.
+					assert_eq!(sent_peers.len(), 1);
+					assert_eq!(sent_assignments, assignments);
+
+					let pos = expected_indices_assignments.iter()
+						.position(|i| &peers[*i].0 == &sent_peers[0])
+						.unwrap();
+					expected_indices_assignments.remove(pos);
+				}
+			);
+		}
+
+		for _ in 0..expected_indices_approvals.len() {
+			assert_matches!(
+				overseer_recv(overseer).await,
+				AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+					sent_peers,
+					Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+						protocol_v1::ApprovalDistributionMessage::Approvals(sent_approvals)
+					))
+				)) => {
+					assert_eq!(sent_peers.len(), 1);
+					assert_eq!(sent_approvals, approvals);
+
+					let pos = expected_indices_approvals.iter()
+						.position(|i| &peers[*i].0 == &sent_peers[0])
+						.unwrap();
+					expected_indices_approvals.remove(pos);
+				}
+			);
+		}
+
+		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
+		virtual_overseer
+	});
+}
+
 // test aggression L1
 #[test]
 fn originator_aggression_l1() {
@@ -1742,6 +1870,7 @@ fn originator_aggression_l1() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
@@ -1815,6 +1944,7 @@ fn originator_aggression_l1() {
 					candidates: vec![],
 					slot: (level as u64).into(),
 					session: 1,
+					disabled_validators: Vec::new(),
 				};
 
 				let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
@@ -1900,6 +2030,7 @@ fn non_originator_aggression_l1() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
@@ -1960,6 +2091,7 @@ fn non_originator_aggression_l1() {
 					candidates: vec![],
 					slot: (level as u64).into(),
 					session: 1,
+					disabled_validators: Vec::new(),
 				};
 
 				let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
@@ -2005,6 +2137,7 @@ fn non_originator_aggression_l2() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
@@ -2069,6 +2202,7 @@ fn non_originator_aggression_l2() {
 					candidates: vec![],
 					slot: (level as u64).into(),
 					session: 1,
+					disabled_validators: Vec::new(),
 				};
 
 				let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
@@ -2095,6 +2229,7 @@ fn non_originator_aggression_l2() {
 					candidates: vec![],
 					slot: (level as u64).into(),
 					session: 1,
+					disabled_validators: Vec::new(),
 				};
 
 				let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
@@ -2171,6 +2306,7 @@ fn resends_messages_periodically() {
 			candidates: vec![Default::default(); 1],
 			slot: 1.into(),
 			session: 1,
+			disabled_validators: Vec::new(),
 		};
 
 		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
@@ -2239,6 +2375,7 @@ fn resends_messages_periodically() {
 						candidates: vec![],
 						slot: (level as u64).into(),
 						session: 1,
+						disabled_validators: Vec::new(),
 					};
 
 					let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
@@ -18,7 +18,7 @@ use futures::{future::Either, FutureExt, StreamExt, TryFutureExt};
 
 use sp_keystore::SyncCryptoStorePtr;
 
-use polkadot_node_network_protocol::request_response::{v1, IncomingRequestReceiver};
+use polkadot_node_network_protocol::request_response::{v1, v2, IncomingRequestReceiver};
 use polkadot_node_subsystem::{
 	messages::AvailabilityDistributionMessage, overseer, FromOrchestra, OverseerSignal,
 	SpawnedSubsystem, SubsystemError,
@@ -39,7 +39,7 @@ mod pov_requester;
 
 /// Responding to erasure chunk requests:
 mod responder;
-use responder::{run_chunk_receiver, run_pov_receiver};
+use responder::{run_chunk_receiver, run_chunk_receiver_v2, run_pov_receiver};
 
 mod metrics;
 /// Prometheus `Metrics` for availability distribution.
@@ -66,6 +66,8 @@ pub struct IncomingRequestReceivers {
 	pub pov_req_receiver: IncomingRequestReceiver<v1::PoVFetchingRequest>,
 	/// Receiver for incoming availability chunk requests.
 	pub chunk_req_receiver: IncomingRequestReceiver<v1::ChunkFetchingRequest>,
+	/// Receiver for incoming availability chunk requests of the second protocol version.
+	pub chunk_req_v2_receiver: IncomingRequestReceiver<v2::ChunkFetchingRequest>,
 }
 
 #[overseer::subsystem(AvailabilityDistribution, error=SubsystemError, prefix=self::overseer)]
@@ -96,7 +98,11 @@ impl AvailabilityDistributionSubsystem {
 	async fn run<Context>(self, mut ctx: Context) -> std::result::Result<(), FatalError> {
 		let Self { mut runtime, recvs, metrics } = self;
 
-		let IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver } = recvs;
+		let IncomingRequestReceivers {
+			pov_req_receiver,
+			chunk_req_receiver,
+			chunk_req_v2_receiver,
+		} = recvs;
 		let mut requester = Requester::new(metrics.clone()).fuse();
 
 		{
@@ -109,7 +115,13 @@ impl AvailabilityDistributionSubsystem {
 
 			ctx.spawn(
 				"chunk-receiver",
-				run_chunk_receiver(sender, chunk_req_receiver, metrics.clone()).boxed(),
+				run_chunk_receiver(sender.clone(), chunk_req_receiver, metrics.clone()).boxed(),
+			)
+			.map_err(FatalError::SpawnTask)?;
+
+			ctx.spawn(
+				"chunk-receiver-v2",
+				run_chunk_receiver_v2(sender, chunk_req_v2_receiver, metrics.clone()).boxed(),
 			)
 			.map_err(FatalError::SpawnTask)?;
 		}
@@ -172,6 +184,33 @@ impl AvailabilityDistributionSubsystem {
 						"pov_requester::fetch_pov",
 					)?;
 				},
+				FromOrchestra::Communication {
+					msg:
+						AvailabilityDistributionMessage::FetchChunk {
+							relay_parent,
+							session_index,
+							group_index,
+							candidate_hash,
+							erasure_root,
+							index,
+						},
+				} => {
+					log_error(
+						requester
+							.get_mut()
+							.fetch_chunk(
+								&mut ctx,
+								&mut runtime,
+								relay_parent,
+								session_index,
+								group_index,
+								v1::ChunkFetchingRequest { candidate_hash, index },
+								erasure_root,
+							)
+							.await,
+						"Requester::fetch_chunk",
+					)?;
+				},
 			}
 		}
 	}
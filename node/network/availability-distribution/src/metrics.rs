@@ -31,6 +31,12 @@ pub const FAILED: &'static str = "failed";
 /// Label for chunks/PoVs that could not be served, because they were not available.
 pub const NOT_FOUND: &'static str = "not-found";
 
+/// Label for the size of chunks before compression.
+pub const RAW: &'static str = "raw";
+
+/// Label for the size of chunks as sent over the wire, after compression if any.
+pub const WIRE: &'static str = "wire";
+
 /// Availability Distribution metrics.
 #[derive(Clone, Default)]
 pub struct Metrics(Option<MetricsInner>);
@@ -47,6 +53,12 @@ struct MetricsInner {
 	/// Number of chunks served.
 	served_chunks: CounterVec<U64>,
 
+	/// Bytes of chunks fetched, before decompression and on the wire.
+	fetched_chunk_bytes: CounterVec<U64>,
+
+	/// Bytes of chunks served, before compression and on the wire.
+	served_chunk_bytes: CounterVec<U64>,
+
 	/// Number of received fetch PoV responses.
 	fetched_povs: CounterVec<U64>,
 
@@ -78,6 +90,20 @@ impl Metrics {
 		}
 	}
 
+	/// Add to the bytes of fetched chunks, either `RAW` or `WIRE`.
+	pub fn on_fetched_chunk_bytes(&self, label: &'static str, bytes: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.fetched_chunk_bytes.with_label_values(&[label]).inc_by(bytes as u64)
+		}
+	}
+
+	/// Add to the bytes of served chunks, either `RAW` or `WIRE`.
+	pub fn on_served_chunk_bytes(&self, label: &'static str, bytes: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.served_chunk_bytes.with_label_values(&[label]).inc_by(bytes as u64)
+		}
+	}
+
 	/// Increment counter on fetched PoVs.
 	pub fn on_fetched_pov(&self, label: &'static str) {
 		if let Some(metrics) = &self.0 {
@@ -123,6 +149,26 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			fetched_chunk_bytes: prometheus::register(
+				CounterVec::new(
+					Opts::new(
+						"polkadot_parachain_fetched_chunk_bytes_total",
+						"Total bytes of fetched chunks, uncompressed (raw) and as received (wire).",
+					),
+					&["kind"]
+				)?,
+				registry,
+			)?,
+			served_chunk_bytes: prometheus::register(
+				CounterVec::new(
+					Opts::new(
+						"polkadot_parachain_served_chunk_bytes_total",
+						"Total bytes of served chunks, uncompressed (raw) and as sent (wire).",
+					),
+					&["kind"]
+				)?,
+				registry,
+			)?,
 			fetched_povs: prometheus::register(
 				CounterVec::new(
 					Opts::new(
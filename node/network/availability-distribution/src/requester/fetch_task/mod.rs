@@ -25,7 +25,8 @@ use futures::{
 use polkadot_erasure_coding::branch_hash;
 use polkadot_node_network_protocol::request_response::{
 	outgoing::{OutgoingRequest, Recipient, RequestError, Requests},
-	v1::{ChunkFetchingRequest, ChunkFetchingResponse},
+	v1::ChunkFetchingRequest,
+	v2::{self, ChunkFetchingResponse},
 };
 use polkadot_node_primitives::ErasureChunk;
 use polkadot_node_subsystem::{
@@ -40,7 +41,7 @@ use polkadot_primitives::v2::{
 
 use crate::{
 	error::{FatalError, Result},
-	metrics::{Metrics, FAILED, SUCCEEDED},
+	metrics::{Metrics, FAILED, RAW, SUCCEEDED, WIRE},
 	requester::session_cache::{BadValidators, SessionInfo},
 	LOG_TARGET,
 };
@@ -169,6 +170,43 @@ impl FetchTaskConfig {
 		};
 		FetchTaskConfig { live_in, prepared_running: Some(prepared_running) }
 	}
+
+	/// Create a configuration for a [`FetchTask`] fetching any chunk of an already backed
+	/// candidate from its backing group, regardless of the candidate still pending availability.
+	///
+	/// The resulting task is not live in any leaf.
+	pub fn refetch(
+		relay_parent: Hash,
+		group_index: GroupIndex,
+		request: ChunkFetchingRequest,
+		erasure_root: Hash,
+		sender: mpsc::Sender<FromFetchTask>,
+		metrics: Metrics,
+		session_info: &SessionInfo,
+	) -> Self {
+		let live_in = HashSet::new();
+
+		let group = match session_info.validator_groups.get(group_index.0 as usize) {
+			Some(group) => group.clone(),
+			None => return FetchTaskConfig { live_in, prepared_running: None },
+		};
+
+		let span = jaeger::Span::new(request.candidate_hash, "availability-distribution")
+			.with_stage(jaeger::Stage::AvailabilityDistribution);
+
+		let prepared_running = RunningTask {
+			session_index: session_info.session_index,
+			group_index,
+			group,
+			request,
+			erasure_root,
+			relay_parent,
+			metrics,
+			sender,
+			span,
+		};
+		FetchTaskConfig { live_in, prepared_running: Some(prepared_running) }
+	}
 }
 
 #[overseer::contextbounds(AvailabilityDistribution, prefix = self::overseer)]
@@ -266,24 +304,9 @@ impl RunningTask {
 			count += 1;
 
 			// Send request:
-			let resp = match self.do_request(&validator).await {
-				Ok(resp) => resp,
-				Err(TaskError::ShuttingDown) => {
-					gum::info!(
-						target: LOG_TARGET,
-						"Node seems to be shutting down, canceling fetch task"
-					);
-					self.metrics.on_fetch(FAILED);
-					return
-				},
-				Err(TaskError::PeerError) => {
-					bad_validators.push(validator);
-					continue
-				},
-			};
-			let chunk = match resp {
-				ChunkFetchingResponse::Chunk(resp) => resp.recombine_into_chunk(&self.request),
-				ChunkFetchingResponse::NoSuchChunk => {
+			let chunk = match self.do_request(&validator).await {
+				Ok(Some(chunk)) => chunk,
+				Ok(None) => {
 					gum::debug!(
 						target: LOG_TARGET,
 						validator = ?validator,
@@ -297,6 +320,18 @@ impl RunningTask {
 					bad_validators.push(validator);
 					continue
 				},
+				Err(TaskError::ShuttingDown) => {
+					gum::info!(
+						target: LOG_TARGET,
+						"Node seems to be shutting down, canceling fetch task"
+					);
+					self.metrics.on_fetch(FAILED);
+					return
+				},
+				Err(TaskError::PeerError) => {
+					bad_validators.push(validator);
+					continue
+				},
 			};
 
 			// Data genuine?
@@ -321,28 +356,41 @@ impl RunningTask {
 		}
 	}
 
-	/// Do request and return response, if successful.
+	/// Do request and return the chunk, if the validator had it.
+	///
+	/// The chunk is requested via `ChunkFetchingV2`, falling back to `ChunkFetchingV1` for
+	/// validators which don't support it yet.
 	async fn do_request(
 		&mut self,
 		validator: &AuthorityDiscoveryId,
-	) -> std::result::Result<ChunkFetchingResponse, TaskError> {
+	) -> std::result::Result<Option<ErasureChunk>, TaskError> {
+		let request = v2::ChunkFetchingRequest::from(self.request);
 		let (full_request, response_recv) =
-			OutgoingRequest::new(Recipient::Authority(validator.clone()), self.request);
-		let requests = Requests::ChunkFetchingV1(full_request);
-
-		self.sender
-			.send(FromFetchTask::Message(
-				NetworkBridgeTxMessage::SendRequests(
-					vec![requests],
-					IfDisconnected::ImmediateError,
-				)
-				.into(),
-			))
-			.await
-			.map_err(|_| TaskError::ShuttingDown)?;
+			OutgoingRequest::new(Recipient::Authority(validator.clone()), request.clone());
+		self.send_request(Requests::ChunkFetchingV2(full_request)).await?;
+
+		let response = match response_recv.await {
+			Err(err) if err.is_unsupported_protocol() => {
+				let (full_request, response_recv) =
+					OutgoingRequest::new(Recipient::Authority(validator.clone()), self.request);
+				self.send_request(Requests::ChunkFetchingV1(full_request)).await?;
+				response_recv.await.map(ChunkFetchingResponse::from)
+			},
+			response => response,
+		};
 
-		match response_recv.await {
-			Ok(resp) => Ok(resp),
+		let chunk = response.and_then(|response| {
+			let wire_size = response.chunk_size();
+			let chunk =
+				response.recombine_into_chunk(&request).map_err(RequestError::InvalidResponse)?;
+			if let (Some(wire_size), Some(chunk)) = (wire_size, &chunk) {
+				self.metrics.on_fetched_chunk_bytes(WIRE, wire_size);
+				self.metrics.on_fetched_chunk_bytes(RAW, chunk.chunk.len());
+			}
+			Ok(chunk)
+		});
+		match chunk {
+			Ok(chunk) => Ok(chunk),
 			Err(RequestError::InvalidResponse(err)) => {
 				gum::warn!(
 					target: LOG_TARGET,
@@ -387,6 +435,17 @@ impl RunningTask {
 		}
 	}
 
+	/// Send a request to the network bridge.
+	async fn send_request(&mut self, request: Requests) -> std::result::Result<(), TaskError> {
+		self.sender
+			.send(FromFetchTask::Message(
+				NetworkBridgeTxMessage::SendRequests(vec![request], IfDisconnected::ImmediateError)
+					.into(),
+			))
+			.await
+			.map_err(|_| TaskError::ShuttingDown)
+	}
+
 	fn validate_chunk(&self, validator: &AuthorityDiscoveryId, chunk: &ErasureChunk) -> bool {
 		let anticipated_hash =
 			match branch_hash(&self.erasure_root, chunk.proof(), chunk.index.0 as usize) {
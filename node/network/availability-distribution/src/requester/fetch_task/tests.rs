@@ -67,6 +67,7 @@ fn task_does_not_accept_invalid_chunk() {
 			m
 		},
 		valid_chunks: HashSet::new(),
+		v1_only: HashSet::new(),
 	};
 	test.run(task, rx);
 }
@@ -99,6 +100,7 @@ fn task_stores_valid_chunk() {
 			s.insert(chunk.chunk);
 			s
 		},
+		v1_only: HashSet::new(),
 	};
 	test.run(task, rx);
 }
@@ -127,6 +129,7 @@ fn task_does_not_accept_wrongly_indexed_chunk() {
 			m
 		},
 		valid_chunks: HashSet::new(),
+		v1_only: HashSet::new(),
 	};
 	test.run(task, rx);
 }
@@ -182,6 +185,75 @@ fn task_stores_valid_chunk_if_there_is_one() {
 			s.insert(chunk.chunk);
 			s
 		},
+		v1_only: HashSet::new(),
+	};
+	test.run(task, rx);
+}
+
+#[test]
+fn task_stores_valid_compressed_chunk() {
+	let (mut task, rx) = get_test_running_task();
+	let pov = PoV { block_data: BlockData(vec![45; 10_000]) };
+	let (root_hash, chunk) = get_valid_chunk_data(pov);
+	task.erasure_root = root_hash;
+	task.request.index = chunk.index;
+
+	let validators = vec![Sr25519Keyring::Alice.public().into()];
+	task.group = validators;
+
+	let response = ChunkFetchingResponse::new(Some(chunk.clone()), &[v2::Compression::Zstd]);
+	assert!(matches!(response, ChunkFetchingResponse::CompressedChunk(_)));
+
+	let test = TestRun {
+		chunk_responses: {
+			let mut m = HashMap::new();
+			m.insert(Recipient::Authority(Sr25519Keyring::Alice.public().into()), response);
+			m
+		},
+		valid_chunks: {
+			let mut s = HashSet::new();
+			s.insert(chunk.chunk);
+			s
+		},
+		v1_only: HashSet::new(),
+	};
+	test.run(task, rx);
+}
+
+/// Validators not supporting `ChunkFetchingV2` yet get asked via `ChunkFetchingV1`.
+#[test]
+fn task_falls_back_to_v1() {
+	let (mut task, rx) = get_test_running_task();
+	let pov = PoV { block_data: BlockData(vec![45, 46, 47]) };
+	let (root_hash, chunk) = get_valid_chunk_data(pov);
+	task.erasure_root = root_hash;
+	task.request.index = chunk.index;
+
+	let validators = vec![Sr25519Keyring::Alice.public().into()];
+	task.group = validators;
+
+	let test = TestRun {
+		chunk_responses: {
+			let mut m = HashMap::new();
+			m.insert(
+				Recipient::Authority(Sr25519Keyring::Alice.public().into()),
+				ChunkFetchingResponse::Chunk(v1::ChunkResponse {
+					chunk: chunk.chunk.clone(),
+					proof: chunk.proof,
+				}),
+			);
+			m
+		},
+		valid_chunks: {
+			let mut s = HashSet::new();
+			s.insert(chunk.chunk);
+			s
+		},
+		v1_only: {
+			let mut s = HashSet::new();
+			s.insert(Recipient::Authority(Sr25519Keyring::Alice.public().into()));
+			s
+		},
 	};
 	test.run(task, rx);
 }
@@ -192,6 +264,8 @@ struct TestRun {
 	chunk_responses: HashMap<Recipient, ChunkFetchingResponse>,
 	/// Set of chunks that should be considered valid:
 	valid_chunks: HashSet<Vec<u8>>,
+	/// Validators which don't support `ChunkFetchingV2`.
+	v1_only: HashSet<Recipient>,
 }
 
 impl TestRun {
@@ -239,19 +313,28 @@ impl TestRun {
 			)) => {
 				let mut valid_responses = 0;
 				for req in reqs {
-					let req = match req {
-						Requests::ChunkFetchingV1(req) => req,
+					let (peer, pending_response, is_v1) = match req {
+						Requests::ChunkFetchingV2(req) => (req.peer, req.pending_response, false),
+						Requests::ChunkFetchingV1(req) => (req.peer, req.pending_response, true),
 						_ => panic!("Unexpected request"),
 					};
+					if !is_v1 && self.v1_only.contains(&peer) {
+						pending_response
+							.send(Err(network::RequestFailure::Network(
+								network::OutboundFailure::UnsupportedProtocols,
+							)))
+							.expect("Sending response should succeed");
+						continue
+					}
 					let response =
-						self.chunk_responses.get(&req.peer).ok_or(network::RequestFailure::Refused);
+						self.chunk_responses.get(&peer).ok_or(network::RequestFailure::Refused);
 
-					if let Ok(ChunkFetchingResponse::Chunk(resp)) = &response {
-						if self.valid_chunks.contains(&resp.chunk) {
+					if let Ok(response) = &response {
+						if chunk_data(response).map_or(false, |c| self.valid_chunks.contains(&c)) {
 							valid_responses += 1;
 						}
 					}
-					req.pending_response
+					pending_response
 						.send(response.map(Encode::encode))
 						.expect("Sending response should succeed");
 				}
@@ -274,6 +357,20 @@ impl TestRun {
 	}
 }
 
+/// The chunk data of a response, decompressed if need be.
+fn chunk_data(response: &ChunkFetchingResponse) -> Option<Vec<u8>> {
+	let request = v2::ChunkFetchingRequest::from(ChunkFetchingRequest {
+		candidate_hash: CandidateHash::default(),
+		index: ValidatorIndex(0),
+	});
+	response
+		.clone()
+		.recombine_into_chunk(&request)
+		.ok()
+		.flatten()
+		.map(|chunk| chunk.chunk)
+}
+
 /// Get a `RunningTask` filled with dummy values.
 fn get_test_running_task() -> (RunningTask, mpsc::Receiver<FromFetchTask>) {
 	let (tx, rx) = mpsc::channel(0);
@@ -32,12 +32,13 @@ use futures::{
 	Stream,
 };
 
+use polkadot_node_network_protocol::request_response::v1::ChunkFetchingRequest;
 use polkadot_node_subsystem::{
 	messages::{ChainApiMessage, RuntimeApiMessage},
 	overseer, ActivatedLeaf, ActiveLeavesUpdate, LeafStatus,
 };
 use polkadot_node_subsystem_util::runtime::{get_occupied_cores, RuntimeInfo};
-use polkadot_primitives::v2::{CandidateHash, Hash, OccupiedCore, SessionIndex};
+use polkadot_primitives::v2::{CandidateHash, GroupIndex, Hash, OccupiedCore, SessionIndex};
 
 use super::{FatalError, Metrics, Result, LOG_TARGET};
 
@@ -65,6 +66,14 @@ pub struct Requester {
 	/// We remove them on failure, so we get retries on the next block still pending availability.
 	fetches: HashMap<CandidateHash, FetchTask>,
 
+	/// Tasks fetching chunks of candidates no longer pending availability, once more.
+	///
+	/// They are not bound to any leaf, so we keep them around until they are finished.
+	refetches: Vec<FetchTask>,
+
+	/// The most recent fresh leaf, used for runtime queries not related to any leaf.
+	last_leaf: Option<Hash>,
+
 	/// Localized information about sessions we are currently interested in.
 	session_cache: SessionCache,
 
@@ -89,7 +98,15 @@ impl Requester {
 	/// by advancing the stream.
 	pub fn new(metrics: Metrics) -> Self {
 		let (tx, rx) = mpsc::channel(1);
-		Requester { fetches: HashMap::new(), session_cache: SessionCache::new(), tx, rx, metrics }
+		Requester {
+			fetches: HashMap::new(),
+			refetches: Vec::new(),
+			last_leaf: None,
+			session_cache: SessionCache::new(),
+			tx,
+			rx,
+			metrics,
+		}
 	}
 
 	/// Update heads that need availability distribution.
@@ -107,10 +124,66 @@ impl Requester {
 		if let Some(leaf) = activated.filter(|leaf| leaf.status == LeafStatus::Fresh) {
 			// Order important! We need to handle activated, prior to deactivated, otherwise we might
 			// cancel still needed jobs.
+			self.last_leaf = Some(leaf.hash);
 			self.start_requesting_chunks(ctx, runtime, leaf).await?;
 		}
 
 		self.stop_requesting_chunks(deactivated.into_iter());
+		self.refetches.retain(|task| !task.is_finished());
+		Ok(())
+	}
+
+	/// Fetch a chunk of a backed candidate from its backing group once more, no matter whether the
+	/// candidate is still pending availability.
+	///
+	/// The chunk is stored in the availability store, once fetched and checked against the
+	/// erasure root.
+	pub async fn fetch_chunk<Context>(
+		&mut self,
+		ctx: &mut Context,
+		runtime: &mut RuntimeInfo,
+		relay_parent: Hash,
+		session_index: SessionIndex,
+		group_index: GroupIndex,
+		request: ChunkFetchingRequest,
+		erasure_root: Hash,
+	) -> Result<()> {
+		let tx = self.tx.clone();
+		let metrics = self.metrics.clone();
+
+		let task_cfg = self
+			.session_cache
+			.with_session_info(
+				ctx,
+				runtime,
+				// The state of the relay parent might be pruned already, while the session
+				// information is still available at the latest leaf.
+				self.last_leaf.unwrap_or(relay_parent),
+				session_index,
+				|info| {
+					FetchTaskConfig::refetch(
+						relay_parent,
+						group_index,
+						request,
+						erasure_root,
+						tx,
+						metrics,
+						info,
+					)
+				},
+			)
+			.await?;
+
+		match task_cfg {
+			Some(task_cfg) => self.refetches.push(FetchTask::start(task_cfg, ctx).await?),
+			// Not a validator, nothing to do.
+			None => gum::debug!(
+				target: LOG_TARGET,
+				candidate_hash = ?request.candidate_hash,
+				"Not fetching chunk, as we are not a validator",
+			),
+		}
+
 		Ok(())
 	}
 
@@ -22,7 +22,7 @@ use futures::channel::oneshot;
 
 use fatality::Nested;
 use polkadot_node_network_protocol::{
-	request_response::{v1, IncomingRequest, IncomingRequestReceiver},
+	request_response::{v1, v2, IncomingRequest, IncomingRequestReceiver},
 	UnifiedReputationChange as Rep,
 };
 use polkadot_node_primitives::{AvailableData, ErasureChunk};
@@ -31,7 +31,7 @@ use polkadot_primitives::v2::{CandidateHash, ValidatorIndex};
 
 use crate::{
 	error::{JfyiError, Result},
-	metrics::{Metrics, FAILED, NOT_FOUND, SUCCEEDED},
+	metrics::{Metrics, FAILED, NOT_FOUND, RAW, SUCCEEDED, WIRE},
 	LOG_TARGET,
 };
 
@@ -97,6 +97,38 @@ pub async fn run_chunk_receiver<Sender>(
 	}
 }
 
+/// Receiver task to be forked as a separate task to handle `ChunkFetchingV2` requests.
+pub async fn run_chunk_receiver_v2<Sender>(
+	mut sender: Sender,
+	mut receiver: IncomingRequestReceiver<v2::ChunkFetchingRequest>,
+	metrics: Metrics,
+) where
+	Sender: SubsystemSender<AvailabilityStoreMessage>,
+{
+	loop {
+		match receiver.recv(|| vec![COST_INVALID_REQUEST]).await.into_nested() {
+			Ok(Ok(msg)) => {
+				answer_chunk_request_v2_log(&mut sender, msg, &metrics).await;
+			},
+			Err(fatal) => {
+				gum::debug!(
+					target: LOG_TARGET,
+					error = ?fatal,
+					"Shutting down chunk receiver (v2)."
+				);
+				return
+			},
+			Ok(Err(jfyi)) => {
+				gum::debug!(
+					target: LOG_TARGET,
+					error = ?jfyi,
+					"Error decoding incoming chunk request (v2)."
+				);
+			},
+		}
+	}
+}
+
 /// Variant of `answer_pov_request` that does Prometheus metric and logging on errors.
 ///
 /// Any errors of `answer_pov_request` will simply be logged.
@@ -132,7 +164,31 @@ pub async fn answer_chunk_request_log<Sender>(
 where
 	Sender: SubsystemSender<AvailabilityStoreMessage>,
 {
-	let res = answer_chunk_request(sender, req).await;
+	let res = answer_chunk_request(sender, req, metrics).await;
+	match res {
+		Ok(result) => metrics.on_served_chunk(if result { SUCCEEDED } else { NOT_FOUND }),
+		Err(err) => {
+			gum::warn!(
+				target: LOG_TARGET,
+				err= ?err,
+				"Serving chunk failed with error"
+			);
+			metrics.on_served_chunk(FAILED);
+		},
+	}
+}
+
+/// Variant of `answer_chunk_request_v2` that does Prometheus metric and logging on errors.
+///
+/// Any errors of `answer_chunk_request_v2` will simply be logged.
+pub async fn answer_chunk_request_v2_log<Sender>(
+	sender: &mut Sender,
+	req: IncomingRequest<v2::ChunkFetchingRequest>,
+	metrics: &Metrics,
+) where
+	Sender: SubsystemSender<AvailabilityStoreMessage>,
+{
+	let res = answer_chunk_request_v2(sender, req, metrics).await;
 	match res {
 		Ok(result) => metrics.on_served_chunk(if result { SUCCEEDED } else { NOT_FOUND }),
 		Err(err) => {
@@ -180,6 +236,7 @@ where
 pub async fn answer_chunk_request<Sender>(
 	sender: &mut Sender,
 	req: IncomingRequest<v1::ChunkFetchingRequest>,
+	metrics: &Metrics,
 ) -> Result<bool>
 where
 	Sender: SubsystemSender<AvailabilityStoreMessage>,
@@ -203,13 +260,60 @@ where
 
 	let response = match chunk {
 		None => v1::ChunkFetchingResponse::NoSuchChunk,
-		Some(chunk) => v1::ChunkFetchingResponse::Chunk(chunk.into()),
+		Some(chunk) => {
+			metrics.on_served_chunk_bytes(RAW, chunk.chunk.len());
+			metrics.on_served_chunk_bytes(WIRE, chunk.chunk.len());
+			v1::ChunkFetchingResponse::Chunk(chunk.into())
+		},
 	};
 
 	req.send_response(response).map_err(|_| JfyiError::SendResponse)?;
 	Ok(result)
 }
 
+/// Answer an incoming `ChunkFetchingV2` request by querying the av store.
+///
+/// The chunk gets compressed if the requester accepts that.
+///
+/// Returns: `Ok(true)` if chunk was found and served.
+pub async fn answer_chunk_request_v2<Sender>(
+	sender: &mut Sender,
+	req: IncomingRequest<v2::ChunkFetchingRequest>,
+	metrics: &Metrics,
+) -> Result<bool>
+where
+	Sender: SubsystemSender<AvailabilityStoreMessage>,
+{
+	let span = jaeger::Span::new(req.payload.candidate_hash, "answer-chunk-request");
+
+	let _child_span = span.child("answer-chunk-request").with_chunk_index(req.payload.index.0);
+
+	let chunk = query_chunk(sender, req.payload.candidate_hash, req.payload.index).await?;
+
+	let result = chunk.is_some();
+
+	gum::trace!(
+		target: LOG_TARGET,
+		hash = ?req.payload.candidate_hash,
+		index = ?req.payload.index,
+		peer = ?req.peer,
+		has_data = ?chunk.is_some(),
+		accepted_compressions = ?req.payload.accepted_compressions,
+		"Serving chunk",
+	);
+
+	if let Some(chunk) = &chunk {
+		metrics.on_served_chunk_bytes(RAW, chunk.chunk.len());
+	}
+	let response = v2::ChunkFetchingResponse::new(chunk, &req.payload.accepted_compressions);
+	if let Some(size) = response.chunk_size() {
+		metrics.on_served_chunk_bytes(WIRE, size);
+	}
+
+	req.send_response(response).map_err(|_| JfyiError::SendResponse)?;
+	Ok(result)
+}
+
 /// Query chunk from the availability store.
 async fn query_chunk<Sender>(
 	sender: &mut Sender,
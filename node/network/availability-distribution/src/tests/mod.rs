@@ -47,14 +47,22 @@ fn test_harness<T: Future<Output = ()>>(
 	let (pov_req_receiver, pov_req_cfg) = IncomingRequest::get_config_receiver(&req_protocol_names);
 	let (chunk_req_receiver, chunk_req_cfg) =
 		IncomingRequest::get_config_receiver(&req_protocol_names);
+	let (chunk_req_v2_receiver, chunk_req_v2_cfg) =
+		IncomingRequest::get_config_receiver(&req_protocol_names);
 	let subsystem = AvailabilityDistributionSubsystem::new(
 		keystore,
-		IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver },
+		IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver, chunk_req_v2_receiver },
 		Default::default(),
 	);
 	let subsystem = subsystem.run(context);
 
-	let test_fut = test_fx(TestHarness { virtual_overseer, pov_req_cfg, chunk_req_cfg, pool });
+	let test_fut = test_fx(TestHarness {
+		virtual_overseer,
+		pov_req_cfg,
+		chunk_req_cfg,
+		chunk_req_v2_cfg,
+		pool,
+	});
 
 	futures::pin_mut!(test_fut);
 	futures::pin_mut!(subsystem);
@@ -36,7 +36,7 @@ use sp_keystore::SyncCryptoStorePtr;
 
 use polkadot_node_network_protocol::{
 	jaeger,
-	request_response::{v1, IncomingRequest, OutgoingRequest, Requests},
+	request_response::{v2, IncomingRequest, OutgoingRequest, Requests},
 };
 use polkadot_node_primitives::ErasureChunk;
 use polkadot_node_subsystem::{
@@ -61,6 +61,7 @@ pub struct TestHarness {
 	pub virtual_overseer: VirtualOverseer,
 	pub pov_req_cfg: RequestResponseConfig,
 	pub chunk_req_cfg: RequestResponseConfig,
+	pub chunk_req_v2_cfg: RequestResponseConfig,
 	pub pool: TaskExecutor,
 }
 
@@ -222,7 +223,7 @@ impl TestState {
 						// Forward requests:
 						let in_req = to_incoming_req(&harness.pool, req);
 						harness
-							.chunk_req_cfg
+							.chunk_req_v2_cfg
 							.inbound_queue
 							.as_mut()
 							.unwrap()
@@ -313,9 +314,9 @@ async fn overseer_recv(rx: &mut mpsc::UnboundedReceiver<AllMessages>) -> AllMess
 fn to_incoming_req(
 	executor: &TaskExecutor,
 	outgoing: Requests,
-) -> IncomingRequest<v1::ChunkFetchingRequest> {
+) -> IncomingRequest<v2::ChunkFetchingRequest> {
 	match outgoing {
-		Requests::ChunkFetchingV1(OutgoingRequest { payload, pending_response, .. }) => {
+		Requests::ChunkFetchingV2(OutgoingRequest { payload, pending_response, .. }) => {
 			let (tx, rx): (oneshot::Sender<netconfig::OutgoingResponse>, oneshot::Receiver<_>) =
 				oneshot::channel();
 			executor.spawn(
@@ -0,0 +1,280 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Response-time and failure statistics of the authorities we request chunks from.
+//!
+//! The statistics are kept across recoveries and sessions, keyed by authority discovery id, and
+//! are used to ask fast and reliable authorities for their chunks first. The preference is
+//! bounded: the request order stays a random one, just skewed, so an attacker can't make us
+//! request only from its own nodes by answering quickly.
+
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use rand::Rng;
+
+use polkadot_primitives::v2::{AuthorityDiscoveryId, ValidatorIndex};
+
+/// Number of authorities to keep statistics for, comfortably more than there are validators in a
+/// session.
+const AUTHORITY_STATS_SIZE: NonZeroUsize = match NonZeroUsize::new(2048) {
+	Some(cap) => cap,
+	None => panic!("Authority statistics size must be non-zero."),
+};
+
+/// Weight of a new sample in the moving averages.
+const SAMPLE_WEIGHT: f64 = 0.2;
+
+/// The response time assumed for authorities which never responded to us.
+const DEFAULT_RESPONSE_TIME: Duration = Duration::from_millis(500);
+
+/// How much more likely than an unknown authority the most preferred one is to get picked next,
+/// and the unknown one than the least preferred one.
+const MAX_PREFERENCE: f64 = 4.0;
+
+/// Quantiles of the statistics over all tracked authorities, which get exported as metrics.
+pub const QUANTILES: [(&str, f64); 3] = [("0.5", 0.5), ("0.9", 0.9), ("0.99", 0.99)];
+
+/// What we know about a single authority.
+#[derive(Debug, Clone, Copy, Default)]
+struct Stats {
+	/// Moving average of the time it took the authority to respond, if it ever did.
+	response_time: Option<Duration>,
+	/// Moving average of the share of requests which failed.
+	failure_rate: f64,
+	/// Whether the authority doesn't support `ChunkFetchingV2`, so it gets asked via
+	/// `ChunkFetchingV1` instead.
+	v1_only: bool,
+}
+
+impl Stats {
+	/// The likelihood of the authority to get picked next, relative to an unknown one.
+	fn weight(&self) -> f64 {
+		let response_time = self.response_time.unwrap_or(DEFAULT_RESPONSE_TIME).as_secs_f64();
+		let weight = (1.0 - self.failure_rate) * DEFAULT_RESPONSE_TIME.as_secs_f64() /
+			response_time.max(f64::EPSILON);
+		weight.clamp(1.0 / MAX_PREFERENCE, MAX_PREFERENCE)
+	}
+}
+
+/// The statistics over all tracked authorities.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+	/// The number of tracked authorities.
+	pub tracked: usize,
+	/// The response time in seconds at each of the `QUANTILES`, empty if nobody responded yet.
+	pub response_times: Vec<(&'static str, f64)>,
+	/// The failure rate at each of the `QUANTILES`, empty if no authority is tracked.
+	pub failure_rates: Vec<(&'static str, f64)>,
+}
+
+/// Statistics of the authorities we requested chunks from, shared by all recovery tasks.
+#[derive(Clone)]
+pub struct AuthorityStats(Arc<Mutex<LruCache<AuthorityDiscoveryId, Stats>>>);
+
+impl Default for AuthorityStats {
+	fn default() -> Self {
+		Self(Arc::new(Mutex::new(LruCache::new(AUTHORITY_STATS_SIZE))))
+	}
+}
+
+impl AuthorityStats {
+	/// The authority responded to a request after the given time.
+	pub fn note_response_time(&self, authority: &AuthorityDiscoveryId, elapsed: Duration) {
+		self.update(authority, |stats| {
+			stats.response_time = Some(match stats.response_time {
+				Some(average) =>
+					average.mul_f64(1.0 - SAMPLE_WEIGHT) + elapsed.mul_f64(SAMPLE_WEIGHT),
+				None => elapsed,
+			});
+		})
+	}
+
+	/// A request to the authority concluded, with a valid chunk or not.
+	pub fn note_outcome(&self, authority: &AuthorityDiscoveryId, success: bool) {
+		let sample = if success { 0.0 } else { 1.0 };
+		self.update(authority, |stats| {
+			stats.failure_rate =
+				stats.failure_rate * (1.0 - SAMPLE_WEIGHT) + sample * SAMPLE_WEIGHT;
+		})
+	}
+
+	/// The authority doesn't support `ChunkFetchingV2`.
+	pub fn note_v1_only(&self, authority: &AuthorityDiscoveryId) {
+		self.update(authority, |stats| stats.v1_only = true)
+	}
+
+	/// Whether chunks need to be requested from the authority via `ChunkFetchingV1`.
+	pub fn is_v1_only(&self, authority: &AuthorityDiscoveryId) -> bool {
+		self.0.lock().get(authority).map_or(false, |stats| stats.v1_only)
+	}
+
+	fn update(&self, authority: &AuthorityDiscoveryId, f: impl FnOnce(&mut Stats)) {
+		let mut stats = self.0.lock();
+		match stats.get_mut(authority) {
+			Some(authority_stats) => f(authority_stats),
+			None => {
+				let mut authority_stats = Stats::default();
+				f(&mut authority_stats);
+				stats.put(authority.clone(), authority_stats);
+			},
+		}
+	}
+
+	/// The order in which to request chunks from the given validators of a session.
+	///
+	/// This is a weighted random shuffling, so validators known to be fast and reliable tend to
+	/// come first, and the ones known to be slow or failing tend to come last.
+	pub fn request_order(&self, authorities: &[AuthorityDiscoveryId]) -> Vec<ValidatorIndex> {
+		let weights: Vec<f64> = {
+			let mut stats = self.0.lock();
+			authorities
+				.iter()
+				.map(|authority| stats.get(authority).copied().unwrap_or_default().weight())
+				.collect()
+		};
+
+		// Sort by `u^(1/weight)` for a uniformly random `u`, which yields a random order in which
+		// every step picks the remaining validators with a probability proportional to their
+		// weight (Efraimidis-Spirakis).
+		let mut rng = rand::thread_rng();
+		let mut keyed: Vec<(f64, ValidatorIndex)> = weights
+			.into_iter()
+			.enumerate()
+			.map(|(i, weight)| (rng.gen::<f64>().powf(1.0 / weight), ValidatorIndex(i as _)))
+			.collect();
+		keyed.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+		keyed.into_iter().map(|(_, validator_index)| validator_index).collect()
+	}
+
+	/// Summarize the statistics of all tracked authorities.
+	pub fn summary(&self) -> Summary {
+		let (response_times, mut failure_rates): (Vec<_>, Vec<_>) = {
+			let stats = self.0.lock();
+			stats.iter().map(|(_, stats)| (stats.response_time, stats.failure_rate)).unzip()
+		};
+		let tracked = failure_rates.len();
+
+		let mut response_times: Vec<f64> =
+			response_times.into_iter().flatten().map(|t| t.as_secs_f64()).collect();
+		response_times.sort_by(f64::total_cmp);
+		failure_rates.sort_by(f64::total_cmp);
+
+		Summary {
+			tracked,
+			response_times: quantiles(&response_times),
+			failure_rates: quantiles(&failure_rates),
+		}
+	}
+}
+
+/// The `QUANTILES` of the given sorted values.
+fn quantiles(sorted: &[f64]) -> Vec<(&'static str, f64)> {
+	if sorted.is_empty() {
+		return Vec::new()
+	}
+
+	QUANTILES
+		.iter()
+		.map(|(label, q)| {
+			let index = ((sorted.len() - 1) as f64 * q).round() as usize;
+			(*label, sorted[index])
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::Sr25519Keyring;
+
+	const ROUNDS: usize = 1000;
+
+	fn authorities() -> Vec<AuthorityDiscoveryId> {
+		[Sr25519Keyring::Alice, Sr25519Keyring::Bob, Sr25519Keyring::Charlie, Sr25519Keyring::Dave]
+			.iter()
+			.map(|k| k.public().into())
+			.collect()
+	}
+
+	/// How often each validator got picked first, over `ROUNDS` orders.
+	fn first_picks(stats: &AuthorityStats, authorities: &[AuthorityDiscoveryId]) -> Vec<usize> {
+		let mut picks = vec![0; authorities.len()];
+		for _ in 0..ROUNDS {
+			let order = stats.request_order(authorities);
+			assert_eq!(order.len(), authorities.len());
+			picks[order[0].0 as usize] += 1;
+		}
+		picks
+	}
+
+	#[test]
+	fn request_order_contains_every_validator_once() {
+		let stats = AuthorityStats::default();
+		let authorities = authorities();
+
+		let mut order = stats.request_order(&authorities);
+		order.sort();
+		assert_eq!(order, (0..authorities.len() as u32).map(ValidatorIndex).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn fast_and_reliable_authorities_are_preferred() {
+		let stats = AuthorityStats::default();
+		let authorities = authorities();
+
+		for _ in 0..10 {
+			stats.note_response_time(&authorities[0], Duration::from_millis(50));
+			stats.note_outcome(&authorities[0], true);
+			stats.note_response_time(&authorities[1], Duration::from_secs(2));
+			stats.note_outcome(&authorities[1], true);
+			stats.note_outcome(&authorities[2], false);
+		}
+
+		let picks = first_picks(&stats, &authorities);
+		// The fast one is four times as likely as the unknown one to come first, the slow and the
+		// failing ones a quarter as likely.
+		assert!(picks[0] > picks[3] * 2, "{:?}", picks);
+		assert!(picks[3] > picks[1] * 2, "{:?}", picks);
+		assert!(picks[3] > picks[2] * 2, "{:?}", picks);
+		// Yet nobody gets excluded.
+		assert!(picks.iter().all(|p| *p > 0), "{:?}", picks);
+	}
+
+	#[test]
+	fn summary_covers_tracked_authorities() {
+		let stats = AuthorityStats::default();
+		let authorities = authorities();
+		assert_eq!(
+			stats.summary(),
+			Summary { tracked: 0, response_times: Vec::new(), failure_rates: Vec::new() },
+		);
+
+		stats.note_response_time(&authorities[0], Duration::from_secs(1));
+		stats.note_outcome(&authorities[0], true);
+		stats.note_outcome(&authorities[1], false);
+
+		let summary = stats.summary();
+		assert_eq!(summary.tracked, 2);
+		assert_eq!(summary.response_times, vec![("0.5", 1.0), ("0.9", 1.0), ("0.99", 1.0)]);
+		assert_eq!(
+			summary.failure_rates,
+			vec![("0.5", SAMPLE_WEIGHT), ("0.9", SAMPLE_WEIGHT), ("0.99", SAMPLE_WEIGHT)]
+		);
+	}
+}
@@ -106,6 +106,12 @@ impl<Output> FuturesUndead<Output> {
 	}
 }
 
+impl<Output> Default for FuturesUndead<Output> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
 impl<Output> Stream for FuturesUndead<Output> {
 	type Item = Output;
 
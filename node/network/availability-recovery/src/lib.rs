@@ -19,15 +19,15 @@
 #![warn(missing_docs)]
 
 use std::{
-	collections::{HashMap, VecDeque},
+	collections::{HashMap, HashSet, VecDeque},
 	num::NonZeroUsize,
 	pin::Pin,
-	time::Duration,
+	time::{Duration, Instant},
 };
 
 use futures::{
 	channel::oneshot,
-	future::{FutureExt, RemoteHandle},
+	future::{BoxFuture, FutureExt, RemoteHandle},
 	pin_mut,
 	prelude::*,
 	stream::FuturesUnordered,
@@ -37,7 +37,9 @@ use lru::LruCache;
 use rand::seq::SliceRandom;
 
 use fatality::Nested;
-use polkadot_erasure_coding::{branch_hash, branches, obtain_chunks_v1, recovery_threshold};
+use polkadot_erasure_coding::{
+	branch_hash, branches, obtain_chunks_v1, recovery_threshold, systematic_recovery_threshold,
+};
 #[cfg(not(test))]
 use polkadot_node_network_protocol::request_response::CHUNK_REQUEST_TIMEOUT;
 use polkadot_node_network_protocol::{
@@ -51,23 +53,29 @@ use polkadot_node_primitives::{AvailableData, ErasureChunk};
 use polkadot_node_subsystem::{
 	errors::RecoveryError,
 	jaeger,
-	messages::{AvailabilityRecoveryMessage, AvailabilityStoreMessage, NetworkBridgeTxMessage},
+	messages::{
+		AvailabilityRecoveryMessage, AvailabilityStoreMessage, NetworkBridgeTxMessage,
+		RecoveryPolicy, RecoveryStrategyKind,
+	},
 	overseer, ActiveLeavesUpdate, FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError,
 	SubsystemResult,
 };
-use polkadot_node_subsystem_util::request_session_info;
+use polkadot_node_subsystem_util::{request_session_info, signing_key_and_index};
 use polkadot_primitives::v2::{
 	AuthorityDiscoveryId, BlakeTwo256, BlockNumber, CandidateHash, CandidateReceipt, GroupIndex,
 	Hash, HashT, SessionIndex, SessionInfo, ValidatorId, ValidatorIndex,
 };
 
+mod authority_stats;
 mod error;
 mod futures_undead;
 mod metrics;
 use metrics::Metrics;
 
+use authority_stats::AuthorityStats;
 use futures_undead::FuturesUndead;
 use sc_network::{OutboundFailure, RequestFailure};
+use sp_keystore::SyncCryptoStorePtr;
 
 #[cfg(test)]
 mod tests;
@@ -85,6 +93,13 @@ const LRU_SIZE: NonZeroUsize = match NonZeroUsize::new(16) {
 
 const COST_INVALID_REQUEST: Rep = Rep::CostMajor("Peer sent unparsable request");
 
+// How many times a validator is asked again for its systematic chunk after a transient failure.
+const SYSTEMATIC_CHUNK_MAX_RETRIES: usize = 2;
+
+// How many times in a row the systematic chunk requests may time out softly before falling back
+// to the regular chunks.
+const SYSTEMATIC_CHUNKS_MAX_SOFT_TIMEOUTS: usize = 2;
+
 /// Time after which we consider a request to have failed
 ///
 /// and we should try more peers. Note in theory the request times out at the network level,
@@ -101,19 +116,39 @@ const TIMEOUT_START_NEW_REQUESTS: Duration = Duration::from_millis(100);
 
 /// The Availability Recovery Subsystem.
 pub struct AvailabilityRecoverySubsystem {
-	fast_path: bool,
+	/// The strategies to recover with, unless the request specifies its own.
+	default_strategies: Vec<RecoveryStrategyKind>,
 	/// Receiver for available data requests.
 	req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
 	/// Metrics for this subsystem.
 	metrics: Metrics,
+	/// The keystore to find our own chunk index with, if recovered chunks are to be served.
+	keystore: Option<SyncCryptoStorePtr>,
 }
 
+/// Looks the data up in the local availability store.
+struct FromLocalStore;
+
 struct RequestFromBackers {
 	// a random shuffling of the validators from the backing group which indicates the order
 	// in which we connect to them and request the chunk.
 	shuffled_backers: Vec<ValidatorIndex>,
 }
 
+struct RequestSystematicChunks {
+	/// The validators holding the systematic chunks we haven't requested yet.
+	unrequested: Vec<ValidatorIndex>,
+	/// The validators which have been asked for their systematic chunk.
+	requested: HashSet<ValidatorIndex>,
+	/// How many times each validator has been asked again after a transient failure.
+	retries: HashMap<ValidatorIndex, usize>,
+	/// The number of systematic chunks, all of which are needed.
+	systematic_threshold: usize,
+	received_chunks: HashMap<ValidatorIndex, ErasureChunk>,
+	/// Pending chunk requests with soft timeout.
+	requesting_chunks: FuturesUndead<Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>>,
+}
+
 struct RequestChunksFromValidators {
 	/// How many request have been unsuccessful so far.
 	error_count: usize,
@@ -121,8 +156,8 @@ struct RequestChunksFromValidators {
 	///
 	/// including failed ones.
 	total_received_responses: usize,
-	/// a random shuffling of the validators which indicates the order in which we connect to the validators and
-	/// request the chunk from them.
+	/// The validators we haven't requested the chunk from yet, in reverse order of request. A
+	/// random order, skewed towards the validators which answered fast and reliably before.
 	shuffling: VecDeque<ValidatorIndex>,
 	received_chunks: HashMap<ValidatorIndex, ErasureChunk>,
 	/// Pending chunk requests with soft timeout.
@@ -147,14 +182,60 @@ struct RecoveryParams {
 
 	/// Metrics to report
 	metrics: Metrics,
+
+	/// Statistics of the authorities, to be updated with the outcome of chunk requests.
+	authority_stats: AuthorityStats,
+
+	/// Our validator index in the session of the candidate, if we are a validator and serve the
+	/// chunks of recovered candidates.
+	own_index: Option<ValidatorIndex>,
 }
 
-/// Source the availability data either by means
-/// of direct request response protocol to
-/// backers (a.k.a. fast-path), or recover from chunks.
-enum Source {
-	RequestFromBackers(RequestFromBackers),
-	RequestChunks(RequestChunksFromValidators),
+/// The recovered data, along with our own chunk from the encoding the data was verified with.
+///
+/// There is no chunk if the data was found locally, or if we don't store our own chunk.
+type Recovered = (AvailableData, Option<ErasureChunk>);
+
+/// State shared by the strategies of a recovery task.
+///
+/// A chunk strategy which couldn't recover the data leaves its chunks and its pending requests
+/// here, to be taken over by the next one.
+#[derive(Default)]
+struct RecoveryTaskState {
+	/// Valid chunks received so far.
+	received_chunks: HashMap<ValidatorIndex, ErasureChunk>,
+	/// Chunk requests still pending, with soft timeout.
+	requesting_chunks: FuturesUndead<Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>>,
+	/// The validators which have already been asked for their chunk and are not to be asked
+	/// again.
+	requested: HashSet<ValidatorIndex>,
+}
+
+/// A way of obtaining the availability data, one step of a [`RecoveryTask`].
+///
+/// Strategies either look the data up locally, request it in full from backers (a.k.a.
+/// fast-path), or recover it from chunks, possibly trying the systematic chunks first.
+#[async_trait::async_trait]
+trait RecoveryStrategy<Sender: overseer::AvailabilityRecoverySenderTrait>: Send {
+	/// The name of the strategy, for logging.
+	fn display_name(&self) -> &'static str;
+
+	/// Whether the strategy only looks the data up locally. Recoveries served locally are not
+	/// counted as recoveries in the metrics.
+	fn is_local(&self) -> bool {
+		false
+	}
+
+	/// Run the strategy to completion.
+	///
+	/// `RecoveryError::Unavailable` hands over to the next strategy of the task, any other outcome
+	/// concludes the recovery.
+	async fn run(
+		&mut self,
+		state: &mut RecoveryTaskState,
+		params: &RecoveryParams,
+		sender: &mut Sender,
+	) -> Result<Recovered, RecoveryError>;
 }
 
 /// A stateful reconstruction of availability data in reference to
@@ -165,8 +246,50 @@ struct RecoveryTask<Sender> {
 	/// The parameters of the recovery process.
 	params: RecoveryParams,
 
-	/// The source to obtain the availability data from.
-	source: Source,
+	/// The strategies to obtain the availability data with, tried in order.
+	strategies: VecDeque<Box<dyn RecoveryStrategy<Sender>>>,
+
+	/// The state shared by the strategies.
+	state: RecoveryTaskState,
+}
+
+#[async_trait::async_trait]
+impl<Sender> RecoveryStrategy<Sender> for FromLocalStore
+where
+	Sender: overseer::AvailabilityRecoverySenderTrait,
+{
+	fn display_name(&self) -> &'static str {
+		"local store"
+	}
+
+	fn is_local(&self) -> bool {
+		true
+	}
+
+	async fn run(
+		&mut self,
+		_state: &mut RecoveryTaskState,
+		params: &RecoveryParams,
+		sender: &mut Sender,
+	) -> Result<Recovered, RecoveryError> {
+		let (tx, rx) = oneshot::channel();
+		sender
+			.send_message(AvailabilityStoreMessage::QueryAvailableData(params.candidate_hash, tx))
+			.await;
+
+		match rx.await {
+			Ok(Some(data)) => Ok((data, None)),
+			Ok(None) => Err(RecoveryError::Unavailable),
+			Err(oneshot::Canceled) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					candidate_hash = ?params.candidate_hash,
+					"Failed to reach the availability store",
+				);
+				Err(RecoveryError::Unavailable)
+			},
+		}
+	}
 }
 
 impl RequestFromBackers {
@@ -175,13 +298,23 @@ impl RequestFromBackers {
 
 		RequestFromBackers { shuffled_backers: backers }
 	}
+}
+
+#[async_trait::async_trait]
+impl<Sender> RecoveryStrategy<Sender> for RequestFromBackers
+where
+	Sender: overseer::AvailabilityRecoverySenderTrait,
+{
+	fn display_name(&self) -> &'static str {
+		"full from backers"
+	}
 
-	// Run this phase to completion.
 	async fn run(
 		&mut self,
+		_state: &mut RecoveryTaskState,
 		params: &RecoveryParams,
-		sender: &mut impl overseer::AvailabilityRecoverySenderTrait,
-	) -> Result<AvailableData, RecoveryError> {
+		sender: &mut Sender,
+	) -> Result<Recovered, RecoveryError> {
 		gum::trace!(
 			target: LOG_TARGET,
 			candidate_hash = ?params.candidate_hash,
@@ -210,18 +343,14 @@ impl RequestFromBackers {
 
 			match response.await {
 				Ok(req_res::v1::AvailableDataFetchingResponse::AvailableData(data)) => {
-					if reconstructed_data_matches_root(
-						params.validators.len(),
-						&params.erasure_root,
-						&data,
-					) {
+					if let Some(own_chunk) = verify_reconstructed_data(params, &data) {
 						gum::trace!(
 							target: LOG_TARGET,
 							candidate_hash = ?params.candidate_hash,
 							"Received full data",
 						);
 
-						return Ok(data)
+						return Ok((data, own_chunk))
 					} else {
 						gum::debug!(
 							target: LOG_TARGET,
@@ -247,14 +376,12 @@ impl RequestFromBackers {
 }
 
 impl RequestChunksFromValidators {
-	fn new(n_validators: u32) -> Self {
-		let mut shuffling: Vec<_> = (0..n_validators).map(ValidatorIndex).collect();
-		shuffling.shuffle(&mut rand::thread_rng());
-
+	/// Request the chunks from the validators in the given order.
+	fn new(request_order: Vec<ValidatorIndex>) -> Self {
 		RequestChunksFromValidators {
 			error_count: 0,
 			total_received_responses: 0,
-			shuffling: shuffling.into(),
+			shuffling: request_order.into_iter().rev().collect(),
 			received_chunks: HashMap::new(),
 			requesting_chunks: FuturesUndead::new(),
 		}
@@ -323,37 +450,9 @@ impl RequestChunksFromValidators {
 
 		while self.requesting_chunks.len() < num_requests {
 			if let Some(validator_index) = self.shuffling.pop_back() {
-				let validator = params.validator_authority_keys[validator_index.0 as usize].clone();
-				gum::trace!(
-					target: LOG_TARGET,
-					?validator,
-					?validator_index,
-					?candidate_hash,
-					"Requesting chunk",
-				);
-
-				// Request data.
-				let raw_request = req_res::v1::ChunkFetchingRequest {
-					candidate_hash: params.candidate_hash,
-					index: validator_index,
-				};
-
-				let (req, res) =
-					OutgoingRequest::new(Recipient::Authority(validator), raw_request.clone());
-				requests.push(Requests::ChunkFetchingV1(req));
-
-				params.metrics.on_chunk_request_issued();
-				let timer = params.metrics.time_chunk_request();
-
-				self.requesting_chunks.push(Box::pin(async move {
-					let _timer = timer;
-					match res.await {
-						Ok(req_res::v1::ChunkFetchingResponse::Chunk(chunk)) =>
-							Ok(Some(chunk.recombine_into_chunk(&raw_request))),
-						Ok(req_res::v1::ChunkFetchingResponse::NoSuchChunk) => Ok(None),
-						Err(e) => Err((validator_index, e)),
-					}
-				}));
+				let (request, response) = request_chunk(params, validator_index);
+				requests.push(request);
+				self.requesting_chunks.push(response);
 			} else {
 				break
 			}
@@ -369,8 +468,6 @@ impl RequestChunksFromValidators {
 
 	/// Wait for a sufficient amount of chunks to reconstruct according to the provided `params`.
 	async fn wait_for_chunks(&mut self, params: &RecoveryParams) {
-		let metrics = &params.metrics;
-
 		// Wait for all current requests to conclude or time-out, or until we reach enough chunks.
 		// We also declare requests undead, once `TIMEOUT_START_NEW_REQUESTS` is reached and will
 		// return in that case for `launch_parallel_requests` to fill up slots again.
@@ -379,65 +476,19 @@ impl RequestChunksFromValidators {
 		{
 			self.total_received_responses += 1;
 
-			match request_result {
-				Ok(Some(chunk)) =>
-					if is_chunk_valid(params, &chunk) {
-						metrics.on_chunk_request_succeeded();
-						gum::trace!(
-							target: LOG_TARGET,
-							candidate_hash = ?params.candidate_hash,
-							validator_index = ?chunk.index,
-							"Received valid chunk",
-						);
-						self.received_chunks.insert(chunk.index, chunk);
-					} else {
-						metrics.on_chunk_request_invalid();
-						self.error_count += 1;
-					},
-				Ok(None) => {
-					metrics.on_chunk_request_no_such_chunk();
+			match process_chunk_response(params, request_result) {
+				ChunkResponse::Chunk(chunk) => {
+					self.received_chunks.insert(chunk.index, chunk);
+				},
+				ChunkResponse::Failed => {
 					self.error_count += 1;
 				},
-				Err((validator_index, e)) => {
+				ChunkResponse::Retry(validator_index) => {
 					self.error_count += 1;
-
-					gum::trace!(
-						target: LOG_TARGET,
-						candidate_hash= ?params.candidate_hash,
-						err = ?e,
-						?validator_index,
-						"Failure requesting chunk",
-					);
-
-					match e {
-						RequestError::InvalidResponse(_) => {
-							metrics.on_chunk_request_invalid();
-
-							gum::debug!(
-								target: LOG_TARGET,
-								candidate_hash = ?params.candidate_hash,
-								err = ?e,
-								?validator_index,
-								"Chunk fetching response was invalid",
-							);
-						},
-						RequestError::NetworkError(err) => {
-							// No debug logs on general network errors - that became very spammy
-							// occasionally.
-							if let RequestFailure::Network(OutboundFailure::Timeout) = err {
-								metrics.on_chunk_request_timeout();
-							} else {
-								metrics.on_chunk_request_error();
-							}
-
-							self.shuffling.push_front(validator_index);
-						},
-						RequestError::Canceled(_) => {
-							metrics.on_chunk_request_error();
-
-							self.shuffling.push_front(validator_index);
-						},
-					}
+					self.shuffling.push_front(validator_index);
+				},
+				ChunkResponse::Fallback(validator_index) => {
+					self.shuffling.push_back(validator_index);
 				},
 			}
 
@@ -457,55 +508,21 @@ impl RequestChunksFromValidators {
 		}
 	}
 
-	async fn run<Sender>(
+	async fn recover<Sender>(
 		&mut self,
 		params: &RecoveryParams,
 		sender: &mut Sender,
-	) -> Result<AvailableData, RecoveryError>
+	) -> Result<Recovered, RecoveryError>
 	where
 		Sender: overseer::AvailabilityRecoverySenderTrait,
 	{
 		let metrics = &params.metrics;
 
 		// First query the store for any chunks we've got.
-		{
-			let (tx, rx) = oneshot::channel();
-			sender
-				.send_message(AvailabilityStoreMessage::QueryAllChunks(params.candidate_hash, tx))
-				.await;
-
-			match rx.await {
-				Ok(chunks) => {
-					// This should either be length 1 or 0. If we had the whole data,
-					// we wouldn't have reached this stage.
-					let chunk_indices: Vec<_> = chunks.iter().map(|c| c.index).collect();
-					self.shuffling.retain(|i| !chunk_indices.contains(i));
-
-					for chunk in chunks {
-						if is_chunk_valid(params, &chunk) {
-							gum::trace!(
-								target: LOG_TARGET,
-								candidate_hash = ?params.candidate_hash,
-								validator_index = ?chunk.index,
-								"Found valid chunk on disk"
-							);
-							self.received_chunks.insert(chunk.index, chunk);
-						} else {
-							gum::error!(
-								target: LOG_TARGET,
-								"Loaded invalid chunk from disk! Disk/Db corruption _very_ likely - please fix ASAP!"
-							);
-						};
-					}
-				},
-				Err(oneshot::Canceled) => {
-					gum::warn!(
-						target: LOG_TARGET,
-						candidate_hash = ?params.candidate_hash,
-						"Failed to reach the availability store"
-					);
-				},
-			}
+		let (local_indices, local_chunks) = query_local_chunks(params, sender).await;
+		self.shuffling.retain(|i| !local_indices.contains(i));
+		for chunk in local_chunks {
+			self.received_chunks.insert(chunk.index, chunk);
 		}
 
 		let _recovery_timer = metrics.time_full_recovery();
@@ -523,8 +540,6 @@ impl RequestChunksFromValidators {
 					"Data recovery is not possible",
 				);
 
-				metrics.on_recovery_failed();
-
 				return Err(RecoveryError::Unavailable)
 			}
 
@@ -535,56 +550,508 @@ impl RequestChunksFromValidators {
 			// If that fails, or a re-encoding of it doesn't match the expected erasure root,
 			// return Err(RecoveryError::Invalid)
 			if self.received_chunks.len() >= params.threshold {
-				let recovery_duration = metrics.time_erasure_recovery();
-
-				return match polkadot_erasure_coding::reconstruct_v1(
-					params.validators.len(),
-					self.received_chunks.values().map(|c| (&c.chunk[..], c.index.0 as usize)),
-				) {
-					Ok(data) => {
-						if reconstructed_data_matches_root(
-							params.validators.len(),
-							&params.erasure_root,
-							&data,
-						) {
-							gum::trace!(
-								target: LOG_TARGET,
-								candidate_hash = ?params.candidate_hash,
-								erasure_root = ?params.erasure_root,
-								"Data recovery complete",
-							);
-							metrics.on_recovery_succeeded();
+				return reconstruct_and_verify(params, || {
+					polkadot_erasure_coding::reconstruct_v1(
+						params.validators.len(),
+						self.received_chunks.values().map(|c| (&c.chunk[..], c.index.0 as usize)),
+					)
+				})
+			}
+		}
+	}
+}
 
-							Ok(data)
-						} else {
-							recovery_duration.map(|rd| rd.stop_and_discard());
-							gum::trace!(
-								target: LOG_TARGET,
-								candidate_hash = ?params.candidate_hash,
-								erasure_root = ?params.erasure_root,
-								"Data recovery - root mismatch",
-							);
-							metrics.on_recovery_invalid();
+#[async_trait::async_trait]
+impl<Sender> RecoveryStrategy<Sender> for RequestChunksFromValidators
+where
+	Sender: overseer::AvailabilityRecoverySenderTrait,
+{
+	fn display_name(&self) -> &'static str {
+		"chunks"
+	}
 
-							Err(RecoveryError::Invalid)
-						}
+	async fn run(
+		&mut self,
+		state: &mut RecoveryTaskState,
+		params: &RecoveryParams,
+		sender: &mut Sender,
+	) -> Result<Recovered, RecoveryError> {
+		self.shuffling
+			.retain(|i| !state.received_chunks.contains_key(i) && !state.requested.contains(i));
+		self.received_chunks.extend(state.received_chunks.drain());
+		self.requesting_chunks = std::mem::take(&mut state.requesting_chunks);
+
+		let result = self.recover(params, sender).await;
+		if let Err(RecoveryError::Unavailable) = result {
+			state.received_chunks.extend(self.received_chunks.drain());
+			state.requesting_chunks = std::mem::take(&mut self.requesting_chunks);
+		}
+		result
+	}
+}
+
+impl RequestSystematicChunks {
+	fn new(systematic_threshold: usize) -> Self {
+		// The validator holding a chunk is the one with the same index.
+		let mut unrequested: Vec<_> =
+			(0..systematic_threshold as u32).map(ValidatorIndex).collect();
+		unrequested.shuffle(&mut rand::thread_rng());
+
+		RequestSystematicChunks {
+			unrequested,
+			requested: HashSet::new(),
+			retries: HashMap::new(),
+			systematic_threshold,
+			received_chunks: HashMap::new(),
+			requesting_chunks: FuturesUndead::new(),
+		}
+	}
+
+	/// Requests all the missing systematic chunks, at most [`N_PARALLEL`] at a time.
+	async fn launch_requests(
+		&mut self,
+		params: &RecoveryParams,
+		sender: &mut impl overseer::AvailabilityRecoverySenderTrait,
+	) {
+		let mut requests = Vec::new();
+		while self.requesting_chunks.len() < N_PARALLEL {
+			match self.unrequested.pop() {
+				Some(validator_index) => {
+					let (request, response) = request_chunk(params, validator_index);
+					requests.push(request);
+					self.requesting_chunks.push(response);
+					self.requested.insert(validator_index);
+				},
+				None => break,
+			}
+		}
+
+		if !requests.is_empty() {
+			sender
+				.send_message(NetworkBridgeTxMessage::SendRequests(
+					requests,
+					IfDisconnected::ImmediateError,
+				))
+				.await;
+		}
+	}
+
+	// Each systematic chunk can only be fetched from a single validator. Transient failures are
+	// retried and slow validators are waited for, but once a chunk is known to be unobtainable this
+	// returns `RecoveryError::Unavailable`, leaving the chunks received so far and the pending
+	// requests to the next strategy.
+	async fn recover(
+		&mut self,
+		params: &RecoveryParams,
+		sender: &mut impl overseer::AvailabilityRecoverySenderTrait,
+	) -> Result<Recovered, RecoveryError> {
+		gum::trace!(
+			target: LOG_TARGET,
+			candidate_hash = ?params.candidate_hash,
+			erasure_root = ?params.erasure_root,
+			systematic_threshold = ?self.systematic_threshold,
+			"Requesting systematic chunks",
+		);
+
+		let recovery_timer = params.metrics.time_full_recovery();
+
+		let (_, local_chunks) = query_local_chunks(params, sender).await;
+		for chunk in local_chunks {
+			self.received_chunks.insert(chunk.index, chunk);
+		}
+		self.unrequested.retain(|i| !self.received_chunks.contains_key(i));
+
+		let mut soft_timeouts = 0;
+		while self.systematic_chunks_received() < self.systematic_threshold {
+			self.launch_requests(params, sender).await;
+
+			let request_result =
+				match self.requesting_chunks.next_with_timeout(TIMEOUT_START_NEW_REQUESTS).await {
+					Some(request_result) => {
+						soft_timeouts = 0;
+						request_result
+					},
+					None if soft_timeouts < SYSTEMATIC_CHUNKS_MAX_SOFT_TIMEOUTS &&
+						self.requesting_chunks.total_len() > 0 =>
+					{
+						// Nobody else has the missing chunks, so keep waiting for the slow
+						// validators.
+						soft_timeouts += 1;
+						continue
+					},
+					None => {
+						gum::debug!(
+							target: LOG_TARGET,
+							candidate_hash = ?params.candidate_hash,
+							received = %self.systematic_chunks_received(),
+							systematic_threshold = %self.systematic_threshold,
+							"Systematic chunk requests timed out, falling back to regular chunks",
+						);
+						recovery_timer.map(|t| t.stop_and_discard());
+						return Err(RecoveryError::Unavailable)
 					},
-					Err(err) => {
-						recovery_duration.map(|rd| rd.stop_and_discard());
+				};
+
+			match process_chunk_response(params, request_result) {
+				ChunkResponse::Chunk(chunk) => {
+					self.received_chunks.insert(chunk.index, chunk);
+				},
+				ChunkResponse::Fallback(validator_index) => {
+					self.requested.remove(&validator_index);
+					self.unrequested.push(validator_index);
+				},
+				ChunkResponse::Retry(validator_index)
+					if self.retries.get(&validator_index).copied().unwrap_or(0) <
+						SYSTEMATIC_CHUNK_MAX_RETRIES =>
+				{
+					*self.retries.entry(validator_index).or_default() += 1;
+					self.requested.remove(&validator_index);
+					// Asked again once the others have been asked.
+					self.unrequested.insert(0, validator_index);
+				},
+				ChunkResponse::Failed | ChunkResponse::Retry(_) => {
+					gum::debug!(
+						target: LOG_TARGET,
+						candidate_hash = ?params.candidate_hash,
+						received = %self.systematic_chunks_received(),
+						systematic_threshold = %self.systematic_threshold,
+						"Failed to fetch a systematic chunk, falling back to regular chunks",
+					);
+					recovery_timer.map(|t| t.stop_and_discard());
+					return Err(RecoveryError::Unavailable)
+				},
+			}
+		}
+
+		let mut systematic_chunks: Vec<_> = self
+			.received_chunks
+			.values()
+			.filter(|chunk| (chunk.index.0 as usize) < self.systematic_threshold)
+			.collect();
+		systematic_chunks.sort_by_key(|chunk| chunk.index);
+
+		reconstruct_and_verify(params, || {
+			polkadot_erasure_coding::reconstruct_from_systematic_v1(
+				params.validators.len(),
+				&systematic_chunks.iter().map(|chunk| &chunk.chunk[..]).collect::<Vec<_>>(),
+			)
+		})
+	}
+
+	fn systematic_chunks_received(&self) -> usize {
+		self.received_chunks
+			.keys()
+			.filter(|index| (index.0 as usize) < self.systematic_threshold)
+			.count()
+	}
+}
+
+#[async_trait::async_trait]
+impl<Sender> RecoveryStrategy<Sender> for RequestSystematicChunks
+where
+	Sender: overseer::AvailabilityRecoverySenderTrait,
+{
+	fn display_name(&self) -> &'static str {
+		"systematic chunks"
+	}
+
+	async fn run(
+		&mut self,
+		state: &mut RecoveryTaskState,
+		params: &RecoveryParams,
+		sender: &mut Sender,
+	) -> Result<Recovered, RecoveryError> {
+		self.received_chunks.extend(state.received_chunks.drain());
+
+		let result = self.recover(params, sender).await;
+		if let Err(RecoveryError::Unavailable) = result {
+			state.received_chunks.extend(self.received_chunks.drain());
+			state.requesting_chunks = std::mem::take(&mut self.requesting_chunks);
+			state.requested.extend(self.requested.drain());
+		}
+		result
+	}
+}
+
+/// Reconstructs the data with the given function and checks that it matches the erasure root.
+///
+/// Returns `RecoveryError::Invalid` if either fails.
+fn reconstruct_and_verify(
+	params: &RecoveryParams,
+	reconstruct: impl FnOnce() -> Result<AvailableData, polkadot_erasure_coding::Error>,
+) -> Result<Recovered, RecoveryError> {
+	let metrics = &params.metrics;
+	let recovery_duration = metrics.time_erasure_recovery();
+
+	match reconstruct() {
+		Ok(data) =>
+			if let Some(own_chunk) = verify_reconstructed_data(params, &data) {
+				gum::trace!(
+					target: LOG_TARGET,
+					candidate_hash = ?params.candidate_hash,
+					erasure_root = ?params.erasure_root,
+					"Data recovery complete",
+				);
+				metrics.on_recovery_succeeded();
+
+				Ok((data, own_chunk))
+			} else {
+				recovery_duration.map(|rd| rd.stop_and_discard());
+				gum::trace!(
+					target: LOG_TARGET,
+					candidate_hash = ?params.candidate_hash,
+					erasure_root = ?params.erasure_root,
+					"Data recovery - root mismatch",
+				);
+				metrics.on_recovery_invalid();
+
+				Err(RecoveryError::Invalid)
+			},
+		Err(err) => {
+			recovery_duration.map(|rd| rd.stop_and_discard());
+			gum::trace!(
+				target: LOG_TARGET,
+				candidate_hash = ?params.candidate_hash,
+				erasure_root = ?params.erasure_root,
+				?err,
+				"Data recovery error ",
+			);
+			metrics.on_recovery_invalid();
+
+			Err(RecoveryError::Invalid)
+		},
+	}
+}
+
+/// Queries the availability store for the chunks we hold ourselves.
+///
+/// Returns the indices of all the chunks found on disk, which there is no point in requesting from
+/// the network, along with the valid ones.
+async fn query_local_chunks(
+	params: &RecoveryParams,
+	sender: &mut impl overseer::AvailabilityRecoverySenderTrait,
+) -> (Vec<ValidatorIndex>, Vec<ErasureChunk>) {
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(AvailabilityStoreMessage::QueryAllChunks(params.candidate_hash, tx))
+		.await;
+
+	match rx.await {
+		Ok(chunks) => {
+			// This should either be length 1 or 0. If we had the whole data,
+			// we wouldn't have reached this stage.
+			let chunk_indices = chunks.iter().map(|c| c.index).collect();
+			let valid_chunks = chunks
+				.into_iter()
+				.filter(|chunk| {
+					if is_chunk_valid(params, chunk) {
 						gum::trace!(
 							target: LOG_TARGET,
 							candidate_hash = ?params.candidate_hash,
-							erasure_root = ?params.erasure_root,
-							?err,
-							"Data recovery error ",
+							validator_index = ?chunk.index,
+							"Found valid chunk on disk"
 						);
-						metrics.on_recovery_invalid();
+						true
+					} else {
+						gum::error!(
+							target: LOG_TARGET,
+							"Loaded invalid chunk from disk! Disk/Db corruption _very_ likely - please fix ASAP!"
+						);
+						false
+					}
+				})
+				.collect();
+			(chunk_indices, valid_chunks)
+		},
+		Err(oneshot::Canceled) => {
+			gum::warn!(
+				target: LOG_TARGET,
+				candidate_hash = ?params.candidate_hash,
+				"Failed to reach the availability store"
+			);
+			(Vec::new(), Vec::new())
+		},
+	}
+}
+
+/// A pending request for the chunk of a validator.
+type ChunkRequest =
+	BoxFuture<'static, Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>>;
+
+/// Prepares a request for the chunk held by the given validator. The request still needs to be
+/// sent to the network bridge, its response will be yielded by the returned future.
+fn request_chunk(
+	params: &RecoveryParams,
+	validator_index: ValidatorIndex,
+) -> (Requests, ChunkRequest) {
+	let validator = params.validator_authority_keys[validator_index.0 as usize].clone();
+	gum::trace!(
+		target: LOG_TARGET,
+		?validator,
+		?validator_index,
+		candidate_hash = ?params.candidate_hash,
+		"Requesting chunk",
+	);
+
+	// Request data.
+	let raw_request = req_res::v2::ChunkFetchingRequest::from(req_res::v1::ChunkFetchingRequest {
+		candidate_hash: params.candidate_hash,
+		index: validator_index,
+	});
+
+	let authority_stats = params.authority_stats.clone();
+	let authority = params.validator_authority_keys[validator_index.0 as usize].clone();
 
-						Err(RecoveryError::Invalid)
+	// Validators which don't support `ChunkFetchingV2` yet get the request they understand, and
+	// their response is read as a `ChunkFetchingV2` one, which it is a subset of.
+	let (req, res) = if authority_stats.is_v1_only(&authority) {
+		let (req, res) = OutgoingRequest::new(
+			Recipient::Authority(validator),
+			req_res::v1::ChunkFetchingRequest::from(&raw_request),
+		);
+		(
+			Requests::ChunkFetchingV1(req),
+			res.map(|res| res.map(req_res::v2::ChunkFetchingResponse::from)).boxed(),
+		)
+	} else {
+		let (req, res) = OutgoingRequest::new(Recipient::Authority(validator), raw_request.clone());
+		(Requests::ChunkFetchingV2(req), res.boxed())
+	};
+
+	params.metrics.on_chunk_request_issued();
+	let timer = params.metrics.time_chunk_request();
+	let metrics = params.metrics.clone();
+
+	let response = Box::pin(async move {
+		let _timer = timer;
+		let start = Instant::now();
+		match res.await {
+			Ok(response) => {
+				authority_stats.note_response_time(&authority, start.elapsed());
+				let wire_size = response.chunk_size();
+				match response.recombine_into_chunk(&raw_request) {
+					Ok(Some(chunk)) => {
+						// Whether the chunk is any good is noted once it got checked.
+						metrics.on_chunk_bytes(chunk.chunk.len(), wire_size.unwrap_or_default());
+						Ok(Some(chunk))
+					},
+					Ok(None) => {
+						authority_stats.note_outcome(&authority, false);
+						Ok(None)
+					},
+					Err(e) => {
+						authority_stats.note_outcome(&authority, false);
+						Err((validator_index, RequestError::InvalidResponse(e)))
 					},
 				}
-			}
+			},
+			Err(e) if e.is_unsupported_protocol() => {
+				authority_stats.note_v1_only(&authority);
+				Err((validator_index, e))
+			},
+			Err(e) => {
+				authority_stats.note_outcome(&authority, false);
+				Err((validator_index, e))
+			},
 		}
+	});
+
+	(req, response)
+}
+
+/// What became of a chunk request.
+enum ChunkResponse {
+	/// A valid chunk was received.
+	Chunk(ErasureChunk),
+	/// The validator doesn't have the chunk or sent an invalid one.
+	Failed,
+	/// The request failed in a way which might be transient, so the validator may be asked again.
+	Retry(ValidatorIndex),
+	/// The validator doesn't support `ChunkFetchingV2`, so it is to be asked again right away,
+	/// via `ChunkFetchingV1`.
+	Fallback(ValidatorIndex),
+}
+
+/// Checks the response to a chunk request, updating the metrics accordingly.
+fn process_chunk_response(
+	params: &RecoveryParams,
+	request_result: Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>,
+) -> ChunkResponse {
+	let metrics = &params.metrics;
+
+	match request_result {
+		Ok(Some(chunk)) => {
+			let authority = &params.validator_authority_keys[chunk.index.0 as usize];
+			if is_chunk_valid(params, &chunk) {
+				params.authority_stats.note_outcome(authority, true);
+				metrics.on_chunk_request_succeeded();
+				gum::trace!(
+					target: LOG_TARGET,
+					candidate_hash = ?params.candidate_hash,
+					validator_index = ?chunk.index,
+					"Received valid chunk",
+				);
+				ChunkResponse::Chunk(chunk)
+			} else {
+				params.authority_stats.note_outcome(authority, false);
+				metrics.on_chunk_request_invalid();
+				ChunkResponse::Failed
+			}
+		},
+		Ok(None) => {
+			metrics.on_chunk_request_no_such_chunk();
+			ChunkResponse::Failed
+		},
+		Err((validator_index, e)) if e.is_unsupported_protocol() => {
+			gum::trace!(
+				target: LOG_TARGET,
+				candidate_hash = ?params.candidate_hash,
+				?validator_index,
+				"Validator doesn't support ChunkFetchingV2, falling back to ChunkFetchingV1",
+			);
+			ChunkResponse::Fallback(validator_index)
+		},
+		Err((validator_index, e)) => {
+			gum::trace!(
+				target: LOG_TARGET,
+				candidate_hash= ?params.candidate_hash,
+				err = ?e,
+				?validator_index,
+				"Failure requesting chunk",
+			);
+
+			match e {
+				RequestError::InvalidResponse(_) => {
+					metrics.on_chunk_request_invalid();
+
+					gum::debug!(
+						target: LOG_TARGET,
+						candidate_hash = ?params.candidate_hash,
+						err = ?e,
+						?validator_index,
+						"Chunk fetching response was invalid",
+					);
+
+					ChunkResponse::Failed
+				},
+				RequestError::NetworkError(err) => {
+					// No debug logs on general network errors - that became very spammy
+					// occasionally.
+					if let RequestFailure::Network(OutboundFailure::Timeout) = err {
+						metrics.on_chunk_request_timeout();
+					} else {
+						metrics.on_chunk_request_error();
+					}
+
+					ChunkResponse::Retry(validator_index)
+				},
+				RequestError::Canceled(_) => {
+					metrics.on_chunk_request_error();
+
+					ChunkResponse::Retry(validator_index)
+				},
+			}
+		},
 	}
 }
 
@@ -641,12 +1108,15 @@ fn is_chunk_valid(params: &RecoveryParams, chunk: &ErasureChunk) -> bool {
 /// data was invalid to begin with. In the former case, validators fetching valid chunks will see
 /// invalid data as well, because the root won't match. In the latter case the situation is the
 /// same for anyone anyways.
-fn reconstructed_data_matches_root(
-	n_validators: usize,
-	expected_root: &Hash,
+///
+/// Returns `None` if the data doesn't match the erasure root. Otherwise, returns our own chunk
+/// along with its proof if `params` has our index, so the data doesn't need to be encoded again
+/// to store it.
+fn verify_reconstructed_data(
+	params: &RecoveryParams,
 	data: &AvailableData,
-) -> bool {
-	let chunks = match obtain_chunks_v1(n_validators, data) {
+) -> Option<Option<ErasureChunk>> {
+	let chunks = match obtain_chunks_v1(params.validators.len(), data) {
 		Ok(chunks) => chunks,
 		Err(e) => {
 			gum::debug!(
@@ -654,75 +1124,110 @@ fn reconstructed_data_matches_root(
 				err = ?e,
 				"Failed to obtain chunks",
 			);
-			return false
+			return None
 		},
 	};
 
-	let branches = branches(&chunks);
+	let mut branches = branches(&chunks);
+	if branches.root() != params.erasure_root {
+		return None
+	}
 
-	branches.root() == *expected_root
+	let own_chunk = params.own_index.and_then(|index| {
+		let (proof, chunk) = branches.nth(index.0 as usize)?;
+		Some(ErasureChunk { chunk: chunk.to_vec(), index, proof })
+	});
+	Some(own_chunk)
 }
 
 impl<Sender> RecoveryTask<Sender>
 where
 	Sender: overseer::AvailabilityRecoverySenderTrait,
 {
-	async fn run(mut self) -> Result<AvailableData, RecoveryError> {
-		// First just see if we have the data available locally.
-		{
-			let (tx, rx) = oneshot::channel();
-			self.sender
-				.send_message(AvailabilityStoreMessage::QueryAvailableData(
-					self.params.candidate_hash,
-					tx,
-				))
-				.await;
+	async fn run(mut self) -> Result<Recovered, RecoveryError> {
+		let mut started = false;
 
-			match rx.await {
-				Ok(Some(data)) => return Ok(data),
-				Ok(None) => {},
-				Err(oneshot::Canceled) => {
-					gum::warn!(
-						target: LOG_TARGET,
-						candidate_hash = ?self.params.candidate_hash,
-						"Failed to reach the availability store",
-					)
-				},
+		while let Some(mut strategy) = self.strategies.pop_front() {
+			gum::trace!(
+				target: LOG_TARGET,
+				candidate_hash = ?self.params.candidate_hash,
+				strategy = strategy.display_name(),
+				"Starting recovery strategy",
+			);
+
+			if !started && !strategy.is_local() {
+				self.params.metrics.on_recovery_started();
+				started = true;
+			}
+
+			match strategy.run(&mut self.state, &self.params, &mut self.sender).await {
+				Err(RecoveryError::Unavailable) => gum::debug!(
+					target: LOG_TARGET,
+					candidate_hash = ?self.params.candidate_hash,
+					strategy = strategy.display_name(),
+					"Recovery strategy could not obtain the data",
+				),
+				result => return result,
 			}
 		}
 
-		self.params.metrics.on_recovery_started();
+		if started {
+			self.params.metrics.on_recovery_failed();
+		}
+		Err(RecoveryError::Unavailable)
+	}
+}
 
-		loop {
-			// These only fail if we cannot reach the underlying subsystem, which case there is nothing
-			// meaningful we can do.
-			match self.source {
-				Source::RequestFromBackers(ref mut from_backers) => {
-					match from_backers.run(&self.params, &mut self.sender).await {
-						Ok(data) => break Ok(data),
-						Err(RecoveryError::Invalid) => break Err(RecoveryError::Invalid),
-						Err(RecoveryError::Unavailable) =>
-							self.source = Source::RequestChunks(RequestChunksFromValidators::new(
-								self.params.validators.len() as _,
-							)),
-					}
-				},
-				Source::RequestChunks(ref mut from_all) =>
-					break from_all.run(&self.params, &mut self.sender).await,
-			}
+/// Sets up the strategies of the given kinds, leaving out the ones which don't apply to the
+/// candidate.
+fn recovery_strategies<Sender>(
+	kinds: &[RecoveryStrategyKind],
+	session_info: &SessionInfo,
+	backing_group: Option<GroupIndex>,
+	authority_stats: &AuthorityStats,
+) -> error::Result<VecDeque<Box<dyn RecoveryStrategy<Sender>>>>
+where
+	Sender: overseer::AvailabilityRecoverySenderTrait,
+{
+	let n_validators = session_info.validators.len();
+	let mut strategies: VecDeque<Box<dyn RecoveryStrategy<Sender>>> = VecDeque::new();
+
+	for kind in kinds {
+		match kind {
+			RecoveryStrategyKind::LocalStore => strategies.push_back(Box::new(FromLocalStore)),
+			RecoveryStrategyKind::FullFromBackers => {
+				if let Some(group) =
+					backing_group.and_then(|g| session_info.validator_groups.get(g.0 as usize))
+				{
+					strategies.push_back(Box::new(RequestFromBackers::new(group.clone())))
+				}
+			},
+			RecoveryStrategyKind::SystematicChunks => strategies.push_back(Box::new(
+				RequestSystematicChunks::new(systematic_recovery_threshold(n_validators)?),
+			)),
+			// Only the validators hold chunks, the discovery keys past them are of the inactive
+			// authorities.
+			RecoveryStrategyKind::Chunks =>
+				strategies.push_back(Box::new(RequestChunksFromValidators::new(
+					authority_stats.request_order(&session_info.discovery_keys[..n_validators]),
+				))),
 		}
 	}
+
+	Ok(strategies)
 }
 
 /// Accumulate all awaiting sides for some particular `AvailableData`.
 struct RecoveryHandle {
 	candidate_hash: CandidateHash,
-	remote: RemoteHandle<Result<AvailableData, RecoveryError>>,
+	remote: RemoteHandle<Result<Recovered, RecoveryError>>,
 	awaiting: Vec<oneshot::Sender<Result<AvailableData, RecoveryError>>>,
 }
 
 impl Future for RecoveryHandle {
-	type Output = Option<(CandidateHash, Result<AvailableData, RecoveryError>)>;
+	/// The recovered data, along with our own chunk to store if recovered chunks are served.
+	type Output =
+		Option<(CandidateHash, Option<ErasureChunk>, Result<AvailableData, RecoveryError>)>;
 
 	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
 		let mut indices_to_remove = Vec::new();
@@ -755,13 +1260,16 @@ impl Future for RecoveryHandle {
 
 		let remote = &mut self.remote;
 		futures::pin_mut!(remote);
-		let result = futures::ready!(remote.poll(cx));
+		let (result, own_chunk) = match futures::ready!(remote.poll(cx)) {
+			Ok((data, own_chunk)) => (Ok(data), own_chunk),
+			Err(err) => (Err(err), None),
+		};
 
 		for awaiting in self.awaiting.drain(..) {
 			let _ = awaiting.send(result.clone());
 		}
 
-		Poll::Ready(Some((self.candidate_hash, result)))
+		Poll::Ready(Some((self.candidate_hash, own_chunk, result)))
 	}
 }
 
@@ -798,6 +1306,9 @@ impl TryFrom<Result<AvailableData, RecoveryError>> for CachedRecovery {
 }
 
 struct State {
+	/// The strategies to recover with, unless the request specifies its own.
+	default_strategies: Vec<RecoveryStrategyKind>,
+
 	/// Each recovery task is implemented as its own async task,
 	/// and these handles are for communicating with them.
 	ongoing_recoveries: FuturesUnordered<RecoveryHandle>,
@@ -807,14 +1318,23 @@ struct State {
 
 	/// An LRU cache of recently recovered data.
 	availability_lru: LruCache<CandidateHash, CachedRecovery>,
+
+	/// Statistics of the authorities we requested chunks from, kept across recoveries.
+	authority_stats: AuthorityStats,
+
+	/// The keystore to find our own chunk index with, if recovered chunks are to be served.
+	keystore: Option<SyncCryptoStorePtr>,
 }
 
 impl Default for State {
 	fn default() -> Self {
 		Self {
+			default_strategies: Vec::new(),
 			ongoing_recoveries: FuturesUnordered::new(),
 			live_block: (0, Hash::default()),
 			availability_lru: LruCache::new(LRU_SIZE),
+			authority_stats: AuthorityStats::default(),
+			keystore: None,
 		}
 	}
 }
@@ -856,11 +1376,19 @@ async fn launch_recovery_task<Context>(
 	session_info: SessionInfo,
 	receipt: CandidateReceipt,
 	backing_group: Option<GroupIndex>,
+	policy: RecoveryPolicy,
 	response_sender: oneshot::Sender<Result<AvailableData, RecoveryError>>,
 	metrics: &Metrics,
 ) -> error::Result<()> {
 	let candidate_hash = receipt.hash();
 
+	let own_index = match state.keystore {
+		Some(ref keystore) => signing_key_and_index(&session_info.validators, keystore)
+			.await
+			.map(|(_, index)| index),
+		None => None,
+	};
+
 	let params = RecoveryParams {
 		validator_authority_keys: session_info.discovery_keys.clone(),
 		validators: session_info.validators.clone(),
@@ -868,16 +1396,24 @@ async fn launch_recovery_task<Context>(
 		candidate_hash,
 		erasure_root: receipt.descriptor.erasure_root,
 		metrics: metrics.clone(),
+		authority_stats: state.authority_stats.clone(),
+		own_index,
 	};
 
-	let phase = backing_group
-		.and_then(|g| session_info.validator_groups.get(g.0 as usize))
-		.map(|group| Source::RequestFromBackers(RequestFromBackers::new(group.clone())))
-		.unwrap_or_else(|| {
-			Source::RequestChunks(RequestChunksFromValidators::new(params.validators.len() as _))
-		});
-
-	let recovery_task = RecoveryTask { sender: ctx.sender().clone(), params, source: phase };
+	let kinds = match policy {
+		RecoveryPolicy::Default => state.default_strategies.clone(),
+		RecoveryPolicy::Custom(kinds) => kinds,
+	};
+	let strategies =
+		recovery_strategies(&kinds, &session_info, backing_group, &state.authority_stats)?;
+	metrics.on_authority_stats(&state.authority_stats.summary());
+
+	let recovery_task = RecoveryTask {
+		sender: ctx.sender().clone(),
+		params,
+		strategies,
+		state: RecoveryTaskState::default(),
+	};
 
 	let (remote, remote_handle) = recovery_task.run().remote_handle();
 
@@ -906,6 +1442,7 @@ async fn handle_recover<Context>(
 	receipt: CandidateReceipt,
 	session_index: SessionIndex,
 	backing_group: Option<GroupIndex>,
+	policy: RecoveryPolicy,
 	response_sender: oneshot::Sender<Result<AvailableData, RecoveryError>>,
 	metrics: &Metrics,
 ) -> error::Result<()> {
@@ -949,6 +1486,7 @@ async fn handle_recover<Context>(
 				session_info,
 				receipt,
 				backing_group,
+				policy,
 				response_sender,
 				metrics,
 			)
@@ -963,6 +1501,57 @@ async fn handle_recover<Context>(
 	}
 }
 
+/// Stores our own chunk of a recovered candidate, unless the availability store has it already,
+/// so we can serve it to the validators requesting it.
+async fn store_own_chunk<Sender>(
+	mut sender: Sender,
+	candidate_hash: CandidateHash,
+	chunk: ErasureChunk,
+	metrics: Metrics,
+) where
+	Sender: overseer::AvailabilityRecoverySenderTrait,
+{
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(AvailabilityStoreMessage::QueryChunkAvailability(
+			candidate_hash,
+			chunk.index,
+			tx,
+		))
+		.await;
+	if rx.await.unwrap_or(true) {
+		return
+	}
+
+	let validator_index = chunk.index;
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(AvailabilityStoreMessage::StoreChunk { candidate_hash, chunk, tx })
+		.await;
+	match rx.await {
+		Ok(Ok(())) => {
+			gum::trace!(
+				target: LOG_TARGET,
+				?candidate_hash,
+				?validator_index,
+				"Stored our own chunk of the recovered data",
+			);
+			metrics.on_own_chunk_stored();
+		},
+		// The availability store only keeps chunks of the candidates it knows about.
+		Ok(Err(())) => gum::debug!(
+			target: LOG_TARGET,
+			?candidate_hash,
+			"Availability store refused our own chunk of the recovered data",
+		),
+		Err(oneshot::Canceled) => gum::debug!(
+			target: LOG_TARGET,
+			?candidate_hash,
+			"Failed to reach the availability store",
+		),
+	}
+}
+
 /// Queries a chunk from av-store.
 #[overseer::contextbounds(AvailabilityRecovery, prefix = self::overseer)]
 async fn query_full_data<Context>(
@@ -984,7 +1573,15 @@ impl AvailabilityRecoverySubsystem {
 		req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
 		metrics: Metrics,
 	) -> Self {
-		Self { fast_path: true, req_receiver, metrics }
+		Self::with_strategies(
+			req_receiver,
+			vec![
+				RecoveryStrategyKind::LocalStore,
+				RecoveryStrategyKind::FullFromBackers,
+				RecoveryStrategyKind::Chunks,
+			],
+			metrics,
+		)
 	}
 
 	/// Create a new instance of `AvailabilityRecoverySubsystem` which requests only chunks
@@ -992,12 +1589,53 @@ impl AvailabilityRecoverySubsystem {
 		req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
 		metrics: Metrics,
 	) -> Self {
-		Self { fast_path: false, req_receiver, metrics }
+		Self::with_strategies(
+			req_receiver,
+			vec![RecoveryStrategyKind::LocalStore, RecoveryStrategyKind::Chunks],
+			metrics,
+		)
+	}
+
+	/// Create a new instance of `AvailabilityRecoverySubsystem` which requests only chunks,
+	/// starting with the systematic ones. Those are enough to recover the data without decoding
+	/// it, which is much cheaper, so the other chunks are only requested if any systematic chunk
+	/// cannot be fetched.
+	pub fn with_systematic_chunks(
+		req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
+		metrics: Metrics,
+	) -> Self {
+		Self::with_strategies(
+			req_receiver,
+			vec![
+				RecoveryStrategyKind::LocalStore,
+				RecoveryStrategyKind::SystematicChunks,
+				RecoveryStrategyKind::Chunks,
+			],
+			metrics,
+		)
+	}
+
+	/// Create a new instance of `AvailabilityRecoverySubsystem` which tries the given strategies
+	/// in order, unless a request specifies its own with [`RecoveryPolicy::Custom`].
+	pub fn with_strategies(
+		req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
+		default_strategies: Vec<RecoveryStrategyKind>,
+		metrics: Metrics,
+	) -> Self {
+		Self { default_strategies, req_receiver, metrics, keystore: None }
+	}
+
+	/// Store our own chunk of the candidates we recover, if the availability store doesn't have
+	/// it yet, so we can serve it to other validators. This helps the availability of candidates
+	/// whose backers went offline.
+	pub fn serving_recovered_chunks(mut self, keystore: SyncCryptoStorePtr) -> Self {
+		self.keystore = Some(keystore);
+		self
 	}
 
 	async fn run<Context>(self, mut ctx: Context) -> SubsystemResult<()> {
-		let mut state = State::default();
-		let Self { fast_path, mut req_receiver, metrics } = self;
+		let Self { default_strategies, mut req_receiver, metrics, keystore } = self;
+		let mut state = State { default_strategies, keystore, ..Default::default() };
 
 		loop {
 			let recv_req = req_receiver.recv(|| vec![COST_INVALID_REQUEST]).fuse();
@@ -1017,6 +1655,7 @@ impl AvailabilityRecoverySubsystem {
 									receipt,
 									session_index,
 									maybe_backing_group,
+									policy,
 									response_sender,
 								) => {
 									if let Err(e) = handle_recover(
@@ -1024,7 +1663,8 @@ impl AvailabilityRecoverySubsystem {
 										&mut ctx,
 										receipt,
 										session_index,
-										maybe_backing_group.filter(|_| fast_path),
+										maybe_backing_group,
+										policy,
 										response_sender,
 										&metrics,
 									).await {
@@ -1068,7 +1708,22 @@ impl AvailabilityRecoverySubsystem {
 					}
 				}
 				output = state.ongoing_recoveries.select_next_some() => {
-					if let Some((candidate_hash, result)) = output {
+					if let Some((candidate_hash, own_chunk, result)) = output {
+						if let Some(own_chunk) = own_chunk {
+							let store = store_own_chunk(
+								ctx.sender().clone(),
+								candidate_hash,
+								own_chunk,
+								metrics.clone(),
+							);
+							if let Err(e) = ctx.spawn("store-own-chunk", Box::pin(store)) {
+								gum::warn!(
+									target: LOG_TARGET,
+									err = ?e,
+									"Failed to spawn storing our own chunk",
+								);
+							}
+						}
 						if let Ok(recovery) = CachedRecovery::try_from(result) {
 							state.availability_lru.put(candidate_hash, recovery);
 						}
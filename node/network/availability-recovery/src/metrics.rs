@@ -16,9 +16,14 @@
 
 use polkadot_node_subsystem_util::metrics::{
 	self,
-	prometheus::{self, Counter, CounterVec, Histogram, Opts, PrometheusError, Registry, U64},
+	prometheus::{
+		self, Counter, CounterVec, Gauge, GaugeVec, Histogram, Opts, PrometheusError, Registry,
+		F64, U64,
+	},
 };
 
+use crate::authority_stats::Summary;
+
 /// Availability Distribution metrics.
 #[derive(Clone, Default)]
 pub struct Metrics(Option<MetricsInner>);
@@ -58,6 +63,21 @@ struct MetricsInner {
 	/// Note: Those are only recoveries which could not get served locally already - so in other
 	/// words: Only real recoveries.
 	full_recoveries_started: Counter<U64>,
+
+	/// Number of authorities we keep response-time and failure statistics for.
+	authorities_tracked: Gauge<U64>,
+
+	/// Quantiles of the average response time of the tracked authorities, in seconds.
+	authority_response_time: GaugeVec<F64>,
+
+	/// Quantiles of the failure rate of the tracked authorities.
+	authority_failure_rate: GaugeVec<F64>,
+
+	/// Number of our own chunks stored from recovered data.
+	own_chunks_stored: Counter<U64>,
+
+	/// Bytes of received chunks, split by `raw` (decompressed) and `wire` (as received).
+	chunk_bytes: CounterVec<U64>,
 }
 
 impl Metrics {
@@ -150,6 +170,37 @@ impl Metrics {
 			metrics.full_recoveries_started.inc()
 		}
 	}
+
+	/// Our own chunk was stored from the recovered data.
+	pub fn on_own_chunk_stored(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.own_chunks_stored.inc()
+		}
+	}
+
+	/// A chunk of `raw` bytes was received, taking `wire` bytes on the wire.
+	pub fn on_chunk_bytes(&self, raw: usize, wire: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.chunk_bytes.with_label_values(&["raw"]).inc_by(raw as u64);
+			metrics.chunk_bytes.with_label_values(&["wire"]).inc_by(wire as u64);
+		}
+	}
+
+	/// Export the current statistics of the authorities.
+	pub fn on_authority_stats(&self, summary: &Summary) {
+		if let Some(metrics) = &self.0 {
+			metrics.authorities_tracked.set(summary.tracked as u64);
+			for (quantile, response_time) in &summary.response_times {
+				metrics
+					.authority_response_time
+					.with_label_values(&[*quantile])
+					.set(*response_time);
+			}
+			for (quantile, failure_rate) in &summary.failure_rates {
+				metrics.authority_failure_rate.with_label_values(&[*quantile]).set(*failure_rate);
+			}
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -210,6 +261,50 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			authorities_tracked: prometheus::register(
+				Gauge::new(
+					"polkadot_parachain_availability_recovery_authorities_tracked",
+					"Number of authorities with chunk request statistics.",
+				)?,
+				registry,
+			)?,
+			authority_response_time: prometheus::register(
+				GaugeVec::new(
+					Opts::new(
+						"polkadot_parachain_availability_recovery_authority_response_time",
+						"Quantiles of the average chunk response time of the tracked authorities, in seconds.",
+					),
+					&["quantile"],
+				)?,
+				registry,
+			)?,
+			authority_failure_rate: prometheus::register(
+				GaugeVec::new(
+					Opts::new(
+						"polkadot_parachain_availability_recovery_authority_failure_rate",
+						"Quantiles of the chunk request failure rate of the tracked authorities.",
+					),
+					&["quantile"],
+				)?,
+				registry,
+			)?,
+			own_chunks_stored: prometheus::register(
+				Counter::new(
+					"polkadot_parachain_availability_recovery_own_chunks_stored",
+					"Total number of our own chunks stored from recovered data.",
+				)?,
+				registry,
+			)?,
+			chunk_bytes: prometheus::register(
+				CounterVec::new(
+					Opts::new(
+						"polkadot_parachain_availability_recovery_chunk_bytes",
+						"Total bytes of received chunks, decompressed (raw) and as received (wire).",
+					),
+					&["kind"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
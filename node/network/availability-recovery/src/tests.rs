@@ -38,6 +38,8 @@ use polkadot_node_subsystem_test_helpers::{make_subsystem_context, TestSubsystem
 use polkadot_node_subsystem_util::TimeoutExt;
 use polkadot_primitives::v2::{AuthorityDiscoveryId, Hash, HeadData, PersistedValidationData};
 use polkadot_primitives_test_helpers::{dummy_candidate_receipt, dummy_hash};
+use sp_application_crypto::AppKey;
+use sp_keystore::SyncCryptoStore;
 
 type VirtualOverseer = TestSubsystemContextHandle<AvailabilityRecoveryMessage>;
 
@@ -115,6 +117,90 @@ fn test_harness_chunks_only<T: Future<Output = (VirtualOverseer, RequestResponse
 	.unwrap();
 }
 
+fn test_harness_systematic_chunks<T: Future<Output = (VirtualOverseer, RequestResponseConfig)>>(
+	test: impl FnOnce(VirtualOverseer, RequestResponseConfig) -> T,
+) {
+	let _ = env_logger::builder()
+		.is_test(true)
+		.filter(Some("polkadot_availability_recovery"), log::LevelFilter::Trace)
+		.try_init();
+
+	let pool = sp_core::testing::TaskExecutor::new();
+
+	let (context, virtual_overseer) = make_subsystem_context(pool.clone());
+
+	let (collation_req_receiver, req_cfg) =
+		IncomingRequest::get_config_receiver(&ReqProtocolNames::new(&GENESIS_HASH, None));
+	let subsystem = AvailabilityRecoverySubsystem::with_systematic_chunks(
+		collation_req_receiver,
+		Metrics::new_dummy(),
+	);
+	let subsystem = subsystem.run(context);
+
+	let test_fut = test(virtual_overseer, req_cfg);
+
+	futures::pin_mut!(test_fut);
+	futures::pin_mut!(subsystem);
+
+	executor::block_on(future::join(
+		async move {
+			let (mut overseer, _req_cfg) = test_fut.await;
+			overseer_signal(&mut overseer, OverseerSignal::Conclude).await;
+		},
+		subsystem,
+	))
+	.1
+	.unwrap();
+}
+
+fn test_harness_serving_recovered_chunks<
+	T: Future<Output = (VirtualOverseer, RequestResponseConfig)>,
+>(
+	test: impl FnOnce(VirtualOverseer, RequestResponseConfig) -> T,
+) {
+	let _ = env_logger::builder()
+		.is_test(true)
+		.filter(Some("polkadot_availability_recovery"), log::LevelFilter::Trace)
+		.try_init();
+
+	let pool = sp_core::testing::TaskExecutor::new();
+
+	let (context, virtual_overseer) = make_subsystem_context(pool.clone());
+
+	// This node is `Ferdie`, the first validator.
+	let keystore = Arc::new(sc_keystore::LocalKeystore::in_memory());
+	SyncCryptoStore::sr25519_generate_new(
+		&*keystore,
+		ValidatorId::ID,
+		Some(&Sr25519Keyring::Ferdie.to_seed()),
+	)
+	.expect("Insert key into keystore");
+
+	let (collation_req_receiver, req_cfg) =
+		IncomingRequest::get_config_receiver(&ReqProtocolNames::new(&GENESIS_HASH, None));
+	let subsystem = AvailabilityRecoverySubsystem::with_chunks_only(
+		collation_req_receiver,
+		Metrics::new_dummy(),
+	)
+	.serving_recovered_chunks(keystore);
+	let subsystem = subsystem.run(context);
+
+	let test_fut = test(virtual_overseer, req_cfg);
+
+	futures::pin_mut!(test_fut);
+	futures::pin_mut!(subsystem);
+
+	executor::block_on(future::join(
+		async move {
+			let (mut overseer, _req_cfg) = test_fut.await;
+			overseer_signal(&mut overseer, OverseerSignal::Conclude).await;
+		},
+		subsystem,
+	))
+	.1
+	.unwrap();
+}
+
 const TIMEOUT: Duration = Duration::from_millis(300);
 
 macro_rules! delay {
@@ -163,6 +249,8 @@ enum Has {
 	No,
 	Yes,
 	NetworkError(sc_network::RequestFailure),
+	/// Has the chunk, but doesn't support `ChunkFetchingV2`.
+	V1Only,
 	/// Make request not return at all, instead the sender is returned from the function.
 	///
 	/// Note, if you use `DoesNotReturn` you have to keep the returned senders alive, otherwise the
@@ -197,6 +285,10 @@ impl TestState {
 		recovery_threshold(self.validators.len()).unwrap()
 	}
 
+	fn systematic_threshold(&self) -> usize {
+		systematic_recovery_threshold(self.validators.len()).unwrap()
+	}
+
 	fn impossibility_threshold(&self) -> usize {
 		self.validators.len() - self.threshold() + 1
 	}
@@ -316,29 +408,43 @@ impl TestState {
 				) => {
 					for req in requests {
 						i += 1;
-						assert_matches!(
-							req,
-							Requests::ChunkFetchingV1(req) => {
-								assert_eq!(req.payload.candidate_hash, candidate_hash);
-
-								let validator_index = req.payload.index.0 as usize;
-								let available_data = match who_has(validator_index) {
-									Has::No => Ok(None),
-									Has::Yes => Ok(Some(self.chunks[validator_index].clone().into())),
-									Has::NetworkError(e) => Err(e),
-									Has::DoesNotReturn => {
-										senders.push(req.pending_response);
-										continue
-									}
-								};
-
-								let _ = req.pending_response.send(
-									available_data.map(|r|
-										req_res::v1::ChunkFetchingResponse::from(r).encode()
-									)
-								);
+						let (payload, pending_response, is_v1) = match req {
+							Requests::ChunkFetchingV2(req) =>
+								(req.payload, req.pending_response, false),
+							Requests::ChunkFetchingV1(req) => (
+								req_res::v2::ChunkFetchingRequest {
+									accepted_compressions: Vec::new(),
+									..req.payload.into()
+								},
+								req.pending_response,
+								true,
+							),
+							_ => panic!("Unexpected request"),
+						};
+						assert_eq!(payload.candidate_hash, candidate_hash);
+
+						let validator_index = payload.index.0 as usize;
+						let available_data = match who_has(validator_index) {
+							Has::No => Ok(None),
+							Has::Yes => Ok(Some(self.chunks[validator_index].clone())),
+							Has::NetworkError(e) => Err(e),
+							Has::V1Only if is_v1 => Ok(Some(self.chunks[validator_index].clone())),
+							Has::V1Only => Err(RequestFailure::Network(
+								sc_network::OutboundFailure::UnsupportedProtocols,
+							)),
+							Has::DoesNotReturn => {
+								senders.push(pending_response);
+								continue
 							}
-						)
+						};
+
+						let _ = pending_response.send(available_data.map(|chunk| {
+							req_res::v2::ChunkFetchingResponse::new(
+								chunk,
+								&payload.accepted_compressions,
+							)
+							.encode()
+						}));
 					}
 				}
 			);
@@ -534,6 +640,7 @@ fn availability_is_recovered_from_chunks_if_no_group_provided() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -571,6 +678,7 @@ fn availability_is_recovered_from_chunks_if_no_group_provided() {
 				new_candidate.clone(),
 				test_state.session_index,
 				None,
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -620,6 +728,7 @@ fn availability_is_recovered_from_chunks_even_if_backing_group_supplied_if_chunk
 				test_state.candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -657,6 +766,7 @@ fn availability_is_recovered_from_chunks_even_if_backing_group_supplied_if_chunk
 				new_candidate.clone(),
 				test_state.session_index,
 				None,
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -682,6 +792,249 @@ fn availability_is_recovered_from_chunks_even_if_backing_group_supplied_if_chunk
 	});
 }
 
+#[test]
+fn availability_is_recovered_from_systematic_chunks() {
+	let test_state = TestState::default();
+
+	test_harness_systematic_chunks(|mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: test_state.current.clone(),
+				number: 1,
+				status: LeafStatus::Fresh,
+				span: Arc::new(jaeger::Span::Disabled),
+			})),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				Some(GroupIndex(0)),
+				RecoveryPolicy::Default,
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+		let systematic_threshold = test_state.systematic_threshold();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		// Only the validators holding the systematic chunks are asked.
+		test_state
+			.test_chunk_requests(candidate_hash, &mut virtual_overseer, systematic_threshold, |i| {
+				assert!(i < systematic_threshold);
+				Has::Yes
+			})
+			.await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		(virtual_overseer, req_cfg)
+	});
+}
+
+#[test]
+fn validators_not_supporting_v2_are_asked_via_v1() {
+	let test_state = TestState::default();
+
+	test_harness_systematic_chunks(|mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: test_state.current.clone(),
+				number: 1,
+				status: LeafStatus::Fresh,
+				span: Arc::new(jaeger::Span::Disabled),
+			})),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				None,
+				RecoveryPolicy::Default,
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+		let systematic_threshold = test_state.systematic_threshold();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		// Every validator is asked twice, the second time via `ChunkFetchingV1`, without giving
+		// up on the systematic chunks.
+		test_state
+			.test_chunk_requests(
+				candidate_hash,
+				&mut virtual_overseer,
+				2 * systematic_threshold,
+				|i| {
+					assert!(i < systematic_threshold);
+					Has::V1Only
+				},
+			)
+			.await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		(virtual_overseer, req_cfg)
+	});
+}
+
+#[test]
+fn systematic_chunk_is_requested_again_after_network_error() {
+	let test_state = TestState::default();
+
+	test_harness_systematic_chunks(|mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: test_state.current.clone(),
+				number: 1,
+				status: LeafStatus::Fresh,
+				span: Arc::new(jaeger::Span::Disabled),
+			})),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				None,
+				RecoveryPolicy::Default,
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+		let systematic_threshold = test_state.systematic_threshold();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		// The first request to the first validator times out, without giving up on the
+		// systematic chunks.
+		let timed_out = std::sync::atomic::AtomicBool::new(false);
+		test_state
+			.test_chunk_requests(
+				candidate_hash,
+				&mut virtual_overseer,
+				systematic_threshold + 1,
+				|i| {
+					assert!(i < systematic_threshold);
+					if i == 0 && !timed_out.swap(true, std::sync::atomic::Ordering::SeqCst) {
+						Has::timeout()
+					} else {
+						Has::Yes
+					}
+				},
+			)
+			.await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		(virtual_overseer, req_cfg)
+	});
+}
+
+#[test]
+fn missing_systematic_chunk_falls_back_to_regular_chunks() {
+	let test_state = TestState::default();
+
+	test_harness_systematic_chunks(|mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: test_state.current.clone(),
+				number: 1,
+				status: LeafStatus::Fresh,
+				span: Arc::new(jaeger::Span::Disabled),
+			})),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				None,
+				RecoveryPolicy::Default,
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+		let systematic_threshold = test_state.systematic_threshold();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		// The first systematic chunk never arrives. Its request is taken over by the regular
+		// chunks, so the validator isn't asked again.
+		let _senders = test_state
+			.test_chunk_requests(candidate_hash, &mut virtual_overseer, systematic_threshold, |i| {
+				if i == 0 {
+					Has::DoesNotReturn
+				} else {
+					Has::Yes
+				}
+			})
+			.await;
+
+		// The systematic chunks received so far count towards the regular recovery.
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+		test_state
+			.test_chunk_requests(
+				candidate_hash,
+				&mut virtual_overseer,
+				test_state.threshold() - (systematic_threshold - 1),
+				|i| {
+					assert!(i == 0 || i >= systematic_threshold);
+					Has::Yes
+				},
+			)
+			.await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		(virtual_overseer, req_cfg)
+	});
+}
+
 #[test]
 fn bad_merkle_path_leads_to_recovery_error() {
 	let mut test_state = TestState::default();
@@ -706,6 +1059,7 @@ fn bad_merkle_path_leads_to_recovery_error() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -764,6 +1118,7 @@ fn wrong_chunk_index_leads_to_recovery_error() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -837,6 +1192,7 @@ fn invalid_erasure_coding_leads_to_invalid_error() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -886,6 +1242,7 @@ fn fast_path_backing_group_recovers() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -936,6 +1293,7 @@ fn no_answers_in_fast_path_causes_chunk_requests() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -974,6 +1332,107 @@ fn no_answers_in_fast_path_causes_chunk_requests() {
 	});
 }
 
+#[test]
+fn custom_policy_overrides_default_strategies() {
+	let test_state = TestState::default();
+
+	test_harness_fast_path(|mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: test_state.current.clone(),
+				number: 1,
+				status: LeafStatus::Fresh,
+				span: Arc::new(jaeger::Span::Disabled),
+			})),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				Some(GroupIndex(0)),
+				RecoveryPolicy::Custom(vec![RecoveryStrategyKind::Chunks]),
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		// Neither the local store nor the backers are asked for the full data.
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		test_state
+			.test_chunk_requests(
+				candidate_hash,
+				&mut virtual_overseer,
+				test_state.threshold(),
+				|_| Has::Yes,
+			)
+			.await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		(virtual_overseer, req_cfg)
+	});
+}
+
+#[test]
+fn recovery_is_unavailable_once_all_strategies_fail() {
+	let test_state = TestState::default();
+
+	test_harness_chunks_only(|mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: test_state.current.clone(),
+				number: 1,
+				status: LeafStatus::Fresh,
+				span: Arc::new(jaeger::Span::Disabled),
+			})),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				Some(GroupIndex(0)),
+				RecoveryPolicy::Custom(vec![
+					RecoveryStrategyKind::LocalStore,
+					RecoveryStrategyKind::FullFromBackers,
+				]),
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+
+		test_state
+			.test_full_data_requests(candidate_hash, &mut virtual_overseer, |_| Has::No)
+			.await;
+
+		// No chunks are requested after the backers.
+		assert_eq!(rx.await.unwrap().unwrap_err(), RecoveryError::Unavailable);
+		(virtual_overseer, req_cfg)
+	});
+}
+
 #[test]
 fn task_canceled_when_receivers_dropped() {
 	let test_state = TestState::default();
@@ -998,6 +1457,7 @@ fn task_canceled_when_receivers_dropped() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -1040,6 +1500,7 @@ fn chunks_retry_until_all_nodes_respond() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -1101,6 +1562,7 @@ fn not_returning_requests_wont_stall_retrieval() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -1173,6 +1635,7 @@ fn all_not_returning_requests_still_recovers_on_return() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				Some(GroupIndex(0)),
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -1250,6 +1713,7 @@ fn returns_early_if_we_have_the_data() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -1287,6 +1751,7 @@ fn does_not_query_local_validator() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -1346,6 +1811,7 @@ fn invalid_local_chunk_is_ignored() {
 				test_state.candidate.clone(),
 				test_state.session_index,
 				None,
+				RecoveryPolicy::Default,
 				tx,
 			),
 		)
@@ -1377,7 +1843,7 @@ fn invalid_local_chunk_is_ignored() {
 fn parallel_request_calculation_works_as_expected() {
 	let num_validators = 100;
 	let threshold = recovery_threshold(num_validators).unwrap();
-	let mut phase = RequestChunksFromValidators::new(100);
+	let mut phase = RequestChunksFromValidators::new((0..100).map(ValidatorIndex).collect());
 	assert_eq!(phase.get_desired_request_count(threshold), threshold);
 	phase.error_count = 1;
 	phase.total_received_responses = 1;
@@ -1403,3 +1869,78 @@ fn parallel_request_calculation_works_as_expected() {
 	// With error count zero - we should fetch exactly as needed:
 	assert_eq!(phase.get_desired_request_count(threshold), threshold - phase.received_chunks.len());
 }
+
+#[test]
+fn own_chunk_is_stored_after_recovery() {
+	let test_state = TestState::default();
+
+	test_harness_serving_recovered_chunks(|mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: test_state.current.clone(),
+				number: 1,
+				status: LeafStatus::Fresh,
+				span: Arc::new(jaeger::Span::Disabled),
+			})),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				None,
+				RecoveryPolicy::Default,
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		test_state
+			.test_chunk_requests(
+				candidate_hash,
+				&mut virtual_overseer,
+				test_state.threshold(),
+				|_| Has::Yes,
+			)
+			.await;
+
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::AvailabilityStore(
+				AvailabilityStoreMessage::QueryChunkAvailability(hash, index, tx)
+			) => {
+				assert_eq!(hash, candidate_hash);
+				assert_eq!(index, ValidatorIndex(0));
+				let _ = tx.send(false);
+			}
+		);
+
+		// Our own chunk is taken from the re-encoding the recovered data was checked with.
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::AvailabilityStore(
+				AvailabilityStoreMessage::StoreChunk { candidate_hash: hash, chunk, tx }
+			) => {
+				assert_eq!(hash, candidate_hash);
+				assert_eq!(chunk, test_state.chunks[0]);
+				let _ = tx.send(Ok(()));
+			}
+		);
+
+		(virtual_overseer, req_cfg)
+	});
+}
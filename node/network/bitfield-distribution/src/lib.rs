@@ -19,6 +19,10 @@
 //! In case this node is a validator, gossips its own signed availability bitfield
 //! for a particular relay parent.
 //! Independently of that, gossips on received messages from peers to other interested peers.
+//!
+//! A validator may re-sign its bitfield for the same relay parent once more chunks became
+//! available. Such a bitfield replaces the earlier one if it is a strict superset of it, and is
+//! gossiped as a new message.
 
 #![deny(unused_crate_dependencies)]
 
@@ -109,9 +113,12 @@ struct PerRelayParentData {
 	validator_set: Vec<ValidatorId>,
 
 	/// Set of validators for a particular relay parent for which we
-	/// received a valid `BitfieldGossipMessage`.
+	/// received a valid `BitfieldGossipMessage`, or sent our own.
 	/// Also serves as the list of known messages for peers connecting
 	/// after bitfield gossips were already received.
+	///
+	/// Only the latest message of each validator is kept, which is a strict
+	/// superset of any earlier one.
 	one_per_validator: HashMap<ValidatorId, BitfieldGossipMessage>,
 
 	/// Avoid duplicate message transmission to our peers.
@@ -158,6 +165,17 @@ impl PerRelayParentData {
 				.map(|pubkeys| !pubkeys.contains(signed_by))
 				.unwrap_or(true)
 	}
+
+	/// Forget which peers sent or got the message signed by the validator, as it got replaced by
+	/// a new one none of them has yet.
+	fn forget_message_from_validator(&mut self, signed_by: &ValidatorId) {
+		for pubkeys in self.message_sent_to_peer.values_mut() {
+			pubkeys.remove(signed_by);
+		}
+		for pubkeys in self.message_received_from_peer.values_mut() {
+			pubkeys.remove(signed_by);
+		}
+	}
 }
 
 const LOG_TARGET: &str = "parachain::bitfield-distribution";
@@ -326,7 +344,25 @@ async fn handle_bitfield_distribution<Context>(
 		return
 	};
 
+	// Our bitfield gets re-signed once more chunks are available, replacing the earlier one.
+	if let Some(old_message) = job_data.one_per_validator.get(&validator) {
+		if !signed_availability
+			.payload()
+			.is_strict_superset_of(old_message.signed_availability.payload())
+		{
+			gum::debug!(
+				target: LOG_TARGET,
+				?relay_parent,
+				"Own bitfield is not a strict superset of the one sent before",
+			);
+			return
+		}
+		job_data.forget_message_from_validator(&validator);
+	}
+
 	let msg = BitfieldGossipMessage { relay_parent, signed_availability };
+	job_data.one_per_validator.insert(validator.clone(), msg.clone());
+
 	let topology = state.topologies.get_topology_or_fallback(session_idx).local_grid_neighbors();
 	let required_routing = topology.required_routing_by_index(validator_index, true);
 
@@ -494,6 +530,13 @@ async fn process_incoming_peer_message<Context>(
 		return
 	};
 
+	// A strict superset of the message we have from the validator replaces it.
+	let is_replacement = job_data.one_per_validator.get(&validator).map_or(false, |old_message| {
+		bitfield
+			.unchecked_payload()
+			.is_strict_superset_of(old_message.signed_availability.payload())
+	});
+
 	// Check if the peer already sent us a message for the validator denoted in the message earlier.
 	// Must be done after validator index verification, in order to avoid storing an unbounded
 	// number of set entries.
@@ -501,25 +544,25 @@ async fn process_incoming_peer_message<Context>(
 
 	if !received_set.contains(&validator) {
 		received_set.insert(validator.clone());
-	} else {
+	} else if !is_replacement {
 		gum::trace!(target: LOG_TARGET, ?validator_index, ?origin, "Duplicate message");
 		modify_reputation(ctx.sender(), relay_parent, origin, COST_PEER_DUPLICATE_MESSAGE).await;
 		return
 	};
 
-	let one_per_validator = &mut (job_data.one_per_validator);
-
-	// relay a message received from a validator at most _once_
-	if let Some(old_message) = one_per_validator.get(&validator) {
-		gum::trace!(
-			target: LOG_TARGET,
-			?validator_index,
-			"already received a message for validator",
-		);
-		if old_message.signed_availability.as_unchecked() == &bitfield {
-			modify_reputation(ctx.sender(), relay_parent, origin, BENEFIT_VALID_MESSAGE).await;
+	// relay a message received from a validator at most _once_, unless it gets replaced
+	if let Some(old_message) = job_data.one_per_validator.get(&validator) {
+		if !is_replacement {
+			gum::trace!(
+				target: LOG_TARGET,
+				?validator_index,
+				"already received a message for validator",
+			);
+			if old_message.signed_availability.as_unchecked() == &bitfield {
+				modify_reputation(ctx.sender(), relay_parent, origin, BENEFIT_VALID_MESSAGE).await;
+			}
+			return
 		}
-		return
 	}
 	let signed_availability = match bitfield.try_into_checked(&signing_context, &validator) {
 		Err(_) => {
@@ -529,6 +572,21 @@ async fn process_incoming_peer_message<Context>(
 		Ok(bitfield) => bitfield,
 	};
 
+	if is_replacement {
+		gum::trace!(
+			target: LOG_TARGET,
+			?validator_index,
+			"received a replacement of the message for validator",
+		);
+		metrics.on_bitfield_replaced();
+		job_data.forget_message_from_validator(&validator);
+		job_data
+			.message_received_from_peer
+			.entry(origin.clone())
+			.or_default()
+			.insert(validator.clone());
+	}
+
 	let message = BitfieldGossipMessage { relay_parent, signed_availability };
 
 	let topology = state
@@ -538,7 +596,7 @@ async fn process_incoming_peer_message<Context>(
 	let required_routing = topology.required_routing_by_index(validator_index, false);
 
 	metrics.on_bitfield_received();
-	one_per_validator.insert(validator.clone(), message.clone());
+	job_data.one_per_validator.insert(validator.clone(), message.clone());
 
 	relay_message(
 		ctx,
@@ -20,6 +20,7 @@ use polkadot_node_subsystem_util::metrics::{prometheus, Metrics as MetricsTrait}
 struct MetricsInner {
 	sent_own_availability_bitfields: prometheus::Counter<prometheus::U64>,
 	received_availability_bitfields: prometheus::Counter<prometheus::U64>,
+	replaced_availability_bitfields: prometheus::Counter<prometheus::U64>,
 	active_leaves_update: prometheus::Histogram,
 	handle_bitfield_distribution: prometheus::Histogram,
 	handle_network_msg: prometheus::Histogram,
@@ -42,6 +43,12 @@ impl Metrics {
 		}
 	}
 
+	pub(crate) fn on_bitfield_replaced(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.replaced_availability_bitfields.inc();
+		}
+	}
+
 	/// Provide a timer for `active_leaves_update` which observes on drop.
 	pub(crate) fn time_active_leaves_update(
 		&self,
@@ -81,6 +88,13 @@ impl MetricsTrait for Metrics {
 				)?,
 				registry,
 			)?,
+			replaced_availability_bitfields: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_replaced_availabilty_bitfields_total",
+					"Number of received availability bitfields which replaced an earlier one of the same validator.",
+				)?,
+				registry,
+			)?,
 			active_leaves_update: prometheus::register(
 				prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
 					"polkadot_parachain_bitfield_distribution_active_leaves_update",
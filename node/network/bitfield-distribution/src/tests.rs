@@ -417,6 +417,176 @@ fn receive_duplicate_messages() {
 	});
 }
 
+/// Sign a bitfield with the first `available` bits set out of 32.
+fn sign_bitfield(
+	keystore: &SyncCryptoStorePtr,
+	signing_context: &SigningContext,
+	validator: &ValidatorId,
+	available: usize,
+) -> SignedAvailabilityBitfield {
+	let mut bits = bitvec![u8, bitvec::order::Lsb0; 0u8; 32];
+	for i in 0..available {
+		bits.set(i, true);
+	}
+	executor::block_on(Signed::<AvailabilityBitfield>::sign(
+		keystore,
+		AvailabilityBitfield(bits),
+		signing_context,
+		ValidatorIndex(0),
+		validator,
+	))
+	.ok()
+	.flatten()
+	.expect("should be signed")
+}
+
+#[test]
+fn receive_replacement_messages() {
+	let hash: Hash = [0; 32].into();
+
+	let peer_a = PeerId::random();
+	let peer_b = PeerId::random();
+	assert_ne!(peer_a, peer_b);
+
+	// validator 0 key pair
+	let (mut state, signing_context, keystore, validator) =
+		state_with_view(our_view![hash], hash.clone());
+	state.peer_views.insert(peer_a.clone(), view![hash]);
+
+	let old_bitfield = sign_bitfield(&keystore, &signing_context, &validator, 16);
+	let new_bitfield = sign_bitfield(&keystore, &signing_context, &validator, 20);
+	let old_msg = BitfieldGossipMessage { relay_parent: hash, signed_availability: old_bitfield };
+	let new_msg = BitfieldGossipMessage { relay_parent: hash, signed_availability: new_bitfield };
+
+	let pool = sp_core::testing::TaskExecutor::new();
+	let (mut ctx, mut handle) = make_subsystem_context::<BitfieldDistributionMessage, _>(pool);
+	let mut rng = dummy_rng();
+
+	executor::block_on(async move {
+		// Peer B sends the old message and then its replacement, both of which get relayed to
+		// peer A.
+		for msg in [&old_msg, &new_msg] {
+			launch!(handle_network_msg(
+				&mut ctx,
+				&mut state,
+				&Default::default(),
+				NetworkBridgeEvent::PeerMessage(peer_b.clone(), msg.clone().into_network_message()),
+				&mut rng,
+			));
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::Provisioner(ProvisionerMessage::ProvisionableData(
+					_,
+					ProvisionableData::Bitfield(h, signed)
+				)) => {
+					assert_eq!(h, hash);
+					assert_eq!(signed, msg.signed_availability)
+				}
+			);
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridgeTx(
+					NetworkBridgeTxMessage::SendValidationMessage(peers, send_msg),
+				) => {
+					assert_eq!(peers, vec![peer_a.clone()]);
+					assert_eq!(send_msg, msg.clone().into_validation_protocol());
+				}
+			);
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridgeTx(
+					NetworkBridgeTxMessage::ReportPeer(peer, rep)
+				) => {
+					assert_eq!(peer, peer_b);
+					assert_eq!(rep, BENEFIT_VALID_MESSAGE_FIRST)
+				}
+			);
+		}
+
+		// The old message doesn't replace the new one.
+		launch!(handle_network_msg(
+			&mut ctx,
+			&mut state,
+			&Default::default(),
+			NetworkBridgeEvent::PeerMessage(peer_a.clone(), old_msg.clone().into_network_message()),
+			&mut rng,
+		));
+		assert!(handle.recv().timeout(Duration::from_millis(10)).await.is_none());
+
+		let job_data = state.per_relay_parent.get(&hash).unwrap();
+		assert_eq!(job_data.one_per_validator.get(&validator), Some(&new_msg));
+	});
+}
+
+#[test]
+fn own_bitfield_gets_replaced() {
+	let hash: Hash = [0; 32].into();
+	let peer_a = PeerId::random();
+
+	// validator 0 key pair
+	let (mut state, signing_context, keystore, validator) =
+		state_with_view(our_view![hash], hash.clone());
+	state.peer_views.insert(peer_a.clone(), view![hash]);
+
+	let old_bitfield = sign_bitfield(&keystore, &signing_context, &validator, 16);
+	let new_bitfield = sign_bitfield(&keystore, &signing_context, &validator, 20);
+
+	let pool = sp_core::testing::TaskExecutor::new();
+	let (mut ctx, mut handle) = make_subsystem_context::<BitfieldDistributionMessage, _>(pool);
+	let mut rng = dummy_rng();
+
+	executor::block_on(async move {
+		for signed in [&old_bitfield, &new_bitfield] {
+			launch!(handle_bitfield_distribution(
+				&mut ctx,
+				&mut state,
+				&Default::default(),
+				hash,
+				signed.clone(),
+				&mut rng,
+			));
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::Provisioner(ProvisionerMessage::ProvisionableData(
+					_,
+					ProvisionableData::Bitfield(_, s)
+				)) => {
+					assert_eq!(&s, signed)
+				}
+			);
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridgeTx(
+					NetworkBridgeTxMessage::SendValidationMessage(peers, send_msg),
+				) => {
+					assert_eq!(peers, vec![peer_a.clone()]);
+					let msg = BitfieldGossipMessage {
+						relay_parent: hash,
+						signed_availability: signed.clone(),
+					};
+					assert_eq!(send_msg, msg.into_validation_protocol());
+				}
+			);
+		}
+
+		// Anything but a strict superset is not sent.
+		launch!(handle_bitfield_distribution(
+			&mut ctx,
+			&mut state,
+			&Default::default(),
+			hash,
+			old_bitfield.clone(),
+			&mut rng,
+		));
+		assert!(handle.recv().timeout(Duration::from_millis(10)).await.is_none());
+	});
+}
+
 #[test]
 fn do_not_relay_message_twice() {
 	let _ = env_logger::builder()
@@ -429,6 +429,11 @@ impl Default for RandomRouting {
 }
 
 impl RandomRouting {
+	/// Routing which never picks any random peers, so messages only get routed along the grid.
+	pub fn none() -> Self {
+		RandomRouting { target: 0, sent: 0, sample_rate: DEFAULT_RANDOM_SAMPLE_RATE }
+	}
+
 	/// Perform random sampling for a specific peer
 	/// Returns `true` for a lucky peer
 	pub fn sample(&self, n_peers_total: usize, rng: &mut (impl CryptoRng + Rng)) -> bool {
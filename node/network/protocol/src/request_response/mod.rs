@@ -30,7 +30,7 @@
 //! `trait IsRequest` .... A trait describing a particular request. It is used for gathering meta
 //! data, like what is the corresponding response type.
 //!
-//!  Versioned (v1 module): The actual requests and responses as sent over the network.
+//!  Versioned (v1 and v2 modules): The actual requests and responses as sent over the network.
 
 use std::{collections::HashMap, time::Duration, u64};
 
@@ -55,12 +55,19 @@ pub use outgoing::{OutgoingRequest, OutgoingResult, Recipient, Requests, Respons
 /// Actual versioned requests and responses, that are sent over the wire.
 pub mod v1;
 
+/// Second version of requests and responses, for the protocols which have one.
+pub mod v2;
+
 /// A protocol per subsystem seems to make the most sense, this way we don't need any dispatching
 /// within protocols.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, EnumIter)]
 pub enum Protocol {
 	/// Protocol for chunk fetching, used by availability distribution and availability recovery.
 	ChunkFetchingV1,
+	/// Protocol for chunk fetching with compressed chunks, used by availability distribution and
+	/// availability recovery. Requesters fall back to `ChunkFetchingV1` for peers which don't
+	/// support it.
+	ChunkFetchingV2,
 	/// Protocol for fetching collations from collators.
 	CollationFetchingV1,
 	/// Protocol for fetching seconded PoVs from validators of the same group.
@@ -138,7 +145,7 @@ impl Protocol {
 		let fallback_names = self.get_fallback_names();
 		let (tx, rx) = mpsc::channel(self.get_channel_size());
 		let cfg = match self {
-			Protocol::ChunkFetchingV1 => RequestResponseConfig {
+			Protocol::ChunkFetchingV1 | Protocol::ChunkFetchingV2 => RequestResponseConfig {
 				name,
 				fallback_names,
 				max_request_size: 1_000,
@@ -213,7 +220,7 @@ impl Protocol {
 			// times (due to network delays), 100 seems big enough to accomodate for "bursts",
 			// assuming we can service requests relatively quickly, which would need to be measured
 			// as well.
-			Protocol::ChunkFetchingV1 => 100,
+			Protocol::ChunkFetchingV1 | Protocol::ChunkFetchingV2 => 100,
 			// 10 seems reasonable, considering group sizes of max 10 validators.
 			Protocol::CollationFetchingV1 => 10,
 			// 10 seems reasonable, considering group sizes of max 10 validators.
@@ -258,6 +265,7 @@ impl Protocol {
 	const fn get_legacy_name(self) -> &'static str {
 		match self {
 			Protocol::ChunkFetchingV1 => "/polkadot/req_chunk/1",
+			Protocol::ChunkFetchingV2 => "/polkadot/req_chunk/2",
 			Protocol::CollationFetchingV1 => "/polkadot/req_collation/1",
 			Protocol::PoVFetchingV1 => "/polkadot/req_pov/1",
 			Protocol::AvailableDataFetchingV1 => "/polkadot/req_available_data/1",
@@ -313,6 +321,7 @@ impl ReqProtocolNames {
 
 		let short_name = match protocol {
 			Protocol::ChunkFetchingV1 => "/req_chunk/1",
+			Protocol::ChunkFetchingV2 => "/req_chunk/2",
 			Protocol::CollationFetchingV1 => "/req_collation/1",
 			Protocol::PoVFetchingV1 => "/req_pov/1",
 			Protocol::AvailableDataFetchingV1 => "/req_available_data/1",
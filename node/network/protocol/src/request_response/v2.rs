@@ -0,0 +1,232 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Requests and responses as sent over the wire for the second version of the individual
+//! protocols.
+
+use parity_scale_codec::{Decode, Encode, Error as DecodingError};
+
+use polkadot_node_primitives::{ErasureChunk, Proof, POV_BOMB_LIMIT};
+use polkadot_primitives::v2::{CandidateHash, ValidatorIndex};
+
+use super::{v1, IsRequest, Protocol};
+
+/// Maximum size of a decompressed chunk.
+///
+/// Chunks are a fraction of the available data, which in turn is dominated by the PoV.
+const CHUNK_BOMB_LIMIT: usize = POV_BOMB_LIMIT;
+
+/// A compression of chunk payloads.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum Compression {
+	/// Zstandard, as done by `sp-maybe-compressed-blob`.
+	#[codec(index = 0)]
+	Zstd,
+}
+
+/// Request an availability chunk, which may be sent compressed.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct ChunkFetchingRequest {
+	/// Hash of candidate we want a chunk for.
+	pub candidate_hash: CandidateHash,
+	/// The index of the chunk to fetch.
+	pub index: ValidatorIndex,
+	/// The compressions the requester is able to decompress the chunk with.
+	pub accepted_compressions: Vec<Compression>,
+}
+
+impl From<v1::ChunkFetchingRequest> for ChunkFetchingRequest {
+	/// Accepts all the compressions we support.
+	fn from(v1::ChunkFetchingRequest { candidate_hash, index }: v1::ChunkFetchingRequest) -> Self {
+		Self { candidate_hash, index, accepted_compressions: vec![Compression::Zstd] }
+	}
+}
+
+impl From<&ChunkFetchingRequest> for v1::ChunkFetchingRequest {
+	/// The request to fall back to for peers which don't support `ChunkFetchingV2`.
+	fn from(req: &ChunkFetchingRequest) -> Self {
+		Self { candidate_hash: req.candidate_hash, index: req.index }
+	}
+}
+
+/// Receive a requested erasure chunk.
+///
+/// The encoding is a superset of the one of `v1::ChunkFetchingResponse`, so a response to a
+/// `ChunkFetchingV1` request decodes as this type as well.
+#[derive(Debug, Clone, Encode, Decode)]
+pub enum ChunkFetchingResponse {
+	/// The requested chunk data.
+	#[codec(index = 0)]
+	Chunk(v1::ChunkResponse),
+	/// Node was not in possession of the requested chunk.
+	#[codec(index = 1)]
+	NoSuchChunk,
+	/// The requested chunk data, compressed.
+	#[codec(index = 2)]
+	CompressedChunk(CompressedChunkResponse),
+}
+
+/// Like `v1::ChunkResponse`, but with the chunk compressed.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct CompressedChunkResponse {
+	/// The compression of `chunk`, one of the compressions accepted by the request.
+	pub compression: Compression,
+	/// The compressed erasure-encoded chunk of data belonging to the candidate block.
+	pub chunk: Vec<u8>,
+	/// Proof for this chunk's branch in the Merkle tree.
+	pub proof: Proof,
+}
+
+impl From<v1::ChunkFetchingResponse> for ChunkFetchingResponse {
+	fn from(response: v1::ChunkFetchingResponse) -> Self {
+		match response {
+			v1::ChunkFetchingResponse::Chunk(chunk) => ChunkFetchingResponse::Chunk(chunk),
+			v1::ChunkFetchingResponse::NoSuchChunk => ChunkFetchingResponse::NoSuchChunk,
+		}
+	}
+}
+
+impl ChunkFetchingResponse {
+	/// Respond with the given chunk, if any.
+	///
+	/// The chunk gets compressed with one of the accepted compressions, unless that doesn't make
+	/// it any smaller.
+	pub fn new(chunk: Option<ErasureChunk>, accepted_compressions: &[Compression]) -> Self {
+		let ErasureChunk { chunk, index: _, proof } = match chunk {
+			Some(chunk) => chunk,
+			None => return ChunkFetchingResponse::NoSuchChunk,
+		};
+
+		if accepted_compressions.contains(&Compression::Zstd) {
+			match sp_maybe_compressed_blob::compress(&chunk, CHUNK_BOMB_LIMIT) {
+				Some(compressed) if compressed.len() < chunk.len() =>
+					return ChunkFetchingResponse::CompressedChunk(CompressedChunkResponse {
+						compression: Compression::Zstd,
+						chunk: compressed,
+						proof,
+					}),
+				_ => {},
+			}
+		}
+
+		ChunkFetchingResponse::Chunk(v1::ChunkResponse { chunk, proof })
+	}
+
+	/// Re-build an `ErasureChunk` from response and request, decompressing it if need be.
+	///
+	/// Returns `None` if the node didn't have the chunk, and an error if it sent a chunk which
+	/// can't be decompressed.
+	pub fn recombine_into_chunk(
+		self,
+		req: &ChunkFetchingRequest,
+	) -> Result<Option<ErasureChunk>, DecodingError> {
+		match self {
+			ChunkFetchingResponse::Chunk(chunk) =>
+				Ok(Some(ErasureChunk { chunk: chunk.chunk, proof: chunk.proof, index: req.index })),
+			ChunkFetchingResponse::NoSuchChunk => Ok(None),
+			ChunkFetchingResponse::CompressedChunk(chunk) => {
+				let decompressed = match chunk.compression {
+					Compression::Zstd =>
+						sp_maybe_compressed_blob::decompress(&chunk.chunk, CHUNK_BOMB_LIMIT)
+							.map_err(|_| DecodingError::from("Chunk could not be decompressed"))?,
+				};
+				Ok(Some(ErasureChunk {
+					chunk: decompressed.into_owned(),
+					proof: chunk.proof,
+					index: req.index,
+				}))
+			},
+		}
+	}
+
+	/// The size of the chunk as sent over the wire, if any.
+	pub fn chunk_size(&self) -> Option<usize> {
+		match self {
+			ChunkFetchingResponse::Chunk(chunk) => Some(chunk.chunk.len()),
+			ChunkFetchingResponse::NoSuchChunk => None,
+			ChunkFetchingResponse::CompressedChunk(chunk) => Some(chunk.chunk.len()),
+		}
+	}
+}
+
+impl IsRequest for ChunkFetchingRequest {
+	type Response = ChunkFetchingResponse;
+	const PROTOCOL: Protocol = Protocol::ChunkFetchingV2;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn request(accepted_compressions: Vec<Compression>) -> ChunkFetchingRequest {
+		ChunkFetchingRequest {
+			candidate_hash: CandidateHash::default(),
+			index: ValidatorIndex(3),
+			accepted_compressions,
+		}
+	}
+
+	fn chunk(data: Vec<u8>) -> ErasureChunk {
+		ErasureChunk { chunk: data, index: ValidatorIndex(3), proof: Proof::dummy_proof() }
+	}
+
+	#[test]
+	fn compressible_chunks_are_sent_compressed() {
+		let req = request(vec![Compression::Zstd]);
+		let response =
+			ChunkFetchingResponse::new(Some(chunk(vec![7; 10_000])), &req.accepted_compressions);
+		assert!(matches!(response, ChunkFetchingResponse::CompressedChunk(_)));
+		assert!(response.chunk_size().unwrap() < 10_000);
+
+		let decoded = ChunkFetchingResponse::decode(&mut &response.encode()[..]).unwrap();
+		assert_eq!(decoded.recombine_into_chunk(&req).unwrap(), Some(chunk(vec![7; 10_000])));
+	}
+
+	#[test]
+	fn chunks_are_sent_uncompressed_unless_accepted_and_smaller() {
+		let response = ChunkFetchingResponse::new(Some(chunk(vec![7; 10_000])), &[]);
+		assert!(matches!(response, ChunkFetchingResponse::Chunk(_)));
+
+		// Too short to get any smaller.
+		let response = ChunkFetchingResponse::new(Some(chunk(vec![1, 2, 3])), &[Compression::Zstd]);
+		assert!(matches!(response, ChunkFetchingResponse::Chunk(_)));
+	}
+
+	#[test]
+	fn v1_responses_decode_as_v2() {
+		let req = request(vec![Compression::Zstd]);
+		let v1_response = v1::ChunkFetchingResponse::Chunk(chunk(vec![1, 2, 3]).into());
+
+		let response = ChunkFetchingResponse::decode(&mut &v1_response.encode()[..]).unwrap();
+		assert_eq!(response.recombine_into_chunk(&req).unwrap(), Some(chunk(vec![1, 2, 3])));
+
+		let response = ChunkFetchingResponse::decode(
+			&mut &v1::ChunkFetchingResponse::NoSuchChunk.encode()[..],
+		)
+		.unwrap();
+		assert_eq!(response.recombine_into_chunk(&req).unwrap(), None);
+	}
+
+	#[test]
+	fn corrupt_compressed_chunks_are_rejected() {
+		let response = ChunkFetchingResponse::CompressedChunk(CompressedChunkResponse {
+			compression: Compression::Zstd,
+			chunk: vec![0x52, 0xBC, 0x53, 0x76, 0x46, 0xDB, 0x8E, 0x05, 1, 2, 3],
+			proof: Proof::dummy_proof(),
+		});
+		assert!(response.recombine_into_chunk(&request(vec![Compression::Zstd])).is_err());
+	}
+}
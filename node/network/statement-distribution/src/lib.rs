@@ -58,7 +58,7 @@ use futures::{
 };
 use indexmap::{map::Entry as IEntry, IndexMap};
 use sp_keystore::SyncCryptoStorePtr;
-use util::runtime::RuntimeInfo;
+use util::runtime::{get_disabled_validators, RuntimeInfo};
 
 use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
 
@@ -670,6 +670,8 @@ struct ActiveHeadData {
 	session_index: sp_staking::SessionIndex,
 	/// How many `Seconded` statements we've seen per validator.
 	seconded_counts: HashMap<ValidatorIndex, usize>,
+	/// Validators disabled for having lost a dispute, whose statements are not accepted.
+	disabled_validators: HashSet<ValidatorIndex>,
 	/// A Jaeger span for this head, so we can attach data to it.
 	span: PerLeafSpan,
 }
@@ -678,6 +680,7 @@ impl ActiveHeadData {
 	fn new(
 		validators: Vec<ValidatorId>,
 		session_index: sp_staking::SessionIndex,
+		disabled_validators: HashSet<ValidatorIndex>,
 		span: PerLeafSpan,
 	) -> Self {
 		ActiveHeadData {
@@ -687,6 +690,7 @@ impl ActiveHeadData {
 			validators,
 			session_index,
 			seconded_counts: Default::default(),
+			disabled_validators,
 			span,
 		}
 	}
@@ -703,10 +707,20 @@ impl ActiveHeadData {
 	/// to have been checked, including that the validator index is not out-of-bounds and
 	/// the signature is valid.
 	///
-	/// Any other statements or those that reference a candidate we are not aware of cannot be accepted
-	/// and will return `NotedStatement::NotUseful`.
+	/// Any other statements, those that reference a candidate we are not aware of and those of
+	/// disabled validators cannot be accepted and will return `NotedStatement::NotUseful`.
 	fn note_statement(&mut self, statement: SignedFullStatement) -> NotedStatement {
 		let validator_index = statement.validator_index();
+		if self.disabled_validators.contains(&validator_index) {
+			gum::trace!(
+				target: LOG_TARGET,
+				?validator_index,
+				?statement,
+				"Statement of disabled validator is ignored"
+			);
+			return NotedStatement::NotUseful
+		}
+
 		let comparator = StoredStatementComparator {
 			compact: statement.payload().to_compact(),
 			validator_index,
@@ -798,6 +812,16 @@ impl ActiveHeadData {
 		statement: &UncheckedSignedStatement,
 	) -> std::result::Result<(), DeniedStatement> {
 		let validator_index = statement.unchecked_validator_index();
+		if self.disabled_validators.contains(&validator_index) {
+			gum::trace!(
+				target: LOG_TARGET,
+				?validator_index,
+				?statement,
+				"Statement of disabled validator is ignored",
+			);
+			return Err(DeniedStatement::NotUseful)
+		}
+
 		let compact = statement.unchecked_payload();
 		let comparator = StoredStatementComparator {
 			compact: compact.clone(),
@@ -2033,10 +2057,25 @@ impl<R: rand::Rng> StatementDistributionSubsystem<R> {
 						.get_session_info_by_index(ctx.sender(), relay_parent, session_index)
 						.await?;
 					let session_info = &info.session_info;
+					let (disabled_session, disabled_validators) =
+						get_disabled_validators(ctx.sender(), relay_parent).await?;
+					let disabled_validators = if disabled_session == session_index {
+						disabled_validators.into_iter().collect()
+					} else {
+						gum::debug!(
+							target: LOG_TARGET,
+							?relay_parent,
+							session_index,
+							disabled_session,
+							"Ignoring disabled validators of a different session",
+						);
+						HashSet::new()
+					};
 
 					active_heads.entry(relay_parent).or_insert(ActiveHeadData::new(
 						session_info.validators.clone(),
 						session_index,
+						disabled_validators,
 						span,
 					));
 				}
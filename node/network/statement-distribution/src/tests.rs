@@ -85,6 +85,7 @@ fn active_head_accepts_only_2_seconded_per_validator() {
 	let mut head_data = ActiveHeadData::new(
 		validators,
 		session_index,
+		HashSet::new(),
 		PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
 	);
 
@@ -199,6 +200,95 @@ fn active_head_accepts_only_2_seconded_per_validator() {
 	assert_matches!(noted, NotedStatement::Fresh(_));
 }
 
+#[test]
+fn active_head_ignores_statements_of_disabled_validators() {
+	let validators =
+		vec![Sr25519Keyring::Alice.public().into(), Sr25519Keyring::Bob.public().into()];
+	let parent_hash: Hash = [1; 32].into();
+
+	let session_index = 1;
+	let signing_context = SigningContext { parent_hash, session_index };
+
+	let candidate = {
+		let mut c = dummy_committed_candidate_receipt(dummy_hash());
+		c.descriptor.relay_parent = parent_hash;
+		c.descriptor.para_id = 1.into();
+		c
+	};
+
+	let mut head_data = ActiveHeadData::new(
+		validators,
+		session_index,
+		[ValidatorIndex(1)].into_iter().collect(),
+		PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
+	);
+
+	let keystore: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+	let alice_public = SyncCryptoStore::sr25519_generate_new(
+		&*keystore,
+		ValidatorId::ID,
+		Some(&Sr25519Keyring::Alice.to_seed()),
+	)
+	.unwrap();
+	let bob_public = SyncCryptoStore::sr25519_generate_new(
+		&*keystore,
+		ValidatorId::ID,
+		Some(&Sr25519Keyring::Bob.to_seed()),
+	)
+	.unwrap();
+
+	// Bob is disabled.
+	let statement = block_on(SignedFullStatement::sign(
+		&keystore,
+		Statement::Seconded(candidate.clone()),
+		&signing_context,
+		ValidatorIndex(1),
+		&bob_public.into(),
+	))
+	.ok()
+	.flatten()
+	.expect("should be signed");
+	assert_eq!(
+		head_data.check_useful_or_unknown(&statement.clone().convert_payload().into()),
+		Err(DeniedStatement::NotUseful),
+	);
+	let noted = head_data.note_statement(statement);
+	assert_matches!(noted, NotedStatement::NotUseful);
+
+	// Alice is not.
+	let statement = block_on(SignedFullStatement::sign(
+		&keystore,
+		Statement::Seconded(candidate.clone()),
+		&signing_context,
+		ValidatorIndex(0),
+		&alice_public.into(),
+	))
+	.ok()
+	.flatten()
+	.expect("should be signed");
+	assert!(head_data
+		.check_useful_or_unknown(&statement.clone().convert_payload().into())
+		.is_ok());
+	let noted = head_data.note_statement(statement);
+	assert_matches!(noted, NotedStatement::Fresh(_));
+
+	// Valid statements of disabled validators are no use either.
+	let statement = block_on(SignedFullStatement::sign(
+		&keystore,
+		Statement::Valid(candidate.hash()),
+		&signing_context,
+		ValidatorIndex(1),
+		&bob_public.into(),
+	))
+	.ok()
+	.flatten()
+	.expect("should be signed");
+	assert_eq!(
+		head_data.check_useful_or_unknown(&statement.clone().convert_payload().into()),
+		Err(DeniedStatement::NotUseful),
+	);
+}
+
 #[test]
 fn note_local_works() {
 	let hash_a = CandidateHash([1; 32].into());
@@ -431,6 +521,7 @@ fn peer_view_update_sends_messages() {
 		let mut data = ActiveHeadData::new(
 			validators,
 			session_index,
+			HashSet::new(),
 			PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
 		);
 
@@ -777,6 +868,28 @@ fn receiving_from_one_sends_to_another_and_to_candidate_backing() {
 			}
 		);
 
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(r, RuntimeApiRequest::DisabledValidators(tx))
+			)
+				if r == hash_a
+			=> {
+				let _ = tx.send(Ok(Vec::new()));
+			}
+		);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(r, RuntimeApiRequest::SessionIndexForChild(tx))
+			)
+				if r == hash_a
+			=> {
+				let _ = tx.send(Ok(session_index));
+			}
+		);
+
 		// notify of peers and view
 		handle
 			.send(FromOrchestra::Communication {
@@ -982,6 +1095,28 @@ fn receiving_large_statement_from_one_sends_to_another_and_to_candidate_backing(
 			}
 		);
 
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(r, RuntimeApiRequest::DisabledValidators(tx))
+			)
+				if r == hash_a
+			=> {
+				let _ = tx.send(Ok(Vec::new()));
+			}
+		);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(r, RuntimeApiRequest::SessionIndexForChild(tx))
+			)
+				if r == hash_a
+			=> {
+				let _ = tx.send(Ok(session_index));
+			}
+		);
+
 		// notify of peers and view
 		handle
 			.send(FromOrchestra::Communication {
@@ -1496,6 +1631,28 @@ fn share_prioritizes_backing_group() {
 			}
 		);
 
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(r, RuntimeApiRequest::DisabledValidators(tx))
+			)
+				if r == hash_a
+			=> {
+				let _ = tx.send(Ok(Vec::new()));
+			}
+		);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(r, RuntimeApiRequest::SessionIndexForChild(tx))
+			)
+				if r == hash_a
+			=> {
+				let _ = tx.send(Ok(session_index));
+			}
+		);
+
 		// notify of dummy peers and view
 		for (peer, pair) in dummy_peers.clone().into_iter().zip(dummy_pairs) {
 			handle
@@ -1791,6 +1948,28 @@ fn peer_cant_flood_with_large_statements() {
 			}
 		);
 
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(r, RuntimeApiRequest::DisabledValidators(tx))
+			)
+				if r == hash_a
+			=> {
+				let _ = tx.send(Ok(Vec::new()));
+			}
+		);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(r, RuntimeApiRequest::SessionIndexForChild(tx))
+			)
+				if r == hash_a
+			=> {
+				let _ = tx.send(Ok(session_index));
+			}
+		);
+
 		// notify of peers and view
 		handle
 			.send(FromOrchestra::Communication {
@@ -1997,6 +2176,28 @@ fn handle_multiple_seconded_statements() {
 			}
 		);
 
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(r, RuntimeApiRequest::DisabledValidators(tx))
+			)
+				if r == relay_parent_hash
+			=> {
+				let _ = tx.send(Ok(Vec::new()));
+			}
+		);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(r, RuntimeApiRequest::SessionIndexForChild(tx))
+			)
+				if r == relay_parent_hash
+			=> {
+				let _ = tx.send(Ok(session_index));
+			}
+		);
+
 		// notify of peers and view
 		for peer in all_peers.iter() {
 			handle
@@ -25,7 +25,7 @@ use std::time::Duration;
 
 use ::test_helpers::{dummy_candidate_descriptor, dummy_hash};
 use polkadot_node_primitives::{BlockData, PoV};
-use polkadot_node_subsystem_types::messages::CandidateValidationMessage;
+use polkadot_node_subsystem_types::{jaeger, messages::CandidateValidationMessage};
 use polkadot_overseer::{
 	self as overseer,
 	dummy::dummy_overseer_builder,
@@ -77,6 +77,7 @@ impl Subsystem1 {
 				candidate_receipt,
 				PoV { block_data: BlockData(Vec::new()) }.into(),
 				Default::default(),
+				jaeger::Span::Disabled,
 				tx,
 			);
 			ctx.send_message(msg).await;
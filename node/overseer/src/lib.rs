@@ -525,6 +525,7 @@ pub struct Overseer<SupportsParachains> {
 	#[subsystem(blocking, AvailabilityStoreMessage, sends: [
 		ChainApiMessage,
 		RuntimeApiMessage,
+		AvailabilityDistributionMessage,
 	])]
 	availability_store: AvailabilityStore,
 
@@ -26,7 +26,7 @@ use polkadot_node_primitives::{
 };
 use polkadot_node_subsystem_types::{
 	jaeger,
-	messages::{NetworkBridgeEvent, RuntimeApiRequest},
+	messages::{NetworkBridgeEvent, RecoveryPolicy, RuntimeApiRequest},
 	ActivatedLeaf, LeafStatus,
 };
 use polkadot_primitives::v2::{
@@ -115,6 +115,7 @@ where
 							candidate_receipt,
 							PoV { block_data: BlockData(Vec::new()) }.into(),
 							Default::default(),
+							jaeger::Span::Disabled,
 							tx,
 						))
 						.await;
@@ -792,6 +793,7 @@ fn test_candidate_validation_msg() -> CandidateValidationMessage {
 		candidate_receipt,
 		pov,
 		Duration::default(),
+		jaeger::Span::Disabled,
 		sender,
 	)
 }
@@ -843,6 +845,7 @@ fn test_availability_recovery_msg() -> AvailabilityRecoveryMessage {
 		dummy_candidate_receipt(dummy_hash()),
 		Default::default(),
 		None,
+		RecoveryPolicy::Default,
 		sender,
 	)
 }
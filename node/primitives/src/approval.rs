@@ -130,6 +130,8 @@ pub struct BlockApprovalMeta {
 	pub slot: Slot,
 	/// The session of the block.
 	pub session: SessionIndex,
+	/// The validators disabled in the session of the block for having lost a dispute.
+	pub disabled_validators: Vec<ValidatorIndex>,
 }
 
 /// Errors that can occur during the approvals protocol.
@@ -32,6 +32,9 @@ mod message;
 pub use message::{DisputeMessage, Error as DisputeMessageCheckError, UncheckedDisputeMessage};
 mod status;
 pub use status::{dispute_is_inactive, DisputeStatus, Timestamp, ACTIVE_DURATION_SECS};
+/// Types for querying the dispute coordinator about the disputes it knows.
+mod query;
+pub use query::{DisputeInfo, DisputesFilter, ParticipationOutcome};
 
 /// A checked dispute statement from an associated validator.
 #[derive(Debug, Clone)]
@@ -0,0 +1,92 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use polkadot_primitives::v2::{
+	CandidateHash, Id as ParaId, InvalidDisputeStatementKind, SessionIndex,
+	ValidDisputeStatementKind, ValidatorIndex,
+};
+
+use super::DisputeStatus;
+use crate::InvalidCandidate;
+
+/// Outcome of the local validation of a disputed candidate.
+#[derive(Clone, Debug)]
+pub enum ParticipationOutcome {
+	/// Candidate was found to be valid.
+	Valid,
+	/// Candidate was found to be invalid.
+	///
+	/// The reason is the one given by candidate validation, if the candidate got that far.
+	Invalid(Option<InvalidCandidate>),
+	/// Candidate was found to be unavailable.
+	Unavailable,
+	/// Something went wrong (bug), details can be found in the logs.
+	Error,
+}
+
+impl ParticipationOutcome {
+	/// If validation was successful, get whether the candidate was valid or invalid.
+	pub fn validity(&self) -> Option<bool> {
+		match self {
+			Self::Valid => Some(true),
+			Self::Invalid(_) => Some(false),
+			Self::Unavailable | Self::Error => None,
+		}
+	}
+}
+
+/// Which disputes to return on a `DisputeCoordinatorMessage::QueryDisputes`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DisputesFilter {
+	/// Only return disputes about candidates of this session.
+	pub session: Option<SessionIndex>,
+	/// Only return disputes about candidates of this para.
+	pub para_id: Option<ParaId>,
+	/// Only return disputes which are still active.
+	pub active_only: bool,
+}
+
+impl DisputesFilter {
+	/// Whether a dispute in the given session and about a candidate of the given para passes
+	/// the filter, regardless of its status.
+	pub fn matches(&self, session: SessionIndex, para_id: ParaId) -> bool {
+		self.session.map_or(true, |s| s == session) && self.para_id.map_or(true, |p| p == para_id)
+	}
+}
+
+/// What the dispute coordinator knows about a dispute.
+#[derive(Clone, Debug)]
+pub struct DisputeInfo {
+	/// The session of the disputed candidate.
+	pub session: SessionIndex,
+	/// The disputed candidate.
+	pub candidate_hash: CandidateHash,
+	/// The para the disputed candidate belongs to.
+	pub para_id: ParaId,
+	/// The current status of the dispute.
+	pub status: DisputeStatus,
+	/// Whether the dispute is still active.
+	pub active: bool,
+	/// Votes of validity, sorted by validator index.
+	pub valid_votes: Vec<(ValidatorIndex, ValidDisputeStatementKind)>,
+	/// Votes of invalidity, sorted by validator index.
+	pub invalid_votes: Vec<(ValidatorIndex, InvalidDisputeStatementKind)>,
+	/// The indices of the validators of the session controlled by this node, so the votes
+	/// above which are our own can be told apart.
+	pub own_validator_indices: Vec<ValidatorIndex>,
+	/// The outcome of our own participation, if we participated since the node got started.
+	pub participation: Option<ParticipationOutcome>,
+}
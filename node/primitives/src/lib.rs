@@ -46,9 +46,10 @@ pub mod approval;
 /// Disputes related types.
 pub mod disputes;
 pub use disputes::{
-	dispute_is_inactive, CandidateVotes, DisputeMessage, DisputeMessageCheckError, DisputeStatus,
-	InvalidDisputeVote, SignedDisputeStatement, Timestamp, UncheckedDisputeMessage,
-	ValidDisputeVote, ACTIVE_DURATION_SECS,
+	dispute_is_inactive, CandidateVotes, DisputeInfo, DisputeMessage, DisputeMessageCheckError,
+	DisputeStatus, DisputesFilter, InvalidDisputeVote, ParticipationOutcome,
+	SignedDisputeStatement, Timestamp, UncheckedDisputeMessage, ValidDisputeVote,
+	ACTIVE_DURATION_SECS,
 };
 
 // For a 16-ary Merkle Prefix Trie, we can expect at most 16 32-byte hashes per node
@@ -207,7 +208,7 @@ pub type SignedFullStatement = Signed<Statement, CompactStatement>;
 pub type UncheckedSignedFullStatement = UncheckedSigned<Statement, CompactStatement>;
 
 /// Candidate invalidity details
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum InvalidCandidate {
 	/// Failed to execute.`validate_block`. This includes function panicking.
 	ExecutionError(String),
@@ -250,7 +251,7 @@ pub enum ValidationResult {
 }
 
 /// A Proof-of-Validity
-#[derive(PartialEq, Eq, Clone, Encode, Decode, Debug)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Debug, Serialize, Deserialize)]
 pub struct PoV {
 	/// The block witness data.
 	pub block_data: BlockData,
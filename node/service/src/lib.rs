@@ -39,13 +39,14 @@ use {
 	polkadot_node_core_approval_voting::{
 		self as approval_voting_subsystem, Config as ApprovalVotingConfig,
 	},
-	polkadot_node_core_av_store::Config as AvailabilityConfig,
 	polkadot_node_core_av_store::Error as AvailabilityError,
 	polkadot_node_core_candidate_validation::Config as CandidateValidationConfig,
 	polkadot_node_core_chain_selection::{
 		self as chain_selection_subsystem, Config as ChainSelectionConfig,
 	},
-	polkadot_node_core_dispute_coordinator::Config as DisputeCoordinatorConfig,
+	polkadot_node_core_dispute_coordinator::{
+		self as dispute_coordinator_subsystem, Config as DisputeCoordinatorConfig,
+	},
 	polkadot_node_network_protocol::{
 		peer_set::PeerSetProtocolNames, request_response::ReqProtocolNames,
 	},
@@ -55,10 +56,14 @@ use {
 	sp_trie::PrefixedMemoryDB,
 };
 
-use polkadot_node_subsystem_util::database::Database;
+pub use polkadot_node_subsystem_util::database::Database;
 
 #[cfg(feature = "full-node")]
 pub use {
+	parachains_db::ReadOnlyDatabase,
+	polkadot_node_core_av_store::{
+		Config as AvailabilityConfig, PruningConfig as AvailabilityPruningConfig,
+	},
 	polkadot_overseer::{Handle, Overseer, OverseerConnector, OverseerHandle},
 	polkadot_primitives::runtime_api::ParachainHost,
 	relay_chain_selection::SelectRelayChain,
@@ -241,6 +246,10 @@ pub enum Error {
 	#[cfg(feature = "full-node")]
 	#[error("Expected at least one of polkadot, kusama, westend or rococo runtime feature")]
 	NoRuntime,
+
+	#[cfg(feature = "full-node")]
+	#[error("No polkadot subsystem db for custom source")]
+	CustomDatabaseSource,
 }
 
 /// Can be called for a `Configuration` to identify which network the configuration targets.
@@ -321,6 +330,41 @@ pub fn open_database(db_source: &DatabaseSource) -> Result<Arc<dyn Database>, Er
 	Ok(parachains_db)
 }
 
+/// Open the parachains database for reading, e.g. for inspecting it offline.
+///
+/// Unlike [`open_database`], this neither creates nor upgrades the database.
+#[cfg(feature = "full-node")]
+pub fn open_database_read_only(db_source: &DatabaseSource) -> Result<ReadOnlyDatabase, Error> {
+	let parachains_db = match db_source {
+		DatabaseSource::RocksDb { path, .. } =>
+			parachains_db::open_rocksdb_read_only(path.clone())?,
+		DatabaseSource::ParityDb { path, .. } => parachains_db::open_paritydb_read_only(
+			path.parent().ok_or(Error::DatabasePathRequired)?.into(),
+		)?,
+		DatabaseSource::Auto { paritydb_path, rocksdb_path, .. } =>
+			if paritydb_path.is_dir() && paritydb_path.exists() {
+				parachains_db::open_paritydb_read_only(
+					paritydb_path.parent().ok_or(Error::DatabasePathRequired)?.into(),
+				)?
+			} else {
+				parachains_db::open_rocksdb_read_only(rocksdb_path.clone())?
+			},
+		DatabaseSource::Custom { .. } => return Err(Error::CustomDatabaseSource),
+	};
+	Ok(parachains_db)
+}
+
+/// The configuration of the availability store, using the columns of the parachains database.
+#[cfg(feature = "full-node")]
+pub fn availability_config(pruning: AvailabilityPruningConfig) -> AvailabilityConfig {
+	AvailabilityConfig {
+		col_data: parachains_db::REAL_COLUMNS.col_availability_data,
+		col_meta: parachains_db::REAL_COLUMNS.col_availability_meta,
+		pruning,
+		scrubbing: Default::default(),
+	}
+}
+
 /// Initialize the `Jeager` collector. The destination must listen
 /// on the given address and port for `UDP` packets.
 #[cfg(any(test, feature = "full-node"))]
@@ -448,6 +492,7 @@ fn new_partial<RuntimeApi, ExecutorDispatch, ChainSelection>(
 		ExecutorDispatch,
 	>,
 	select_chain: ChainSelection,
+	overseer_handle: Option<Handle>,
 ) -> Result<
 	service::PartialComponents<
 		FullClient<RuntimeApi, ExecutorDispatch>,
@@ -596,6 +641,7 @@ where
 					beefy_best_block_stream: beefy_rpc_links.from_voter_best_beefy_stream.clone(),
 					subscription_executor,
 				},
+				overseer_handle: overseer_handle.clone(),
 			};
 
 			polkadot_rpc::create_full(deps, backend.clone()).map_err(Into::into)
@@ -718,10 +764,36 @@ where
 	Ok(leaves.into_iter().rev().take(MAX_ACTIVE_LEAVES).collect())
 }
 
-pub const AVAILABILITY_CONFIG: AvailabilityConfig = AvailabilityConfig {
-	col_data: parachains_db::REAL_COLUMNS.col_availability_data,
-	col_meta: parachains_db::REAL_COLUMNS.col_availability_meta,
-};
+/// Checks that the availability store keeps the data of finalized candidates for the whole
+/// dispute window, that is `dispute_period` sessions as configured in the runtime at the best
+/// block.
+#[cfg(feature = "full-node")]
+fn check_availability_pruning_config<C>(
+	client: &C,
+	session_duration: Duration,
+	pruning_config: &AvailabilityPruningConfig,
+) -> Result<(), Error>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: ParachainHost<Block>,
+{
+	let at = BlockId::Hash(client.info().best_hash);
+	let runtime_api = client.runtime_api();
+	let session_info = runtime_api
+		.session_index_for_child(&at)
+		.and_then(|session_index| runtime_api.session_info(&at, session_index));
+
+	match session_info {
+		Ok(Some(session_info)) => {
+			pruning_config.check_dispute_window(session_duration * session_info.dispute_period)?;
+		},
+		Ok(None) | Err(_) => gum::warn!(
+			"Unable to determine the dispute period, the availability store pruning configuration is not checked."
+		),
+	}
+
+	Ok(())
+}
 
 /// Create a new full node of arbitrary runtime and executor.
 ///
@@ -745,6 +817,10 @@ pub fn new_full<RuntimeApi, ExecutorDispatch, OverseerGenerator>(
 	overseer_message_channel_capacity_override: Option<usize>,
 	_malus_finality_delay: Option<u32>,
 	hwbench: Option<sc_sysinfo::HwBench>,
+	enable_pvf_sandbox: bool,
+	pvf_artifacts_cache_max_size: Option<u64>,
+	availability_pruning_config: AvailabilityPruningConfig,
+	serve_recovered_chunks: bool,
 ) -> Result<NewFull<Arc<FullClient<RuntimeApi, ExecutorDispatch>>>, Error>
 where
 	RuntimeApi: ConstructRuntimeApi<Block, FullClient<RuntimeApi, ExecutorDispatch>>
@@ -833,6 +909,7 @@ where
 		&mut config,
 		basics,
 		select_chain,
+		Some(overseer_handle.clone()),
 	)?;
 
 	let shared_voter_state = rpc_setup;
@@ -887,6 +964,8 @@ where
 	config.network.request_response_protocols.push(cfg);
 	let (chunk_req_receiver, cfg) = IncomingRequest::get_config_receiver(&req_protocol_names);
 	config.network.request_response_protocols.push(cfg);
+	let (chunk_req_v2_receiver, cfg) = IncomingRequest::get_config_receiver(&req_protocol_names);
+	config.network.request_response_protocols.push(cfg);
 	let (collation_req_receiver, cfg) = IncomingRequest::get_config_receiver(&req_protocol_names);
 	config.network.request_response_protocols.push(cfg);
 	let (available_data_req_receiver, cfg) =
@@ -947,6 +1026,8 @@ where
 		slot_duration_millis: slot_duration.as_millis() as u64,
 	};
 
+	let availability_config = availability_config(availability_pruning_config);
+
 	let candidate_validation_config = CandidateValidationConfig {
 		artifacts_cache_path: config
 			.database
@@ -957,6 +1038,8 @@ where
 			None => std::env::current_exe()?,
 			Some(p) => p,
 		},
+		enable_pvf_sandbox,
+		artifacts_cache_max_size: pvf_artifacts_cache_max_size,
 	};
 
 	let chain_selection_config = ChainSelectionConfig {
@@ -967,6 +1050,8 @@ where
 
 	let dispute_coordinator_config = DisputeCoordinatorConfig {
 		col_data: parachains_db::REAL_COLUMNS.col_dispute_coordinator_data,
+		participation_queues: Default::default(),
+		max_spam_votes: dispute_coordinator_subsystem::DEFAULT_MAX_SPAM_VOTES,
 	};
 
 	let rpc_handlers = service::spawn_tasks(service::SpawnTasksParams {
@@ -998,6 +1083,12 @@ where
 
 	let (block_import, link_half, babe_link, beefy_links) = import_setup;
 
+	// Sessions last one BABE epoch.
+	let session_duration = Duration::from_millis(
+		babe_link.config().slot_duration().as_millis() as u64 * babe_link.config().epoch_length,
+	);
+	check_availability_pruning_config(&*client, session_duration, &availability_config.pruning)?;
+
 	let overseer_client = client.clone();
 	let spawner = task_manager.spawn_handle();
 	// Cannot use the `RelayChainSelection`, since that'd require a setup _and running_ overseer
@@ -1067,6 +1158,7 @@ where
 					authority_discovery_service,
 					pov_req_receiver,
 					chunk_req_receiver,
+					chunk_req_v2_receiver,
 					collation_req_receiver,
 					available_data_req_receiver,
 					statement_req_receiver,
@@ -1075,11 +1167,12 @@ where
 					spawner,
 					is_collator,
 					approval_voting_config,
-					availability_config: AVAILABILITY_CONFIG,
+					availability_config,
 					candidate_validation_config,
 					chain_selection_config,
 					dispute_coordinator_config,
 					pvf_checker_enabled,
+					serve_recovered_chunks,
 					overseer_message_channel_capacity_override,
 					req_protocol_names,
 					peerset_protocol_names,
@@ -1324,6 +1417,7 @@ macro_rules! chain_ops {
 				&mut config,
 				basics,
 				chain_selection,
+				None,
 			)?;
 		Ok((Arc::new(Client::$variant(client)), backend, import_queue, task_manager))
 	}};
@@ -1394,6 +1488,10 @@ pub fn build_full(
 	overseer_message_channel_override: Option<usize>,
 	malus_finality_delay: Option<u32>,
 	hwbench: Option<sc_sysinfo::HwBench>,
+	enable_pvf_sandbox: bool,
+	pvf_artifacts_cache_max_size: Option<u64>,
+	availability_pruning_config: AvailabilityPruningConfig,
+	serve_recovered_chunks: bool,
 ) -> Result<NewFull<Client>, Error> {
 	#[cfg(feature = "rococo-native")]
 	if config.chain_spec.is_rococo() ||
@@ -1413,6 +1511,10 @@ pub fn build_full(
 			overseer_message_channel_override,
 			malus_finality_delay,
 			hwbench,
+			enable_pvf_sandbox,
+			pvf_artifacts_cache_max_size,
+			availability_pruning_config,
+			serve_recovered_chunks,
 		)
 		.map(|full| full.with_client(Client::Rococo))
 	}
@@ -1432,6 +1534,10 @@ pub fn build_full(
 			overseer_message_channel_override,
 			malus_finality_delay,
 			hwbench,
+			enable_pvf_sandbox,
+			pvf_artifacts_cache_max_size,
+			availability_pruning_config,
+			serve_recovered_chunks,
 		)
 		.map(|full| full.with_client(Client::Kusama))
 	}
@@ -1451,6 +1557,10 @@ pub fn build_full(
 			overseer_message_channel_override,
 			malus_finality_delay,
 			hwbench,
+			enable_pvf_sandbox,
+			pvf_artifacts_cache_max_size,
+			availability_pruning_config,
+			serve_recovered_chunks,
 		)
 		.map(|full| full.with_client(Client::Westend))
 	}
@@ -1473,6 +1583,10 @@ pub fn build_full(
 			}),
 			malus_finality_delay,
 			hwbench,
+			enable_pvf_sandbox,
+			pvf_artifacts_cache_max_size,
+			availability_pruning_config,
+			serve_recovered_chunks,
 		)
 		.map(|full| full.with_client(Client::Polkadot))
 	}
@@ -26,7 +26,9 @@ use polkadot_node_core_chain_selection::Config as ChainSelectionConfig;
 use polkadot_node_core_dispute_coordinator::Config as DisputeCoordinatorConfig;
 use polkadot_node_network_protocol::{
 	peer_set::PeerSetProtocolNames,
-	request_response::{v1 as request_v1, IncomingRequestReceiver, ReqProtocolNames},
+	request_response::{
+		v1 as request_v1, v2 as request_v2, IncomingRequestReceiver, ReqProtocolNames,
+	},
 };
 #[cfg(any(feature = "malus", test))]
 pub use polkadot_overseer::{
@@ -96,6 +98,7 @@ where
 	/// POV request receiver
 	pub pov_req_receiver: IncomingRequestReceiver<request_v1::PoVFetchingRequest>,
 	pub chunk_req_receiver: IncomingRequestReceiver<request_v1::ChunkFetchingRequest>,
+	pub chunk_req_v2_receiver: IncomingRequestReceiver<request_v2::ChunkFetchingRequest>,
 	pub collation_req_receiver: IncomingRequestReceiver<request_v1::CollationFetchingRequest>,
 	pub available_data_req_receiver:
 		IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
@@ -119,6 +122,8 @@ where
 	pub dispute_coordinator_config: DisputeCoordinatorConfig,
 	/// Enable PVF pre-checking
 	pub pvf_checker_enabled: bool,
+	/// Store and serve our own chunks of the candidates we recover.
+	pub serve_recovered_chunks: bool,
 	/// Overseer channel capacity override.
 	pub overseer_message_channel_capacity_override: Option<usize>,
 	/// Request-response protocol names source.
@@ -139,6 +144,7 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 		authority_discovery_service,
 		pov_req_receiver,
 		chunk_req_receiver,
+		chunk_req_v2_receiver,
 		collation_req_receiver,
 		available_data_req_receiver,
 		statement_req_receiver,
@@ -152,6 +158,7 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 		chain_selection_config,
 		dispute_coordinator_config,
 		pvf_checker_enabled,
+		serve_recovered_chunks,
 		overseer_message_channel_capacity_override,
 		req_protocol_names,
 		peerset_protocol_names,
@@ -221,13 +228,24 @@ where
 		))
 		.availability_distribution(AvailabilityDistributionSubsystem::new(
 			keystore.clone(),
-			IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver },
-			Metrics::register(registry)?,
-		))
-		.availability_recovery(AvailabilityRecoverySubsystem::with_chunks_only(
-			available_data_req_receiver,
+			IncomingRequestReceivers {
+				pov_req_receiver,
+				chunk_req_receiver,
+				chunk_req_v2_receiver,
+			},
 			Metrics::register(registry)?,
 		))
+		.availability_recovery({
+			let subsystem = AvailabilityRecoverySubsystem::with_systematic_chunks(
+				available_data_req_receiver,
+				Metrics::register(registry)?,
+			);
+			if serve_recovered_chunks {
+				subsystem.serving_recovered_chunks(keystore.clone())
+			} else {
+				subsystem
+			}
+		})
 		.availability_store(AvailabilityStoreSubsystem::new(
 			parachains_db.clone(),
 			availability_config,
@@ -244,6 +262,7 @@ where
 		))
 		.candidate_validation(CandidateValidationSubsystem::with_config(
 			candidate_validation_config,
+			keystore.clone(),
 			Metrics::register(registry)?, // candidate-validation metrics
 			Metrics::register(registry)?, // validation host metrics
 		))
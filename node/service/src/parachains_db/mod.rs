@@ -156,3 +156,91 @@ pub fn open_creating_paritydb(
 	);
 	Ok(Arc::new(db))
 }
+
+/// Open an existing RocksDB database for reading, without upgrading it.
+///
+/// The database is opened as a secondary instance, so it may be used alongside a running node.
+#[cfg(feature = "full-node")]
+pub fn open_rocksdb_read_only(root: PathBuf) -> io::Result<ReadOnlyDatabase> {
+	use kvdb_rocksdb::{Database, DatabaseConfig};
+
+	let path = root.join("parachains").join("db");
+	upgrade::check_db_version(&path, DatabaseKind::RocksDB)?;
+
+	// The secondary instance keeps its own logs, which must not be written next to the database.
+	let secondary_path =
+		std::env::temp_dir().join(format!("polkadot-parachains-db-{}", std::process::id()));
+
+	let mut db_config = DatabaseConfig::with_columns(columns::v1::NUM_COLUMNS);
+	db_config.create_if_missing = false;
+	db_config.secondary = Some(secondary_path.clone());
+
+	let path_str = path
+		.to_str()
+		.ok_or_else(|| other_io_error(format!("Bad database path: {:?}", path)))?;
+
+	// Removes the files of the secondary instance, also if opening it fails.
+	let secondary = SecondaryPath(secondary_path);
+	let db = Database::open(&db_config, &path_str)?;
+	let db = polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(
+		db,
+		columns::v1::ORDERED_COL,
+	);
+
+	Ok(ReadOnlyDatabase { db: Arc::new(db), _secondary: Some(secondary) })
+}
+
+/// Open an existing parity db database for reading, without upgrading it.
+#[cfg(feature = "full-node")]
+pub fn open_paritydb_read_only(root: PathBuf) -> io::Result<ReadOnlyDatabase> {
+	let path = root.join("parachains");
+	upgrade::check_db_version(&path, DatabaseKind::ParityDB)?;
+
+	let db = parity_db::Db::open_read_only(&upgrade::paritydb_version_1_config(&path))
+		.map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{:?}", err)))?;
+
+	let db = polkadot_node_subsystem_util::database::paritydb_impl::DbAdapter::new(
+		db,
+		columns::v1::ORDERED_COL,
+	);
+	Ok(ReadOnlyDatabase { db: Arc::new(db), _secondary: None })
+}
+
+/// A database opened for reading, see [`open_rocksdb_read_only`] and [`open_paritydb_read_only`].
+///
+/// Removes the files of the secondary instance, if any, once dropped.
+#[cfg(feature = "full-node")]
+pub struct ReadOnlyDatabase {
+	db: Arc<dyn Database>,
+	// Declared after `db`, so that the database is closed before its files are removed.
+	_secondary: Option<SecondaryPath>,
+}
+
+#[cfg(feature = "full-node")]
+impl std::ops::Deref for ReadOnlyDatabase {
+	type Target = Arc<dyn Database>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.db
+	}
+}
+
+/// The directory of the files of a secondary `RocksDB` instance, removed once dropped.
+#[cfg(feature = "full-node")]
+struct SecondaryPath(PathBuf);
+
+#[cfg(feature = "full-node")]
+impl Drop for SecondaryPath {
+	fn drop(&mut self) {
+		if let Err(err) = std::fs::remove_dir_all(&self.0) {
+			if err.kind() != io::ErrorKind::NotFound {
+				gum::warn!(
+					target: LOG_TARGET,
+					?err,
+					path = ?self.0,
+					"Failed to remove the files of the secondary database instance",
+				);
+			}
+		}
+	}
+}
@@ -38,6 +38,8 @@ pub enum Error {
 	CorruptedVersionFile,
 	#[error("Future version (expected {current:?}, found {got:?})")]
 	FutureVersion { current: Version, got: Version },
+	#[error("The database must be upgraded by starting the node first")]
+	OutdatedVersion,
 }
 
 impl From<Error> for io::Error {
@@ -73,6 +75,18 @@ pub(crate) fn try_upgrade_db(db_path: &Path, db_kind: DatabaseKind) -> Result<()
 	update_version(db_path)
 }
 
+/// Check that the database is at the current version, without upgrading it.
+pub(crate) fn check_db_version(db_path: &Path, db_kind: DatabaseKind) -> Result<(), Error> {
+	match get_db_version(db_path)? {
+		Some(CURRENT_VERSION) => Ok(()),
+		Some(v) if v > CURRENT_VERSION =>
+			Err(Error::FutureVersion { current: CURRENT_VERSION, got: v }),
+		// No version file. For `RocksDB` this is the same as the current version.
+		None if db_kind == DatabaseKind::RocksDB => Ok(()),
+		_ => Err(Error::OutdatedVersion),
+	}
+}
+
 /// Reads current database version from the file at given path.
 /// If the file does not exist returns `None`, otherwise the version stored in the file.
 fn get_db_version(path: &Path) -> Result<Option<Version>, Error> {
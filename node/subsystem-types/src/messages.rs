@@ -35,17 +35,20 @@ use polkadot_node_network_protocol::{
 use polkadot_node_primitives::{
 	approval::{BlockApprovalMeta, IndirectAssignmentCert, IndirectSignedApprovalVote},
 	AvailableData, BabeEpoch, BlockWeight, CandidateVotes, CollationGenerationConfig,
-	CollationSecondedSignal, DisputeMessage, DisputeStatus, ErasureChunk, PoV,
-	SignedDisputeStatement, SignedFullStatement, ValidationResult,
+	CollationSecondedSignal, DisputeInfo, DisputeMessage, DisputeStatus, DisputesFilter,
+	ErasureChunk, PoV, SignedDisputeStatement, SignedFullStatement, ValidationResult,
 };
-use polkadot_primitives::v2::{
-	AuthorityDiscoveryId, BackedCandidate, BlockNumber, CandidateEvent, CandidateHash,
-	CandidateIndex, CandidateReceipt, CollatorId, CommittedCandidateReceipt, CoreState,
-	DisputeState, GroupIndex, GroupRotationInfo, Hash, Header as BlockHeader, Id as ParaId,
-	InboundDownwardMessage, InboundHrmpMessage, MultiDisputeStatementSet, OccupiedCoreAssumption,
-	PersistedValidationData, PvfCheckStatement, SessionIndex, SessionInfo,
-	SignedAvailabilityBitfield, SignedAvailabilityBitfields, ValidationCode, ValidationCodeHash,
-	ValidatorId, ValidatorIndex, ValidatorSignature,
+use polkadot_primitives::{
+	v2::{
+		AuthorityDiscoveryId, BackedCandidate, BlockNumber, CandidateEvent, CandidateHash,
+		CandidateIndex, CandidateReceipt, CollatorId, CommittedCandidateReceipt, CoreState,
+		DisputeState, GroupIndex, GroupRotationInfo, Hash, Header as BlockHeader, Id as ParaId,
+		InboundDownwardMessage, InboundHrmpMessage, MultiDisputeStatementSet,
+		OccupiedCoreAssumption, PersistedValidationData, PvfCheckStatement, SessionIndex,
+		SessionInfo, SignedAvailabilityBitfield, SignedAvailabilityBitfields, ValidationCode,
+		ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
+	},
+	vstaging::{slashing, ExecutorParams},
 };
 use polkadot_statement_table::v2::Misbehavior;
 use std::{
@@ -137,6 +140,8 @@ pub enum CandidateValidationMessage {
 		Arc<PoV>,
 		/// Execution timeout
 		Duration,
+		/// The span of the requester, under which the validation is traced.
+		crate::jaeger::Span,
 		oneshot::Sender<Result<ValidationResult, ValidationFailed>>,
 	),
 	/// Validate a candidate with provided, exhaustive parameters for validation.
@@ -153,8 +158,13 @@ pub enum CandidateValidationMessage {
 		ValidationCode,
 		CandidateReceipt,
 		Arc<PoV>,
+		/// The session the candidate belongs to. The candidate is executed with the executor
+		/// parameters of that session.
+		SessionIndex,
 		/// Execution timeout
 		Duration,
+		/// The span of the requester, under which the validation is traced.
+		crate::jaeger::Span,
 		oneshot::Sender<Result<ValidationResult, ValidationFailed>>,
 	),
 	/// Try to compile the given validation code and send back
@@ -174,8 +184,8 @@ impl CandidateValidationMessage {
 	/// If the current variant contains the relay parent hash, return it.
 	pub fn relay_parent(&self) -> Option<Hash> {
 		match self {
-			Self::ValidateFromChainState(_, _, _, _) => None,
-			Self::ValidateFromExhaustive(_, _, _, _, _, _) => None,
+			Self::ValidateFromChainState(_, _, _, _, _) => None,
+			Self::ValidateFromExhaustive(_, _, _, _, _, _, _, _) => None,
 			Self::PreCheck(relay_parent, _, _) => Some(*relay_parent),
 		}
 	}
@@ -280,6 +290,11 @@ pub enum DisputeCoordinatorMessage {
 		Vec<(SessionIndex, CandidateHash)>,
 		oneshot::Sender<Vec<(SessionIndex, CandidateHash, CandidateVotes)>>,
 	),
+	/// Get the recent disputes passing the filter, along with their votes and the outcome of
+	/// our own participation, ordered by session and candidate hash.
+	///
+	/// Meant for introspection by operators, not for use by other subsystems.
+	QueryDisputes(DisputesFilter, oneshot::Sender<Vec<DisputeInfo>>),
 	/// Sign and issue local dispute votes. A value of `true` indicates validity, and `false` invalidity.
 	IssueLocalStatement(SessionIndex, CandidateHash, CandidateReceipt, bool),
 	/// Determine the highest undisputed block within the given chain, based on where candidates
@@ -438,16 +453,63 @@ pub enum AvailabilityDistributionMessage {
 		/// The sender will be canceled if the fetching failed for some reason.
 		tx: oneshot::Sender<PoV>,
 	},
+	/// Instruct availability distribution to fetch a chunk of a backed candidate from its backing
+	/// group once more, e.g. because the stored one turned out to be corrupt.
+	///
+	/// The chunk is checked against the erasure root and stored in the availability store.
+	FetchChunk {
+		/// The relay parent of the candidate.
+		relay_parent: Hash,
+		/// The session the candidate was backed in.
+		session_index: SessionIndex,
+		/// The group which backed the candidate.
+		group_index: GroupIndex,
+		/// Candidate hash to fetch the chunk for.
+		candidate_hash: CandidateHash,
+		/// The erasure root of the candidate, a chunk not matching it will be rejected.
+		erasure_root: Hash,
+		/// The index of the chunk to fetch.
+		index: ValidatorIndex,
+	},
+}
+
+/// A way of obtaining the available data of a candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryStrategyKind {
+	/// Look the data up in the local availability store.
+	LocalStore,
+	/// Request the full data from the validators of the backing group, if it is known.
+	FullFromBackers,
+	/// Request the systematic chunks, which are enough to recover the data without decoding.
+	SystematicChunks,
+	/// Request chunks from all validators in a random order.
+	Chunks,
+}
+
+/// The strategies used to recover the available data of a candidate.
+///
+/// The strategies are tried in order, until one of them either recovers the data or finds it
+/// to be invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecoveryPolicy {
+	/// The strategies the availability recovery subsystem is configured with.
+	Default,
+	/// The given strategies.
+	Custom(Vec<RecoveryStrategyKind>),
 }
 
 /// Availability Recovery Message.
 #[derive(Debug, derive_more::From)]
 pub enum AvailabilityRecoveryMessage {
 	/// Recover available data from validators on the network.
+	///
+	/// If a recovery of the candidate is already ongoing, the request joins it, whatever its
+	/// policy.
 	RecoverAvailableData(
 		CandidateReceipt,
 		SessionIndex,
 		Option<GroupIndex>, // Optional backing group to request from first.
+		RecoveryPolicy,
 		oneshot::Sender<Result<AvailableData, crate::errors::RecoveryError>>,
 	),
 }
@@ -700,6 +762,31 @@ pub enum RuntimeApiRequest {
 	),
 	/// Returns all on-chain disputes at given block number. Available in `v3`.
 	Disputes(RuntimeApiSender<Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>>),
+	/// Get the execution environment parameters for the given session, if stored.
+	/// Available in `v4`.
+	SessionExecutorParams(SessionIndex, RuntimeApiSender<Option<ExecutorParams>>),
+	/// Get the hash of the validation code the specified para is scheduled to upgrade to, if any.
+	/// Available in `v5`.
+	FutureValidationCodeHash(ParaId, RuntimeApiSender<Option<ValidationCodeHash>>),
+	/// Returns a list of validators that lost a past session dispute and need to be slashed.
+	/// Available in `v6`.
+	UnappliedSlashes(
+		RuntimeApiSender<Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>>,
+	),
+	/// Returns a merkle proof of a validator session key.
+	/// Available in `v6`.
+	KeyOwnershipProof(ValidatorId, RuntimeApiSender<Option<slashing::OpaqueKeyOwnershipProof>>),
+	/// Submits an unsigned extrinsic to slash validators who lost a past session dispute.
+	/// Available in `v6`.
+	SubmitReportDisputeLost(
+		slashing::DisputeProof,
+		slashing::OpaqueKeyOwnershipProof,
+		RuntimeApiSender<Option<()>>,
+	),
+	/// Get the validators disabled in the current session for having lost a dispute, sorted by
+	/// validator index.
+	/// Available in `v7`.
+	DisabledValidators(RuntimeApiSender<Vec<ValidatorIndex>>),
 }
 
 impl RuntimeApiRequest {
@@ -707,6 +794,18 @@ impl RuntimeApiRequest {
 
 	/// `Disputes`
 	pub const DISPUTES_RUNTIME_REQUIREMENT: u32 = 3;
+
+	/// `SessionExecutorParams`
+	pub const EXECUTOR_PARAMS_RUNTIME_REQUIREMENT: u32 = 4;
+
+	/// `FutureValidationCodeHash`
+	pub const FUTURE_VALIDATION_CODE_HASH_RUNTIME_REQUIREMENT: u32 = 5;
+
+	/// `UnappliedSlashes`, `KeyOwnershipProof` and `SubmitReportDisputeLost`
+	pub const SLASHING_RUNTIME_REQUIREMENT: u32 = 6;
+
+	/// `DisabledValidators`
+	pub const DISABLED_VALIDATORS_RUNTIME_REQUIREMENT: u32 = 7;
 }
 
 /// A message to the Runtime API subsystem.
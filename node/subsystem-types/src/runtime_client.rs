@@ -24,6 +24,7 @@ use polkadot_primitives::{
 		PersistedValidationData, PvfCheckStatement, ScrapedOnChainVotes, SessionIndex, SessionInfo,
 		ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 	},
+	vstaging::{slashing, ExecutorParams},
 };
 use sp_api::{ApiError, ApiExt, ProvideRuntimeApi};
 use sp_authority_discovery::AuthorityDiscoveryApi;
@@ -191,6 +192,51 @@ pub trait RuntimeApiSubsystemClient {
 		at: Hash,
 	) -> Result<Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>, ApiError>;
 
+	/// Returns the execution environment parameters for the given session, if stored.
+	/// This is a staging method! Do not use on production runtimes!
+	async fn session_executor_params(
+		&self,
+		at: Hash,
+		session_index: SessionIndex,
+	) -> Result<Option<ExecutorParams>, ApiError>;
+
+	/// Returns the hash of the validation code the para is scheduled to upgrade to, if any.
+	/// This is a staging method! Do not use on production runtimes!
+	async fn future_validation_code_hash(
+		&self,
+		at: Hash,
+		para_id: Id,
+	) -> Result<Option<ValidationCodeHash>, ApiError>;
+
+	/// Returns a list of validators that lost a past session dispute and need to be slashed.
+	/// This is a staging method! Do not use on production runtimes!
+	async fn unapplied_slashes(
+		&self,
+		at: Hash,
+	) -> Result<Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>, ApiError>;
+
+	/// Returns a merkle proof of a validator session key in a past session.
+	/// This is a staging method! Do not use on production runtimes!
+	async fn key_ownership_proof(
+		&self,
+		at: Hash,
+		validator_id: ValidatorId,
+	) -> Result<Option<slashing::OpaqueKeyOwnershipProof>, ApiError>;
+
+	/// Submits an unsigned extrinsic to slash validators who lost a dispute about
+	/// a candidate of a past session.
+	/// This is a staging method! Do not use on production runtimes!
+	async fn submit_report_dispute_lost(
+		&self,
+		at: Hash,
+		dispute_proof: slashing::DisputeProof,
+		key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+	) -> Result<Option<()>, ApiError>;
+
+	/// Returns the validators disabled in the current session for having lost a dispute.
+	/// This is a staging method! Do not use on production runtimes!
+	async fn disabled_validators(&self, at: Hash) -> Result<Vec<ValidatorIndex>, ApiError>;
+
 	// === BABE API ===
 
 	/// Returns information regarding the current epoch.
@@ -381,4 +427,52 @@ where
 	) -> Result<Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>, ApiError> {
 		self.runtime_api().disputes(&BlockId::Hash(at))
 	}
+
+	async fn session_executor_params(
+		&self,
+		at: Hash,
+		session_index: SessionIndex,
+	) -> Result<Option<ExecutorParams>, ApiError> {
+		self.runtime_api().session_executor_params(&BlockId::Hash(at), session_index)
+	}
+
+	async fn future_validation_code_hash(
+		&self,
+		at: Hash,
+		para_id: Id,
+	) -> Result<Option<ValidationCodeHash>, ApiError> {
+		self.runtime_api().future_validation_code_hash(&BlockId::Hash(at), para_id)
+	}
+
+	async fn unapplied_slashes(
+		&self,
+		at: Hash,
+	) -> Result<Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>, ApiError> {
+		self.runtime_api().unapplied_slashes(&BlockId::Hash(at))
+	}
+
+	async fn key_ownership_proof(
+		&self,
+		at: Hash,
+		validator_id: ValidatorId,
+	) -> Result<Option<slashing::OpaqueKeyOwnershipProof>, ApiError> {
+		self.runtime_api().key_ownership_proof(&BlockId::Hash(at), validator_id)
+	}
+
+	async fn submit_report_dispute_lost(
+		&self,
+		at: Hash,
+		dispute_proof: slashing::DisputeProof,
+		key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+	) -> Result<Option<()>, ApiError> {
+		self.runtime_api().submit_report_dispute_lost(
+			&BlockId::Hash(at),
+			dispute_proof,
+			key_ownership_proof,
+		)
+	}
+
+	async fn disabled_validators(&self, at: Hash) -> Result<Vec<ValidatorIndex>, ApiError> {
+		self.runtime_api().disabled_validators(&BlockId::Hash(at))
+	}
 }
@@ -25,17 +25,23 @@ use sp_application_crypto::AppKey;
 use sp_core::crypto::ByteArray;
 use sp_keystore::{CryptoStore, SyncCryptoStorePtr};
 
-use polkadot_node_subsystem::{messages::RuntimeApiMessage, overseer, SubsystemSender};
-use polkadot_primitives::v2::{
-	CandidateEvent, CoreState, EncodeAs, GroupIndex, GroupRotationInfo, Hash, OccupiedCore,
-	ScrapedOnChainVotes, SessionIndex, SessionInfo, Signed, SigningContext, UncheckedSigned,
-	ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
+use polkadot_node_subsystem::{
+	errors::RuntimeApiError, messages::RuntimeApiMessage, overseer, SubsystemSender,
+};
+use polkadot_primitives::{
+	v2::{
+		CandidateEvent, CandidateHash, CoreState, EncodeAs, GroupIndex, GroupRotationInfo, Hash,
+		OccupiedCore, ScrapedOnChainVotes, SessionIndex, SessionInfo, Signed, SigningContext,
+		UncheckedSigned, ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
+	},
+	vstaging::slashing,
 };
 
 use crate::{
-	request_availability_cores, request_candidate_events, request_on_chain_votes,
-	request_session_index_for_child, request_session_info, request_validation_code_by_hash,
-	request_validator_groups,
+	request_availability_cores, request_candidate_events, request_disabled_validators,
+	request_key_ownership_proof, request_on_chain_votes, request_session_index_for_child,
+	request_session_info, request_submit_report_dispute_lost, request_unapplied_slashes,
+	request_validation_code_by_hash, request_validator_groups,
 };
 
 /// Errors that can happen on runtime fetches.
@@ -340,3 +346,75 @@ where
 	recv_runtime(request_validation_code_by_hash(relay_parent, validation_code_hash, sender).await)
 		.await
 }
+
+/// Fetch the validators who lost a dispute about a candidate of a past session and are yet to be
+/// slashed.
+pub async fn get_unapplied_slashes<Sender>(
+	sender: &mut Sender,
+	relay_parent: Hash,
+) -> Result<Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>>
+where
+	Sender: SubsystemSender<RuntimeApiMessage>,
+{
+	recv_runtime(request_unapplied_slashes(relay_parent, sender).await).await
+}
+
+/// Generate the key ownership proof of a validator in a past session.
+///
+/// Returns `None` if the validator is unknown to the runtime.
+pub async fn key_ownership_proof<Sender>(
+	sender: &mut Sender,
+	relay_parent: Hash,
+	validator_id: ValidatorId,
+) -> Result<Option<slashing::OpaqueKeyOwnershipProof>>
+where
+	Sender: SubsystemSender<RuntimeApiMessage>,
+{
+	recv_runtime(request_key_ownership_proof(relay_parent, validator_id, sender).await).await
+}
+
+/// Submit an unsigned report of a lost dispute about a candidate of a past session.
+///
+/// Returns `None` if the report could not be submitted.
+pub async fn submit_report_dispute_lost<Sender>(
+	sender: &mut Sender,
+	relay_parent: Hash,
+	dispute_proof: slashing::DisputeProof,
+	key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+) -> Result<Option<()>>
+where
+	Sender: SubsystemSender<RuntimeApiMessage>,
+{
+	recv_runtime(
+		request_submit_report_dispute_lost(
+			relay_parent,
+			dispute_proof,
+			key_ownership_proof,
+			sender,
+		)
+		.await,
+	)
+	.await
+}
+
+/// Fetch the validators disabled for having lost a dispute, together with the session they are
+/// disabled in.
+///
+/// The runtime returns the set of the session stored in the state of `relay_parent`, which is the
+/// session `SessionIndexForChild` reports for it. Nobody is disabled on runtimes which don't
+/// support disabling yet.
+pub async fn get_disabled_validators<Sender>(
+	sender: &mut Sender,
+	relay_parent: Hash,
+) -> Result<(SessionIndex, Vec<ValidatorIndex>)>
+where
+	Sender: SubsystemSender<RuntimeApiMessage>,
+{
+	let disabled = match recv_runtime(request_disabled_validators(relay_parent, sender).await).await
+	{
+		Err(Error::RuntimeRequest(RuntimeApiError::NotSupported { .. })) => Vec::new(),
+		result => result?,
+	};
+	let session = recv_runtime(request_session_index_for_child(relay_parent, sender).await).await?;
+	Ok((session, disabled))
+}
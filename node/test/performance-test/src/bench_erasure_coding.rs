@@ -0,0 +1,103 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Compare the time and the peak memory of the single-threaded and the parallel erasure encoding
+//! of a max-size PoV.
+
+use polkadot_performance_test::PerfCheckError;
+
+fn main() -> Result<(), PerfCheckError> {
+	#[cfg(build_type = "release")]
+	{
+		run::run()
+	}
+	#[cfg(not(build_type = "release"))]
+	{
+		Err(PerfCheckError::WrongBuildType)
+	}
+}
+
+#[cfg(build_type = "release")]
+mod run {
+	use polkadot_performance_test::{
+		available_data_with_pov_size, measure_erasure_encoding, measure_erasure_encoding_parallel,
+		measure_peak_memory, PerfCheckError, ERASURE_CODING_N_VALIDATORS,
+	};
+	use polkadot_primitives::v2::MAX_POV_SIZE;
+	use std::time::Duration;
+
+	const WARM_UP_RUNS: usize = 4;
+	const RUNS: usize = 16;
+
+	fn average(
+		measure: impl Fn() -> Result<Duration, PerfCheckError>,
+	) -> Result<Duration, PerfCheckError> {
+		for _ in 0..WARM_UP_RUNS {
+			measure()?;
+		}
+
+		let mut total = Duration::ZERO;
+		for _ in 0..RUNS {
+			total += measure()?;
+		}
+		Ok(total / RUNS as u32)
+	}
+
+	pub fn run() -> Result<(), PerfCheckError> {
+		let _ = env_logger::builder().filter(None, log::LevelFilter::Info).try_init();
+
+		let data = available_data_with_pov_size(MAX_POV_SIZE as usize);
+
+		log::info!(
+			"Encoding a {} byte PoV for {} validators, number of iterations: {}",
+			MAX_POV_SIZE,
+			ERASURE_CODING_N_VALIDATORS,
+			RUNS,
+		);
+
+		// The peak memory is measured on the first run of each encoding. Memory the allocator kept
+		// from earlier runs is reused without growing the resident set, so it is a lower bound.
+		let sequential_memory =
+			measure_peak_memory(|| measure_erasure_encoding(ERASURE_CODING_N_VALIDATORS, &data))?;
+		let sequential = average(|| measure_erasure_encoding(ERASURE_CODING_N_VALIDATORS, &data))?;
+		log::info!(
+			"Single-threaded encoding: {:?}, peak memory: {}",
+			sequential,
+			format_memory(sequential_memory),
+		);
+
+		let parallel_memory = measure_peak_memory(|| {
+			measure_erasure_encoding_parallel(ERASURE_CODING_N_VALIDATORS, &data)
+		})?;
+		let parallel =
+			average(|| measure_erasure_encoding_parallel(ERASURE_CODING_N_VALIDATORS, &data))?;
+		log::info!(
+			"Parallel encoding: {:?} ({:.2}x), peak memory: {}",
+			parallel,
+			sequential.as_secs_f64() / parallel.as_secs_f64(),
+			format_memory(parallel_memory),
+		);
+
+		Ok(())
+	}
+
+	fn format_memory(bytes: Option<u64>) -> String {
+		match bytes {
+			Some(bytes) => format!("{} KiB", bytes / 1024),
+			None => "unknown".to_string(),
+		}
+	}
+}
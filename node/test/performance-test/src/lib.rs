@@ -16,7 +16,10 @@
 
 //! A Polkadot performance tests utilities.
 
-use polkadot_erasure_coding::{obtain_chunks, reconstruct};
+use polkadot_erasure_coding::{
+	branches, obtain_chunks, obtain_chunks_v1, obtain_chunks_v1_parallel, reconstruct,
+	BranchesBuilder,
+};
 use polkadot_node_core_pvf::{sc_executor_common, sp_maybe_compressed_blob};
 use std::time::{Duration, Instant};
 
@@ -24,6 +27,7 @@ mod constants;
 
 pub use constants::*;
 pub use polkadot_node_primitives::VALIDATION_CODE_BOMB_LIMIT;
+use polkadot_node_primitives::{AvailableData, BlockData, PoV};
 
 /// Value used for reference benchmark of erasure-coding.
 pub const ERASURE_CODING_N_VALIDATORS: usize = 1024;
@@ -69,7 +73,9 @@ pub fn measure_pvf_prepare(wasm_code: &[u8]) -> Result<Duration, PerfCheckError>
 
 	// Recreate the pipeline from the pvf prepare worker.
 	let blob = polkadot_node_core_pvf::prevalidate(code.as_ref()).map_err(PerfCheckError::from)?;
-	polkadot_node_core_pvf::prepare(blob).map_err(PerfCheckError::from)?;
+	let semantics = polkadot_node_core_pvf::params_to_wasmtime_semantics(&Default::default())
+		.map_err(sc_executor_common::error::WasmError::Other)?;
+	polkadot_node_core_pvf::prepare(blob, &semantics).map_err(PerfCheckError::from)?;
 
 	Ok(start.elapsed())
 }
@@ -88,3 +94,90 @@ pub fn measure_erasure_coding(
 
 	Ok(start.elapsed())
 }
+
+/// Builds `AvailableData` with a PoV of the given size, as a backer or a block author would
+/// erasure-code it.
+pub fn available_data_with_pov_size(pov_size: usize) -> AvailableData {
+	let block_data = (0..pov_size).map(|i| (i % 251) as u8).collect();
+	AvailableData {
+		pov: PoV { block_data: BlockData(block_data) }.into(),
+		validation_data: Default::default(),
+	}
+}
+
+/// Measure the time it takes to obtain the chunks of the data and their merkle proofs, encoding on
+/// a single thread.
+pub fn measure_erasure_encoding(
+	n_validators: usize,
+	data: &AvailableData,
+) -> Result<Duration, PerfCheckError> {
+	let start = Instant::now();
+
+	let chunks = obtain_chunks_v1(n_validators, data)?;
+	let _: Vec<_> = branches(chunks.as_ref()).collect();
+
+	Ok(start.elapsed())
+}
+
+/// Measure the time it takes to obtain the chunks of the data and their merkle proofs, encoding on
+/// multiple threads and building the trie incrementally.
+pub fn measure_erasure_encoding_parallel(
+	n_validators: usize,
+	data: &AvailableData,
+) -> Result<Duration, PerfCheckError> {
+	let start = Instant::now();
+
+	let chunks = obtain_chunks_v1_parallel(n_validators, data)?;
+	let mut builder = BranchesBuilder::with_capacity(chunks.len());
+	for chunk in &chunks {
+		builder.push(chunk);
+	}
+	let proofs = builder.build();
+	let _: Vec<_> = (0..chunks.len()).map(|i| proofs.proof(i)).collect();
+
+	Ok(start.elapsed())
+}
+
+/// Measure the memory used by the given measurement, as the growth of the peak resident set size
+/// of the process over its resident set size before the measurement.
+///
+/// Returns `None` if the resident set size is not available on this platform.
+#[cfg(target_os = "linux")]
+pub fn measure_peak_memory(
+	measure: impl FnOnce() -> Result<Duration, PerfCheckError>,
+) -> Result<Option<u64>, PerfCheckError> {
+	let before = proc_status_bytes("VmRSS:");
+	// Resets the peak resident set size of the process to its current resident set size.
+	std::fs::write("/proc/self/clear_refs", "5")?;
+	measure()?;
+	let peak = proc_status_bytes("VmHWM:");
+
+	Ok(before.zip(peak).map(|(before, peak)| peak.saturating_sub(before)))
+}
+
+/// Measure the memory used by the given measurement, as the growth of the peak resident set size
+/// of the process over its resident set size before the measurement.
+///
+/// Returns `None` if the resident set size is not available on this platform.
+#[cfg(not(target_os = "linux"))]
+pub fn measure_peak_memory(
+	measure: impl FnOnce() -> Result<Duration, PerfCheckError>,
+) -> Result<Option<u64>, PerfCheckError> {
+	measure()?;
+	Ok(None)
+}
+
+/// Returns the value of the given memory field of the status of the current process in bytes.
+#[cfg(target_os = "linux")]
+fn proc_status_bytes(field: &str) -> Option<u64> {
+	let status = std::fs::read_to_string("/proc/self/status").ok()?;
+	let kib = status
+		.lines()
+		.find_map(|line| line.strip_prefix(field))?
+		.trim()
+		.strip_suffix("kB")?
+		.trim()
+		.parse::<u64>()
+		.ok()?;
+	Some(kib * 1024)
+}
@@ -101,6 +101,10 @@ pub fn new_full(
 		None,
 		None,
 		None,
+		false,
+		None,
+		Default::default(),
+		false,
 	)
 }
 
@@ -68,6 +68,10 @@ fn main() -> Result<()> {
 					None,
 					None,
 					None,
+					false,
+					None,
+					Default::default(),
+					false,
 				)
 				.map_err(|e| e.to_string())?;
 				let mut overseer_handle = full_node
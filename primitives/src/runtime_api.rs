@@ -110,7 +110,7 @@
 //! All staging API functions should use primitives from `vstaging`. They should be clearly separated
 //! from the stable primitives.
 
-use crate::v2;
+use crate::{v2, vstaging};
 use parity_scale_codec::{Decode, Encode};
 use polkadot_core_primitives as pcp;
 use polkadot_parachain::primitives as ppp;
@@ -221,5 +221,40 @@ sp_api::decl_runtime_apis! {
 		/// Returns all onchain disputes.
 		#[api_version(3)]
 		fn disputes() -> Vec<(v2::SessionIndex, v2::CandidateHash, v2::DisputeState<v2::BlockNumber>)>;
+
+		/// Returns the executor parameters agreed on-chain for the given session, if stored.
+		#[api_version(4)]
+		fn session_executor_params(session_index: sp_staking::SessionIndex) -> Option<vstaging::ExecutorParams>;
+
+		/// Returns the hash of the validation code a para is scheduled to upgrade to, if any.
+		///
+		/// The code itself can be fetched with `validation_code_by_hash`.
+		#[api_version(5)]
+		fn future_validation_code_hash(para_id: ppp::Id) -> Option<ppp::ValidationCodeHash>;
+
+		/// Returns the validators who lost a dispute about a candidate of a past session and are
+		/// yet to be slashed.
+		#[api_version(6)]
+		fn unapplied_slashes() -> Vec<(v2::SessionIndex, v2::CandidateHash, vstaging::slashing::PendingSlashes)>;
+
+		/// Returns a proof that the given validator key was part of a past validator set, if it
+		/// is known.
+		#[api_version(6)]
+		fn key_ownership_proof(validator_id: v2::ValidatorId) -> Option<vstaging::slashing::OpaqueKeyOwnershipProof>;
+
+		/// Submits an unsigned report of a lost dispute about a candidate of a past session into
+		/// the transaction pool, so the validator gets slashed.
+		///
+		/// Returns `None` if the report could not be submitted.
+		#[api_version(6)]
+		fn submit_report_dispute_lost(
+			dispute_proof: vstaging::slashing::DisputeProof,
+			key_ownership_proof: vstaging::slashing::OpaqueKeyOwnershipProof,
+		) -> Option<()>;
+
+		/// Returns the validators disabled in the current session for having lost a dispute about
+		/// an invalid candidate, sorted by validator index.
+		#[api_version(7)]
+		fn disabled_validators() -> Vec<v2::ValidatorIndex>;
 	}
 }
@@ -608,7 +608,7 @@ impl Ord for CommittedCandidateReceipt {
 /// The `PersistedValidationData` should be relatively lightweight primarily because it is constructed
 /// during inclusion for each candidate and therefore lies on the critical path of inclusion.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo, RuntimeDebug)]
-#[cfg_attr(feature = "std", derive(Default, MallocSizeOf))]
+#[cfg_attr(feature = "std", derive(Default, MallocSizeOf, Serialize, Deserialize))]
 pub struct PersistedValidationData<H = Hash, N = BlockNumber> {
 	/// The parent head-data.
 	pub parent_head: HeadData,
@@ -664,6 +664,15 @@ impl From<BitVec<u8, bitvec::order::Lsb0>> for AvailabilityBitfield {
 	}
 }
 
+impl AvailabilityBitfield {
+	/// Whether this bitfield has all the bits of `other` set, and more, so it may replace `other`.
+	pub fn is_strict_superset_of(&self, other: &Self) -> bool {
+		self.0.len() == other.0.len() &&
+			self.0.count_ones() > other.0.count_ones() &&
+			other.0.iter_ones().all(|i| self.0[i])
+	}
+}
+
 /// A signed compact statement, suitable to be sent to the chain.
 pub type SignedStatement = Signed<CompactStatement>;
 /// A signed compact statement, with signature not yet checked.
@@ -1810,6 +1819,23 @@ mod tests {
 		assert_eq!(supermajority_threshold(7), 5);
 	}
 
+	#[test]
+	fn availability_bitfield_strict_superset() {
+		use bitvec::{bitvec, order::Lsb0};
+
+		let bitfield = |bits: &[bool]| AvailabilityBitfield(bits.iter().copied().collect());
+		let old = bitfield(&[true, false, false, true]);
+
+		assert!(bitfield(&[true, true, false, true]).is_strict_superset_of(&old));
+		assert!(bitfield(&[true, true, true, true]).is_strict_superset_of(&old));
+		// Same bits.
+		assert!(!old.is_strict_superset_of(&old));
+		// A bit of the old one got unset.
+		assert!(!bitfield(&[false, true, true, true]).is_strict_superset_of(&old));
+		// Different length.
+		assert!(!AvailabilityBitfield(bitvec![u8, Lsb0; 1; 5]).is_strict_superset_of(&old));
+	}
+
 	#[test]
 	fn balance_bigger_than_usize() {
 		let zero_b: Balance = 0;
@@ -0,0 +1,150 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Abstract execution environment parameter set.
+//!
+//! Parameters are set by the runtime and are used by the node to prepare and execute PVFs. Every
+//! validator must use exactly the same set of parameters for a given session, otherwise the
+//! results of the preparation and execution of the same PVF could diverge.
+
+use crate::v2::{BlakeTwo256, Hash, HashT};
+use parity_scale_codec::{Decode, Encode};
+use primitives::RuntimeDebug;
+use scale_info::TypeInfo;
+use sp_std::prelude::*;
+
+#[cfg(feature = "std")]
+use parity_util_mem::MallocSizeOf;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// The execution environment the parameter set is meant for.
+///
+/// This serves as the version of the parameter set: the meaning (and the defaults) of the
+/// parameters is defined by the environment. A new environment should be introduced every time
+/// a change of the node-side executor would make previously agreed parameters mean something
+/// different.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, MallocSizeOf))]
+pub enum ExecutionEnvironment {
+	/// Generic Wasmtime executor.
+	#[codec(index = 0)]
+	WasmtimeGeneric,
+}
+
+/// A single executor parameter.
+///
+/// Any parameter not specified in the set takes its default value as defined by the node for the
+/// given [`ExecutionEnvironment`].
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, MallocSizeOf))]
+pub enum ExecutorParam {
+	/// Number of extra heap pages allocated for the PVF instance.
+	#[codec(index = 0)]
+	ExtraHeapPages(u64),
+	/// Maximum number of 64KiB pages the linear memory of the PVF instance may grow to.
+	#[codec(index = 1)]
+	MaxMemoryPages(u32),
+	/// Wasm logical stack size limit, in units of the deterministic stack instrumentation.
+	#[codec(index = 2)]
+	StackLogicalMax(u32),
+	/// Executor machine stack size limit, in bytes.
+	#[codec(index = 3)]
+	StackNativeMax(u32),
+}
+
+/// Unit type wrapper around [`type@Hash`] that represents an executor parameter set hash.
+///
+/// This type is produced by [`ExecutorParams::hash`].
+#[derive(Clone, Copy, Encode, Decode, Hash, Eq, PartialEq, PartialOrd, Ord, TypeInfo)]
+pub struct ExecutorParamsHash(Hash);
+
+impl sp_std::fmt::Display for ExecutorParamsHash {
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter<'_>) -> sp_std::fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+impl sp_std::fmt::Debug for ExecutorParamsHash {
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter<'_>) -> sp_std::fmt::Result {
+		write!(f, "{:?}", self.0)
+	}
+}
+
+impl sp_std::fmt::LowerHex for ExecutorParamsHash {
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter<'_>) -> sp_std::fmt::Result {
+		sp_std::fmt::LowerHex::fmt(&self.0, f)
+	}
+}
+
+impl From<Hash> for ExecutorParamsHash {
+	fn from(hash: Hash) -> ExecutorParamsHash {
+		ExecutorParamsHash(hash)
+	}
+}
+
+impl From<[u8; 32]> for ExecutorParamsHash {
+	fn from(hash: [u8; 32]) -> ExecutorParamsHash {
+		ExecutorParamsHash(hash.into())
+	}
+}
+
+/// A versioned set of executor parameters.
+///
+/// The default value is the [`ExecutionEnvironment::WasmtimeGeneric`] environment without any
+/// overrides, meaning that the node uses its built-in defaults.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, MallocSizeOf))]
+pub struct ExecutorParams {
+	environment: ExecutionEnvironment,
+	params: Vec<ExecutorParam>,
+}
+
+impl ExecutorParams {
+	/// Creates a new parameter set for the given environment.
+	///
+	/// Should a parameter be specified more than once, the last occurrence wins.
+	pub fn new(environment: ExecutionEnvironment, params: Vec<ExecutorParam>) -> Self {
+		Self { environment, params }
+	}
+
+	/// Returns the execution environment the parameters are meant for.
+	pub fn environment(&self) -> ExecutionEnvironment {
+		self.environment
+	}
+
+	/// Returns an iterator over the parameters, in the order they were specified.
+	pub fn iter(&self) -> impl Iterator<Item = &ExecutorParam> {
+		self.params.iter()
+	}
+
+	/// Returns the hash of the parameter set.
+	pub fn hash(&self) -> ExecutorParamsHash {
+		ExecutorParamsHash(BlakeTwo256::hash_of(self))
+	}
+}
+
+impl Default for ExecutorParams {
+	fn default() -> Self {
+		Self { environment: ExecutionEnvironment::WasmtimeGeneric, params: Vec::new() }
+	}
+}
+
+impl From<Vec<ExecutorParam>> for ExecutorParams {
+	fn from(params: Vec<ExecutorParam>) -> Self {
+		Self::new(ExecutionEnvironment::WasmtimeGeneric, params)
+	}
+}
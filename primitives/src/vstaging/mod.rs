@@ -17,3 +17,10 @@
 //! Staging Primitives.
 
 // Put any primitives used by staging APIs functions here
+
+mod executor_params;
+pub mod slashing;
+
+pub use executor_params::{
+	ExecutionEnvironment, ExecutorParam, ExecutorParams, ExecutorParamsHash,
+};
@@ -0,0 +1,285 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for introspecting the disputes known to the dispute coordinator of the node.
+
+use futures::channel::oneshot;
+use jsonrpsee::{
+	core::{async_trait, Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use serde::{Deserialize, Serialize};
+
+use polkadot_node_primitives::{DisputeInfo, DisputeStatus, DisputesFilter, Timestamp};
+use polkadot_node_subsystem_types::messages::DisputeCoordinatorMessage;
+use polkadot_overseer::Handle;
+use polkadot_primitives::v2::{
+	Hash, Id as ParaId, InvalidDisputeStatementKind, SessionIndex, ValidDisputeStatementKind,
+};
+use sc_rpc::DenyUnsafe;
+
+/// Error code of a query the dispute coordinator did not answer.
+const DISPUTE_COORDINATOR_UNAVAILABLE: i32 = 9500;
+
+/// Disputes RPC methods.
+#[rpc(client, server)]
+pub trait DisputesApi {
+	/// Returns the recent disputes known to the node, with the votes on them and the outcome of
+	/// the node's own participation.
+	///
+	/// Only disputes about candidates of the given session and para are returned, if given, and
+	/// only the active ones if `active_only` is set.
+	#[method(name = "parachain_disputes")]
+	async fn disputes(
+		&self,
+		session: Option<SessionIndex>,
+		para_id: Option<u32>,
+		active_only: Option<bool>,
+	) -> RpcResult<Vec<Dispute>>;
+}
+
+/// A dispute, as returned by `parachain_disputes`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dispute {
+	/// The session of the disputed candidate.
+	pub session: SessionIndex,
+	/// The hash of the disputed candidate.
+	pub candidate_hash: Hash,
+	/// The para the disputed candidate belongs to.
+	pub para_id: u32,
+	/// The status of the dispute.
+	pub status: Status,
+	/// Whether the dispute is still active.
+	pub active: bool,
+	/// The votes on the candidate, sorted by validator index.
+	pub votes: Vec<Vote>,
+	/// The outcome of the node's own participation, if it participated since it got started.
+	pub participation: Option<Participation>,
+}
+
+/// The status of a dispute.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Status {
+	/// The dispute is active and unconcluded.
+	Active,
+	/// The dispute is active and not a spam dispute.
+	Confirmed,
+	/// The dispute has concluded in favor of the candidate, since the given UNIX timestamp.
+	ConcludedFor(Timestamp),
+	/// The dispute has concluded against the candidate, since the given UNIX timestamp.
+	ConcludedAgainst(Timestamp),
+}
+
+/// A vote on a disputed candidate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Vote {
+	/// The index of the validator in the session.
+	pub validator_index: u32,
+	/// Whether the vote is one for the validity of the candidate.
+	pub valid: bool,
+	/// How the vote was cast: `explicit`, `backingSeconded`, `backingValid` or
+	/// `approvalChecking`.
+	pub kind: String,
+	/// Whether the validator is controlled by the node, so this is the node's own vote.
+	pub own: bool,
+}
+
+/// The outcome of the node's own validation of a disputed candidate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "outcome")]
+pub enum Participation {
+	/// The candidate was found to be valid.
+	Valid,
+	/// The candidate was found to be invalid, for the given reason if it got validated at all.
+	Invalid {
+		/// The reason given by candidate validation.
+		reason: Option<String>,
+	},
+	/// The candidate could not be recovered.
+	Unavailable,
+	/// Participation failed, details can be found in the logs of the node.
+	Error,
+}
+
+impl From<DisputeStatus> for Status {
+	fn from(status: DisputeStatus) -> Self {
+		match status {
+			DisputeStatus::Active => Status::Active,
+			DisputeStatus::Confirmed => Status::Confirmed,
+			DisputeStatus::ConcludedFor(since) => Status::ConcludedFor(since),
+			DisputeStatus::ConcludedAgainst(since) => Status::ConcludedAgainst(since),
+		}
+	}
+}
+
+impl From<DisputeInfo> for Dispute {
+	fn from(info: DisputeInfo) -> Self {
+		let own = |index| info.own_validator_indices.contains(&index);
+		let mut votes: Vec<Vote> = info
+			.valid_votes
+			.iter()
+			.map(|(index, kind)| Vote {
+				validator_index: index.0,
+				valid: true,
+				kind: match kind {
+					ValidDisputeStatementKind::Explicit => "explicit",
+					ValidDisputeStatementKind::BackingSeconded(_) => "backingSeconded",
+					ValidDisputeStatementKind::BackingValid(_) => "backingValid",
+					ValidDisputeStatementKind::ApprovalChecking => "approvalChecking",
+				}
+				.into(),
+				own: own(*index),
+			})
+			.chain(info.invalid_votes.iter().map(|(index, kind)| {
+				Vote {
+					validator_index: index.0,
+					valid: false,
+					kind: match kind {
+						InvalidDisputeStatementKind::Explicit => "explicit",
+					}
+					.into(),
+					own: own(*index),
+				}
+			}))
+			.collect();
+		votes.sort_by_key(|vote| (vote.validator_index, !vote.valid));
+
+		let participation = info.participation.map(|outcome| {
+			use polkadot_node_primitives::ParticipationOutcome;
+
+			match outcome {
+				ParticipationOutcome::Valid => Participation::Valid,
+				ParticipationOutcome::Invalid(reason) =>
+					Participation::Invalid { reason: reason.map(|r| format!("{:?}", r)) },
+				ParticipationOutcome::Unavailable => Participation::Unavailable,
+				ParticipationOutcome::Error => Participation::Error,
+			}
+		});
+
+		Dispute {
+			session: info.session,
+			candidate_hash: info.candidate_hash.0,
+			para_id: info.para_id.into(),
+			status: info.status.into(),
+			active: info.active,
+			votes,
+			participation,
+		}
+	}
+}
+
+/// Implements the `DisputesApi` by querying the dispute coordinator via the overseer.
+pub struct Disputes {
+	overseer_handle: Handle,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl Disputes {
+	/// Create a new `Disputes` RPC handler.
+	pub fn new(overseer_handle: Handle, deny_unsafe: DenyUnsafe) -> Self {
+		Self { overseer_handle, deny_unsafe }
+	}
+}
+
+#[async_trait]
+impl DisputesApiServer for Disputes {
+	async fn disputes(
+		&self,
+		session: Option<SessionIndex>,
+		para_id: Option<u32>,
+		active_only: Option<bool>,
+	) -> RpcResult<Vec<Dispute>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let filter = DisputesFilter {
+			session,
+			para_id: para_id.map(ParaId::from),
+			active_only: active_only.unwrap_or(false),
+		};
+		let (tx, rx) = oneshot::channel();
+		self.overseer_handle
+			.clone()
+			.send_msg(
+				DisputeCoordinatorMessage::QueryDisputes(filter, tx),
+				std::any::type_name::<Self>(),
+			)
+			.await;
+
+		// The request gets dropped if there is no overseer running, or if the dispute
+		// coordinator can't answer it for lack of session info.
+		let disputes = rx.await.map_err(|_| {
+			JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+				DISPUTE_COORDINATOR_UNAVAILABLE,
+				"The dispute coordinator is not available",
+				None::<()>,
+			)))
+		})?;
+
+		Ok(disputes.into_iter().map(Into::into).collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_node_primitives::{InvalidCandidate, ParticipationOutcome};
+	use polkadot_primitives::v2::{CandidateHash, ValidatorIndex};
+
+	#[test]
+	fn disputes_get_converted_with_sorted_votes() {
+		let info = DisputeInfo {
+			session: 3,
+			candidate_hash: CandidateHash(Hash::repeat_byte(1)),
+			para_id: ParaId::from(2000),
+			status: DisputeStatus::ConcludedAgainst(100),
+			active: true,
+			valid_votes: vec![(
+				ValidatorIndex(2),
+				ValidDisputeStatementKind::BackingValid(Hash::zero()),
+			)],
+			invalid_votes: vec![
+				(ValidatorIndex(0), InvalidDisputeStatementKind::Explicit),
+				(ValidatorIndex(5), InvalidDisputeStatementKind::Explicit),
+			],
+			own_validator_indices: vec![ValidatorIndex(5)],
+			participation: Some(ParticipationOutcome::Invalid(Some(InvalidCandidate::Timeout))),
+		};
+
+		let dispute = Dispute::from(info);
+		assert_eq!(dispute.para_id, 2000);
+		assert_eq!(dispute.status, Status::ConcludedAgainst(100));
+		assert_eq!(
+			dispute
+				.votes
+				.iter()
+				.map(|vote| (vote.validator_index, vote.valid, vote.kind.as_str(), vote.own))
+				.collect::<Vec<_>>(),
+			vec![
+				(0, false, "explicit", false),
+				(2, true, "backingValid", false),
+				(5, false, "explicit", true),
+			],
+		);
+		assert_eq!(
+			dispute.participation,
+			Some(Participation::Invalid { reason: Some("Timeout".into()) })
+		);
+	}
+}
@@ -1478,6 +1478,7 @@ pub type Executive = frame_executive::Executive<
 		pallet_multisig::migrations::v1::MigrateToV1<Runtime>,
 		// "Properly migrate weights to v2" <https://github.com/paritytech/polkadot/pull/6091>
 		parachains_configuration::migration::v3::MigrateToV3<Runtime>,
+		parachains_configuration::migration::v4::MigrateToV4<Runtime>,
 	),
 >;
 /// The payload being signed in the transactions.
@@ -22,7 +22,10 @@ use crate::shared;
 use frame_support::{pallet_prelude::*, weights::constants::WEIGHT_PER_MILLIS};
 use frame_system::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
-use primitives::v2::{Balance, SessionIndex, MAX_CODE_SIZE, MAX_HEAD_DATA_SIZE, MAX_POV_SIZE};
+use primitives::{
+	v2::{Balance, SessionIndex, MAX_CODE_SIZE, MAX_HEAD_DATA_SIZE, MAX_POV_SIZE},
+	vstaging::{ExecutorParam, ExecutorParams},
+};
 use sp_runtime::traits::Zero;
 use sp_std::prelude::*;
 
@@ -38,6 +41,9 @@ pub mod migration;
 
 const LOG_TARGET: &str = "runtime::configuration";
 
+/// The maximum number of 64KiB pages the linear memory of a 32-bit wasm instance can have.
+const MAX_WASM_MEMORY_PAGES: u32 = 65536;
+
 /// All configuration of the runtime with respect to parachains and parathreads.
 #[derive(Clone, Encode, Decode, PartialEq, sp_core::RuntimeDebug, scale_info::TypeInfo)]
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
@@ -241,6 +247,16 @@ pub struct HostConfiguration<BlockNumber> {
 	/// This value should be greater than [`chain_availability_period`] and
 	/// [`thread_availability_period`].
 	pub minimum_validation_upgrade_delay: BlockNumber,
+	/// The parameters of the execution environment used by validators to prepare and execute
+	/// PVFs.
+	///
+	/// The parameters are captured for every session at its start and cannot change for the
+	/// session's duration. See [`crate::session_info::Pallet::session_executor_params`].
+	///
+	/// Every parameter may be specified at most once, the memory parameters must fit into a
+	/// 32-bit wasm linear memory, with the maximum memory not less than the extra heap pages, and
+	/// the stack limits must not be zero.
+	pub executor_params: ExecutorParams,
 }
 
 impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber> {
@@ -290,6 +306,7 @@ impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber
 			pvf_checking_enabled: false,
 			pvf_voting_ttl: 2u32.into(),
 			minimum_validation_upgrade_delay: 2.into(),
+			executor_params: Default::default(),
 		}
 	}
 }
@@ -329,6 +346,12 @@ pub enum InconsistentError<BlockNumber> {
 	MaxHrmpOutboundChannelsExceeded,
 	/// Maximum number of HRMP inbound channels exceeded.
 	MaxHrmpInboundChannelsExceeded,
+	/// A parameter is specified more than once in `executor_params`.
+	DuplicateExecutorParam,
+	/// A parameter in `executor_params` is zero or exceeds its hard limit.
+	ExecutorParamOutOfBounds,
+	/// The `MaxMemoryPages` of `executor_params` is less than its `ExtraHeapPages`.
+	MaxMemoryPagesLessThanExtraHeapPages { max_memory_pages: u32, extra_heap_pages: u64 },
 }
 
 impl<BlockNumber> HostConfiguration<BlockNumber>
@@ -406,6 +429,42 @@ where
 			return Err(MaxHrmpInboundChannelsExceeded)
 		}
 
+		let mut seen = Vec::new();
+		let (mut max_memory_pages, mut extra_heap_pages) = (None, None);
+		for param in self.executor_params.iter() {
+			let kind = sp_std::mem::discriminant(param);
+			if seen.contains(&kind) {
+				return Err(DuplicateExecutorParam)
+			}
+			seen.push(kind);
+
+			let in_bounds = match *param {
+				ExecutorParam::ExtraHeapPages(pages) => {
+					extra_heap_pages = Some(pages);
+					pages <= MAX_WASM_MEMORY_PAGES as u64
+				},
+				ExecutorParam::MaxMemoryPages(pages) => {
+					max_memory_pages = Some(pages);
+					pages > 0 && pages <= MAX_WASM_MEMORY_PAGES
+				},
+				ExecutorParam::StackLogicalMax(max) | ExecutorParam::StackNativeMax(max) => max > 0,
+			};
+			if !in_bounds {
+				return Err(ExecutorParamOutOfBounds)
+			}
+		}
+
+		if let (Some(max_memory_pages), Some(extra_heap_pages)) =
+			(max_memory_pages, extra_heap_pages)
+		{
+			if (max_memory_pages as u64) < extra_heap_pages {
+				return Err(MaxMemoryPagesLessThanExtraHeapPages {
+					max_memory_pages,
+					extra_heap_pages,
+				})
+			}
+		}
+
 		Ok(())
 	}
 
@@ -428,6 +487,7 @@ pub trait WeightInfo {
 	fn set_config_with_weight() -> Weight;
 	fn set_config_with_balance() -> Weight;
 	fn set_hrmp_open_request_ttl() -> Weight;
+	fn set_config_with_executor_params() -> Weight;
 }
 
 pub struct TestWeightInfo;
@@ -450,6 +510,9 @@ impl WeightInfo for TestWeightInfo {
 	fn set_hrmp_open_request_ttl() -> Weight {
 		Weight::MAX
 	}
+	fn set_config_with_executor_params() -> Weight {
+		Weight::MAX
+	}
 }
 
 #[frame_support::pallet]
@@ -1099,6 +1162,21 @@ pub mod pallet {
 			})
 		}
 
+		/// Set the executor parameters used by validators to prepare and execute PVFs.
+		///
+		/// Every parameter may be specified at most once. See the field documentation for the
+		/// other constraints.
+		#[pallet::weight((
+			T::WeightInfo::set_config_with_executor_params(),
+			DispatchClass::Operational,
+		))]
+		pub fn set_executor_params(origin: OriginFor<T>, new: ExecutorParams) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::schedule_config_update(|config| {
+				config.executor_params = new;
+			})
+		}
+
 		/// Setting this to true will disable consistency checks for the configuration setters.
 		/// Use with caution.
 		#[pallet::weight((
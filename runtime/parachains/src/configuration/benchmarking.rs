@@ -17,6 +17,7 @@
 use crate::configuration::*;
 use frame_benchmarking::{benchmarks, BenchmarkError, BenchmarkResult};
 use frame_system::RawOrigin;
+use primitives::vstaging::{ExecutorParam, ExecutorParams};
 use sp_runtime::traits::One;
 
 benchmarks! {
@@ -36,6 +37,13 @@ benchmarks! {
 
 	set_config_with_balance {}: set_hrmp_sender_deposit(RawOrigin::Root, 100_000_000_000)
 
+	set_config_with_executor_params {}: set_executor_params(RawOrigin::Root, ExecutorParams::from(vec![
+		ExecutorParam::ExtraHeapPages(2048),
+		ExecutorParam::MaxMemoryPages(2080),
+		ExecutorParam::StackLogicalMax(65536),
+		ExecutorParam::StackNativeMax(256 * 1024 * 1024),
+	]))
+
 	impl_benchmark_test_suite!(
 		Pallet,
 		crate::mock::new_test_ext(Default::default()),
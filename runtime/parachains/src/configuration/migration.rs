@@ -29,7 +29,8 @@ use frame_system::pallet_prelude::BlockNumberFor;
 /// v0-v1: <https://github.com/paritytech/polkadot/pull/3575>
 /// v1-v2: <https://github.com/paritytech/polkadot/pull/4420>
 /// v2-v3: <https://github.com/paritytech/polkadot/pull/6091>
-pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+/// v3-v4: executor parameters were added to the host configuration.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
 
 pub mod v3 {
 	use super::*;
@@ -144,7 +145,7 @@ pub mod v3 {
 				let weight_consumed = migrate_to_v3::<T>();
 
 				log::info!(target: configuration::LOG_TARGET, "MigrateToV3 executed successfully");
-				STORAGE_VERSION.put::<Pallet<T>>();
+				StorageVersion::new(3).put::<Pallet<T>>();
 
 				weight_consumed
 			} else {
@@ -155,6 +156,143 @@ pub mod v3 {
 	}
 }
 
+pub mod v4 {
+	use super::*;
+	use frame_support::{storage_alias, traits::OnRuntimeUpgrade};
+	use primitives::v2::{Balance, SessionIndex};
+
+	// Copied over from configuration.rs before the executor parameters were added and removed all
+	// the comments.
+	#[derive(parity_scale_codec::Encode, parity_scale_codec::Decode, Debug)]
+	pub struct OldHostConfiguration<BlockNumber> {
+		pub max_code_size: u32,
+		pub max_head_data_size: u32,
+		pub max_upward_queue_count: u32,
+		pub max_upward_queue_size: u32,
+		pub max_upward_message_size: u32,
+		pub max_upward_message_num_per_candidate: u32,
+		pub hrmp_max_message_num_per_candidate: u32,
+		pub validation_upgrade_cooldown: BlockNumber,
+		pub validation_upgrade_delay: BlockNumber,
+		pub max_pov_size: u32,
+		pub max_downward_message_size: u32,
+		pub ump_service_total_weight: Weight,
+		pub hrmp_max_parachain_outbound_channels: u32,
+		pub hrmp_max_parathread_outbound_channels: u32,
+		pub hrmp_sender_deposit: Balance,
+		pub hrmp_recipient_deposit: Balance,
+		pub hrmp_channel_max_capacity: u32,
+		pub hrmp_channel_max_total_size: u32,
+		pub hrmp_max_parachain_inbound_channels: u32,
+		pub hrmp_max_parathread_inbound_channels: u32,
+		pub hrmp_channel_max_message_size: u32,
+		pub code_retention_period: BlockNumber,
+		pub parathread_cores: u32,
+		pub parathread_retries: u32,
+		pub group_rotation_frequency: BlockNumber,
+		pub chain_availability_period: BlockNumber,
+		pub thread_availability_period: BlockNumber,
+		pub scheduling_lookahead: u32,
+		pub max_validators_per_core: Option<u32>,
+		pub max_validators: Option<u32>,
+		pub dispute_period: SessionIndex,
+		pub dispute_post_conclusion_acceptance_period: BlockNumber,
+		pub dispute_max_spam_slots: u32,
+		pub dispute_conclusion_by_time_out_period: BlockNumber,
+		pub no_show_slots: u32,
+		pub n_delay_tranches: u32,
+		pub zeroth_delay_tranche_width: u32,
+		pub needed_approvals: u32,
+		pub relay_vrf_modulo_samples: u32,
+		pub ump_max_individual_weight: Weight,
+		pub pvf_checking_enabled: bool,
+		pub pvf_voting_ttl: SessionIndex,
+		pub minimum_validation_upgrade_delay: BlockNumber,
+	}
+
+	impl<BlockNumber: Default + From<u32>> Default for OldHostConfiguration<BlockNumber> {
+		fn default() -> Self {
+			Self {
+				group_rotation_frequency: 1u32.into(),
+				chain_availability_period: 1u32.into(),
+				thread_availability_period: 1u32.into(),
+				no_show_slots: 1u32.into(),
+				validation_upgrade_cooldown: Default::default(),
+				validation_upgrade_delay: 2u32.into(),
+				code_retention_period: Default::default(),
+				max_code_size: Default::default(),
+				max_pov_size: Default::default(),
+				max_head_data_size: Default::default(),
+				parathread_cores: Default::default(),
+				parathread_retries: Default::default(),
+				scheduling_lookahead: Default::default(),
+				max_validators_per_core: Default::default(),
+				max_validators: None,
+				dispute_period: 6,
+				dispute_post_conclusion_acceptance_period: 100.into(),
+				dispute_max_spam_slots: 2,
+				dispute_conclusion_by_time_out_period: 200.into(),
+				n_delay_tranches: Default::default(),
+				zeroth_delay_tranche_width: Default::default(),
+				needed_approvals: Default::default(),
+				relay_vrf_modulo_samples: Default::default(),
+				max_upward_queue_count: Default::default(),
+				max_upward_queue_size: Default::default(),
+				max_downward_message_size: Default::default(),
+				ump_service_total_weight: Default::default(),
+				max_upward_message_size: Default::default(),
+				max_upward_message_num_per_candidate: Default::default(),
+				hrmp_sender_deposit: Default::default(),
+				hrmp_recipient_deposit: Default::default(),
+				hrmp_channel_max_capacity: Default::default(),
+				hrmp_channel_max_total_size: Default::default(),
+				hrmp_max_parachain_inbound_channels: Default::default(),
+				hrmp_max_parathread_inbound_channels: Default::default(),
+				hrmp_channel_max_message_size: Default::default(),
+				hrmp_max_parachain_outbound_channels: Default::default(),
+				hrmp_max_parathread_outbound_channels: Default::default(),
+				hrmp_max_message_num_per_candidate: Default::default(),
+				ump_max_individual_weight: (20u64 *
+					frame_support::weights::constants::WEIGHT_PER_MILLIS)
+					.set_proof_size(MAX_POV_SIZE as u64),
+				pvf_checking_enabled: false,
+				pvf_voting_ttl: 2u32.into(),
+				minimum_validation_upgrade_delay: 2.into(),
+			}
+		}
+	}
+
+	/// `ActiveConfig` as it is laid out in the storage version 3.
+	#[storage_alias]
+	pub(super) type ActiveConfig<T: Config> =
+		StorageValue<Pallet<T>, OldHostConfiguration<BlockNumberFor<T>>, OptionQuery>;
+
+	/// `PendingConfigs` as it is laid out in the storage version 3.
+	#[storage_alias]
+	pub(super) type PendingConfigs<T: Config> = StorageValue<
+		Pallet<T>,
+		Vec<(SessionIndex, OldHostConfiguration<BlockNumberFor<T>>)>,
+		OptionQuery,
+	>;
+
+	pub struct MigrateToV4<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() == 3 {
+				let weight_consumed = migrate_to_v4::<T>();
+
+				log::info!(target: configuration::LOG_TARGET, "MigrateToV4 executed successfully");
+				STORAGE_VERSION.put::<Pallet<T>>();
+
+				weight_consumed
+			} else {
+				log::warn!(target: configuration::LOG_TARGET, "MigrateToV4 should be removed.");
+				T::DbWeight::get().reads(1)
+			}
+		}
+	}
+}
+
 fn migrate_to_v3<T: Config>() -> Weight {
 	// Unusual formatting is justified:
 	// - make it easier to verify that fields assign what they supposed to assign.
@@ -163,9 +301,9 @@ fn migrate_to_v3<T: Config>() -> Weight {
 	#[rustfmt::skip]
 	let translate =
 		|pre: v3::OldHostConfiguration<BlockNumberFor<T>>| ->
-configuration::HostConfiguration<BlockNumberFor<T>>
+v4::OldHostConfiguration<BlockNumberFor<T>>
 	{
-		super::HostConfiguration {
+		v4::OldHostConfiguration {
 max_code_size                            : pre.max_code_size,
 max_head_data_size                       : pre.max_head_data_size,
 max_upward_queue_count                   : pre.max_upward_queue_count,
@@ -213,7 +351,7 @@ ump_max_individual_weight: Weight::from_ref_time(pre.ump_max_individual_weight.0
 		}
 	};
 
-	if let Err(_) = <Pallet<T> as Store>::ActiveConfig::translate(|pre| pre.map(translate)) {
+	if let Err(_) = v4::ActiveConfig::<T>::translate(|pre| pre.map(translate)) {
 		// `Err` is returned when the pre-migration type cannot be deserialized. This
 		// cannot happen if the migration runs correctly, i.e. against the expected version.
 		//
@@ -228,6 +366,91 @@ ump_max_individual_weight: Weight::from_ref_time(pre.ump_max_individual_weight.0
 	T::DbWeight::get().reads_writes(1, 1)
 }
 
+fn migrate_to_v4<T: Config>() -> Weight {
+	// See the comment in `migrate_to_v3` on the formatting.
+	#[rustfmt::skip]
+	let translate =
+		|pre: v4::OldHostConfiguration<BlockNumberFor<T>>| ->
+configuration::HostConfiguration<BlockNumberFor<T>>
+	{
+		super::HostConfiguration {
+max_code_size                            : pre.max_code_size,
+max_head_data_size                       : pre.max_head_data_size,
+max_upward_queue_count                   : pre.max_upward_queue_count,
+max_upward_queue_size                    : pre.max_upward_queue_size,
+max_upward_message_size                  : pre.max_upward_message_size,
+max_upward_message_num_per_candidate     : pre.max_upward_message_num_per_candidate,
+hrmp_max_message_num_per_candidate       : pre.hrmp_max_message_num_per_candidate,
+validation_upgrade_cooldown              : pre.validation_upgrade_cooldown,
+validation_upgrade_delay                 : pre.validation_upgrade_delay,
+max_pov_size                             : pre.max_pov_size,
+max_downward_message_size                : pre.max_downward_message_size,
+ump_service_total_weight                 : pre.ump_service_total_weight,
+hrmp_max_parachain_outbound_channels     : pre.hrmp_max_parachain_outbound_channels,
+hrmp_max_parathread_outbound_channels    : pre.hrmp_max_parathread_outbound_channels,
+hrmp_sender_deposit                      : pre.hrmp_sender_deposit,
+hrmp_recipient_deposit                   : pre.hrmp_recipient_deposit,
+hrmp_channel_max_capacity                : pre.hrmp_channel_max_capacity,
+hrmp_channel_max_total_size              : pre.hrmp_channel_max_total_size,
+hrmp_max_parachain_inbound_channels      : pre.hrmp_max_parachain_inbound_channels,
+hrmp_max_parathread_inbound_channels     : pre.hrmp_max_parathread_inbound_channels,
+hrmp_channel_max_message_size            : pre.hrmp_channel_max_message_size,
+code_retention_period                    : pre.code_retention_period,
+parathread_cores                         : pre.parathread_cores,
+parathread_retries                       : pre.parathread_retries,
+group_rotation_frequency                 : pre.group_rotation_frequency,
+chain_availability_period                : pre.chain_availability_period,
+thread_availability_period               : pre.thread_availability_period,
+scheduling_lookahead                     : pre.scheduling_lookahead,
+max_validators_per_core                  : pre.max_validators_per_core,
+max_validators                           : pre.max_validators,
+dispute_period                           : pre.dispute_period,
+dispute_post_conclusion_acceptance_period: pre.dispute_post_conclusion_acceptance_period,
+dispute_max_spam_slots                   : pre.dispute_max_spam_slots,
+dispute_conclusion_by_time_out_period    : pre.dispute_conclusion_by_time_out_period,
+no_show_slots                            : pre.no_show_slots,
+n_delay_tranches                         : pre.n_delay_tranches,
+zeroth_delay_tranche_width               : pre.zeroth_delay_tranche_width,
+needed_approvals                         : pre.needed_approvals,
+relay_vrf_modulo_samples                 : pre.relay_vrf_modulo_samples,
+ump_max_individual_weight                : pre.ump_max_individual_weight,
+pvf_checking_enabled                     : pre.pvf_checking_enabled,
+pvf_voting_ttl                           : pre.pvf_voting_ttl,
+minimum_validation_upgrade_delay         : pre.minimum_validation_upgrade_delay,
+
+executor_params: Default::default(),
+		}
+	};
+
+	if let Err(_) = <Pallet<T> as Store>::ActiveConfig::translate(|pre| pre.map(translate)) {
+		// See the comment in `migrate_to_v3` on why we only log here.
+		log::error!(
+			target: configuration::LOG_TARGET,
+			"unexpected error when performing translation of the configuration type during storage upgrade to v4."
+		);
+	}
+
+	// The pending configurations are laid out the same way, so they have to be migrated as well.
+	// Otherwise they would fail to decode and the pending changes would be lost.
+	if let Err(_) = <Pallet<T> as Store>::PendingConfigs::translate(
+		|pre: Option<Vec<(_, v4::OldHostConfiguration<BlockNumberFor<T>>)>>| {
+			pre.map(|pending| {
+				pending
+					.into_iter()
+					.map(|(session, config)| (session, translate(config)))
+					.collect()
+			})
+		},
+	) {
+		log::error!(
+			target: configuration::LOG_TARGET,
+			"unexpected error when performing translation of the pending configurations during storage upgrade to v4."
+		);
+	}
+
+	T::DbWeight::get().reads_writes(2, 2)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -287,7 +510,7 @@ mod tests {
 
 			migrate_to_v3::<Test>();
 
-			let v3 = configuration::ActiveConfig::<Test>::get();
+			let v3 = v4::ActiveConfig::<Test>::get().unwrap();
 
 			#[rustfmt::skip]
 			{
@@ -340,4 +563,114 @@ mod tests {
 			}; // ; makes this a statement. `rustfmt::skip` cannot be put on an expression.
 		});
 	}
+
+	#[test]
+	fn test_migrate_to_v4() {
+		// The migration only adds the executor parameters. Same as for v3, we specify only a few
+		// picked fields and let the rest be provided by the `Default` implementation.
+		let v3 = v4::OldHostConfiguration::<primitives::v2::BlockNumber> {
+			ump_max_individual_weight: Weight::from_ref_time(0x71616e6f6e0au64)
+				.set_proof_size(MAX_POV_SIZE as u64),
+			needed_approvals: 69,
+			thread_availability_period: 55,
+			hrmp_recipient_deposit: 1337,
+			max_pov_size: 1111,
+			chain_availability_period: 33,
+			minimum_validation_upgrade_delay: 20,
+			..Default::default()
+		};
+
+		let pending_v3 = vec![
+			(
+				1u32,
+				v4::OldHostConfiguration::<primitives::v2::BlockNumber> {
+					needed_approvals: 70,
+					..Default::default()
+				},
+			),
+			(
+				2u32,
+				v4::OldHostConfiguration::<primitives::v2::BlockNumber> {
+					needed_approvals: 71,
+					..Default::default()
+				},
+			),
+		];
+
+		new_test_ext(Default::default()).execute_with(|| {
+			// Implant the v3 version in the state.
+			frame_support::storage::unhashed::put_raw(
+				&configuration::ActiveConfig::<Test>::hashed_key(),
+				&v3.encode(),
+			);
+			frame_support::storage::unhashed::put_raw(
+				&configuration::PendingConfigs::<Test>::hashed_key(),
+				&pending_v3.encode(),
+			);
+
+			migrate_to_v4::<Test>();
+
+			let v4 = configuration::ActiveConfig::<Test>::get();
+
+			let pending_v4 = configuration::PendingConfigs::<Test>::get();
+			assert_eq!(pending_v4.len(), 2);
+			for ((session_v3, config_v3), (session_v4, config_v4)) in
+				pending_v3.iter().zip(pending_v4.iter())
+			{
+				assert_eq!(session_v3, session_v4);
+				assert_eq!(config_v3.needed_approvals, config_v4.needed_approvals);
+				assert_eq!(config_v3.max_pov_size, config_v4.max_pov_size);
+				assert_eq!(config_v4.executor_params, Default::default());
+			}
+
+			#[rustfmt::skip]
+			{
+				assert_eq!(v3.max_code_size                            , v4.max_code_size);
+				assert_eq!(v3.max_head_data_size                       , v4.max_head_data_size);
+				assert_eq!(v3.max_upward_queue_count                   , v4.max_upward_queue_count);
+				assert_eq!(v3.max_upward_queue_size                    , v4.max_upward_queue_size);
+				assert_eq!(v3.max_upward_message_size                  , v4.max_upward_message_size);
+				assert_eq!(v3.max_upward_message_num_per_candidate     , v4.max_upward_message_num_per_candidate);
+				assert_eq!(v3.hrmp_max_message_num_per_candidate       , v4.hrmp_max_message_num_per_candidate);
+				assert_eq!(v3.validation_upgrade_cooldown              , v4.validation_upgrade_cooldown);
+				assert_eq!(v3.validation_upgrade_delay                 , v4.validation_upgrade_delay);
+				assert_eq!(v3.max_pov_size                             , v4.max_pov_size);
+				assert_eq!(v3.max_downward_message_size                , v4.max_downward_message_size);
+				assert_eq!(v3.ump_service_total_weight                 , v4.ump_service_total_weight);
+				assert_eq!(v3.hrmp_max_parachain_outbound_channels     , v4.hrmp_max_parachain_outbound_channels);
+				assert_eq!(v3.hrmp_max_parathread_outbound_channels    , v4.hrmp_max_parathread_outbound_channels);
+				assert_eq!(v3.hrmp_sender_deposit                      , v4.hrmp_sender_deposit);
+				assert_eq!(v3.hrmp_recipient_deposit                   , v4.hrmp_recipient_deposit);
+				assert_eq!(v3.hrmp_channel_max_capacity                , v4.hrmp_channel_max_capacity);
+				assert_eq!(v3.hrmp_channel_max_total_size              , v4.hrmp_channel_max_total_size);
+				assert_eq!(v3.hrmp_max_parachain_inbound_channels      , v4.hrmp_max_parachain_inbound_channels);
+				assert_eq!(v3.hrmp_max_parathread_inbound_channels     , v4.hrmp_max_parathread_inbound_channels);
+				assert_eq!(v3.hrmp_channel_max_message_size            , v4.hrmp_channel_max_message_size);
+				assert_eq!(v3.code_retention_period                    , v4.code_retention_period);
+				assert_eq!(v3.parathread_cores                         , v4.parathread_cores);
+				assert_eq!(v3.parathread_retries                       , v4.parathread_retries);
+				assert_eq!(v3.group_rotation_frequency                 , v4.group_rotation_frequency);
+				assert_eq!(v3.chain_availability_period                , v4.chain_availability_period);
+				assert_eq!(v3.thread_availability_period               , v4.thread_availability_period);
+				assert_eq!(v3.scheduling_lookahead                     , v4.scheduling_lookahead);
+				assert_eq!(v3.max_validators_per_core                  , v4.max_validators_per_core);
+				assert_eq!(v3.max_validators                           , v4.max_validators);
+				assert_eq!(v3.dispute_period                           , v4.dispute_period);
+				assert_eq!(v3.dispute_post_conclusion_acceptance_period, v4.dispute_post_conclusion_acceptance_period);
+				assert_eq!(v3.dispute_max_spam_slots                   , v4.dispute_max_spam_slots);
+				assert_eq!(v3.dispute_conclusion_by_time_out_period    , v4.dispute_conclusion_by_time_out_period);
+				assert_eq!(v3.no_show_slots                            , v4.no_show_slots);
+				assert_eq!(v3.n_delay_tranches                         , v4.n_delay_tranches);
+				assert_eq!(v3.zeroth_delay_tranche_width               , v4.zeroth_delay_tranche_width);
+				assert_eq!(v3.needed_approvals                         , v4.needed_approvals);
+				assert_eq!(v3.relay_vrf_modulo_samples                 , v4.relay_vrf_modulo_samples);
+				assert_eq!(v3.ump_max_individual_weight                , v4.ump_max_individual_weight);
+				assert_eq!(v3.pvf_checking_enabled                     , v4.pvf_checking_enabled);
+				assert_eq!(v3.pvf_voting_ttl                           , v4.pvf_voting_ttl);
+				assert_eq!(v3.minimum_validation_upgrade_delay         , v4.minimum_validation_upgrade_delay);
+
+				assert_eq!(v4.executor_params, Default::default());
+			}; // ; makes this a statement. `rustfmt::skip` cannot be put on an expression.
+		});
+	}
 }
@@ -17,6 +17,7 @@
 use super::*;
 use crate::mock::{new_test_ext, Configuration, ParasShared, RuntimeOrigin, Test};
 use frame_support::{assert_err, assert_ok};
+use primitives::vstaging::ExecutorParam;
 
 fn on_new_session(session_index: SessionIndex) -> (HostConfiguration<u32>, HostConfiguration<u32>) {
 	ParasShared::set_session_index(session_index);
@@ -263,6 +264,41 @@ fn invariants() {
 			Configuration::set_validation_upgrade_delay(RuntimeOrigin::root(), 0),
 			Error::<Test>::InvalidNewValue
 		);
+
+		assert_err!(
+			Configuration::set_executor_params(
+				RuntimeOrigin::root(),
+				ExecutorParams::from(vec![
+					ExecutorParam::ExtraHeapPages(1024),
+					ExecutorParam::ExtraHeapPages(2048),
+				]),
+			),
+			Error::<Test>::InvalidNewValue
+		);
+		assert_err!(
+			Configuration::set_executor_params(
+				RuntimeOrigin::root(),
+				ExecutorParams::from(vec![ExecutorParam::StackLogicalMax(0)]),
+			),
+			Error::<Test>::InvalidNewValue
+		);
+		assert_err!(
+			Configuration::set_executor_params(
+				RuntimeOrigin::root(),
+				ExecutorParams::from(vec![ExecutorParam::MaxMemoryPages(65537)]),
+			),
+			Error::<Test>::InvalidNewValue
+		);
+		assert_err!(
+			Configuration::set_executor_params(
+				RuntimeOrigin::root(),
+				ExecutorParams::from(vec![
+					ExecutorParam::ExtraHeapPages(2048),
+					ExecutorParam::MaxMemoryPages(1024),
+				]),
+			),
+			Error::<Test>::InvalidNewValue
+		);
 	});
 }
 
@@ -336,6 +372,7 @@ fn setting_pending_config_members() {
 			pvf_checking_enabled: true,
 			pvf_voting_ttl: 3,
 			minimum_validation_upgrade_delay: 20,
+			executor_params: ExecutorParams::from(vec![ExecutorParam::ExtraHeapPages(1024)]),
 		};
 
 		Configuration::set_validation_upgrade_cooldown(
@@ -519,6 +556,11 @@ fn setting_pending_config_members() {
 		.unwrap();
 		Configuration::set_pvf_voting_ttl(RuntimeOrigin::root(), new_config.pvf_voting_ttl)
 			.unwrap();
+		Configuration::set_executor_params(
+			RuntimeOrigin::root(),
+			new_config.executor_params.clone(),
+		)
+		.unwrap();
 
 		assert_eq!(
 			<Configuration as Store>::PendingConfigs::get(),
@@ -42,16 +42,22 @@
 //! Later on, a block producer can submit an unsigned transaction with
 //! `KeyOwnershipProof` of an offender and submit it to the runtime
 //! to produce an offence.
+//!
+//! Validators who backed or approved an invalid candidate are disabled right
+//! away, for the session of the candidate and the current session, so the
+//! node can ignore their statements until the offence is applied.
 
-use crate::{disputes, initializer::ValidatorSetCount, session_info::IdentificationTuple};
+use crate::{disputes, initializer::ValidatorSetCount, session_info::IdentificationTuple, shared};
 use frame_support::{
 	dispatch::Pays,
 	traits::{Defensive, Get, KeyOwnerProofSystem, ValidatorSet, ValidatorSetWithIdentification},
 	weights::Weight,
 };
 
-use parity_scale_codec::{Decode, Encode};
 use primitives::v2::{CandidateHash, SessionIndex, ValidatorId, ValidatorIndex};
+pub use primitives::vstaging::slashing::{
+	DisputeProof, DisputesTimeSlot, PendingSlashes, SlashingOffenceKind,
+};
 use scale_info::TypeInfo;
 use sp_runtime::{
 	traits::Convert,
@@ -63,10 +69,7 @@ use sp_runtime::{
 };
 use sp_session::{GetSessionNumber, GetValidatorCount};
 use sp_staking::offence::{DisableStrategy, Kind, Offence, OffenceError, ReportOffence};
-use sp_std::{
-	collections::btree_map::{BTreeMap, Entry},
-	prelude::*,
-};
+use sp_std::{collections::btree_map::Entry, prelude::*};
 
 const LOG_TARGET: &str = "runtime::parachains::slashing";
 
@@ -90,21 +93,6 @@ impl<const M: u32> BenchmarkingConfiguration for BenchConfig<M> {
 	const MAX_VALIDATORS: u32 = M;
 }
 
-/// Timeslots should uniquely identify offences and are used for the offence
-/// deduplication.
-#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Encode, Decode, TypeInfo, RuntimeDebug)]
-pub struct DisputesTimeSlot {
-	// The order of these matters for `derive(Ord)`.
-	session_index: SessionIndex,
-	candidate_hash: CandidateHash,
-}
-
-impl DisputesTimeSlot {
-	pub fn new(session_index: SessionIndex, candidate_hash: CandidateHash) -> Self {
-		Self { session_index, candidate_hash }
-	}
-}
-
 /// An offence that is filed when a series of validators lost a dispute.
 #[derive(RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Clone, PartialEq, Eq))]
@@ -239,6 +227,9 @@ where
 			Some(info) => info,
 			None => return,
 		};
+		if kind == SlashingOffenceKind::ForInvalid {
+			Pallet::<T>::disable_validators(session_index, &losers, &session_info.validators);
+		}
 		let maybe = Self::maybe_identify_validators(session_index, losers.iter().cloned());
 		if let Some(offenders) = maybe {
 			let validator_set_count = session_info.discovery_keys.len() as ValidatorSetCount;
@@ -299,39 +290,6 @@ where
 	}
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
-pub enum SlashingOffenceKind {
-	#[codec(index = 0)]
-	ForInvalid,
-	#[codec(index = 1)]
-	AgainstValid,
-}
-
-/// We store most of the information about a lost dispute on chain. This struct
-/// is required to identify and verify it.
-#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
-pub struct DisputeProof {
-	/// Time slot when the dispute occured.
-	pub time_slot: DisputesTimeSlot,
-	/// The dispute outcome.
-	pub kind: SlashingOffenceKind,
-	/// The index of the validator who lost a dispute.
-	pub validator_index: ValidatorIndex,
-	/// The parachain session key of the validator.
-	pub validator_id: ValidatorId,
-}
-
-/// Slashes that are waiting to be applied once we have validator key
-/// identification.
-#[derive(Encode, Decode, RuntimeDebug, TypeInfo)]
-pub struct PendingSlashes {
-	/// Indices and keys of the validators who lost a dispute and are pending
-	/// slashes.
-	pub keys: BTreeMap<ValidatorIndex, ValidatorId>,
-	/// The dispute outcome.
-	pub kind: SlashingOffenceKind,
-}
-
 /// A trait that defines methods to report an offence (after the slashing report
 /// has been validated) and for submitting a transaction to report a slash (from
 /// an offchain context).
@@ -456,6 +414,11 @@ pub mod pallet {
 	pub(super) type ValidatorSetCounts<T> =
 		StorageMap<_, Twox64Concat, SessionIndex, ValidatorSetCount>;
 
+	/// Validators who lost a dispute about an invalid candidate, per session, sorted by index.
+	#[pallet::storage]
+	pub(super) type DisabledValidators<T> =
+		StorageMap<_, Twox64Concat, SessionIndex, Vec<ValidatorIndex>, ValueQuery>;
+
 	#[pallet::error]
 	pub enum Error<T> {
 		/// The key ownership proof is invalid.
@@ -577,6 +540,66 @@ impl<T: Config> Pallet<T> {
 
 		let old_session = session_index - config.dispute_period - 1;
 		let _ = <UnappliedSlashes<T>>::clear_prefix(old_session, REMOVE_LIMIT, None);
+		<DisabledValidators<T>>::remove(old_session);
+	}
+
+	/// Disable the given validators in the given session and, if that is a past session, the
+	/// same validators in the current session as well.
+	fn disable_validators(
+		session_index: SessionIndex,
+		losers: &[ValidatorIndex],
+		validators: &[ValidatorId],
+	) {
+		Self::insert_disabled(session_index, losers.iter().copied());
+
+		let current_session = <shared::Pallet<T>>::session_index();
+		if session_index == current_session {
+			return
+		}
+
+		let offenders: Vec<&ValidatorId> =
+			losers.iter().filter_map(|i| validators.get(i.0 as usize)).collect();
+		let current_indices = <shared::Pallet<T>>::active_validator_keys()
+			.iter()
+			.enumerate()
+			.filter(|(_, key)| offenders.contains(key))
+			.map(|(i, _)| ValidatorIndex(i as _))
+			.collect::<Vec<_>>();
+		Self::insert_disabled(current_session, current_indices);
+	}
+
+	fn insert_disabled(
+		session_index: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		<DisabledValidators<T>>::mutate(session_index, |disabled| {
+			for validator in validators {
+				if let Err(pos) = disabled.binary_search(&validator) {
+					disabled.insert(pos, validator);
+				}
+			}
+		});
+	}
+
+	/// The validators disabled in the current session.
+	pub(crate) fn disabled_validators() -> Vec<ValidatorIndex> {
+		<DisabledValidators<T>>::get(<shared::Pallet<T>>::session_index())
+	}
+
+	/// The validators who lost a dispute about a candidate of a past session and are yet to be
+	/// slashed.
+	pub(crate) fn unapplied_slashes() -> Vec<(SessionIndex, CandidateHash, PendingSlashes)> {
+		<UnappliedSlashes<T>>::iter().collect()
+	}
+
+	/// Submit an unsigned report of a lost dispute to the transaction pool.
+	///
+	/// Returns `None` if the report could not be submitted.
+	pub(crate) fn submit_unsigned_slashing_report(
+		dispute_proof: DisputeProof,
+		key_ownership_proof: <T as Config>::KeyOwnerProof,
+	) -> Option<()> {
+		T::HandleReports::submit_unsigned_slashing_report(dispute_proof, key_ownership_proof).ok()
 	}
 }
 
@@ -16,8 +16,13 @@
 
 //! Put implementations of functions from staging APIs here.
 
-use crate::disputes;
-use primitives::v2::{CandidateHash, DisputeState, SessionIndex};
+use crate::{disputes, paras, session_info};
+use primitives::{
+	v2::{
+		CandidateHash, DisputeState, Id as ParaId, SessionIndex, ValidationCodeHash, ValidatorIndex,
+	},
+	vstaging::{slashing, ExecutorParams},
+};
 use sp_std::prelude::*;
 
 /// Implementation for `get_session_disputes` function from the runtime API
@@ -25,3 +30,41 @@ pub fn get_session_disputes<T: disputes::Config>(
 ) -> Vec<(SessionIndex, CandidateHash, DisputeState<T::BlockNumber>)> {
 	<disputes::Pallet<T>>::disputes()
 }
+
+/// Implementation of `session_executor_params` function from the runtime API
+pub fn session_executor_params<T: session_info::Config>(
+	session_index: SessionIndex,
+) -> Option<ExecutorParams> {
+	<session_info::Pallet<T>>::session_executor_params(session_index)
+}
+
+/// Implementation of `future_validation_code_hash` function from the runtime API
+pub fn future_validation_code_hash<T: paras::Config>(
+	para_id: ParaId,
+) -> Option<ValidationCodeHash> {
+	<paras::Pallet<T>>::future_code_hash(para_id)
+}
+
+/// Implementation of `unapplied_slashes` function from the runtime API
+pub fn unapplied_slashes<T: disputes::slashing::Config>(
+) -> Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)> {
+	<disputes::slashing::Pallet<T>>::unapplied_slashes()
+}
+
+/// Implementation of `submit_report_dispute_lost` function from the runtime API
+pub fn submit_unsigned_slashing_report<T: disputes::slashing::Config>(
+	dispute_proof: slashing::DisputeProof,
+	key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+) -> Option<()> {
+	let key_ownership_proof = key_ownership_proof.decode()?;
+
+	<disputes::slashing::Pallet<T>>::submit_unsigned_slashing_report(
+		dispute_proof,
+		key_ownership_proof,
+	)
+}
+
+/// Implementation of `disabled_validators` function from the runtime API
+pub fn disabled_validators<T: disputes::slashing::Config>() -> Vec<ValidatorIndex> {
+	<disputes::slashing::Pallet<T>>::disabled_validators()
+}
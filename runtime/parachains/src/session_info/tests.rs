@@ -25,7 +25,10 @@ use crate::{
 	util::take_active_subset,
 };
 use keyring::Sr25519Keyring;
-use primitives::v2::{BlockNumber, ValidatorId, ValidatorIndex};
+use primitives::{
+	v2::{BlockNumber, ValidatorId, ValidatorIndex},
+	vstaging::{ExecutorParam, ExecutorParams},
+};
 
 fn run_to_block(
 	to: BlockNumber,
@@ -162,6 +165,29 @@ fn session_info_is_based_on_config() {
 	})
 }
 
+#[test]
+fn session_executor_params_are_based_on_config() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		run_to_block(1, new_session_every_block);
+		assert_eq!(SessionExecutorParams::<Test>::get(&1), Some(ExecutorParams::default()));
+
+		let executor_params = ExecutorParams::from(vec![ExecutorParam::StackLogicalMax(1024)]);
+		Configuration::set_executor_params(RuntimeOrigin::root(), executor_params.clone()).unwrap();
+
+		// The new parameters do not affect the ongoing session.
+		run_to_block(2, new_session_every_block);
+		assert_eq!(SessionExecutorParams::<Test>::get(&2), Some(ExecutorParams::default()));
+		// 2 sessions later
+		run_to_block(3, new_session_every_block);
+		assert_eq!(SessionExecutorParams::<Test>::get(&3), Some(executor_params));
+
+		// Executor params are pruned along with the session info.
+		run_to_block(10, new_session_every_block);
+		assert!(SessionExecutorParams::<Test>::get(&1).is_none());
+		assert!(SessionExecutorParams::<Test>::get(&8).is_some());
+	})
+}
+
 #[test]
 fn session_info_active_subsets() {
 	let unscrambled = vec![